@@ -0,0 +1,68 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use gpgpu::BufOps;
+
+const ENQUEUES: usize = 10_000;
+
+const SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read_write> counter: array<u32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    counter[0] += 1u;
+}
+"#;
+
+// Enqueues a trivial kernel 10k times, creating its bind groups on each enqueue with `Kernel::run`
+// or once with `KernelBuilder::bind_set` for `Kernel::run_bound`, and submitting each
+// dispatch or all of them at once with `Framework::sequence`.
+fn enqueue(c: &mut Criterion) {
+    let fw = gpgpu::Framework::default();
+    let shader = gpgpu::Shader::from_wgsl_string(&fw, SHADER.to_owned(), None).unwrap();
+    let layouts = gpgpu::SetLayout::from_shader(&shader, "main").unwrap();
+    let counter = gpgpu::GpuBuffer::<u32>::with_capacity(&fw, 1);
+
+    let kernel = gpgpu::Kernel::new(&fw, &shader, "main", layouts.clone());
+    let bound = gpgpu::Kernel::builder(&fw, &shader, "main")
+        .layouts(layouts)
+        .bind_set(0, gpgpu::SetBindings::default().add_buffer(0, &counter))
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("10k enqueues");
+    group.sample_size(10);
+
+    group.bench_function("run", |b| {
+        b.iter(|| {
+            for _ in 0..ENQUEUES {
+                let bindings = gpgpu::SetBindings::default().add_buffer(0, &counter);
+                kernel.run(&fw, vec![bindings], 1, 1, 1);
+            }
+            fw.blocking_poll();
+        })
+    });
+
+    group.bench_function("run_bound", |b| {
+        b.iter(|| {
+            for _ in 0..ENQUEUES {
+                bound.run_bound(&fw, 1, 1, 1);
+            }
+            fw.blocking_poll();
+        })
+    });
+
+    group.bench_function("sequence", |b| {
+        b.iter(|| {
+            let mut sequence = fw.sequence();
+            for _ in 0..ENQUEUES {
+                sequence = sequence.dispatch(&bound, 1, 1, 1);
+            }
+            sequence.submit();
+            fw.blocking_poll();
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, enqueue);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use gpgpu::{BufOps, ImgOps};
+
+const SIZES: [usize; 2] = [4 << 10, 4 << 20];
+
+/// Sides of the square RGBA8 images, of 4 KiB and 4 MiB.
+const IMAGE_SIDES: [u32; 2] = [32, 1024];
+
+// Reads back buffers of 4 KiB and 4 MiB into a new `Vec` with `GpuBuffer::read_vec_blocking`,
+// or without copying them out of the staging memory with `GpuBuffer::read_mapped`.
+fn read_mapped(c: &mut Criterion) {
+    let fw = gpgpu::Framework::default();
+    let mut group = c.benchmark_group("read_mapped");
+
+    for size in SIZES.iter() {
+        let buffer = gpgpu::GpuBuffer::<u8>::with_capacity(&fw, *size as u64);
+
+        group.bench_with_input(BenchmarkId::new("read_vec", size), size, |b, _| {
+            b.iter(|| black_box(buffer.read_vec_blocking().unwrap()))
+        });
+
+        group.bench_with_input(BenchmarkId::new("read_mapped", size), size, |b, _| {
+            b.iter(|| {
+                let mapped = buffer.read_mapped().unwrap();
+                black_box(&*mapped);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// Reads back images of 4 KiB and 4 MiB into a new `Vec` with `GpuImage::read_vec_blocking`,
+// or into the same preallocated buffer with `GpuImage::read_into_blocking`.
+fn read_into(c: &mut Criterion) {
+    let fw = gpgpu::Framework::default();
+    let mut group = c.benchmark_group("read_into");
+
+    for side in IMAGE_SIDES.iter() {
+        let image = gpgpu::GpuImage::<gpgpu::primitives::pixels::Rgba8Uint>::new(&fw, *side, *side);
+        let mut target = vec![0u8; image.packed_size_bytes()];
+        let size = target.len();
+
+        group.bench_with_input(BenchmarkId::new("read_vec", size), &size, |b, _| {
+            b.iter(|| black_box(image.read_vec_blocking().unwrap()))
+        });
+
+        group.bench_with_input(BenchmarkId::new("read_into", size), &size, |b, _| {
+            b.iter(|| {
+                image.read_into_blocking(&mut target).unwrap();
+                black_box(&target);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, read_mapped, read_into);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gpgpu::BufOps;
+
+const SIZES: [usize; 2] = [4 << 10, 4 << 20];
+
+// Reads back buffers of 4 KiB and 4 MiB, reusing the staging buffers retained by the staging pool
+// of the framework, or allocating a new one on every read with `FrameworkBuilder::staging_pool_limit(0)`.
+fn read(c: &mut Criterion, name: &str, fw: gpgpu::Framework) {
+    let mut group = c.benchmark_group("staging");
+
+    for size in SIZES.iter() {
+        let buffer = gpgpu::GpuBuffer::<u8>::with_capacity(&fw, *size as u64);
+        let mut target = vec![0u8; *size];
+
+        group.bench_with_input(BenchmarkId::new(name, size), size, |b, _| {
+            b.iter(|| buffer.read_blocking(&mut target).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn pooled(c: &mut Criterion) {
+    read(c, "pooled", gpgpu::Framework::default());
+}
+
+fn unpooled(c: &mut Criterion) {
+    let fw = gpgpu::Framework::builder()
+        .staging_pool_limit(0)
+        .build()
+        .unwrap();
+
+    read(c, "unpooled", fw);
+}
+
+criterion_group!(benches, pooled, unpooled);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+use gpgpu::BufOps;
+
+// Compacts the positive values of a signal, then doubles them with a kernel dispatched
+// indirectly from the compacted count: the workgroup count never goes back to the CPU.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let shader =
+        gpgpu::Shader::from_wgsl_file(&fw, "examples/indirect-dispatch/shader.wgsl").unwrap();
+
+    let signal = (0..200)
+        .map(|idx| if idx % 3 == 0 { idx as f32 } else { -1.0 })
+        .collect::<Vec<f32>>();
+
+    let input = gpgpu::GpuBuffer::from_slice(&fw, &signal);
+    let compacted = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, signal.len() as u64);
+    let count = gpgpu::GpuBuffer::from_slice(&fw, &[0u32]);
+    let args = gpgpu::GpuBuffer::from_indirect_args(&fw, &[gpgpu::DispatchIndirectArgs::default()]);
+
+    let kernel = |entry_point: &str, bindings: gpgpu::SetBindings| {
+        gpgpu::Kernel::builder(&fw, &shader, entry_point)
+            .layouts(gpgpu::SetLayout::from_shader(&shader, entry_point).unwrap())
+            .bind_set(0, bindings)
+            .build()
+            .unwrap()
+    };
+
+    let compact = kernel(
+        "compact",
+        gpgpu::SetBindings::default()
+            .bind("input", &input)
+            .bind("compacted", &compacted)
+            .bind("count", &count),
+    );
+    let prepare = kernel(
+        "prepare",
+        gpgpu::SetBindings::default()
+            .bind("count", &count)
+            .bind("args", &args),
+    );
+    let double = kernel(
+        "double",
+        gpgpu::SetBindings::default()
+            .bind("compacted", &compacted)
+            .bind("count", &count),
+    );
+
+    let workgroups = (signal.len() as u32).div_ceil(64);
+
+    fw.sequence()
+        .dispatch(&compact, workgroups, 1, 1)
+        .dispatch(&prepare, 1, 1, 1)
+        .dispatch_indirect(&double, &args, 0)
+        .submit();
+
+    let count = count.read_vec_blocking().unwrap()[0] as usize;
+    let mut doubled = compacted.read_vec_blocking().unwrap();
+    doubled.truncate(count);
+    // The compaction order depends on the scheduling of the invocations.
+    doubled.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    println!("dispatched: {:?}", args.read_vec_blocking().unwrap()[0]);
+    println!("{} positive values, doubled: {:?}", count, doubled);
+
+    let expected = signal
+        .iter()
+        .filter(|&&value| value > 0.0)
+        .map(|value| value * 2.0)
+        .collect::<Vec<_>>();
+    assert_eq!(doubled, expected);
+}
@@ -0,0 +1,42 @@
+// Compaction of the positive values of `input`, then doubling of the compacted values,
+// with a workgroup count decided on the GPU.
+struct DispatchIndirectArgs {
+    x: u32,
+    y: u32,
+    z: u32,
+}
+
+@group(0) @binding(0) var<storage, read> input: array<f32>;
+@group(0) @binding(1) var<storage, read_write> compacted: array<f32>;
+@group(0) @binding(2) var<storage, read_write> count: atomic<u32>;
+@group(0) @binding(3) var<storage, read_write> args: DispatchIndirectArgs;
+
+@compute @workgroup_size(64, 1, 1)
+fn compact(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x >= arrayLength(&input)) {
+        return;
+    }
+
+    let value = input[global_id.x];
+
+    if (value > 0.0) {
+        compacted[atomicAdd(&count, 1u)] = value;
+    }
+}
+
+// Single invocation writing the workgroup count of `double` from the compacted count.
+@compute @workgroup_size(1, 1, 1)
+fn prepare() {
+    args.x = (atomicLoad(&count) + 63u) / 64u;
+    args.y = 1u;
+    args.z = 1u;
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn double(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x >= atomicLoad(&count)) {
+        return;
+    }
+
+    compacted[global_id.x] = compacted[global_id.x] * 2.0;
+}
@@ -0,0 +1,44 @@
+use gpgpu::primitives::pixels::R32Float;
+
+const SIZE: u32 = 64;
+const ITERATIONS: usize = 2000;
+
+// This example solves the steady heat distribution of a plate whose left border is hot
+// with Jacobi iterations, swapping a pair of images every iteration.
+fn main() {
+    let fw = gpgpu::Framework::default();
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/jacobi-image/shader.wgsl").unwrap();
+
+    let kernel = gpgpu::Kernel::new(
+        &fw,
+        &shader,
+        "main",
+        vec![gpgpu::new_set_layout!(0: ConstImage<R32Float>, 1: Image<R32Float>)],
+    );
+
+    // Left border at 1.0, everything else at 0.0
+    let initial = (0..SIZE * SIZE)
+        .map(|i| if i % SIZE == 0 { 1.0 } else { 0.0 })
+        .collect::<Vec<f32>>();
+
+    let mut plate = gpgpu::PingPongImage::<R32Float>::new(&fw, SIZE, SIZE);
+    plate.front().write_pixels(&initial).unwrap();
+
+    for _ in 0..ITERATIONS {
+        // Reads the front image and writes the back one
+        let binds = gpgpu::SetBindings::default().add_ping_pong_image(0, 1, &plate);
+        kernel.run(&fw, vec![binds], SIZE.div_ceil(8), SIZE.div_ceil(8), 1);
+
+        plate.swap(); // The written image is the input of the next iteration
+    }
+
+    let heat = plate.front().read_pixels_blocking().unwrap();
+    let middle_row = &heat[(SIZE * SIZE / 2) as usize..][..SIZE as usize];
+
+    for x in (0..SIZE as usize).step_by(8) {
+        println!("x = {:>2}: {:.4}", x, middle_row[x]);
+    }
+
+    // Heat decreases away from the hot border
+    assert!(middle_row.windows(2).all(|pair| pair[0] >= pair[1]));
+}
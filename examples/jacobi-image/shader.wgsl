@@ -0,0 +1,26 @@
+// Jacobi iteration of the Laplace equation: each inner cell becomes the mean of its neighbours.
+@group(0) @binding(0) var input: texture_2d<f32>;
+@group(0) @binding(1) var output: texture_storage_2d<r32float, write>;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let dimensions = vec2<i32>(textureDimensions(input));
+    let coords = vec2<i32>(global_id.xy);
+
+    if (coords.x >= dimensions.x || coords.y >= dimensions.y) {
+        return;
+    }
+
+    var value = textureLoad(input, coords, 0).x;
+
+    // Border cells keep their value.
+    if (coords.x > 0 && coords.y > 0 && coords.x < dimensions.x - 1 && coords.y < dimensions.y - 1) {
+        let left = textureLoad(input, coords - vec2<i32>(1, 0), 0).x;
+        let right = textureLoad(input, coords + vec2<i32>(1, 0), 0).x;
+        let up = textureLoad(input, coords - vec2<i32>(0, 1), 0).x;
+        let down = textureLoad(input, coords + vec2<i32>(0, 1), 0).x;
+        value = (left + right + up + down) * 0.25;
+    }
+
+    textureStore(output, coords, vec4<f32>(value, 0.0, 0.0, 1.0));
+}
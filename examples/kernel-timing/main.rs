@@ -0,0 +1,46 @@
+use gpgpu::BufOps;
+
+// Measures the GPU execution time of a matrix multiplication at several sizes with timestamp
+// queries, unlike a wall-clock timer around the submission.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/kernel-timing/shader.wgsl").unwrap();
+
+    for &size in [64u32, 128, 256, 512].iter() {
+        let len = (size * size) as usize;
+        let a = gpgpu::GpuBuffer::from_slice(&fw, &vec![1.0f32; len]);
+        let b = gpgpu::GpuBuffer::from_slice(&fw, &vec![2.0f32; len]);
+        let c = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, len as u64);
+        let uniform = gpgpu::GpuUniformBuffer::from_value(&fw, &size);
+
+        let matmul = gpgpu::Kernel::builder(&fw, &shader, "matmul")
+            .layouts(gpgpu::SetLayout::from_shader(&shader, "matmul").unwrap())
+            .bind_set(
+                0,
+                gpgpu::SetBindings::default()
+                    .bind("size", &uniform)
+                    .bind("a", &a)
+                    .bind("b", &b)
+                    .bind("c", &c),
+            )
+            .build()
+            .unwrap();
+
+        let mut recorder = match fw.create_recorder().with_timing() {
+            Ok(recorder) => recorder,
+            Err(err) => {
+                println!("{} ({})", err, fw.adapter_info().name);
+                return;
+            }
+        };
+
+        let [x, y, z] = matmul
+            .workgroup_count(&fw, size as u64, size as u64, 1)
+            .unwrap();
+        recorder.dispatch_kernel(&matmul, x, y, z);
+
+        let timings = recorder.submit_timed().unwrap();
+        println!("{0}x{0}: {1:?}", size, timings[0].gpu_duration);
+    }
+}
@@ -0,0 +1,19 @@
+// Naive product of two square matrices of `size` x `size` elements.
+@group(0) @binding(0) var<uniform> size: u32;
+@group(0) @binding(1) var<storage, read> a: array<f32>;
+@group(0) @binding(2) var<storage, read> b: array<f32>;
+@group(0) @binding(3) var<storage, read_write> c: array<f32>;
+
+@compute @workgroup_size(8, 8)
+fn matmul(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= size || id.y >= size) {
+        return;
+    }
+
+    var sum = 0.0;
+    for (var k = 0u; k < size; k++) {
+        sum += a[id.y * size + k] * b[k * size + id.x];
+    }
+
+    c[id.y * size + id.x] = sum;
+}
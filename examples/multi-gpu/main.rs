@@ -0,0 +1,69 @@
+use gpgpu::BufOps;
+
+// Multiplies 2 vectors A and B, splitting the work across all the GPUs of the system.
+// The partial results are gathered on the first GPU.
+fn main() {
+    let frameworks = gpgpu::Framework::builder()
+        .build_with_all_adapters()
+        .unwrap(); // One framework per adapter, all from the same instance.
+
+    for (idx, fw) in frameworks.iter().enumerate() {
+        println!("Adapter {}: {}", idx, fw.adapter_info().name);
+    }
+
+    let size = 10000; // Size of the vectors
+
+    let data_a = (0..size).collect::<Vec<u32>>();
+    let data_b = (0..size).rev().collect::<Vec<u32>>();
+
+    let chunk_size = (size as usize).div_ceil(frameworks.len()); // Elements per GPU
+
+    // Each GPU computes its own chunk of C...
+    let partial_results = frameworks
+        .iter()
+        .zip(data_a.chunks(chunk_size).zip(data_b.chunks(chunk_size)))
+        .map(|(fw, (chunk_a, chunk_b))| {
+            let shader =
+                gpgpu::Shader::from_wgsl_file(fw, "examples/multi-gpu/shader.wgsl").unwrap();
+            let kernel = gpgpu::Kernel::new(
+                fw,
+                &shader,
+                "main",
+                vec![gpgpu::new_set_layout!(
+                    0: Buffer(gpgpu::GpuBufferUsage::ReadOnly),
+                    1: Buffer(gpgpu::GpuBufferUsage::ReadOnly),
+                    2: Buffer(gpgpu::GpuBufferUsage::ReadWrite)
+                )],
+            );
+
+            let gpu_a = gpgpu::GpuBuffer::from_slice(fw, chunk_a);
+            let gpu_b = gpgpu::GpuBuffer::from_slice(fw, chunk_b);
+            let gpu_c = gpgpu::GpuBuffer::<u32>::with_capacity(fw, chunk_a.len() as u64);
+
+            let bindings = gpgpu::SetBindings::default()
+                .add_buffer(0, &gpu_a)
+                .add_buffer(1, &gpu_b)
+                .add_buffer(2, &gpu_c);
+
+            kernel.run(fw, vec![bindings], chunk_a.len() as u32, 1, 1);
+
+            gpu_c
+        })
+        .collect::<Vec<_>>();
+
+    // ... and the partial results are transferred to the first GPU.
+    let main_fw = &frameworks[0];
+    let gathered = partial_results
+        .iter()
+        .map(|partial| partial.copy_to_framework_blocking(main_fw).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut gpu_result = Vec::with_capacity(size as usize);
+    for partial in gathered {
+        gpu_result.extend(partial.read_vec_blocking().unwrap());
+    }
+
+    for (idx, (a, b)) in data_a.into_iter().zip(data_b).enumerate() {
+        assert_eq!(a * b, gpu_result[idx]);
+    }
+}
@@ -2,19 +2,15 @@ use std::sync::Arc;
 
 use gpgpu::BufOps;
 
-// Framework is required to be static because of std::thread::spawn lifetime requirements.
-// By using crossbeam ScopedThreads this could be avoided.
-lazy_static::lazy_static! {
-    static ref FW: gpgpu::Framework = gpgpu::Framework::default();
-}
-
 fn main() {
+    let fw = gpgpu::Framework::default();
+
     let shader = Arc::new(
-        gpgpu::Shader::from_wgsl_file(&FW, "examples/parallel-compute/shader.wgsl").unwrap(),
+        gpgpu::Shader::from_wgsl_file(&fw, "examples/parallel-compute/shader.wgsl").unwrap(),
     );
 
     let kernel = Arc::new(gpgpu::Kernel::new(
-        &FW,
+        &fw,
         &shader,
         "main",
         vec![gpgpu::new_set_layout!(
@@ -28,26 +24,27 @@ fn main() {
     let size = 32000; // Must be multiple of 32
 
     let cpu_data = (0..size).into_iter().collect::<Vec<u32>>();
-    let shader_input_buffer = Arc::new(gpgpu::GpuBuffer::from_slice(&FW, &cpu_data)); // Data shared across threads shader invocations
+    let shader_input_buffer = Arc::new(gpgpu::GpuBuffer::from_slice(&fw, &cpu_data)); // Data shared across threads shader invocations
 
     let mut handles = Vec::with_capacity(threading);
     for _ in 0..threading {
         let local_shader_input_buffer = shader_input_buffer.clone();
         let kernel = kernel.clone();
+        let fw = fw.clone(); // Cheap clone: every thread shares the same device
 
         // Threads spawn
         let handle = std::thread::spawn(move || {
             // Current thread GPU objects
             let local_cpu_data = (0..size).into_iter().collect::<Vec<u32>>();
-            let local_input_buffer = gpgpu::GpuBuffer::from_slice(&FW, &local_cpu_data);
-            let local_output_buffer = gpgpu::GpuBuffer::<u32>::with_capacity(&FW, size as u64);
+            let local_input_buffer = gpgpu::GpuBuffer::from_slice(&fw, &local_cpu_data);
+            let local_output_buffer = gpgpu::GpuBuffer::<u32>::with_capacity(&fw, size as u64);
 
             let binds = gpgpu::SetBindings::default()
                 .add_buffer(0, &local_shader_input_buffer)
                 .add_buffer(1, &local_input_buffer)
                 .add_buffer(2, &local_output_buffer);
 
-            kernel.run(&FW, vec![binds], size / 32, 1, 1);
+            kernel.run(&fw, vec![binds], size / 32, 1, 1);
 
             local_output_buffer.read_vec_blocking().unwrap()
         });
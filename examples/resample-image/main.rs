@@ -0,0 +1,53 @@
+use gpgpu::{primitives::pixels::Rgba8UintNorm, ImgOps};
+
+// This example resizes an image with bilinear sampling.
+fn main() {
+    let fw = gpgpu::Framework::default();
+    let shader = gpgpu::Shader::from_wgsl_file(&fw, "examples/resample-image/shader.wgsl").unwrap();
+
+    let kernel = gpgpu::Kernel::new(
+        &fw,
+        &shader,
+        "main",
+        vec![gpgpu::new_set_layout!(
+            0: ConstImage<Rgba8UintNorm>,
+            1: Sampler(wgpu::SamplerBindingType::Filtering),
+            2: Image<Rgba8UintNorm>
+        )],
+    );
+
+    let dynamic_img = image::open("examples/mirror-image/monke.jpg").unwrap(); // RGB8 image ...
+    let rgba = dynamic_img.into_rgba8(); // ... converted to RGBA8
+
+    let (width, height) = rgba.dimensions();
+    let (out_width, out_height) = (width * 3 / 2, height * 3 / 2);
+
+    // GPU image and sampler creation
+    let input_img = gpgpu::GpuConstImage::<Rgba8UintNorm>::from_bytes(&fw, &rgba, width, height); // Input
+    let output_img = gpgpu::GpuImage::<Rgba8UintNorm>::new(&fw, out_width, out_height); // Output
+    let sampler = gpgpu::GpuSampler::new(&fw, &gpgpu::SamplerOptions::linear()); // Bilinear filtering
+
+    let binds = gpgpu::SetBindings::default()
+        .add_const_image(0, &input_img)
+        .add_sampler(1, &sampler)
+        .add_image(2, &output_img);
+
+    // Since the kernel workgroup size is (8, 8, 1), dims are divided and rounded up
+    kernel.run(
+        &fw,
+        vec![binds],
+        out_width.div_ceil(8),
+        out_height.div_ceil(8),
+        1,
+    );
+
+    let output_bytes = output_img.read_vec_blocking().unwrap();
+    image::save_buffer(
+        "examples/resample-image/resampled-monke.png",
+        &output_bytes,
+        out_width,
+        out_height,
+        image::ColorType::Rgba8,
+    )
+    .unwrap();
+}
@@ -0,0 +1,18 @@
+@group(0) @binding(0) var input: texture_2d<f32>;
+@group(0) @binding(1) var input_sampler: sampler;
+@group(0) @binding(2) var output: texture_storage_2d<rgba8unorm, write>;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let dims = vec2<u32>(textureDimensions(output));
+
+    if (global_id.x >= dims.x || global_id.y >= dims.y) {
+        return;
+    }
+
+    // Center of the output pixel, in normalized coordinates of the input image.
+    let uv = (vec2<f32>(global_id.xy) + 0.5) / vec2<f32>(dims);
+    let pixel = textureSampleLevel(input, input_sampler, uv, 0.0);
+
+    textureStore(output, vec2<i32>(global_id.xy), pixel);
+}
@@ -0,0 +1,45 @@
+use gpgpu::BufOps;
+
+// Box blur of a step signal, with one shader specialized at three radii through its `override` constant.
+fn main() {
+    let fw = gpgpu::Framework::default();
+
+    let shader =
+        gpgpu::Shader::from_wgsl_file(&fw, "examples/specialized-blur/shader.wgsl").unwrap();
+    let layouts = gpgpu::SetLayout::from_shader(&shader, "main").unwrap();
+
+    // The builder is built once per radius, each kernel having its own pipeline.
+    let builder = gpgpu::Kernel::builder(&fw, &shader, "main").layouts(layouts);
+
+    let signal = (0..16)
+        .map(|idx| if idx < 8 { 0.0 } else { 1.0 })
+        .collect::<Vec<f32>>();
+
+    let input = gpgpu::GpuBuffer::from_slice(&fw, &signal);
+    let output = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, signal.len() as u64);
+
+    println!("signal:   {:?}", signal);
+
+    for radius in [1, 2, 4] {
+        let kernel = builder
+            .clone()
+            .constant("RADIUS", radius as f64)
+            .build()
+            .unwrap();
+
+        let bindings = gpgpu::SetBindings::default()
+            .bind("input", &input)
+            .bind("output", &output);
+        kernel.run(&fw, vec![bindings], signal.len() as u32, 1, 1);
+
+        let blurred = output.read_vec_blocking().unwrap();
+        println!(
+            "radius {}: {:?}",
+            radius,
+            blurred
+                .iter()
+                .map(|value| (value * 100.0).round() / 100.0)
+                .collect::<Vec<_>>()
+        );
+    }
+}
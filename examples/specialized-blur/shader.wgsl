@@ -0,0 +1,18 @@
+// Blur radius, set when the kernel is created.
+override RADIUS: i32 = 1;
+
+@group(0) @binding(0) var<storage, read> input: array<f32>;
+@group(0) @binding(1) var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let center = i32(global_id.x);
+    let last = i32(arrayLength(&input)) - 1;
+
+    var sum = 0.0;
+    for (var offset = -RADIUS; offset <= RADIUS; offset += 1) {
+        sum += input[clamp(center + offset, 0, last)];
+    }
+
+    output[global_id.x] = sum / f32(2 * RADIUS + 1);
+}
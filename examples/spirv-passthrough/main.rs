@@ -0,0 +1,57 @@
+use gpgpu::BufOps;
+
+// Runs a SPIR-V kernel compiled by rust-gpu, passed to the driver without being translated by naga.
+// Only Vulkan adapters support SPIR-V passthrough.
+//
+// The kernel doubles a buffer of `u32`, for example compiled with `spirv-builder` from:
+//
+// #[spirv(compute(threads(64)))]
+// pub fn main_cs(
+//     #[spirv(global_invocation_id)] id: UVec3,
+//     #[spirv(storage_buffer, descriptor_set = 0, binding = 0)] data: &mut [u32],
+// ) {
+//     if let Some(value) = data.get_mut(id.x as usize) {
+//         *value *= 2;
+//     }
+// }
+//
+// Usage: cargo r --example spirv-passthrough -- <path to the .spv> [entry point, `main_cs` by default]
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let path = args
+        .next()
+        .expect("Usage: spirv-passthrough <path to the .spv> [entry point]");
+    let entry_point = args.next().unwrap_or_else(|| "main_cs".to_owned());
+
+    let fw = gpgpu::Framework::builder()
+        .backends(wgpu::Backends::VULKAN)
+        .features(wgpu::Features::SPIRV_SHADER_PASSTHROUGH)
+        .build()
+        .unwrap();
+
+    // Safety: the SPIR-V is trusted to be valid, the driver does not validate it.
+    let shader = unsafe { gpgpu::Shader::from_spirv_file_passthrough(&fw, &path) }.unwrap();
+
+    let size = 10000;
+    let data = (0..size).collect::<Vec<u32>>();
+    let gpu_data = gpgpu::GpuBuffer::from_slice(&fw, &data);
+
+    let kernel = gpgpu::Kernel::builder(&fw, &shader, &entry_point)
+        .layouts(vec![gpgpu::new_set_layout!(
+            0: Buffer(gpgpu::GpuBufferUsage::ReadWrite)
+        )])
+        .bind_set(0, gpgpu::SetBindings::default().add_buffer(0, &gpu_data))
+        .workgroup_size(64, 1, 1) // `threads(64)` of the kernel, in case naga cannot reflect it.
+        .build()
+        .unwrap();
+
+    kernel.run_elements(&fw, size as u64, 1, 1);
+
+    let gpu_result = gpu_data.read_vec_blocking().unwrap();
+
+    for (cpu, gpu) in data.into_iter().zip(gpu_result) {
+        assert_eq!(cpu * 2, gpu);
+    }
+
+    println!("{} elements doubled by `{}`", size, entry_point);
+}
@@ -0,0 +1,68 @@
+use gpgpu::BufOps;
+
+// Multiplies 2 vectors A and B like `simple-compute`, but with the async constructor of the framework,
+// which is the one usable on wasm32, where the main thread cannot be blocked.
+//
+// Natively, it runs like the other examples. In a browser supporting WebGPU, it is built with:
+//     RUSTFLAGS=--cfg=web_sys_unstable_apis cargo build --example wasm --target wasm32-unknown-unknown
+//     wasm-bindgen --target web --out-dir examples/wasm/pkg target/wasm32-unknown-unknown/debug/examples/wasm.wasm
+// and runs by serving the `examples/wasm` directory and opening `index.html`.
+fn main() {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(run());
+
+    #[cfg(not(target_arch = "wasm32"))]
+    futures::executor::block_on(run());
+}
+
+async fn run() {
+    let fw = gpgpu::Framework::new_async().await.unwrap(); // Awaits the adapter and the device, without blocking.
+    log(&format!("Adapter: {}", fw.adapter_info().name));
+
+    // The shader is embedded, since there is no file system in the browser.
+    let shader =
+        gpgpu::Shader::from_wgsl_string(&fw, include_str!("shader.wgsl").to_owned(), None).unwrap();
+    let kernel = gpgpu::Kernel::new(
+        &fw,
+        &shader,
+        "main",
+        vec![gpgpu::new_set_layout!(
+            0: Buffer(gpgpu::GpuBufferUsage::ReadOnly),
+            1: Buffer(gpgpu::GpuBufferUsage::ReadOnly),
+            2: Buffer(gpgpu::GpuBufferUsage::ReadWrite)
+        )],
+    );
+
+    let size = 10000; // Size of the vectors
+
+    let data_a = (0..size).collect::<Vec<u32>>();
+    let data_b = (0..size).rev().collect::<Vec<u32>>();
+
+    let gpu_vec_a = gpgpu::GpuBuffer::from_slice(&fw, &data_a);
+    let gpu_vec_b = gpgpu::GpuBuffer::from_slice(&fw, &data_b);
+    let gpu_vec_c = gpgpu::GpuBuffer::<u32>::with_capacity(&fw, size as u64);
+
+    let bindings = gpgpu::SetBindings::default()
+        .add_buffer(0, &gpu_vec_a)
+        .add_buffer(1, &gpu_vec_b)
+        .add_buffer(2, &gpu_vec_c);
+
+    kernel.run(&fw, vec![bindings], size, 1, 1);
+
+    // Awaited instead of `read_vec_blocking`, which is not available on wasm32.
+    let gpu_result = gpu_vec_c.read_vec().await.unwrap();
+
+    for (idx, (a, b)) in data_a.into_iter().zip(data_b).enumerate() {
+        assert_eq!(a * b, gpu_result[idx]);
+    }
+
+    log("All the products are correct");
+}
+
+fn log(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::log_1(&message.into());
+
+    #[cfg(not(target_arch = "wasm32"))]
+    println!("{}", message);
+}
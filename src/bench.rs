@@ -0,0 +1,210 @@
+//! Micro-benchmarks of kernel dispatches, e.g. to compare workgroup sizes.
+//!
+//! # Example
+//! ```no_run
+//! # let fw = gpgpu::Framework::default();
+//! # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+//! # let kernel = gpgpu::Kernel::builder(&fw, &shader, "step").build().unwrap();
+//! let options = gpgpu::bench::BenchOptions {
+//!     elements: Some(1 << 20),
+//!     ..Default::default()
+//! };
+//! let report = gpgpu::bench::run(&fw, &kernel, [4096, 1, 1], options).unwrap();
+//! println!("{}", report);
+//! ```
+
+use std::{fmt, time::Duration};
+
+use crate::{framework::GpuResult, Framework, Kernel, TimingSource};
+
+/// Configures [`run`].
+#[derive(Clone, Debug)]
+pub struct BenchOptions {
+    /// Dispatches executed before the timed ones, e.g. to let the GPU reach its clock speed.
+    pub warmup_iters: u32,
+    /// Timed dispatches.
+    pub iters: u32,
+    /// Dispatches recorded per submission, so the timings are not dominated by the submission overhead.
+    pub batch_size: u32,
+    /// Elements processed by a dispatch, to report the throughput in elements per second.
+    pub elements: Option<u64>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            warmup_iters: 10,
+            iters: 100,
+            batch_size: 10,
+            elements: None,
+        }
+    }
+}
+
+/// Statistics of the dispatch durations measured by [`run`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchReport {
+    /// Entry point of the benchmarked kernel.
+    pub kernel: String,
+    /// How the durations were measured.
+    ///
+    /// With [`TimingSource::WallClock`], each sample is the mean duration of the dispatches of a batch.
+    pub source: TimingSource,
+    /// Measured durations, in the order of the dispatches.
+    pub samples: Vec<Duration>,
+    /// Shortest sample.
+    pub min: Duration,
+    /// Middle sample, or mean of the two middle ones.
+    pub median: Duration,
+    /// Mean of the samples.
+    pub mean: Duration,
+    /// 95th percentile, the duration exceeded by 5% of the samples.
+    pub p95: Duration,
+    /// Standard deviation of the samples.
+    pub std_dev: Duration,
+    /// Elements per second of a dispatch of the mean duration, if [`BenchOptions::elements`] is set.
+    pub throughput: Option<f64>,
+}
+
+impl BenchReport {
+    fn new(
+        kernel: String,
+        source: TimingSource,
+        samples: Vec<Duration>,
+        elements: Option<u64>,
+    ) -> Self {
+        let mut sorted = samples.clone();
+        sorted.sort();
+
+        let len = sorted.len();
+        let median = if len.is_multiple_of(2) {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2
+        } else {
+            sorted[len / 2]
+        };
+        // Nearest rank percentile.
+        let p95 = sorted[(len * 95).div_ceil(100) - 1];
+
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / len as f64;
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / len as f64;
+
+        Self {
+            kernel,
+            source,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            p95,
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            throughput: elements.map(|elements| elements as f64 / mean),
+            samples,
+        }
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: mean {:?} ± {:?}, median {:?}, min {:?}, p95 {:?}",
+            self.kernel, self.mean, self.std_dev, self.median, self.min, self.p95
+        )?;
+
+        if let Some(throughput) = self.throughput {
+            write!(f, ", {:.3e} elements/s", throughput)?;
+        }
+
+        let source = match self.source {
+            TimingSource::Timestamps => "GPU timestamps",
+            TimingSource::WallClock => "wall clock",
+        };
+
+        write!(f, " ({} samples, {})", self.samples.len(), source)
+    }
+}
+
+/// Benchmarks `kernel` dispatched with the `workgroups` per dimension and the bindings of
+/// [`Kernel::rebind_set`], returning the statistics of the timed dispatches.
+///
+/// The dispatches are measured by timestamp queries if the device supports [`wgpu::Features::TIMESTAMP_QUERY`],
+/// otherwise by the wall-clock time of each batch of [`BenchOptions::batch_size`] dispatches, see [`Kernel::run_timed`].
+///
+/// Returns [`GpuError::UnboundEntries`](crate::GpuError::UnboundEntries) if a set of `kernel` was never bound,
+/// or [`GpuError::Buffer`](crate::GpuError::Buffer) if the timestamps cannot be read back.
+///
+/// # Panics
+/// If [`BenchOptions::iters`] is 0.
+pub fn run(
+    fw: &Framework,
+    kernel: &Kernel,
+    workgroups: [u32; 3],
+    options: BenchOptions,
+) -> GpuResult<BenchReport> {
+    if options.iters == 0 {
+        panic!("A benchmark needs at least one timed iteration")
+    }
+
+    let [x, y, z] = workgroups;
+    let batch_size = std::cmp::max(options.batch_size, 1);
+    let batches = |iters: u32| {
+        (0..iters)
+            .step_by(batch_size as usize)
+            .map(move |first| std::cmp::min(batch_size, iters - first))
+    };
+
+    for batch in batches(options.warmup_iters) {
+        let mut recorder = fw.create_recorder();
+        for _ in 0..batch {
+            recorder.try_dispatch_kernel(kernel, x, y, z)?;
+        }
+        recorder.submit();
+    }
+    fw.wait_idle();
+
+    let timed = fw.create_recorder().with_timing().is_ok();
+    let mut samples = Vec::with_capacity(options.iters as usize);
+
+    for batch in batches(options.iters) {
+        if timed {
+            let mut recorder = fw.create_recorder().with_timing()?;
+            for _ in 0..batch {
+                recorder.try_dispatch_kernel(kernel, x, y, z)?;
+            }
+
+            samples.extend(
+                recorder
+                    .submit_timed()?
+                    .into_iter()
+                    .map(|timing| timing.gpu_duration),
+            );
+        } else {
+            let mut recorder = fw.create_recorder();
+            for _ in 0..batch {
+                recorder.try_dispatch_kernel(kernel, x, y, z)?;
+            }
+
+            let start = std::time::Instant::now();
+            let submission = recorder.submit();
+            fw.wait_for(&submission);
+
+            samples.push(start.elapsed() / batch);
+        }
+    }
+
+    let source = if timed {
+        TimingSource::Timestamps
+    } else {
+        TimingSource::WallClock
+    };
+
+    Ok(BenchReport::new(
+        kernel.entry_point().to_owned(),
+        source,
+        samples,
+        options.elements,
+    ))
+}
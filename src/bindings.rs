@@ -1,12 +1,93 @@
-use crate::{entry_type::EntryType, primitives::*, *};
+use crate::{
+    entry_type::EntryType,
+    framework::GpuResult,
+    primitives::buffers::GpuBufferSlice,
+    primitives::images::{
+        GpuConstImageLayer, GpuImageLayer, GpuImageMip, GpuImageView, PingPongImage,
+    },
+    primitives::*,
+    *,
+};
 
 #[derive(Clone, Default)]
 pub struct SetBindings<'res> {
     pub(crate) bindings: Vec<wgpu::BindGroupEntry<'res>>,
     pub(crate) entry_type: Vec<EntryType>,
+    /// Bindings of [`SetBindings::bind`], resolved by name when the bind group is created.
+    pub(crate) named: Vec<(&'res str, wgpu::BindingResource<'res>, EntryType)>,
+}
+
+/// Resource bound by name with [`SetBindings::bind`], converted from a reference to a buffer,
+/// a uniform buffer, an image or a sampler.
+pub struct Bindable<'res> {
+    resource: wgpu::BindingResource<'res>,
+    entry_type: EntryType,
+}
+
+macro_rules! bindable_impl {
+    ($($resource:ident<$param:ident: $bound:path>, $entry_type:expr);+) => {
+        $(
+            impl<'res, $param: $bound> From<&'res $resource<$param>> for Bindable<'res> {
+                fn from(resource: &'res $resource<$param>) -> Self {
+                    Self {
+                        resource: resource.as_binding_resource(),
+                        entry_type: $entry_type,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+bindable_impl! {
+    GpuBuffer<T: bytemuck::Pod>, EntryType::Buffer;
+    GpuUniformBuffer<T: bytemuck::Pod>, EntryType::Uniform;
+    GpuImage<P: PixelInfo>, EntryType::Image;
+    GpuConstImage<P: PixelInfo>, EntryType::ConstImage;
+    GpuImage3d<P: PixelInfo>, EntryType::Image3d;
+    GpuConstImage3d<P: PixelInfo>, EntryType::ConstImage3d;
+    GpuImage1d<P: PixelInfo>, EntryType::Image1d;
+    GpuConstImage1d<P: PixelInfo>, EntryType::ConstImage1d;
+    GpuImageArray<P: PixelInfo>, EntryType::ImageArray;
+    GpuConstImageArray<P: PixelInfo>, EntryType::ConstImageArray
+}
+
+impl<'res> From<&'res GpuSampler> for Bindable<'res> {
+    fn from(sampler: &'res GpuSampler) -> Self {
+        Self {
+            resource: sampler.as_binding_resource(),
+            entry_type: EntryType::Sampler(sampler.binding_type()),
+        }
+    }
 }
 
 impl<'res> SetBindings<'res> {
+    /// Binds `resource` to the entry named `name` in the shader, whatever its binding.
+    ///
+    /// Names are only known for layouts reflected by [`SetLayout::from_shader`](crate::SetLayout::from_shader):
+    /// [`Kernel::run`](crate::Kernel::run) fails with [`GpuError::UnknownBinding`] if the layout of the set
+    /// has no entry named `name`, and with [`GpuError::BindingMismatch`] if the entry expects another
+    /// kind of resource, e.g. a uniform buffer instead of a storage buffer.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var<storage, read_write> particles: array<Particle>;
+    /// @group(0) @binding(1)
+    /// var<uniform> params: Params;
+    ///
+    /// // SetBindings::default().bind("particles", &particles).bind("params", &params)
+    /// ```
+    pub fn bind(mut self, name: &'res str, resource: impl Into<Bindable<'res>>) -> Self {
+        let Bindable {
+            resource,
+            entry_type,
+        } = resource.into();
+        self.named.push((name, resource, entry_type));
+
+        self
+    }
+
     pub fn add_buffer<T>(mut self, bind_id: u32, buffer: &'res GpuBuffer<T>) -> Self
     where
         T: bytemuck::Pod,
@@ -22,6 +103,40 @@ impl<'res> SetBindings<'res> {
         self
     }
 
+    /// Binds only the region of a [`GpuBuffer`] described by `slice`,
+    /// to an entry added with [`SetLayout::add_buffer`](crate::SetLayout::add_buffer).
+    pub fn add_buffer_slice<T>(mut self, bind_id: u32, slice: GpuBufferSlice<'res, T>) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: slice.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::Buffer);
+
+        self
+    }
+
+    /// Binds a scratch buffer of a [`BufferArena`], to an entry added with
+    /// [`SetLayout::add_buffer`](crate::SetLayout::add_buffer).
+    pub fn add_arena_buffer<T>(mut self, bind_id: u32, buffer: &'res ArenaBuffer<'_, T>) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: buffer.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::Buffer);
+
+        self
+    }
+
     pub fn add_uniform_buffer<T>(mut self, bind_id: u32, buffer: &'res GpuUniformBuffer<T>) -> Self
     where
         T: bytemuck::Pod,
@@ -37,27 +152,544 @@ impl<'res> SetBindings<'res> {
         self
     }
 
-    pub(crate) fn into_bind_group<'fw, 'la>(
+    /// Binds the first `len` elements of a [`GpuBuffer`] to an entry added with
+    /// [`SetLayout::add_dynamic_buffer`](crate::SetLayout::add_dynamic_buffer). The bound region is moved
+    /// by the dynamic offset given on each execution by [`Kernel::run_with_offsets`](crate::Kernel::run_with_offsets).
+    ///
+    /// # Panics
+    /// If `len` is 0: `wgpu` would bind the whole buffer, leaving no room to move the region.
+    pub fn add_dynamic_buffer<T>(
+        mut self,
+        bind_id: u32,
+        buffer: &'res GpuBuffer<T>,
+        len: u64,
+    ) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        assert!(len > 0, "dynamic buffers cannot bind an empty region");
+
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: buffer.as_wgpu_buffer(),
+                offset: 0,
+                size: std::num::NonZeroU64::new(len * std::mem::size_of::<T>() as u64),
+            }),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::DynamicBuffer);
+
+        self
+    }
+
+    /// Binds a single `T` of a [`GpuUniformBuffer`] holding several blocks of parameters, to an entry added with
+    /// [`SetLayout::add_dynamic_uniform_buffer`](crate::SetLayout::add_dynamic_uniform_buffer). The bound block is
+    /// selected by the dynamic offset given on each execution by [`Kernel::run_with_offsets`](crate::Kernel::run_with_offsets).
+    pub fn add_dynamic_uniform_buffer<T>(
+        mut self,
+        bind_id: u32,
+        buffer: &'res GpuUniformBuffer<T>,
+    ) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: buffer.as_wgpu_buffer(),
+                offset: 0,
+                size: std::num::NonZeroU64::new(std::mem::size_of::<T>() as u64),
+            }),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::DynamicUniform);
+
+        self
+    }
+
+    /// Binds a [`GpuImage`] to an entry added with [`SetLayout::add_image`](crate::SetLayout::add_image).
+    pub fn add_image<P>(mut self, bind_id: u32, image: &'res GpuImage<P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::Image);
+
+        self
+    }
+
+    /// Binds a [`GpuConstImage`] to an entry added with [`SetLayout::add_const_image`](crate::SetLayout::add_const_image).
+    pub fn add_const_image<P>(mut self, bind_id: u32, image: &'res GpuConstImage<P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::ConstImage);
+
+        self
+    }
+
+    /// Binds a [`GpuImage3d`] to an entry added with [`SetLayout::add_image_3d`](crate::SetLayout::add_image_3d).
+    pub fn add_image_3d<P>(mut self, bind_id: u32, image: &'res GpuImage3d<P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::Image3d);
+
+        self
+    }
+
+    /// Binds a [`GpuConstImage3d`] to an entry added with [`SetLayout::add_const_image_3d`](crate::SetLayout::add_const_image_3d).
+    pub fn add_const_image_3d<P>(mut self, bind_id: u32, image: &'res GpuConstImage3d<P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::ConstImage3d);
+
+        self
+    }
+
+    /// Binds a [`GpuImage1d`] to an entry added with [`SetLayout::add_image_1d`](crate::SetLayout::add_image_1d).
+    pub fn add_image_1d<P>(mut self, bind_id: u32, image: &'res GpuImage1d<P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::Image1d);
+
+        self
+    }
+
+    /// Binds a [`GpuConstImage1d`] to an entry added with [`SetLayout::add_const_image_1d`](crate::SetLayout::add_const_image_1d).
+    pub fn add_const_image_1d<P>(mut self, bind_id: u32, image: &'res GpuConstImage1d<P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::ConstImage1d);
+
+        self
+    }
+
+    /// Binds a [`GpuImageArray`] to an entry added with [`SetLayout::add_image_array`](crate::SetLayout::add_image_array).
+    pub fn add_image_array<P>(mut self, bind_id: u32, image: &'res GpuImageArray<P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::ImageArray);
+
+        self
+    }
+
+    /// Binds a [`GpuConstImageArray`] to an entry added with [`SetLayout::add_const_image_array`](crate::SetLayout::add_const_image_array).
+    pub fn add_const_image_array<P>(
+        mut self,
+        bind_id: u32,
+        image: &'res GpuConstImageArray<P>,
+    ) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: image.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::ConstImageArray);
+
+        self
+    }
+
+    /// Binds a [`GpuSampler`] to an entry added with [`SetLayout::add_sampler`](crate::SetLayout::add_sampler)
+    /// of the same binding type.
+    pub fn add_sampler(mut self, bind_id: u32, sampler: &'res GpuSampler) -> Self {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: sampler.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type
+            .push(EntryType::Sampler(sampler.binding_type()));
+
+        self
+    }
+
+    /// Binds a [`GpuImageView`] as a storage image, to an entry added with
+    /// [`SetLayout::add_image`](crate::SetLayout::add_image), or with
+    /// [`SetLayout::add_image_array`](crate::SetLayout::add_image_array) for views of image arrays.
+    ///
+    /// # Panics
+    /// If the view has more than one mip level, which storage images cannot have.
+    pub fn add_image_view<P>(mut self, bind_id: u32, view: &'res GpuImageView<'_, P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        assert!(
+            view.mip_level_count() == 1,
+            "views of {} mip levels cannot be bound as storage images, only single mip levels can",
+            view.mip_level_count()
+        );
+
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: view.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(match view.dimension() {
+            wgpu::TextureViewDimension::D2Array => EntryType::ImageArray,
+            _ => EntryType::Image,
+        });
+
+        self
+    }
+
+    /// Binds a [`GpuImageView`] as a sampled image, to an entry added with
+    /// [`SetLayout::add_const_image`](crate::SetLayout::add_const_image), or with
+    /// [`SetLayout::add_const_image_array`](crate::SetLayout::add_const_image_array) for views of image arrays.
+    ///
+    /// The GL backend has no texture views: kernels sample the first mip level of the image instead.
+    ///
+    /// # Panics
+    /// If the image of the view cannot be sampled, see [`GpuImageView::is_sampled`].
+    pub fn add_const_image_view<P>(mut self, bind_id: u32, view: &'res GpuImageView<'_, P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        assert!(
+            view.is_sampled(),
+            "the image of the view cannot be sampled, create it with `GpuImage::try_with_mip_levels`"
+        );
+
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: view.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(match view.dimension() {
+            wgpu::TextureViewDimension::D2Array => EntryType::ConstImageArray,
+            _ => EntryType::ConstImage,
+        });
+
+        self
+    }
+
+    /// Binds the front image of a [`PingPongImage`] as a sampled image at `input_id`, to an entry added with
+    /// [`SetLayout::add_const_image`](crate::SetLayout::add_const_image), and its back image as a storage image
+    /// at `output_id`, to an entry added with [`SetLayout::add_image`](crate::SetLayout::add_image).
+    ///
+    /// The bindings follow the images of the pair: bindings added after [`PingPongImage::swap`] read the image
+    /// written by the previous ones.
+    pub fn add_ping_pong_image<P>(
+        mut self,
+        input_id: u32,
+        output_id: u32,
+        images: &'res PingPongImage<P>,
+    ) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: input_id,
+            resource: images.front().as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::ConstImage);
+
+        self.add_image(output_id, images.back())
+    }
+
+    /// Binds a single mip level of a [`GpuImage`] to an entry added with [`SetLayout::add_image`](crate::SetLayout::add_image).
+    /// See [`GpuImage::mip_view`].
+    pub fn add_image_mip<P>(mut self, bind_id: u32, mip: &'res GpuImageMip<'_, P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: mip.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::Image);
+
+        self
+    }
+
+    /// Binds a single layer of a [`GpuImageArray`] to an entry added with [`SetLayout::add_image`](crate::SetLayout::add_image).
+    /// See [`GpuImageArray::layer_view`].
+    pub fn add_image_layer<P>(mut self, bind_id: u32, layer: &'res GpuImageLayer<'_, P>) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: layer.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::Image);
+
+        self
+    }
+
+    /// Binds a single layer of a [`GpuConstImageArray`] to an entry added with [`SetLayout::add_const_image`](crate::SetLayout::add_const_image).
+    /// See [`GpuConstImageArray::layer_view`].
+    pub fn add_const_image_layer<P>(
+        mut self,
+        bind_id: u32,
+        layer: &'res GpuConstImageLayer<'_, P>,
+    ) -> Self
+    where
+        P: PixelInfo,
+    {
+        let bind = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: layer.as_binding_resource(),
+        };
+
+        self.bindings.push(bind);
+        self.entry_type.push(EntryType::ConstImage);
+
+        self
+    }
+
+    /// Creates the bind group of the set `set`, after checking that the bindings match
+    /// the entries of `set_layout`, in any order.
+    pub(crate) fn into_bind_group(
         &self,
-        fw: &'fw Framework,
-        layout: &'la wgpu::BindGroupLayout,
-        entry_types: &Vec<EntryType>,
-    ) -> wgpu::BindGroup {
-        // TODO: Make custom error struct/enum
-        if self.entry_type.len() != entry_types.len() {
-            panic!("SetBindings must have the same layout as SetLayout")
+        fw: &Framework,
+        set: u32,
+        layout: &wgpu::BindGroupLayout,
+        set_layout: &SetLayout,
+    ) -> GpuResult<wgpu::BindGroup> {
+        let mismatch = |binding, reason| GpuError::BindingMismatch {
+            set,
+            binding,
+            reason,
+        };
+
+        let mut bindings = self.bindings.clone();
+        let mut entry_types = self.entry_type.clone();
+
+        for (name, resource, entry_type) in &self.named {
+            let binding = set_layout
+                .binding_of(name)
+                .ok_or_else(|| GpuError::UnknownBinding {
+                    set,
+                    name: name.to_string(),
+                })?;
+
+            bindings.push(wgpu::BindGroupEntry {
+                binding,
+                resource: resource.clone(),
+            });
+            entry_types.push(*entry_type);
         }
 
-        for entry_type in self.entry_type.iter().zip(entry_types.iter()) {
-            if entry_type.0 != entry_type.1 {
-                panic!("SetBindings do not have the same entry type as SetLayout")
+        for (bind, &entry_type) in bindings.iter().zip(&entry_types) {
+            match set_layout.entry_type_of(bind.binding) {
+                None => {
+                    return Err(mismatch(
+                        bind.binding,
+                        "the layout does not declare it".to_owned(),
+                    ))
+                }
+                Some(expected) if !expected.accepts(entry_type) => {
+                    return Err(mismatch(
+                        bind.binding,
+                        format!(
+                            "{} expects a {:?}, but a {:?} was bound",
+                            set_layout.describe(bind.binding),
+                            expected,
+                            entry_type
+                        ),
+                    ))
+                }
+                _ => (),
             }
         }
 
-        fw.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        let unbound = set_layout
+            .layout_entry
+            .iter()
+            .filter(|entry| !bindings.iter().any(|bind| bind.binding == entry.binding))
+            .map(|entry| {
+                format!(
+                    "{} at binding {}",
+                    set_layout.describe(entry.binding),
+                    entry.binding
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if !unbound.is_empty() {
+            return Err(GpuError::UnboundEntries { set, unbound });
+        }
+
+        // Dynamic offsets are given in the order of the bindings.
+        bindings.sort_by_key(|bind| bind.binding);
+
+        Ok(fw.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: None,
             layout,
-            entries: &self.bindings,
-        })
+            entries: &bindings,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    /// Adds the uniform `offset` to the elements of `data`.
+    const OFFSET_SHADER: &str = "
+        @group(0) @binding(0) var<uniform> offset: vec4<u32>;
+        @group(0) @binding(1) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            if (id.x < arrayLength(&data)) {
+                data[id.x] = data[id.x] + offset.x;
+            }
+        }";
+
+    fn kernel(fw: &Framework) -> Kernel {
+        let shader = Shader::from_wgsl_string(fw, OFFSET_SHADER.to_owned(), None).unwrap();
+
+        Kernel::from_reflection(fw, &shader, "main").unwrap()
+    }
+
+    #[test]
+    fn bind_by_name() {
+        testing::with_framework(|fw| {
+            let mut kernel = kernel(fw);
+            let offset = GpuUniformBuffer::from_slice(fw, &[10u32, 0, 0, 0]);
+            let data = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+
+            // In another order than the bindings.
+            kernel
+                .try_rebind_set(
+                    fw,
+                    0,
+                    SetBindings::default()
+                        .bind("data", &data)
+                        .bind("offset", &offset),
+                )
+                .unwrap();
+            kernel.run_bound(fw, 1, 1, 1);
+
+            assert_eq!(data.read_vec_blocking().unwrap(), [11, 12, 13]);
+        });
+    }
+
+    #[test]
+    fn bind_leaves_entries_unbound() {
+        testing::with_framework(|fw| {
+            let mut kernel = kernel(fw);
+            let data = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+
+            let result = kernel.try_rebind_set(fw, 0, SetBindings::default().bind("data", &data));
+
+            match result {
+                Err(GpuError::UnboundEntries { set: 0, unbound }) => {
+                    assert_eq!(unbound, ["`offset` at binding 0"])
+                }
+                _ => panic!("expected unbound entries"),
+            }
+        });
+    }
+
+    #[test]
+    fn bind_unknown_name() {
+        testing::with_framework(|fw| {
+            let mut kernel = kernel(fw);
+            let offset = GpuUniformBuffer::from_slice(fw, &[10u32, 0, 0, 0]);
+            let data = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+
+            let result = kernel.try_rebind_set(
+                fw,
+                0,
+                SetBindings::default()
+                    .bind("offset", &offset)
+                    .bind("elements", &data),
+            );
+
+            assert!(matches!(
+                result,
+                Err(GpuError::UnknownBinding { set: 0, name }) if name == "elements"
+            ));
+        });
+    }
+
+    #[test]
+    fn bind_mismatched_type() {
+        testing::with_framework(|fw| {
+            let mut kernel = kernel(fw);
+            let offset = GpuUniformBuffer::from_slice(fw, &[10u32, 0, 0, 0]);
+
+            let result = kernel.try_rebind_set(
+                fw,
+                0,
+                SetBindings::default()
+                    .bind("offset", &offset)
+                    .bind("data", &offset),
+            );
+
+            match result {
+                Err(GpuError::BindingMismatch {
+                    set: 0,
+                    binding: 1,
+                    reason,
+                }) => assert_eq!(reason, "`data` expects a Buffer, but a Uniform was bound"),
+                _ => panic!("expected a binding mismatch"),
+            }
+        });
     }
 }
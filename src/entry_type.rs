@@ -1,7 +1,27 @@
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum EntryType {
     Buffer,
     Uniform,
+    DynamicBuffer,
+    DynamicUniform,
     ConstImage,
     Image,
+    ConstImage3d,
+    Image3d,
+    ConstImage1d,
+    Image1d,
+    ConstImageArray,
+    ImageArray,
+    Sampler(wgpu::SamplerBindingType),
+}
+
+impl EntryType {
+    /// Returns `true` if a resource bound as `bound` can fill an entry of this type.
+    ///
+    /// Filtering sampler entries also accept non-filtering samplers.
+    pub(crate) fn accepts(self, bound: EntryType) -> bool {
+        self == bound
+            || (self == Self::Sampler(wgpu::SamplerBindingType::Filtering)
+                && bound == Self::Sampler(wgpu::SamplerBindingType::NonFiltering))
+    }
 }
@@ -6,3 +6,15 @@ pub mod integrate_image;
 
 #[cfg(feature = "integrate-ndarray")]
 pub mod integrate_ndarray;
+
+#[cfg(feature = "integrate-encase")]
+pub mod integrate_encase;
+
+#[cfg(feature = "export")]
+pub mod export;
+
+#[cfg(feature = "glsl")]
+pub mod glsl;
+
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
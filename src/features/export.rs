@@ -0,0 +1,338 @@
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::{
+    primitives::{images::ImageOutputError, PixelInfo},
+    GpuImage,
+};
+
+/// Formats saved by [`GpuImage::save_png`].
+const PNG_FORMATS: &[wgpu::TextureFormat] = &[
+    wgpu::TextureFormat::Rgba8Uint,
+    wgpu::TextureFormat::Rgba8Unorm,
+    wgpu::TextureFormat::Rgba8UnormSrgb,
+];
+/// Formats saved by [`GpuImage::save_exr`].
+const EXR_FORMATS: &[wgpu::TextureFormat] = &[
+    wgpu::TextureFormat::R32Float,
+    wgpu::TextureFormat::Rgba32Float,
+];
+
+pub type ExportResult<T> = Result<T, ExportError>;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error(
+        "images of format {format:?} cannot be saved as {container}, only {exportable:?} can."
+    )]
+    UnsupportedFormat {
+        format: wgpu::TextureFormat,
+        container: &'static str,
+        exportable: &'static [wgpu::TextureFormat],
+    },
+    #[error(transparent)]
+    Read(#[from] ImageOutputError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Png(#[from] image::ImageError),
+    #[error(transparent)]
+    Exr(#[from] exr::error::Error),
+}
+
+/// Fails with [`ExportError::UnsupportedFormat`] unless `P` is one of the `exportable` formats.
+fn check_format<P: PixelInfo>(
+    container: &'static str,
+    exportable: &'static [wgpu::TextureFormat],
+) -> ExportResult<()> {
+    let format = P::wgpu_format();
+
+    if !exportable.contains(&format) {
+        return Err(ExportError::UnsupportedFormat {
+            format,
+            container,
+            exportable,
+        });
+    }
+
+    Ok(())
+}
+
+impl<P> GpuImage<P>
+where
+    P: PixelInfo,
+{
+    /// Saves the pixels of this [`GpuImage`] as a PNG file at `path`, e.g. to inspect the output of a kernel.
+    ///
+    /// Only 8 bit unsigned formats can be saved, and their bytes are written unchanged. PNG files hold
+    /// sRGB encoded colors: [`Rgba8UintNormSrgb`](crate::primitives::pixels::Rgba8UintNormSrgb) images
+    /// already store them, so kernels writing linear colors into them are displayed correctly without
+    /// a second gamma correction. The bytes of other formats are displayed as if they were sRGB encoded.
+    ///
+    /// Fails with [`ExportError::UnsupportedFormat`] for other formats.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> ExportResult<()> {
+        check_format::<P>("PNG", PNG_FORMATS)?;
+
+        let bytes = self.read_vec_blocking()?;
+        image::save_buffer(
+            path,
+            &bytes,
+            self.width(),
+            self.height(),
+            image::ColorType::Rgba8,
+        )?;
+
+        Ok(())
+    }
+
+    /// Saves the pixels of this [`GpuImage`] as an uncompressed OpenEXR file at `path`, keeping their linear
+    /// 32 bit float values.
+    ///
+    /// [`Rgba32Float`](crate::primitives::pixels::Rgba32Float) images are saved with their R, G, B and A
+    /// channels, [`R32Float`](crate::primitives::pixels::R32Float) ones with a single Y (luminance) channel,
+    /// displayed in grayscale.
+    ///
+    /// Fails with [`ExportError::UnsupportedFormat`] for other formats, and with [`ExportError::Exr`]
+    /// if the file cannot be written.
+    pub fn save_exr(&self, path: impl AsRef<Path>) -> ExportResult<()> {
+        check_format::<P>("OpenEXR", EXR_FORMATS)?;
+
+        use exr::prelude::{Encoding, Image, SpecificChannels, Vec2, WritableImage};
+
+        let texels = self.read_pixels_blocking()?;
+        let values: &[f32] = bytemuck::cast_slice(&texels);
+        let size = (self.width() as usize, self.height() as usize);
+        let index = |pos: Vec2<usize>| pos.y() * size.0 + pos.x();
+
+        match P::wgpu_format() {
+            wgpu::TextureFormat::R32Float => {
+                let channels = SpecificChannels::build()
+                    .with_channel("Y")
+                    .with_pixel_fn(|pos| (values[index(pos)],));
+
+                Image::from_encoded_channels(size, Encoding::UNCOMPRESSED, channels)
+                    .write()
+                    .to_file(path)?
+            }
+            _ => {
+                let channels = SpecificChannels::rgba(|pos| {
+                    let texel = &values[index(pos) * 4..][..4];
+                    (texel[0], texel[1], texel[2], texel[3])
+                });
+
+                Image::from_encoded_channels(size, Encoding::UNCOMPRESSED, channels)
+                    .write()
+                    .to_file(path)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        new_set_layout,
+        primitives::pixels::{R32Float, Rgba32Float, Rgba8Uint, Rgba8UintNorm, Rgba8UintNormSrgb},
+        testing, BufOps, Framework, GpuBuffer, ImgOps, Kernel, SetBindings, Shader,
+    };
+
+    /// Gradient of `golden/gradient.png`, whose red and blue channels go across its 16 columns
+    /// and green channel down its 4 rows.
+    const GRADIENT_SHADER: &str = "
+        @group(0) @binding(0) var output: texture_storage_2d<rgba8unorm, write>;
+
+        @compute @workgroup_size(16, 4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let x = f32(id.x) / 15.0;
+            textureStore(output, vec2<i32>(id.xy), vec4<f32>(x, f32(id.y) / 3.0, 1.0 - x, 1.0));
+        }";
+
+    fn golden_gradient() -> Vec<u8> {
+        image::load_from_memory(include_bytes!("golden/gradient.png"))
+            .unwrap()
+            .to_rgba8()
+            .into_raw()
+    }
+
+    /// Saves `image` as a PNG file, returning the pixels decoded from the file.
+    fn saved_pixels<P: PixelInfo>(image: &GpuImage<P>, name: &str) -> Vec<u8> {
+        let path = std::env::temp_dir().join(format!("gpgpu-{}-{}.png", name, std::process::id()));
+        image.save_png(&path).unwrap();
+        let pixels = image::open(&path).unwrap().to_rgba8().into_raw();
+        std::fs::remove_file(&path).unwrap();
+
+        pixels
+    }
+
+    /// Writes the gradient into a new 16x4 image with a kernel.
+    fn gradient(fw: &Framework) -> GpuImage<Rgba8UintNorm> {
+        let shader = Shader::from_wgsl_string(fw, GRADIENT_SHADER.to_owned(), None).unwrap();
+        let image = GpuImage::new(fw, 16, 4);
+
+        let kernel = Kernel::builder(fw, &shader, "main")
+            .layouts(vec![new_set_layout!(0: Image<Rgba8UintNorm>)])
+            .bind_set(0, SetBindings::default().add_image(0, &image))
+            .build()
+            .unwrap();
+        kernel.run_elements(fw, 16, 4, 1);
+
+        image
+    }
+
+    #[test]
+    fn save_png_gradient() {
+        testing::with_framework(|fw| {
+            let image = gradient(fw);
+
+            assert_eq!(saved_pixels(&image, "gradient"), golden_gradient());
+        });
+    }
+
+    /// Decodes the sRGB encoded `byte` into a linear color.
+    fn srgb_to_linear(byte: u8) -> f32 {
+        let color = byte as f32 / 255.0;
+
+        if color <= 0.04045 {
+            color / 12.92
+        } else {
+            ((color + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    #[test]
+    fn save_png_srgb() {
+        testing::with_framework(|fw| {
+            // Most adapters cannot store sRGB images: the texture only has the copy and sampled usages,
+            // and the encoded colors are copied from a linear image.
+            let linear = gradient(fw);
+            let bytes = GpuBuffer::<u8>::with_capacity(fw, 16 * 4 * 4);
+            linear.copy_to_buffer(&bytes).unwrap();
+
+            let size = wgpu::Extent3d {
+                width: 16,
+                height: 4,
+                depth_or_array_layers: 1,
+            };
+            let texture = fw.device.create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::COPY_SRC
+                    | wgpu::TextureUsages::COPY_DST
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let srgb = GpuImage::<Rgba8UintNormSrgb>::from_gpu_parts(fw, texture, size);
+            srgb.copy_from_buffer(&bytes).unwrap();
+
+            // Kernels read the linear colors of the gradient, alpha being linear already...
+            let decoded = srgb.convert::<Rgba32Float>().unwrap();
+            let golden = golden_gradient();
+            for (texel, bytes) in decoded
+                .read_pixels_blocking()
+                .unwrap()
+                .iter()
+                .zip(golden.chunks(4))
+            {
+                for c in 0..3 {
+                    assert!((texel[c] - srgb_to_linear(bytes[c])).abs() < 5e-3);
+                }
+                assert_eq!(texel[3], bytes[3] as f32 / 255.0);
+            }
+
+            // ... and the file keeps their encoded bytes, without a second gamma correction.
+            assert_eq!(saved_pixels(&srgb, "srgb"), golden);
+        });
+    }
+
+    /// Saves `image` as an OpenEXR file, returning the channels decoded from the file with their samples.
+    fn saved_channels<P: PixelInfo>(image: &GpuImage<P>, name: &str) -> Vec<(String, Vec<f32>)> {
+        let path = std::env::temp_dir().join(format!("gpgpu-{}-{}.exr", name, std::process::id()));
+        image.save_exr(&path).unwrap();
+        let decoded = exr::prelude::read_first_flat_layer_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let layer = decoded.layer_data;
+        assert_eq!(
+            (layer.size.width(), layer.size.height()),
+            (image.width() as usize, image.height() as usize)
+        );
+        assert_eq!(
+            layer.encoding.compression,
+            exr::prelude::Compression::Uncompressed
+        );
+
+        layer
+            .channel_data
+            .list
+            .iter()
+            .map(|channel| {
+                let samples = channel.sample_data.values_as_f32().collect();
+                (channel.name.to_string(), samples)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn save_exr_rgba() {
+        testing::with_framework(|fw| {
+            let pixels = (0..12)
+                .map(|i| {
+                    let i = i as f32;
+                    [i * 0.25, -i, i * 1000.0, 1.0 / (i + 1.0)]
+                })
+                .collect::<Vec<_>>();
+            let image = GpuImage::<Rgba32Float>::from_pixels(fw, &pixels, 4, 3).unwrap();
+
+            let channel = |c: usize| pixels.iter().map(|texel| texel[c]).collect::<Vec<_>>();
+            // Sorted by name in the file.
+            assert_eq!(
+                saved_channels(&image, "rgba"),
+                [
+                    ("A".to_owned(), channel(3)),
+                    ("B".to_owned(), channel(2)),
+                    ("G".to_owned(), channel(1)),
+                    ("R".to_owned(), channel(0)),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn save_exr_luminance() {
+        testing::with_framework(|fw| {
+            let pixels = (0..15).map(|i| i as f32 * 0.5 - 2.0).collect::<Vec<_>>();
+            let image = GpuImage::<R32Float>::from_pixels(fw, &pixels, 5, 3).unwrap();
+
+            assert_eq!(
+                saved_channels(&image, "luminance"),
+                [("Y".to_owned(), pixels)]
+            );
+        });
+    }
+
+    #[test]
+    fn unsupported_formats() {
+        testing::with_framework(|fw| {
+            let image = GpuImage::<Rgba8Uint>::new(fw, 1, 1);
+            let path = std::env::temp_dir().join("gpgpu-unsupported.exr");
+
+            assert!(matches!(
+                image.save_exr(&path),
+                Err(ExportError::UnsupportedFormat {
+                    format: wgpu::TextureFormat::Rgba8Uint,
+                    container: "OpenEXR",
+                    exportable: EXR_FORMATS,
+                })
+            ));
+            assert!(!path.exists());
+        });
+    }
+}
@@ -0,0 +1,145 @@
+use std::{borrow::Cow, path::Path};
+
+use crate::{
+    shader::{ShaderError, ShaderResult},
+    Framework, Shader,
+};
+
+/// Formats a GLSL compilation `error` of the shader `name` with its line and column
+/// in `source`, followed by the offending line.
+fn diagnostic(name: &str, source: &str, error: &naga::front::glsl::Error) -> String {
+    let location = error.meta.location(source);
+    let line = source
+        .lines()
+        .nth(location.line_number as usize - 1)
+        .unwrap_or_default();
+
+    format!(
+        "{}:{}:{}: {}\n    {}",
+        name, location.line_number, location.line_position, error.kind, line
+    )
+}
+
+impl Shader {
+    /// Initialises a [`Shader`] from a GLSL compute shader file, compiled with the
+    /// preprocessor `defines`, as if the source started with a `#define key value` per pair.
+    ///
+    /// Returns the IO error of reading `path`, the compilation diagnostics of the shader as
+    /// [`ShaderError::Glsl`] or its validation errors as [`ShaderError::Invalid`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_glsl_file(
+        fw: &Framework,
+        path: impl AsRef<Path>,
+        defines: &[(&str, &str)],
+    ) -> ShaderResult<Self> {
+        let source = std::fs::read_to_string(&path)?;
+
+        Self::from_glsl_string(fw, &source, path.as_ref().to_str(), defines)
+    }
+
+    /// Initialises a [`Shader`] from a GLSL compute shader `source` with an optional `name`.
+    /// See [`Shader::from_glsl_file`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_glsl_string(
+        fw: &Framework,
+        source: &str,
+        name: Option<&str>,
+        defines: &[(&str, &str)],
+    ) -> ShaderResult<Self> {
+        let options = naga::front::glsl::Options {
+            stage: naga::ShaderStage::Compute,
+            defines: defines
+                .iter()
+                .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+        };
+
+        let module = naga::front::glsl::Parser::default()
+            .parse(&options, source)
+            .map_err(|errors| {
+                ShaderError::Glsl(
+                    errors
+                        .iter()
+                        .map(|error| diagnostic(name.unwrap_or("glsl"), source, error))
+                        .collect(),
+                )
+            })?;
+
+        let shader = fw.error_scope(|| {
+            fw.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: name,
+                    source: wgpu::ShaderSource::Naga(Cow::Owned(module.clone())),
+                })
+        })?;
+
+        Ok(Self {
+            module: shader,
+            naga: Some(module),
+            wgsl: None,
+            overrides: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_set_layout, testing, BufOps, GpuBuffer, Kernel, SetBindings};
+
+    const SHADER: &str = "#version 450
+layout(local_size_x = 64) in;
+
+layout(set = 0, binding = 0) buffer Data {
+    uint data[];
+};
+
+void main() {
+    uint i = gl_GlobalInvocationID.x;
+    if (i < data.length()) {
+        data[i] = data[i] * FACTOR;
+    }
+}
+";
+
+    #[test]
+    fn defines() {
+        testing::with_framework(|fw| {
+            let shader =
+                Shader::from_glsl_string(fw, SHADER, Some("scale.comp"), &[("FACTOR", "3u")])
+                    .unwrap();
+            let data = (0..100).collect::<Vec<u32>>();
+            let buffer = GpuBuffer::from_slice(fw, &data);
+
+            let kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &buffer))
+                .build()
+                .unwrap();
+            kernel.run_elements(fw, data.len() as u64, 1, 1);
+
+            let expected = data.iter().map(|x| x * 3).collect::<Vec<_>>();
+            assert_eq!(buffer.read_vec_blocking().unwrap(), expected);
+        });
+    }
+
+    #[test]
+    fn compilation_error() {
+        testing::with_framework(|fw| {
+            let source = SHADER.replace("data[i] * FACTOR;", "data[i] * undeclared;");
+
+            let err = match Shader::from_glsl_string(fw, &source, Some("scale.comp"), &[]) {
+                Err(err @ ShaderError::Glsl(_)) => err.to_string(),
+                Err(err) => panic!("unexpected error: {}", err),
+                Ok(_) => panic!("the shader compiled"),
+            };
+
+            assert!(err.contains("scale.comp:11:"), "{}", err);
+            assert!(
+                err.contains("        data[i] = data[i] * undeclared;"),
+                "{}",
+                err
+            );
+        });
+    }
+}
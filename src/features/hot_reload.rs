@@ -0,0 +1,319 @@
+//! Hot reloading of WGSL kernels during development, behind the `hot-reload` feature.
+//!
+//! # Example
+//! ```no_run
+//! # use gpgpu::BufOps;
+//! # let fw = gpgpu::Framework::default();
+//! # let data = gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 1024);
+//! let set = gpgpu::SharedSet::new(&fw, gpgpu::new_set_layout!(0: Buffer));
+//! set.rebind(&fw, gpgpu::SetBindings::default().add_buffer(0, &data));
+//!
+//! // The watcher and its kernels own a clone of the framework, so they can be stored alongside it.
+//! struct App {
+//!     fw: gpgpu::Framework,
+//!     /// Notifies `kernel` of the changes while alive.
+//!     _watcher: gpgpu::ShaderWatcher,
+//!     kernel: gpgpu::WatchedKernel,
+//! }
+//!
+//! let watcher = gpgpu::ShaderWatcher::new(&fw)
+//!     .unwrap()
+//!     .on_error(|path, err| eprintln!("{} failed to reload: {}", path.display(), err));
+//! let kernel = watcher
+//!     .watch_kernel("shader.wgsl", "main", vec![set])
+//!     .unwrap();
+//! let app = App { fw, _watcher: watcher, kernel };
+//!
+//! loop {
+//!     // Runs the last version of `shader.wgsl` that compiled.
+//!     app.kernel.get().run_elements(&app.fw, 1024, 1, 1);
+//! #   break;
+//! }
+//! ```
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock, Weak,
+    },
+};
+
+use notify::Watcher;
+
+use crate::{
+    preprocessor::{self, FileResolver},
+    shader::{ShaderError, ShaderResult},
+    Framework, Kernel, Shader, SharedSet,
+};
+
+type ErrorCallback = dyn Fn(&Path, &ShaderError) + Send + Sync;
+
+/// Watches WGSL shader files, reloading the kernels of [`ShaderWatcher::watch_kernel`]
+/// when the files or their includes change.
+///
+/// The kernels are reloaded by [`WatchedKernel::get`], on the thread calling it. A kernel failing
+/// to reload keeps its previous version, and reports the error to the callback of
+/// [`ShaderWatcher::on_error`]. The kernels are only reloaded while the watcher is alive.
+pub struct ShaderWatcher {
+    fw: Framework,
+    watch: Arc<Mutex<Watch>>,
+    /// Kernels notified of the changes by the `notify` thread.
+    watched: Arc<Mutex<Vec<Weak<Watched>>>>,
+    on_error: Arc<ErrorCallback>,
+}
+
+impl ShaderWatcher {
+    /// Creates a [`ShaderWatcher`] logging the reload errors.
+    ///
+    /// Fails with [`ShaderError::Watch`] if the files cannot be watched on this platform.
+    pub fn new(fw: &Framework) -> ShaderResult<Self> {
+        let watched: Arc<Mutex<Vec<Weak<Watched>>>> = Arc::default();
+        let notified = Arc::downgrade(&watched);
+
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let (event, notified) = match (event, notified.upgrade()) {
+                (Ok(event), Some(notified)) if !event.kind.is_access() => (event, notified),
+                _ => return,
+            };
+
+            notified
+                .lock()
+                .unwrap()
+                .retain(|watched| match watched.upgrade() {
+                    Some(watched) => {
+                        // A failed load may miss the files of its includes.
+                        let files = watched.files.lock().unwrap();
+                        if watched.failed.load(Ordering::Acquire)
+                            || event.paths.iter().any(|path| files.contains(path))
+                        {
+                            watched.changed.store(true, Ordering::Release);
+                        }
+                        true
+                    }
+                    None => false,
+                });
+        })?;
+
+        Ok(Self {
+            fw: fw.clone(),
+            watch: Arc::new(Mutex::new(Watch {
+                watcher,
+                directories: HashSet::new(),
+            })),
+            watched,
+            on_error: Arc::new(|path: &Path, err: &ShaderError| {
+                log::error!("Failed to reload {}: {}", path.display(), err)
+            }),
+        })
+    }
+
+    /// Sets the `callback` receiving the path and the error of the kernels failing to reload,
+    /// instead of logging them. Applies to the kernels watched afterwards.
+    pub fn on_error(
+        mut self,
+        callback: impl Fn(&Path, &ShaderError) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_error = Arc::new(callback);
+        self
+    }
+
+    /// Creates the [`Kernel`] of the `entry_point` of the WGSL file `path`, whose set `i` is `sets[i]`, and
+    /// reloads it when the file or its includes change, see [`Shader::from_wgsl_file_with_defines`].
+    ///
+    /// The bindings live in the [`SharedSet`]s, so they are kept by the reloaded kernels. The layouts
+    /// of the sets are checked against each version of the shader, see [`KernelBuilder::shared_set`](crate::KernelBuilder::shared_set).
+    ///
+    /// Fails if the first version of the shader fails to load, like [`Shader::from_wgsl_file_with_defines`]
+    /// and [`KernelBuilder::build`](crate::KernelBuilder::build), whose errors are reported as [`ShaderError::Invalid`].
+    pub fn watch_kernel(
+        &self,
+        path: impl AsRef<Path>,
+        entry_point: &str,
+        sets: Vec<SharedSet>,
+    ) -> ShaderResult<WatchedKernel> {
+        let watched = Arc::new(Watched {
+            files: Mutex::new(Vec::new()),
+            changed: AtomicBool::new(false),
+            failed: AtomicBool::new(false),
+        });
+
+        let source = KernelSource {
+            fw: self.fw.clone(),
+            path: path.as_ref().to_owned(),
+            entry_point: entry_point.to_owned(),
+            sets,
+        };
+        let kernel = source.load(&watched, &self.watch)?;
+
+        self.watched.lock().unwrap().push(Arc::downgrade(&watched));
+
+        Ok(WatchedKernel {
+            source,
+            kernel: RwLock::new(Arc::new(kernel)),
+            watched,
+            watch: self.watch.clone(),
+            on_error: self.on_error.clone(),
+        })
+    }
+}
+
+/// Files watched by a [`ShaderWatcher`].
+struct Watch {
+    watcher: notify::RecommendedWatcher,
+    /// Directories of the watched files, watched instead of the files, which editors may replace.
+    directories: HashSet<PathBuf>,
+}
+
+/// State of a [`WatchedKernel`] shared with the `notify` thread.
+struct Watched {
+    /// Canonical paths of the shader file and its includes.
+    files: Mutex<Vec<PathBuf>>,
+    /// Whether one of the `files` changed since the last load.
+    changed: AtomicBool,
+    /// Whether the last reload failed, in which case any change of the watched directories reloads.
+    failed: AtomicBool,
+}
+
+/// Kernel reloaded when its shader file changes, see [`ShaderWatcher::watch_kernel`].
+pub struct WatchedKernel {
+    source: KernelSource,
+    /// Current version of the kernel.
+    kernel: RwLock<Arc<Kernel>>,
+    watched: Arc<Watched>,
+    watch: Arc<Mutex<Watch>>,
+    on_error: Arc<ErrorCallback>,
+}
+
+impl WatchedKernel {
+    /// Returns the current version of the [`Kernel`], after reloading it if its files changed.
+    ///
+    /// If the reload fails, the error is reported to the callback of [`ShaderWatcher::on_error`]
+    /// and the previous version is returned. It is reloaded again on the next change of the
+    /// directories of its files.
+    pub fn get(&self) -> Arc<Kernel> {
+        if self.watched.changed.swap(false, Ordering::Acquire) {
+            let loaded = self.source.load(&self.watched, &self.watch);
+            self.watched
+                .failed
+                .store(loaded.is_err(), Ordering::Release);
+
+            match loaded {
+                Ok(kernel) => *self.kernel.write().unwrap() = Arc::new(kernel),
+                Err(err) => (self.on_error)(&self.source.path, &err),
+            }
+        }
+
+        self.kernel.read().unwrap().clone()
+    }
+
+    /// Returns the path of the shader file of the kernel.
+    pub fn path(&self) -> &Path {
+        &self.source.path
+    }
+}
+
+/// What a [`WatchedKernel`] is built from.
+struct KernelSource {
+    fw: Framework,
+    path: PathBuf,
+    entry_point: String,
+    sets: Vec<SharedSet>,
+}
+
+impl KernelSource {
+    /// Loads the shader file and builds the kernel, watching the shader file and its includes
+    /// with `watch`, as the files of `watched`.
+    fn load(&self, watched: &Watched, watch: &Mutex<Watch>) -> ShaderResult<Kernel> {
+        let source = std::fs::read_to_string(&self.path)?;
+        let name = self.path.to_string_lossy();
+
+        // Watched before compiling, so that fixing an included file reloads the kernel.
+        let processed = preprocessor::preprocess_with_includes(&source, &name, &[], &FileResolver)?;
+        let files = processed
+            .source_map
+            .files()
+            .iter()
+            .map(std::fs::canonicalize)
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let mut watch = watch.lock().unwrap();
+        for directory in files.iter().filter_map(|file| file.parent()) {
+            if !watch.directories.contains(directory) {
+                watch
+                    .watcher
+                    .watch(directory, notify::RecursiveMode::NonRecursive)?;
+                watch.directories.insert(directory.to_owned());
+            }
+        }
+        drop(watch);
+
+        *watched.files.lock().unwrap() = files;
+
+        // Compiled from the processed source, whose source map locates the parse errors.
+        let shader = Shader::from_processed(&self.fw, processed, &name)?;
+
+        let builder = self.sets.iter().enumerate().fold(
+            Kernel::builder(&self.fw, &shader, &self.entry_point),
+            |builder, (set, shared)| builder.shared_set(set as u32, shared),
+        );
+
+        Ok(builder.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{new_set_layout, testing, BufOps, GpuBuffer, SetBindings};
+
+    const SHADER: &str = "//#include \"lib.wgsl\"
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x < arrayLength(&data)) {
+        data[id.x] = value(id.x);
+    }
+}
+";
+
+    #[test]
+    fn watch_kernel_locates_included_errors() {
+        testing::with_framework(|fw| {
+            let root =
+                std::env::temp_dir().join(format!("gpgpu-hot-reload-{}", std::process::id()));
+            std::fs::create_dir_all(&root).unwrap();
+            let path = root.join("kernel.wgsl");
+            let lib = root.join("lib.wgsl");
+            std::fs::write(&path, SHADER).unwrap();
+
+            let data = GpuBuffer::<u32>::from_slice(fw, &[0; 100]);
+            let set = SharedSet::new(fw, new_set_layout!(0: Buffer));
+            set.rebind(fw, SetBindings::default().add_buffer(0, &data));
+            let watcher = ShaderWatcher::new(fw).unwrap();
+
+            std::fs::write(&lib, "fn value(x: u32) -> u32 {\n    return x +;\n}").unwrap();
+            match watcher.watch_kernel(&path, "main", vec![set.clone()]) {
+                Err(ShaderError::Parse { file, line, .. }) => {
+                    assert_eq!(Path::new(&file), lib);
+                    assert_eq!(line, 2);
+                }
+                Err(err) => panic!("unexpected error: {}", err),
+                Ok(_) => panic!("the shader should fail to parse"),
+            }
+
+            std::fs::write(&lib, "fn value(x: u32) -> u32 {\n    return x * 2u;\n}").unwrap();
+            let kernel = watcher.watch_kernel(&path, "main", vec![set]).unwrap();
+            kernel.get().run_elements(fw, 100, 1, 1);
+
+            assert_eq!(
+                data.read_vec_blocking().unwrap(),
+                (0..100).map(|x| x * 2).collect::<Vec<u32>>()
+            );
+
+            std::fs::remove_dir_all(&root).unwrap();
+        });
+    }
+}
@@ -0,0 +1,252 @@
+use encase::{
+    internal::{CreateFrom, WriteInto},
+    ShaderType, StorageBuffer, UniformBuffer,
+};
+use thiserror::Error;
+
+use crate::{primitives::buffers::BufferError, *};
+
+#[derive(Error, Debug)]
+pub enum ShaderTypeError {
+    #[error(transparent)]
+    EncaseError(#[from] encase::internal::Error),
+    #[error(transparent)]
+    BufferError(#[from] BufferError),
+}
+
+pub type ShaderTypeResult<T> = Result<T, ShaderTypeError>;
+
+/// Serializes `value` with the storage buffer (std430) layout.
+fn storage_bytes<S>(value: &S) -> Vec<u8>
+where
+    S: ShaderType + WriteInto,
+{
+    let mut buffer = StorageBuffer::new(Vec::new());
+    buffer
+        .write(value)
+        .expect("writing into a Vec cannot run out of space");
+
+    buffer.into_inner()
+}
+
+/// Serializes `value` with the uniform buffer (std140) layout.
+fn uniform_bytes<S>(value: &S) -> Vec<u8>
+where
+    S: ShaderType + WriteInto,
+{
+    let mut buffer = UniformBuffer::new(Vec::new());
+    buffer
+        .write(value)
+        .expect("writing into a Vec cannot run out of space");
+
+    buffer.into_inner()
+}
+
+impl GpuBuffer<u8> {
+    /// Constructs a new [`GpuBuffer`] holding `value`, laid out like WGSL storage buffers expect it,
+    /// e.g. a `vec3<f32>` field is 16 bytes aligned without any manual padding.
+    ///
+    /// `S` derives [`encase::ShaderType`]. Its vector fields must be vector types like the ones
+    /// of `glam` or `mint` (through the features of `encase`): `[f32; 3]` is an array.
+    pub fn from_shader_type<S>(fw: &crate::Framework, value: &S) -> Self
+    where
+        S: ShaderType + WriteInto,
+    {
+        GpuBuffer::from_slice(fw, &storage_bytes(value))
+    }
+
+    /// Writes `value` into this [`GpuBuffer`] with the storage buffer layout. The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if `value` does not fit in the [`GpuBuffer`].
+    pub fn write_shader_type<S>(&self, value: &S) -> ShaderTypeResult<()>
+    where
+        S: ShaderType + WriteInto,
+    {
+        self.write(&storage_bytes(value))?;
+
+        Ok(())
+    }
+
+    /// Reads the [`GpuBuffer`] back into a value of type `S`, laid out with the storage buffer layout.
+    ///
+    /// Runtime-sized arrays take all the remaining elements of the [`GpuBuffer`].
+    pub async fn read_shader_type<S>(&self) -> ShaderTypeResult<S>
+    where
+        S: ShaderType + CreateFrom,
+    {
+        let bytes = self.read_vec().await?;
+
+        Ok(StorageBuffer::new(bytes).create()?)
+    }
+
+    /// Blocking version of `GpuBuffer::read_shader_type()`.
+    pub fn read_shader_type_blocking<S>(&self) -> ShaderTypeResult<S>
+    where
+        S: ShaderType + CreateFrom,
+    {
+        futures::executor::block_on(self.read_shader_type())
+    }
+}
+
+impl GpuUniformBuffer<u8> {
+    /// Constructs a new [`GpuUniformBuffer`] holding `value`, laid out like WGSL uniform buffers expect it,
+    /// e.g. arrays elements are 16 bytes aligned. See [`GpuBuffer::from_shader_type`].
+    pub fn from_shader_type<S>(fw: &crate::Framework, value: &S) -> Self
+    where
+        S: ShaderType + WriteInto,
+    {
+        GpuUniformBuffer::from_slice(fw, &uniform_bytes(value))
+    }
+
+    /// Writes `value` into this [`GpuUniformBuffer`] with the uniform buffer layout. The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if `value` does not fit in the [`GpuUniformBuffer`].
+    pub fn write_shader_type<S>(&self, value: &S) -> ShaderTypeResult<()>
+    where
+        S: ShaderType + WriteInto,
+    {
+        self.write(&uniform_bytes(value))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+// The `ShaderType` derive of `encase` generates unused functions checking the fields.
+#[allow(dead_code)]
+mod tests {
+    use mint::Vector3;
+
+    use super::*;
+    use crate::testing;
+
+    #[derive(ShaderType, Debug, PartialEq)]
+    struct Params {
+        weights: [f32; 3],
+        count: u32,
+        points: [Vector3<f32>; 2],
+    }
+
+    #[derive(ShaderType)]
+    struct UniformParams {
+        origin: Vector3<f32>,
+        scale: f32,
+        points: [Vector3<f32>; 2],
+    }
+
+    fn params() -> Params {
+        Params {
+            weights: [1.0, 2.0, 3.0],
+            count: 4,
+            points: [[5.0, 6.0, 7.0].into(), [8.0, 9.0, 10.0].into()],
+        }
+    }
+
+    /// Runs the `main` entry point of `source` with the buffer `params` at the binding 0, and
+    /// returns the 10 floats it writes into the binding 1.
+    fn shader_fields(
+        fw: &Framework,
+        source: &str,
+        layout: SetLayout,
+        params: SetBindings,
+    ) -> Vec<f32> {
+        let shader = Shader::from_wgsl_string(fw, source.to_owned(), None).unwrap();
+        let kernel = Kernel::new(fw, &shader, "main", vec![layout]);
+        let fields = GpuBuffer::<f32>::with_capacity(fw, 10);
+
+        kernel.run(fw, vec![params.add_buffer(1, &fields)], 1, 1, 1);
+
+        fields.read_vec_blocking().unwrap()
+    }
+
+    #[test]
+    fn storage_round_trip() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_shader_type(fw, &params());
+
+            assert_eq!(
+                buffer.read_shader_type_blocking::<Params>().unwrap(),
+                params()
+            );
+        });
+    }
+
+    #[test]
+    fn storage_layout() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_shader_type(fw, &params());
+
+            let fields = shader_fields(
+                fw,
+                "struct Params {
+                    weights: array<f32, 3>,
+                    count: u32,
+                    points: array<vec3<f32>, 2>,
+                }
+
+                @group(0) @binding(0) var<storage, read> params: Params;
+                @group(0) @binding(1) var<storage, read_write> fields: array<f32, 10>;
+
+                @compute @workgroup_size(1)
+                fn main() {
+                    fields[0] = params.weights[0];
+                    fields[1] = params.weights[1];
+                    fields[2] = params.weights[2];
+                    fields[3] = f32(params.count);
+                    fields[4] = params.points[0].x;
+                    fields[5] = params.points[0].y;
+                    fields[6] = params.points[0].z;
+                    fields[7] = params.points[1].x;
+                    fields[8] = params.points[1].y;
+                    fields[9] = params.points[1].z;
+                }",
+                new_set_layout!(0: Buffer(GpuBufferUsage::ReadOnly), 1: Buffer),
+                SetBindings::default().add_buffer(0, &buffer),
+            );
+
+            assert_eq!(fields, (1..=10).map(|i| i as f32).collect::<Vec<_>>());
+        });
+    }
+
+    #[test]
+    fn uniform_layout() {
+        testing::with_framework(|fw| {
+            let params = UniformParams {
+                origin: [1.0, 2.0, 3.0].into(),
+                scale: 4.0,
+                points: [[5.0, 6.0, 7.0].into(), [8.0, 9.0, 10.0].into()],
+            };
+            let buffer = GpuUniformBuffer::from_shader_type(fw, &params);
+
+            let fields = shader_fields(
+                fw,
+                "struct Params {
+                    origin: vec3<f32>,
+                    scale: f32,
+                    points: array<vec3<f32>, 2>,
+                }
+
+                @group(0) @binding(0) var<uniform> params: Params;
+                @group(0) @binding(1) var<storage, read_write> fields: array<f32, 10>;
+
+                @compute @workgroup_size(1)
+                fn main() {
+                    fields[0] = params.origin.x;
+                    fields[1] = params.origin.y;
+                    fields[2] = params.origin.z;
+                    fields[3] = params.scale;
+                    fields[4] = params.points[0].x;
+                    fields[5] = params.points[0].y;
+                    fields[6] = params.points[0].z;
+                    fields[7] = params.points[1].x;
+                    fields[8] = params.points[1].y;
+                    fields[9] = params.points[1].z;
+                }",
+                new_set_layout!(0: UniformBuffer, 1: Buffer),
+                SetBindings::default().add_uniform_buffer(0, &buffer),
+            );
+
+            assert_eq!(fields, (1..=10).map(|i| i as f32).collect::<Vec<_>>());
+        });
+    }
+}
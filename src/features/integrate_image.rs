@@ -1,12 +1,12 @@
 use crate::{
     primitives::{
-        images::{ImageInputError, ImageOutputError},
+        images::{ImageInputError, ImageInputResult, ImageOutputError, ImageOutputResult},
         pixels, ImgOps, PixelInfo,
     },
     GpuConstImage, GpuImage,
 };
 
-use image::ImageBuffer;
+use image::{DynamicImage, ImageBuffer};
 
 /// Contains information about the `image::ImageBuffer` -> `gpgpu::GpuImage` or `gpgpu::GpuConstImage` images conversion.
 pub trait ImageToGpgpu {
@@ -54,89 +54,189 @@ image_to_gpgpu_impl! {
     // ::image::Luma<u8>, pixels::Luma8, pixels::Luma8Norm
 }
 
+/// Conversion between the pixels of a [`image::DynamicImage`] and the ones of a `gpgpu` image.
+///
+/// Float pixels are converted on the CPU through 16 bit channels, which `image` images can hold:
+/// [0, 65535] maps to [0.0, 1.0] and floats out of that range are clamped.
+/// Signed pixels have no [`image::DynamicImage`] counterpart, so they do not implement it.
+pub trait DynamicImagePixel: PixelInfo {
+    /// Converts `img` into tightly packed pixels.
+    fn from_dynamic_image(img: &DynamicImage) -> Vec<u8>;
+
+    /// Converts tightly packed pixels of a `width` x `height` image into a [`image::DynamicImage`].
+    fn to_dynamic_image(width: u32, height: u32, bytes: Vec<u8>) -> DynamicImage;
+}
+
+macro_rules! dynamic_image_rgba8_impl {
+    ($($pixel:ty),+) => {
+        $(
+            impl DynamicImagePixel for $pixel {
+                fn from_dynamic_image(img: &DynamicImage) -> Vec<u8> {
+                    img.to_rgba8().into_raw()
+                }
+
+                fn to_dynamic_image(width: u32, height: u32, bytes: Vec<u8>) -> DynamicImage {
+                    DynamicImage::ImageRgba8(
+                        ImageBuffer::from_raw(width, height, bytes).expect("Cannot fail here."),
+                    )
+                }
+            }
+        )+
+    }
+}
+
+dynamic_image_rgba8_impl! {
+    pixels::Rgba8Uint, pixels::Rgba8UintNorm, pixels::Rgba8UintNormSrgb
+}
+
+fn u16_to_f32_bytes(channels: &[u16]) -> Vec<u8> {
+    channels
+        .iter()
+        .flat_map(|&c| (c as f32 / u16::MAX as f32).to_ne_bytes())
+        .collect()
+}
+
+fn f32_bytes_to_u16(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(4)
+        .map(|c| {
+            let f = f32::from_ne_bytes([c[0], c[1], c[2], c[3]]);
+            (f.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+        })
+        .collect()
+}
+
+impl DynamicImagePixel for pixels::Rgba32Float {
+    fn from_dynamic_image(img: &DynamicImage) -> Vec<u8> {
+        u16_to_f32_bytes(&img.to_rgba16())
+    }
+
+    fn to_dynamic_image(width: u32, height: u32, bytes: Vec<u8>) -> DynamicImage {
+        DynamicImage::ImageRgba16(
+            ImageBuffer::from_raw(width, height, f32_bytes_to_u16(&bytes))
+                .expect("Cannot fail here."),
+        )
+    }
+}
+
+impl DynamicImagePixel for pixels::R32Float {
+    fn from_dynamic_image(img: &DynamicImage) -> Vec<u8> {
+        u16_to_f32_bytes(&img.to_luma16())
+    }
+
+    fn to_dynamic_image(width: u32, height: u32, bytes: Vec<u8>) -> DynamicImage {
+        DynamicImage::ImageLuma16(
+            ImageBuffer::from_raw(width, height, f32_bytes_to_u16(&bytes))
+                .expect("Cannot fail here."),
+        )
+    }
+}
+
+impl<P> GpuImage<P>
+where
+    P: DynamicImagePixel,
+{
+    /// Constructs a new [`GpuImage`] from a [`image::DynamicImage`] of any color type,
+    /// converted on the CPU to the pixels of `P`, e.g. an image opened with [`image::open`].
+    ///
+    /// Fails like [`GpuImage::try_new`].
+    pub fn from_dynamic_image(fw: &crate::Framework, img: &DynamicImage) -> ImageInputResult<Self> {
+        let (width, height) = image::GenericImageView::dimensions(img);
+
+        GpuImage::try_from_bytes(fw, &P::from_dynamic_image(img), width, height)
+    }
+
+    /// Pulls all the pixels from the [`GpuImage`] into a [`image::DynamicImage`],
+    /// converted on the CPU if needed. See [`DynamicImagePixel`].
+    pub async fn to_dynamic_image(&self) -> ImageOutputResult<DynamicImage> {
+        let bytes = self.read_vec().await?;
+        let (width, height) = self.dimensions();
+
+        Ok(P::to_dynamic_image(width, height, bytes))
+    }
+
+    /// Blocking version of `GpuImage::to_dynamic_image()`.
+    pub fn to_dynamic_image_blocking(&self) -> ImageOutputResult<DynamicImage> {
+        futures::executor::block_on(self.to_dynamic_image())
+    }
+}
+
+impl<P> GpuConstImage<P>
+where
+    P: DynamicImagePixel,
+{
+    /// Constructs a new [`GpuConstImage`] from a [`image::DynamicImage`] of any color type,
+    /// converted on the CPU to the pixels of `P`. See [`GpuImage::from_dynamic_image`].
+    pub fn from_dynamic_image(fw: &crate::Framework, img: &DynamicImage) -> ImageInputResult<Self> {
+        let (width, height) = image::GenericImageView::dimensions(img);
+
+        GpuConstImage::try_from_bytes(fw, &P::from_dynamic_image(img), width, height)
+    }
+}
+
 type PixelContainer<P> = Vec<<<P as GpgpuToImage>::ImgPixel as image::Pixel>::Subpixel>;
 
-impl<'fw, Pixel> GpuImage<'fw, Pixel>
+impl<Pixel> GpuImage<Pixel>
 where
     Pixel: image::Pixel + ImageToGpgpu + 'static,
     Pixel::Subpixel: bytemuck::Pod,
 {
     /// Constructs a new [`GpuImage`] from a [`image::ImageBuffer`].
     pub fn from_image_buffer<Container>(
-        fw: &'fw crate::Framework,
+        fw: &crate::Framework,
         img: &ImageBuffer<Pixel, Container>,
-    ) -> GpuImage<'fw, Pixel::GpgpuPixel>
+    ) -> GpuImage<Pixel::GpgpuPixel>
     where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 
     /// Constructs a new normalised [`GpuImage`] from a [`image::ImageBuffer`].
     pub fn from_image_buffer_normalised<Container>(
-        fw: &'fw crate::Framework,
+        fw: &crate::Framework,
         img: &ImageBuffer<Pixel, Container>,
-    ) -> GpuImage<'fw, Pixel::NormGpgpuPixel>
+    ) -> GpuImage<Pixel::NormGpgpuPixel>
     where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 }
 
-impl<'fw, Pixel> GpuConstImage<'fw, Pixel>
+impl<Pixel> GpuConstImage<Pixel>
 where
     Pixel: image::Pixel + ImageToGpgpu + 'static,
     Pixel::Subpixel: bytemuck::Pod,
 {
     /// Constructs a new [`GpuConstImage`] from a [`image::ImageBuffer`].
     pub fn from_image_buffer<Container>(
-        fw: &'fw crate::Framework,
+        fw: &crate::Framework,
         img: &ImageBuffer<Pixel, Container>,
-    ) -> GpuConstImage<'fw, Pixel::GpgpuPixel>
+    ) -> GpuConstImage<Pixel::GpgpuPixel>
     where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuConstImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuConstImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 
     /// Constructs a new normalised [`GpuConstImage`] from a [`image::ImageBuffer`].
     pub fn from_image_buffer_normalised<Container>(
-        fw: &'fw crate::Framework,
+        fw: &crate::Framework,
         img: &ImageBuffer<Pixel, Container>,
-    ) -> GpuConstImage<'fw, Pixel::NormGpgpuPixel>
+    ) -> GpuConstImage<Pixel::NormGpgpuPixel>
     where
         Container: std::ops::Deref<Target = [Pixel::Subpixel]>,
     {
         let (width, height) = img.dimensions();
-        GpuConstImage::from_bytes(
-            fw,
-            bytemuck::cast_slice(img),
-            width * Pixel::GpgpuPixel::byte_size() as u32,
-            height,
-        )
+        GpuConstImage::from_bytes(fw, bytemuck::cast_slice(img), width, height)
     }
 }
 
-impl<'fw, P> GpuImage<'fw, P>
+impl<P> GpuImage<P>
 where
     P: PixelInfo + GpgpuToImage,
     <<P as GpgpuToImage>::ImgPixel as image::Pixel>::Subpixel: bytemuck::Pod,
@@ -205,7 +305,7 @@ where
     }
 }
 
-impl<'fw, P> GpuConstImage<'fw, P>
+impl<P> GpuConstImage<P>
 where
     P: PixelInfo + GpgpuToImage,
     <<P as GpgpuToImage>::ImgPixel as image::Pixel>::Subpixel: bytemuck::Pod,
@@ -20,22 +20,19 @@ pub enum ArrayError {
 
 pub type ArrayResult<T> = Result<T, ArrayError>;
 
-pub struct GpuArray<'fw, T, D>(GpuBuffer<'fw, T>, D);
+pub struct GpuArray<T, D>(GpuBuffer<T>, D);
 
-impl<'fw, T, D> GpuArray<'fw, T, D>
+impl<T, D> GpuArray<T, D>
 where
     T: bytemuck::Pod,
     D: ndarray::Dimension,
 {
-    pub fn from_array(
-        fw: &'fw crate::Framework,
-        array: ndarray::ArrayView<T, D>,
-    ) -> ArrayResult<Self> {
-        let slice: Result<&[T], _> = array
-            .as_slice_memory_order()
-            .ok_or(NdarrayError::ArrayNotContiguous);
-
-        let buf = GpuBuffer::from_slice(fw, slice?);
+    /// Constructs a new [`GpuArray`] from an array in any memory layout.
+    ///
+    /// The elements are uploaded in row-major (standard) order, so non-contiguous
+    /// or column-major arrays are copied into a contiguous one first.
+    pub fn from_array(fw: &crate::Framework, array: ndarray::ArrayView<T, D>) -> ArrayResult<Self> {
+        let buf = GpuBuffer::from_array(fw, &array);
 
         Ok(Self(buf, array.raw_dim()))
     }
@@ -51,19 +48,66 @@ where
         futures::executor::block_on(self.read())
     }
 
+    /// Writes an array in any memory layout into this [`GpuArray`], in row-major (standard) order.
     pub fn write(&self, array: ndarray::ArrayView<T, D>) -> ArrayResult<u64> {
-        let slice: Result<&[T], _> = array
-            .as_slice_memory_order()
-            .ok_or(NdarrayError::ArrayNotContiguous);
+        let array = array.as_standard_layout();
+        let slice = array
+            .as_slice()
+            .expect("standard layout arrays are contiguous");
 
-        Ok(self.0.write(slice?)?)
+        Ok(self.0.write(slice)?)
     }
 
-    pub fn to_gpu_buffer(self) -> GpuBuffer<'fw, T> {
+    pub fn to_gpu_buffer(self) -> GpuBuffer<T> {
         self.0
     }
 }
 
+impl<T> GpuBuffer<T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new [`GpuBuffer`] from the elements of `array` in row-major (standard) order.
+    ///
+    /// Non-contiguous or column-major arrays, e.g. transposed ones, are copied into a contiguous one first.
+    pub fn from_array<S, D>(fw: &crate::Framework, array: &ndarray::ArrayBase<S, D>) -> Self
+    where
+        S: ndarray::Data<Elem = T>,
+        D: ndarray::Dimension,
+    {
+        let array = array.as_standard_layout();
+
+        GpuBuffer::from_slice(
+            fw,
+            array
+                .as_slice()
+                .expect("standard layout arrays are contiguous"),
+        )
+    }
+
+    /// Pulls all the elements from the [`GpuBuffer`] into an array of the given `shape`,
+    /// e.g. `(rows, cols)` for an [`ndarray::Array2`] or `(depth, rows, cols).f()` for a column-major [`ndarray::Array3`].
+    ///
+    /// Fails with [`NdarrayError::InvalidShape`] if the number of elements of `shape`
+    /// does not match the [`GpuBuffer`] capacity.
+    pub async fn read_to_array<Sh>(&self, shape: Sh) -> ArrayResult<ndarray::Array<T, Sh::Dim>>
+    where
+        Sh: ndarray::ShapeBuilder,
+    {
+        let v = self.read_vec().await?;
+
+        Ok(ndarray::Array::from_shape_vec(shape, v).map_err(NdarrayError::InvalidShape)?)
+    }
+
+    /// Blocking version of `GpuBuffer::read_to_array()`.
+    pub fn read_to_array_blocking<Sh>(&self, shape: Sh) -> ArrayResult<ndarray::Array<T, Sh::Dim>>
+    where
+        Sh: ndarray::ShapeBuilder,
+    {
+        futures::executor::block_on(self.read_to_array(shape))
+    }
+}
+
 impl SetLayout {
     pub fn add_array(&mut self, bind_id: u32, usage: GpuBufferUsage) {
         self.add_buffer(bind_id, usage)
@@ -79,3 +123,52 @@ impl<'res> SetBindings<'res> {
         self.add_buffer(bind_id, &arr.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ndarray::ShapeBuilder;
+
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn transposed_array() {
+        testing::with_framework(|fw| {
+            let array =
+                ndarray::Array::from_shape_vec((3, 4), (0..12).collect::<Vec<u32>>()).unwrap();
+            let transposed = array.t();
+
+            let buffer = GpuBuffer::from_array(fw, &transposed);
+
+            assert_eq!(buffer.read_to_array_blocking((4, 3)).unwrap(), transposed);
+        });
+    }
+
+    #[test]
+    fn column_major_array() {
+        testing::with_framework(|fw| {
+            let array =
+                ndarray::Array::from_shape_vec((2, 3, 4).f(), (0..24).collect::<Vec<u32>>())
+                    .unwrap();
+
+            let gpu_array = GpuArray::from_array(fw, array.view()).unwrap();
+            assert_eq!(gpu_array.read_blocking().unwrap(), array);
+
+            // Uploaded in row-major order.
+            let buffer = gpu_array.to_gpu_buffer();
+            assert_eq!(buffer.read_to_array_blocking((2, 3, 4)).unwrap(), array);
+        });
+    }
+
+    #[test]
+    fn read_to_array_shape_mismatch() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &[0u32; 12]);
+
+            assert!(matches!(
+                buffer.read_to_array_blocking((5, 3)),
+                Err(ArrayError::NdarrayError(NdarrayError::InvalidShape(_)))
+            ));
+        });
+    }
+}
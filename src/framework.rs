@@ -1,25 +1,362 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    future::Future,
+    path::Path,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use crate::{
+    memory::{MemoryCounters, ResourceKind},
+    primitives::buffers::BufferError,
+    staging::StagingPool,
+    GpuRecorder, Sequence,
+};
+
+pub type FrameworkResult<T> = Result<T, FrameworkError>;
+
+#[derive(Error, Debug)]
+pub enum FrameworkError {
+    #[error("no suitable adapter was found.")]
+    NoAdapter,
+    #[error("adapter does not support the required features: {0:?}.")]
+    UnsupportedFeatures(wgpu::Features),
+    #[error(transparent)]
+    DeviceRequest(#[from] wgpu::RequestDeviceError),
+}
+
+pub type GpuResult<T> = Result<T, GpuError>;
+
+/// Error reported by `wgpu` for a GPU operation. See [`Framework::error_scope`].
+#[derive(Error, Debug)]
+pub enum GpuError {
+    #[error("validation error: {0}")]
+    Validation(String),
+    #[error("out of memory: {0}")]
+    OutOfMemory(String),
+    #[error("the device was lost.")]
+    DeviceLost,
+    #[error("binding {binding} of set {set} cannot be a {access:?} storage image of format {format:?}: {reason}.")]
+    UnsupportedStorageAccess {
+        set: u32,
+        binding: u32,
+        access: wgpu::StorageTextureAccess,
+        format: wgpu::TextureFormat,
+        reason: &'static str,
+    },
+    #[error("binding {binding} of set {set} does not match the kernel layout: {reason}.")]
+    BindingMismatch {
+        set: u32,
+        binding: u32,
+        reason: String,
+    },
+    #[error("push constants of {size} bytes are not supported: {reason}.")]
+    UnsupportedPushConstants { size: u32, reason: String },
+    #[error("push constants of {size} bytes do not match the {expected} bytes of the kernel.")]
+    PushConstantsMismatch { size: u32, expected: u32 },
+    #[error("cannot set the override constant `{name}`: {reason}.")]
+    InvalidConstant { name: String, reason: &'static str },
+    #[error("set {set} has no entry named `{name}`.")]
+    UnknownBinding { set: u32, name: String },
+    #[error("set {set} leaves entries unbound: {}.", .unbound.join(", "))]
+    UnboundEntries { set: u32, unbound: Vec<String> },
+    #[error("{count} dynamic offsets were given, but the layouts of the kernel declare {expected} dynamic entries.")]
+    DynamicOffsetsMismatch { count: usize, expected: usize },
+    #[error("binding {binding} of set {set} does not match the shader: {reason}.")]
+    LayoutMismatch {
+        set: u32,
+        binding: u32,
+        reason: String,
+    },
+    #[error("the dynamic offset {offset} of binding {binding} of set {set} is not a multiple of {alignment} bytes.")]
+    MisalignedDynamicOffset {
+        set: u32,
+        binding: u32,
+        offset: u32,
+        alignment: u32,
+    },
+    #[error("indirect arguments {index} are out of the {len} arguments of the buffer.")]
+    IndirectArgsOutOfRange { index: u64, len: u64 },
+    #[error("the buffer of indirect arguments lacks the `INDIRECT` usage, create it with `GpuBuffer::from_indirect_args`.")]
+    MissingIndirectUsage,
+    #[error(
+        "the workgroup size of the kernel is unknown, set it with `KernelBuilder::workgroup_size`."
+    )]
+    UnknownWorkgroupSize,
+    #[error(
+        "workgroup size {size:?} does not match the `@workgroup_size` {expected:?} of the shader."
+    )]
+    WorkgroupSizeMismatch { size: [u32; 3], expected: [u32; 3] },
+    #[error("{elements} elements exceed the {max} elements the split dispatches of the kernel can cover.")]
+    SplitCapacityExceeded { elements: u64, max: u64 },
+    #[error("binding {binding} of set {set} is dynamic, but kernels with split dispatches only give offsets to their split set.")]
+    SplitDynamicEntry { set: u32, binding: u32 },
+    #[error("{count} workgroups in the {dimension} dimension exceed the {limit} allowed by the device limit `max_compute_workgroups_per_dimension`.")]
+    TooManyWorkgroups {
+        dimension: &'static str,
+        count: u64,
+        limit: u32,
+    },
+    #[error("the shader has no compute entry point named `{name}`, the available ones are {available:?}.")]
+    UnknownEntryPoint {
+        name: String,
+        available: Vec<String>,
+    },
+    #[error("the device does not support the {0:?} feature required by this operation.")]
+    MissingFeature(wgpu::Features),
+    #[error(transparent)]
+    Buffer(#[from] BufferError),
+}
+
+impl GpuError {
+    /// Returns `true` if the error reports that the device was lost.
+    ///
+    /// `wgpu` has no dedicated device lost error at the moment, so this
+    /// is inferred from the error message.
+    pub fn is_device_lost(&self) -> bool {
+        match self {
+            Self::Validation(description) | Self::OutOfMemory(description) => {
+                description.to_lowercase().contains("device is lost")
+            }
+            Self::DeviceLost | Self::Buffer(BufferError::DeviceLost) => true,
+            Self::UnsupportedStorageAccess { .. }
+            | Self::BindingMismatch { .. }
+            | Self::UnsupportedPushConstants { .. }
+            | Self::PushConstantsMismatch { .. }
+            | Self::InvalidConstant { .. }
+            | Self::UnknownBinding { .. }
+            | Self::UnboundEntries { .. }
+            | Self::DynamicOffsetsMismatch { .. }
+            | Self::LayoutMismatch { .. }
+            | Self::MisalignedDynamicOffset { .. }
+            | Self::IndirectArgsOutOfRange { .. }
+            | Self::MissingIndirectUsage
+            | Self::UnknownWorkgroupSize
+            | Self::SplitCapacityExceeded { .. }
+            | Self::SplitDynamicEntry { .. }
+            | Self::WorkgroupSizeMismatch { .. }
+            | Self::TooManyWorkgroups { .. }
+            | Self::UnknownEntryPoint { .. }
+            | Self::MissingFeature(_)
+            | Self::Buffer(_) => false,
+        }
+    }
+}
+
+impl From<wgpu::Error> for GpuError {
+    fn from(error: wgpu::Error) -> Self {
+        match error {
+            wgpu::Error::Validation { description, .. } => Self::Validation(description),
+            error @ wgpu::Error::OutOfMemory { .. } => Self::OutOfMemory(error.to_string()),
+        }
+    }
+}
+
+/// Capabilities of a [`wgpu::TextureFormat`] on the device of a [`Framework`].
+/// See [`Framework::image_format_capabilities`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormatCaps {
+    /// Usages allowed for textures of the format.
+    pub usages: wgpu::TextureUsages,
+    /// The format can be written by kernels, e.g. by [`GpuImage`](crate::GpuImage)s.
+    pub storage: bool,
+    /// The format can also be read by kernels, with [`StorageAccess::ReadOnly`](crate::StorageAccess::ReadOnly)
+    /// or [`StorageAccess::ReadWrite`](crate::StorageAccess::ReadWrite).
+    pub storage_read_write: bool,
+    /// The format can be sampled, e.g. by [`GpuConstImage`](crate::GpuConstImage)s.
+    pub sampled: bool,
+    /// The format can be sampled by filtering [`GpuSampler`](crate::GpuSampler)s.
+    pub filterable: bool,
+    /// The format can be a render attachment.
+    pub renderable: bool,
+    /// The format can be copied from and to buffers and other images.
+    pub copyable: bool,
+}
 
 /// Entry point of `gpgpu`. A [`Framework`] must be created
 /// first as all GPU primitives needs it to be created.
+///
+/// Cloning a [`Framework`] is cheap: all the clones share the same device and queue.
+/// GPU primitives keep a clone of the [`Framework`] that created them, so they are
+/// not bound to its lifetime.
+#[derive(Clone)]
 pub struct Framework {
     pub(crate) device: Arc<wgpu::Device>,
-    pub(crate) queue: wgpu::Queue,
-    pub(crate) adapter: wgpu::Adapter,
+    pub(crate) queue: Arc<wgpu::Queue>,
+    pub(crate) adapter: Arc<wgpu::Adapter>,
+    pub(crate) adapter_info: Arc<wgpu::AdapterInfo>,
+    tracing: bool,
+    polling: Arc<Polling>,
+    pub(crate) memory: Arc<MemoryCounters>,
+    errors: Arc<DeviceErrors>,
+    staging_chunk_size: u64,
+    pub(crate) staging: Arc<StagingPool>,
+    submissions: Arc<Submissions>,
 }
 
-impl Default for Framework {
-    fn default() -> Self {
-        let instance = wgpu::Instance::default();
+/// Command submission of a [`Framework`], e.g. returned by [`Kernel::run`](crate::Kernel::run)
+/// and [`GpuRecorder::submit`](crate::GpuRecorder::submit).
+///
+/// Its completion is awaited by [`Framework::wait_for`] and queried by [`Framework::is_done`].
+#[derive(Clone, Debug)]
+pub struct Submission {
+    index: wgpu::SubmissionIndex,
+    /// Order of the submission among the ones of [`Framework::submit`], from 1.
+    serial: u64,
+}
+
+impl Submission {
+    /// Returns the [`wgpu::SubmissionIndex`] of the submission.
+    pub fn index(&self) -> &wgpu::SubmissionIndex {
+        &self.index
+    }
+}
 
-        futures::executor::block_on(async {
-            let adapter = instance
-                .request_adapter(&wgpu::RequestAdapterOptions::default())
-                .await
-                .expect("Failed at adapter creation.");
+/// Future resolved when the GPU has completed a [`Submission`], returned by [`Framework::wait_async`]
+/// and [`GpuRecorder::submit_async`](crate::GpuRecorder::submit_async).
+///
+/// Like the other asynchronous operations, it is resolved when the device is polled by the polling
+/// thread, [`Framework::poll`] or [`Framework::blocking_poll`]. It can be dropped at any time: its
+/// callback is still released by `wgpu` once the submission is completed.
+pub struct WorkDone {
+    state: Arc<Mutex<WorkDoneState>>,
+}
 
-            Self::new(adapter, Duration::from_millis(10)).await
-        })
+#[derive(Default)]
+struct WorkDoneState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl WorkDone {
+    fn ready() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(WorkDoneState {
+                done: true,
+                waker: None,
+            })),
+        }
+    }
+}
+
+impl Future for WorkDone {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.done {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for WorkDone {
+    fn drop(&mut self) {
+        // The callback may outlive the future, it must not keep waking a finished task.
+        self.state.lock().unwrap().waker = None;
+    }
+}
+
+/// Completion tracking of the [`Submission`]s of a [`Framework`] and its clones.
+#[derive(Default)]
+struct Submissions {
+    /// Serial of the last submission.
+    submitted: AtomicU64,
+    /// Serial of the last submission known to be completed.
+    completed: AtomicU64,
+    /// Serial of the last submission awaited by a `on_submitted_work_done` callback.
+    awaited: AtomicU64,
+}
+
+/// Default of [`FrameworkBuilder::staging_chunk_size`].
+const STAGING_CHUNK_SIZE: u64 = 64 << 20;
+/// Default of [`FrameworkBuilder::staging_pool_limit`].
+const STAGING_POOL_LIMIT: u64 = 64 << 20;
+
+type ErrorCallback = Box<dyn Fn(&GpuError) + Send + Sync>;
+
+/// Uncaptured error handling of a [`Framework`] and its clones.
+#[derive(Default)]
+struct DeviceErrors {
+    lost: AtomicBool,
+    on_device_lost: Mutex<Option<ErrorCallback>>,
+    on_uncaptured_error: Mutex<Option<ErrorCallback>>,
+}
+
+impl DeviceErrors {
+    fn handle(&self, error: wgpu::Error) {
+        let error = GpuError::from(error);
+        let lost = error.is_device_lost();
+
+        if lost {
+            self.set_lost(&error);
+        }
+
+        match &*self.on_uncaptured_error.lock().unwrap() {
+            Some(callback) => callback(&error),
+            // Once lost, every call fails: the device lost callback already reported it.
+            None if lost => (),
+            // Same behaviour as the default handler of `wgpu`.
+            None => panic!("wgpu error: {}", error),
+        }
+    }
+
+    /// Sets the lost flag, calling the device lost callback the first time.
+    fn set_lost(&self, error: &GpuError) {
+        if !self.lost.swap(true, Ordering::SeqCst) {
+            log::error!("Device lost: {}", error);
+
+            if let Some(callback) = &*self.on_device_lost.lock().unwrap() {
+                callback(error);
+            }
+        }
+    }
+}
+
+/// Background polling thread of a [`Framework`] and its clones.
+#[derive(Default)]
+struct Polling(Mutex<Option<PollingThread>>);
+
+struct PollingThread {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl Polling {
+    fn stop(&self) {
+        let thread = self.0.lock().unwrap().take();
+
+        if let Some(PollingThread { stop, handle }) = thread {
+            stop.store(true, Ordering::Relaxed);
+            handle.join().expect("Polling thread panicked.");
+        }
+    }
+}
+
+impl Drop for Polling {
+    fn drop(&mut self) {
+        // The polling thread must end before the device is dropped.
+        self.stop();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for Framework {
+    fn default() -> Self {
+        FrameworkBuilder::default()
+            .build()
+            .expect("Failed at framework creation.")
     }
 }
 
@@ -28,53 +365,1028 @@ impl Framework {
     ///
     /// Use this method when there are multiple GPUs in use or when a [`wgpu::Surface`] is required.
     pub async fn new(adapter: wgpu::Adapter, polling_time: Duration) -> Self {
+        let features = adapter.features(); // Change this to allow proper WebGL2 support (in the future™️).
+        let limits = adapter.limits(); // Bye WebGL2 support :(
+
+        Self::from_adapter_parts(adapter, None, features, limits, None, Some(polling_time))
+            .await
+            .expect("Failed at device creation.")
+    }
+
+    /// Creates a new [`Framework`] owning a new device on an already selected `adapter`.
+    ///
+    /// Unlike [`Framework::new`], only the `features` and `limits` requested are enabled.
+    /// Fails if the `adapter` does not support any of the `features`.
+    ///
+    /// Use this method to place the [`Framework`] on the same physical GPU as other `wgpu` resources.
+    pub async fn from_adapter(
+        adapter: wgpu::Adapter,
+        features: wgpu::Features,
+        limits: wgpu::Limits,
+    ) -> FrameworkResult<Self> {
+        FrameworkBuilder::default()
+            .features(features)
+            .optional_features(wgpu::Features::empty())
+            .limits(limits)
+            .build_with_adapter(adapter)
+            .await
+    }
+
+    /// Fallible version of [`Framework::default`].
+    ///
+    /// Returns an error instead of panicking when no adapter is found or the
+    /// device cannot be created, e.g. on machines without a GPU.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_default() -> FrameworkResult<Self> {
+        FrameworkBuilder::default().build()
+    }
+
+    /// Creates a new [`Framework`] on the fallback (software) adapter, e.g. lavapipe or SwiftShader.
+    /// Intended for machines without a GPU, like most CI runners.
+    ///
+    /// Fails with [`FrameworkError::NoAdapter`] if the system does not provide a fallback adapter.
+    ///
+    /// All the operations are executed on the CPU, so kernel dispatches and
+    /// large buffer or image transfers are expected to be much slower than on a GPU.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn software() -> FrameworkResult<Self> {
+        FrameworkBuilder::default()
+            .force_fallback_adapter(true)
+            .build()
+    }
+
+    /// Async version of [`Framework::try_default`].
+    ///
+    /// Unlike the blocking constructors, it can be used on `wasm32`, where the main thread cannot be blocked.
+    ///
+    /// # Example
+    /// ```ignore
+    /// wasm_bindgen_futures::spawn_local(async {
+    ///     let fw = gpgpu::Framework::new_async().await.unwrap();
+    ///     // ...
+    /// });
+    /// ```
+    pub async fn new_async() -> FrameworkResult<Self> {
+        FrameworkBuilder::default().build_async().await
+    }
+
+    /// Lists the information of all the adapters available in the system.
+    ///
+    /// The position of an adapter in this list is the index expected by [`Framework::from_adapter_index`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn enumerate_adapters() -> Vec<wgpu::AdapterInfo> {
+        wgpu::Instance::default()
+            .enumerate_adapters(wgpu::Backends::all())
+            .map(|adapter| adapter.get_info())
+            .collect()
+    }
+
+    /// Creates a new [`Framework`] on the adapter at position `index` of [`Framework::enumerate_adapters`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_adapter_index(index: usize) -> FrameworkResult<Self> {
+        FrameworkBuilder::default().build_with_adapter_index(index)
+    }
+
+    /// Creates a new [`Framework`] on the first adapter whose information satisfies `filter`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// let fw = gpgpu::Framework::from_adapter_filter(|info| info.name.contains("NVIDIA")).unwrap();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_adapter_filter<F>(filter: F) -> FrameworkResult<Self>
+    where
+        F: FnMut(&wgpu::AdapterInfo) -> bool,
+    {
+        FrameworkBuilder::default().build_with_adapter_filter(filter)
+    }
+
+    /// Creates a [`FrameworkBuilder`] to configure the adapter and device
+    /// requested by the [`Framework`].
+    pub fn builder<'a>() -> FrameworkBuilder<'a> {
+        FrameworkBuilder::default()
+    }
+
+    async fn from_adapter_parts(
+        adapter: wgpu::Adapter,
+        label: Option<&str>,
+        features: wgpu::Features,
+        limits: wgpu::Limits,
+        trace_path: Option<&Path>,
+        polling_time: Option<Duration>,
+    ) -> FrameworkResult<Self> {
+        let trace_path = trace_path.filter(|_| {
+            if !cfg!(feature = "trace") {
+                log::warn!(
+                    "API trace requested, but the `trace` feature of `gpgpu` is not enabled."
+                );
+            }
+
+            cfg!(feature = "trace")
+        });
+
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    label: None,
-                    features: adapter.features(), // Change this to allow proper WebGL2 support (in the future™️).
-                    limits: adapter.limits(),     // Bye WebGL2 support :(
+                    label,
+                    features,
+                    limits,
                 },
-                None,
+                trace_path,
             )
-            .await
-            .expect("Failed at device creation.");
+            .await?;
 
-        let info = adapter.get_info();
+        let adapter_info = adapter.get_info();
         log::info!(
             "Using {} ({}) - {:#?}.",
-            info.name,
-            info.device,
-            info.backend
+            adapter_info.name,
+            adapter_info.device,
+            adapter_info.backend
         );
+        log::info!("Enabled features: {:?}.", device.features());
 
-        let device = Arc::new(device);
-        let polling_device = Arc::clone(&device);
+        let fw = Self {
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            adapter: Arc::new(adapter),
+            adapter_info: Arc::new(adapter_info),
+            tracing: trace_path.is_some(),
+            polling: Arc::default(),
+            memory: Arc::default(),
+            errors: Arc::default(),
+            staging_chunk_size: STAGING_CHUNK_SIZE,
+            staging: Arc::new(StagingPool::new(STAGING_POOL_LIMIT)),
+            submissions: Arc::default(),
+        };
+
+        let errors = Arc::clone(&fw.errors);
+        fw.device
+            .on_uncaptured_error(Box::new(move |error| errors.handle(error)));
+
+        if let Some(polling_time) = polling_time {
+            fw.start_polling_thread(polling_time);
+        }
+
+        Ok(fw)
+    }
+
+    /// Polls the device, resolving the callbacks of the completed GPU operations
+    /// (e.g. buffer mappings) without blocking.
+    ///
+    /// Only needed when the polling thread is not running.
+    pub fn poll(&self) {
+        self.device.poll(wgpu::Maintain::Poll);
+    }
 
-        std::thread::spawn(move || loop {
-            polling_device.poll(wgpu::Maintain::Poll);
-            std::thread::sleep(polling_time);
+    /// Polls the device, blocking until all the submitted GPU operations are completed.
+    ///
+    /// Only needed when the polling thread is not running.
+    pub fn blocking_poll(&self) {
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    /// Submits `commands` to the queue, returning its [`Submission`].
+    pub(crate) fn submit(&self, commands: wgpu::CommandBuffer) -> Submission {
+        let index = self.queue.submit(Some(commands));
+        // Counted once submitted, so the work submitted before any `on_submitted_work_done` callback
+        // includes all the serials counted before it.
+        let serial = self.submissions.submitted.fetch_add(1, Ordering::SeqCst) + 1;
+
+        Submission { index, serial }
+    }
+
+    /// Blocks until the GPU has completed `submission`, and the submissions before it.
+    ///
+    /// Unlike [`Framework::blocking_poll`], the submissions made after `submission` are not awaited.
+    /// On `wasm32` this returns immediately, the browser does not allow blocking on the GPU.
+    pub fn wait_for(&self, submission: &Submission) {
+        self.device.poll(wgpu::Maintain::WaitForSubmissionIndex(
+            submission.index.clone(),
+        ));
+        self.submissions
+            .completed
+            .fetch_max(submission.serial, Ordering::SeqCst);
+    }
+
+    /// Blocks until the GPU has completed all the submitted work, like [`Framework::blocking_poll`].
+    ///
+    /// On `wasm32` this returns immediately, the browser does not allow blocking on the GPU.
+    pub fn wait_idle(&self) {
+        let submitted = self.submissions.submitted.load(Ordering::SeqCst);
+
+        self.device.poll(wgpu::Maintain::Wait);
+        self.submissions
+            .completed
+            .fetch_max(submitted, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the GPU has completed `submission`, without blocking.
+    ///
+    /// The completions are only noticed when the device is polled: this polls it once, so
+    /// calling it in a loop ends even without the polling thread (see [`Framework::start_polling_thread`]).
+    pub fn is_done(&self, submission: &Submission) -> bool {
+        let submissions = &self.submissions;
+
+        if submissions.completed.load(Ordering::SeqCst) >= submission.serial {
+            return true;
+        }
+
+        // A single callback awaits the submissions made since the previous one.
+        let submitted = submissions.submitted.load(Ordering::SeqCst);
+        if submissions.awaited.fetch_max(submitted, Ordering::SeqCst) < submitted {
+            let submissions = Arc::clone(submissions);
+
+            self.queue.on_submitted_work_done(move || {
+                submissions.completed.fetch_max(submitted, Ordering::SeqCst);
+            });
+        }
+
+        self.device.poll(wgpu::Maintain::Poll);
+
+        submissions.completed.load(Ordering::SeqCst) >= submission.serial
+    }
+
+    /// Calls `callback` once the GPU has completed all the work submitted so far.
+    ///
+    /// The callback runs on the thread polling the device (the polling thread, [`Framework::poll`]
+    /// or [`Framework::blocking_poll`]), exactly once, and is dropped right after.
+    pub fn on_work_done(&self, callback: impl FnOnce() + Send + 'static) {
+        let submissions = Arc::clone(&self.submissions);
+        let submitted = submissions.submitted.load(Ordering::SeqCst);
+
+        self.queue.on_submitted_work_done(move || {
+            submissions.completed.fetch_max(submitted, Ordering::SeqCst);
+            callback();
         });
+    }
 
-        Self {
-            device,
-            queue,
-            adapter,
+    /// Returns a [`WorkDone`] future resolved once the GPU has completed `submission`.
+    ///
+    /// Unlike [`Framework::wait_for`], the device is never polled by the future, see [`WorkDone`].
+    pub fn wait_async(&self, submission: &Submission) -> WorkDone {
+        if self.submissions.completed.load(Ordering::SeqCst) >= submission.serial {
+            return WorkDone::ready();
         }
+
+        let state = Arc::new(Mutex::new(WorkDoneState::default()));
+        let callback_state = Arc::clone(&state);
+
+        self.on_work_done(move || {
+            let waker = {
+                let mut state = callback_state.lock().unwrap();
+                state.done = true;
+                state.waker.take()
+            };
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+
+        WorkDone { state }
+    }
+
+    /// Starts a background thread that polls the device every `polling_time`,
+    /// so asynchronous GPU operations (e.g. [`GpuBuffer::read`](crate::GpuBuffer::read))
+    /// are resolved without calling [`Framework::poll`].
+    ///
+    /// The thread is started by default. If it is already running, its polling time is updated.
+    /// The thread is shared by all the clones of the [`Framework`] and stopped when the last one is dropped.
+    ///
+    /// On `wasm32` this is a no-op since the browser drives WebGPU by itself.
+    pub fn start_polling_thread(&self, polling_time: Duration) {
+        cfg_if::cfg_if! {
+            if #[cfg(not(target_arch = "wasm32"))] {
+                self.stop_polling_thread();
+
+                let stop = Arc::new(AtomicBool::new(false));
+                let thread_stop = Arc::clone(&stop);
+                let polling_device = Arc::clone(&self.device);
+
+                let handle = std::thread::spawn(move || {
+                    while !thread_stop.load(Ordering::Relaxed) {
+                        polling_device.poll(wgpu::Maintain::Poll);
+                        std::thread::sleep(polling_time);
+                    }
+                });
+
+                *self.polling.0.lock().unwrap() = Some(PollingThread { stop, handle });
+            } else {
+                let _ = polling_time;
+            }
+        }
+    }
+
+    /// Stops the background polling thread, waiting for it to finish. Does nothing if it is not running.
+    ///
+    /// Afterwards, [`Framework::poll`] or [`Framework::blocking_poll`] must be called
+    /// to complete the asynchronous GPU operations.
+    pub fn stop_polling_thread(&self) {
+        self.polling.stop();
+    }
+
+    /// Returns `true` if the background polling thread is running.
+    pub fn is_polling(&self) -> bool {
+        self.polling.0.lock().unwrap().is_some()
     }
 
     /// Gets info about the adapter that created this [`Framework`].
+    #[deprecated(since = "0.3.0", note = "use `Framework::adapter_info` instead")]
     pub fn info(&self) -> wgpu::AdapterInfo {
-        self.adapter.get_info()
+        (*self.adapter_info).clone()
+    }
+
+    /// Gets info (name, backend, device type, vendor...) about the adapter that created this [`Framework`].
+    pub fn adapter_info(&self) -> &wgpu::AdapterInfo {
+        &self.adapter_info
+    }
+
+    /// Creates a [`GpuRecorder`] to record kernel dispatches, copies and raw `wgpu` commands,
+    /// submitted at once by [`GpuRecorder::submit`].
+    pub fn create_recorder(&self) -> GpuRecorder<'_> {
+        GpuRecorder::new(self)
     }
 
-    /// Gets the features that may be used with this [`Framework`].
+    /// Starts a [`Sequence`] of kernel dispatches and buffer copies, submitted at once by [`Sequence::submit`].
+    pub fn sequence(&self) -> Sequence<'_> {
+        Sequence::new(self)
+    }
+
+    /// Executes `f`, returning the first validation or out of memory error that `wgpu`
+    /// reported during its execution instead of the default panic of `wgpu`.
+    ///
+    /// Error scopes are tracked per device, so the errors of GPU operations
+    /// executed concurrently by other threads on this [`Framework`] may be caught too.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use gpgpu::BufOps;
+    /// let fw = gpgpu::Framework::default();
+    /// let result = fw.error_scope(|| gpgpu::GpuBuffer::<u32>::with_capacity(&fw, 1 << 40));
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn error_scope<F, R>(&self, f: F) -> GpuResult<R>
+    where
+        F: FnOnce() -> R,
+    {
+        futures::executor::block_on(self.error_scope_async(f))
+    }
+
+    /// Async version of [`Framework::error_scope`].
+    pub async fn error_scope_async<F, R>(&self, f: F) -> GpuResult<R>
+    where
+        F: FnOnce() -> R,
+    {
+        self.device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let result = f();
+
+        let validation = self.device.pop_error_scope().await;
+        let out_of_memory = self.device.pop_error_scope().await;
+
+        match validation.or(out_of_memory).map(GpuError::from) {
+            Some(error) => {
+                if error.is_device_lost() {
+                    self.errors.set_lost(&error);
+                }
+
+                Err(error)
+            }
+            None => Ok(result),
+        }
+    }
+
+    /// Sets the `callback` called with the errors of `wgpu` not caught by [`Framework::error_scope`],
+    /// replacing the default handler which panics.
+    ///
+    /// The callback is shared by all the clones of the [`Framework`].
+    pub fn on_uncaptured_error<F>(&self, callback: F)
+    where
+        F: Fn(&GpuError) + Send + Sync + 'static,
+    {
+        *self.errors.on_uncaptured_error.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// Sets the `callback` called once when the device of this [`Framework`] is lost,
+    /// e.g. after a driver timeout (TDR) caused by a long running kernel.
+    ///
+    /// A lost device cannot be recovered, a new [`Framework`] must be created.
+    ///
+    /// `wgpu` does not provide a device lost callback yet, so the loss is
+    /// detected from the uncaptured errors of the device (see [`GpuError::is_device_lost`]).
+    pub fn on_device_lost<F>(&self, callback: F)
+    where
+        F: Fn(&GpuError) + Send + Sync + 'static,
+    {
+        *self.errors.on_device_lost.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// Returns `true` if the device of this [`Framework`] was lost. See [`Framework::on_device_lost`].
+    ///
+    /// Once lost, buffer reads fail with [`BufferError::DeviceLost`]
+    /// and [`Kernel::run_checked`](crate::Kernel::run_checked) with [`GpuError::DeviceLost`].
+    pub fn is_device_lost(&self) -> bool {
+        self.errors.lost.load(Ordering::SeqCst)
+    }
+
+    /// Returns the bytes of GPU memory currently allocated by the `gpgpu` primitives of this [`Framework`].
+    pub fn allocated_bytes(&self) -> u64 {
+        self.memory.total_bytes()
+    }
+
+    /// Returns the bytes of GPU memory currently allocated by the `gpgpu` primitives of `kind`.
+    pub fn allocated_bytes_of(&self, kind: ResourceKind) -> u64 {
+        self.memory.bytes(kind)
+    }
+
+    /// Returns the number of `gpgpu` primitives currently allocated on this [`Framework`].
+    pub fn allocation_count(&self) -> u64 {
+        self.memory.total_count()
+    }
+
+    /// Sets a soft limit of allocated bytes. Every allocation exceeding it logs a warning
+    /// and calls `callback` with the total bytes allocated. The allocation itself is not prevented.
+    pub fn set_memory_soft_limit<F>(&self, limit: u64, callback: F)
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        self.memory
+            .set_soft_limit(Some((limit, Box::new(callback))));
+    }
+
+    /// Removes the soft limit set by [`Framework::set_memory_soft_limit`].
+    pub fn clear_memory_soft_limit(&self) {
+        self.memory.set_soft_limit(None);
+    }
+
+    /// Returns the maximum size in bytes of the staging buffers used by
+    /// [`GpuBuffer::write_async`](crate::GpuBuffer::write_async). See [`FrameworkBuilder::staging_chunk_size`].
+    pub fn staging_chunk_size(&self) -> u64 {
+        self.staging_chunk_size
+    }
+
+    /// Returns the bytes of GPU memory retained by the staging buffer pool of this [`Framework`].
+    ///
+    /// Buffer reads copy through staging buffers, which are kept for the next reads
+    /// instead of being allocated every time. See [`FrameworkBuilder::staging_pool_limit`].
+    pub fn staging_pool_bytes(&self) -> u64 {
+        self.staging.retained_bytes()
+    }
+
+    /// Releases all the staging buffers retained by the staging buffer pool of this [`Framework`].
+    pub fn trim_staging_pool(&self) {
+        self.staging.trim();
+    }
+
+    /// Returns `true` if this [`Framework`] is writing an API trace. See [`FrameworkBuilder::trace_path`].
+    pub fn is_tracing(&self) -> bool {
+        self.tracing
+    }
+
+    /// Returns the capabilities of `format` on the device of this [`Framework`], e.g. to check
+    /// that an image format picked at runtime can be written by kernels.
+    ///
+    /// The capabilities of the adapter are only available with the
+    /// [`wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] feature, otherwise they are
+    /// the ones guaranteed by `wgpu`. Formats whose features are not enabled have no capabilities.
+    pub fn image_format_capabilities(&self, format: wgpu::TextureFormat) -> FormatCaps {
+        let info = format.describe();
+        let features = self.device.features();
+
+        let format_features = if !features.contains(info.required_features) {
+            wgpu::TextureFormatFeatures {
+                allowed_usages: wgpu::TextureUsages::empty(),
+                flags: wgpu::TextureFormatFeatureFlags::empty(),
+            }
+        } else if features.contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+            self.adapter.get_texture_format_features(format)
+        } else {
+            info.guaranteed_format_features
+        };
+
+        let usages = format_features.allowed_usages;
+        let storage = usages.contains(wgpu::TextureUsages::STORAGE_BINDING);
+        let sampled = usages.contains(wgpu::TextureUsages::TEXTURE_BINDING);
+
+        FormatCaps {
+            usages,
+            storage,
+            // Reading storage images needs adapter specific features, see `Kernel::try_new`.
+            storage_read_write: storage
+                && features.contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+                && format_features
+                    .flags
+                    .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE),
+            sampled,
+            filterable: sampled
+                && format_features
+                    .flags
+                    .contains(wgpu::TextureFormatFeatureFlags::FILTERABLE),
+            renderable: usages.contains(wgpu::TextureUsages::RENDER_ATTACHMENT),
+            copyable: usages
+                .contains(wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST),
+        }
+    }
+
+    /// Gets the features enabled on the device of this [`Framework`].
     pub fn features(&self) -> wgpu::Features {
         self.device.features()
     }
 
-    /// Gets the limits of this [`Framework`].
+    /// Gets the limits of the device of this [`Framework`], e.g. `max_storage_buffer_binding_size`
+    /// or `max_compute_workgroups_per_dimension`.
     pub fn limits(&self) -> wgpu::Limits {
         self.device.limits()
     }
 }
+
+/// Configures the creation of a [`Framework`].
+///
+/// By default, the builder behaves like [`Framework::default`]: every feature
+/// and the best limits of the selected adapter are requested.
+///
+/// # Example
+/// ```no_run
+/// let fw = gpgpu::Framework::builder()
+///     .power_preference(wgpu::PowerPreference::LowPower)
+///     .optional_features(wgpu::Features::TIMESTAMP_QUERY)
+///     .build()
+///     .unwrap();
+///
+/// let timestamps = fw.features().contains(wgpu::Features::TIMESTAMP_QUERY);
+/// ```
+#[derive(Clone)]
+pub struct FrameworkBuilder<'a> {
+    backends: wgpu::Backends,
+    power_preference: wgpu::PowerPreference,
+    force_fallback_adapter: bool,
+    required_features: wgpu::Features,
+    optional_features: wgpu::Features,
+    limits: Option<wgpu::Limits>,
+    label: Option<&'a str>,
+    trace_path: Option<&'a Path>,
+    background_poll: bool,
+    polling_time: Duration,
+    staging_chunk_size: u64,
+    staging_pool_limit: u64,
+}
+
+impl Default for FrameworkBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            required_features: wgpu::Features::empty(),
+            optional_features: wgpu::Features::all(),
+            limits: None,
+            label: None,
+            trace_path: None,
+            background_poll: true,
+            polling_time: Duration::from_millis(10),
+            staging_chunk_size: STAGING_CHUNK_SIZE,
+            staging_pool_limit: STAGING_POOL_LIMIT,
+        }
+    }
+}
+
+impl<'a> FrameworkBuilder<'a> {
+    /// Sets the backends the adapter can be selected from. Defaults to [`wgpu::Backends::all`].
+    ///
+    /// [`FrameworkBuilder::build`] fails with [`FrameworkError::NoAdapter`] if none
+    /// of the `backends` has an adapter available.
+    pub fn backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// Sets the power preference used to select the adapter.
+    pub fn power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// Forces the selection of the fallback (software) adapter. See [`Framework::software`].
+    ///
+    /// When no limits are set, the limits of the fallback adapter are used,
+    /// which are usually lower than the ones of a real GPU.
+    pub fn force_fallback_adapter(mut self, force_fallback_adapter: bool) -> Self {
+        self.force_fallback_adapter = force_fallback_adapter;
+        self
+    }
+
+    /// Sets the features the device must support.
+    ///
+    /// [`FrameworkBuilder::build`] fails if the adapter lacks any of them.
+    pub fn features(mut self, features: wgpu::Features) -> Self {
+        self.required_features = features;
+        self
+    }
+
+    /// Sets the features that are enabled only if the adapter supports them.
+    ///
+    /// The features finally enabled can be checked with [`Framework::features`].
+    pub fn optional_features(mut self, features: wgpu::Features) -> Self {
+        self.optional_features = features;
+        self
+    }
+
+    /// Sets the limits the device must support. Defaults to the adapter limits.
+    pub fn limits(mut self, limits: wgpu::Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Sets the debug label of the device.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Sets the directory where the `wgpu` API trace of the device is written.
+    /// Useful when filing driver or `wgpu` bugs.
+    ///
+    /// Requires the `trace` feature of `gpgpu`. Otherwise, a warning is logged
+    /// and the [`Framework`] is created without tracing.
+    pub fn trace_path(mut self, trace_path: &'a Path) -> Self {
+        self.trace_path = Some(trace_path);
+        self
+    }
+
+    /// Sets the time between device polls of the [`Framework`] polling thread. Defaults to 10ms.
+    pub fn polling_time(mut self, polling_time: Duration) -> Self {
+        self.polling_time = polling_time;
+        self
+    }
+
+    /// Enables or disables the [`Framework`] background polling thread. Enabled by default.
+    ///
+    /// See [`Framework::start_polling_thread`].
+    pub fn background_poll(mut self, background_poll: bool) -> Self {
+        self.background_poll = background_poll;
+        self
+    }
+
+    /// Sets the maximum size in bytes of the staging buffers used by
+    /// [`GpuBuffer::write_async`](crate::GpuBuffer::write_async). Defaults to 64 MiB.
+    ///
+    /// Larger uploads are split into chunks of this size, bounding their peak memory usage.
+    /// It is rounded up to a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`].
+    pub fn staging_chunk_size(mut self, staging_chunk_size: u64) -> Self {
+        self.staging_chunk_size =
+            wgpu::util::align_to(staging_chunk_size.max(1), wgpu::COPY_BUFFER_ALIGNMENT);
+        self
+    }
+
+    /// Sets the maximum bytes of staging buffers retained for reuse by the buffer reads. Defaults to 64 MiB.
+    ///
+    /// Staging buffers are bucketed by power-of-two sizes. A limit of 0 disables the pool.
+    /// See [`Framework::trim_staging_pool`].
+    pub fn staging_pool_limit(mut self, staging_pool_limit: u64) -> Self {
+        self.staging_pool_limit = staging_pool_limit;
+        self
+    }
+
+    /// Creates the [`Framework`] with the builder configuration.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build(self) -> FrameworkResult<Framework> {
+        futures::executor::block_on(self.build_async())
+    }
+
+    /// Async version of [`FrameworkBuilder::build`]. Usable on `wasm32`.
+    pub async fn build_async(self) -> FrameworkResult<Framework> {
+        let adapter = self
+            .instance()
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: self.power_preference,
+                force_fallback_adapter: self.force_fallback_adapter,
+                compatible_surface: None,
+            })
+            .await
+            .ok_or(FrameworkError::NoAdapter)?;
+
+        self.build_with_adapter(adapter).await
+    }
+
+    /// Creates the [`Framework`] with the builder configuration on the adapter
+    /// at position `index` of [`Framework::enumerate_adapters`].
+    ///
+    /// If backends other than [`wgpu::Backends::all`] are set, `index` refers to
+    /// the list of adapters of those `backends` only.
+    ///
+    /// The power preference and the fallback adapter option are ignored.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build_with_adapter_index(self, index: usize) -> FrameworkResult<Framework> {
+        let adapter = self
+            .instance()
+            .enumerate_adapters(self.backends)
+            .nth(index)
+            .ok_or(FrameworkError::NoAdapter)?;
+
+        futures::executor::block_on(self.build_with_adapter(adapter))
+    }
+
+    /// Creates the [`Framework`] with the builder configuration on the first
+    /// adapter whose information satisfies `filter`.
+    ///
+    /// The power preference and the fallback adapter option are ignored.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build_with_adapter_filter<F>(self, mut filter: F) -> FrameworkResult<Framework>
+    where
+        F: FnMut(&wgpu::AdapterInfo) -> bool,
+    {
+        let adapter = self
+            .instance()
+            .enumerate_adapters(self.backends)
+            .find(|adapter| filter(&adapter.get_info()))
+            .ok_or(FrameworkError::NoAdapter)?;
+
+        futures::executor::block_on(self.build_with_adapter(adapter))
+    }
+
+    fn instance(&self) -> wgpu::Instance {
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: self.backends,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a [`Framework`] with the builder configuration on every adapter available,
+    /// in the same order as [`Framework::enumerate_adapters`].
+    ///
+    /// All the frameworks are created from the same [`wgpu::Instance`]. Useful to split
+    /// workloads across multiple GPUs.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build_with_all_adapters(self) -> FrameworkResult<Vec<Framework>> {
+        self.instance()
+            .enumerate_adapters(self.backends)
+            .map(|adapter| futures::executor::block_on(self.clone().build_with_adapter(adapter)))
+            .collect()
+    }
+
+    /// Creates the [`Framework`] with the builder configuration on an already selected `adapter`.
+    ///
+    /// The backends, power preference and fallback adapter option are ignored.
+    pub async fn build_with_adapter(self, adapter: wgpu::Adapter) -> FrameworkResult<Framework> {
+        let supported = adapter.features();
+        let missing = self.required_features - supported;
+        if !missing.is_empty() {
+            return Err(FrameworkError::UnsupportedFeatures(missing));
+        }
+
+        let features = self.required_features | (self.optional_features & supported);
+        let limits = self.limits.unwrap_or_else(|| adapter.limits());
+        let polling_time = if self.background_poll {
+            Some(self.polling_time)
+        } else {
+            None
+        };
+
+        let mut fw = Framework::from_adapter_parts(
+            adapter,
+            self.label,
+            features,
+            limits,
+            self.trace_path,
+            polling_time,
+        )
+        .await?;
+        fw.staging_chunk_size = self.staging_chunk_size;
+        fw.staging = Arc::new(StagingPool::new(self.staging_pool_limit));
+
+        Ok(fw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        new_set_layout,
+        primitives::{images::ImageInputError, pixels::Rgba8UintNormSrgb},
+        testing, BufOps, GpuBuffer, GpuImage, Kernel, SetBindings, Shader,
+    };
+
+    #[test]
+    fn no_adapter() {
+        let result = Framework::builder()
+            .backends(wgpu::Backends::empty())
+            .build();
+
+        assert!(matches!(result, Err(FrameworkError::NoAdapter)));
+    }
+
+    #[test]
+    fn no_adapter_index() {
+        let result = Framework::builder()
+            .backends(wgpu::Backends::empty())
+            .build_with_adapter_index(0);
+
+        assert!(matches!(result, Err(FrameworkError::NoAdapter)));
+    }
+
+    #[test]
+    fn run_kernel() {
+        testing::with_framework(|fw| {
+            if std::env::var(testing::ADAPTER_VAR).as_deref() == Ok("software") {
+                assert_eq!(fw.adapter_info().device_type, wgpu::DeviceType::Cpu);
+            }
+
+            let shader = Shader::from_wgsl_string(
+                fw,
+                "@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+                @compute @workgroup_size(64)
+                fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                    if (id.x < arrayLength(&data)) {
+                        data[id.x] = data[id.x] * 2u;
+                    }
+                }"
+                .to_owned(),
+                None,
+            )
+            .unwrap();
+            let data = (0..1000).collect::<Vec<u32>>();
+            let buffer = GpuBuffer::from_slice(fw, &data);
+
+            let kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &buffer))
+                .build()
+                .unwrap();
+            kernel.run_elements(fw, data.len() as u64, 1, 1);
+
+            let expected = data.iter().map(|x| x * 2).collect::<Vec<_>>();
+            assert_eq!(buffer.read_vec_blocking().unwrap(), expected);
+        });
+    }
+
+    /// Kernel doubling the elements of `buffer`, run by the tests of the submissions.
+    fn double_kernel(fw: &Framework, buffer: &GpuBuffer<u32>) -> Kernel {
+        let shader = Shader::from_wgsl_string(
+            fw,
+            "@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+            @compute @workgroup_size(64)
+            fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                if (id.x < arrayLength(&data)) {
+                    data[id.x] = data[id.x] * 2u;
+                }
+            }"
+            .to_owned(),
+            None,
+        )
+        .unwrap();
+
+        Kernel::builder(fw, &shader, "main")
+            .layouts(vec![new_set_layout!(0: Buffer)])
+            .bind_set(0, SetBindings::default().add_buffer(0, buffer))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn on_work_done_once_per_submission() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &[1u32; 256]);
+            let kernel = double_kernel(fw, &buffer);
+            let calls = (0..8)
+                .map(|_| Arc::new(AtomicU64::new(0)))
+                .collect::<Vec<_>>();
+
+            // All the submissions are in flight before the device is polled.
+            for count in &calls {
+                kernel.run_elements(fw, 256, 1, 1);
+
+                let count = Arc::clone(count);
+                fw.on_work_done(move || {
+                    count.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+
+            fw.wait_idle();
+            fw.poll();
+
+            for (submission, count) in calls.iter().enumerate() {
+                assert_eq!(count.load(Ordering::SeqCst), 1, "submission {}", submission);
+                // The callback is dropped once called.
+                assert_eq!(Arc::strong_count(count), 1, "submission {}", submission);
+            }
+            assert_eq!(buffer.read_vec_blocking().unwrap(), vec![1 << 8; 256]);
+        });
+    }
+
+    #[test]
+    fn wait_async_several_in_flight() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &[1u32; 256]);
+            let kernel = double_kernel(fw, &buffer);
+
+            let done = (0..4)
+                .map(|_| fw.wait_async(&kernel.run_elements(fw, 256, 1, 1)))
+                .collect::<Vec<_>>();
+            // A future dropped before its submission completes is never woken.
+            drop(fw.wait_async(&kernel.run_elements(fw, 256, 1, 1)));
+
+            fw.wait_idle();
+            for done in done {
+                // The callback resolving the future is dropped once called.
+                assert_eq!(Arc::strong_count(&done.state), 1);
+                futures::executor::block_on(done);
+            }
+            assert_eq!(buffer.read_vec_blocking().unwrap(), vec![1 << 5; 256]);
+        });
+    }
+
+    #[test]
+    fn image_format_capabilities() {
+        use wgpu::{TextureFormat, TextureUsages};
+
+        let formats = [
+            TextureFormat::R8Unorm,
+            TextureFormat::R32Uint,
+            TextureFormat::R32Float,
+            TextureFormat::Rg32Float,
+            TextureFormat::Rgba8Unorm,
+            TextureFormat::Rgba8UnormSrgb,
+            TextureFormat::Rgba8Snorm,
+            TextureFormat::Rgba8Uint,
+            TextureFormat::Rgba8Sint,
+            TextureFormat::Bgra8Unorm,
+            TextureFormat::Rgb10a2Unorm,
+            TextureFormat::Rg11b10Float,
+            TextureFormat::Rgba16Float,
+            TextureFormat::Rgba32Float,
+            TextureFormat::Bc1RgbaUnorm,
+        ];
+        let usages = [
+            TextureUsages::STORAGE_BINDING,
+            TextureUsages::TEXTURE_BINDING,
+            TextureUsages::RENDER_ATTACHMENT,
+            TextureUsages::COPY_SRC | TextureUsages::COPY_DST,
+        ];
+
+        testing::with_framework(|fw| {
+            for &format in &formats {
+                let caps = fw.image_format_capabilities(format);
+
+                for &usage in &usages {
+                    fw.device.push_error_scope(wgpu::ErrorFilter::Validation);
+                    fw.device.create_texture(&wgpu::TextureDescriptor {
+                        label: None,
+                        size: wgpu::Extent3d {
+                            width: 4,
+                            height: 4,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format,
+                        usage,
+                        view_formats: &[],
+                    });
+                    let created =
+                        futures::executor::block_on(fw.device.pop_error_scope()).is_none();
+
+                    assert_eq!(
+                        caps.usages.contains(usage),
+                        created,
+                        "{:?} {:?}",
+                        format,
+                        usage
+                    );
+                }
+
+                assert_eq!(
+                    caps.storage,
+                    caps.usages.contains(TextureUsages::STORAGE_BINDING)
+                );
+                assert_eq!(
+                    caps.sampled,
+                    caps.usages.contains(TextureUsages::TEXTURE_BINDING)
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn unsupported_image_format() {
+        testing::with_framework(|fw| {
+            let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+            if !fw.image_format_capabilities(format).storage {
+                let result = GpuImage::<Rgba8UintNormSrgb>::try_new(fw, 4, 4);
+
+                assert!(matches!(
+                    result,
+                    Err(ImageInputError::UnsupportedUsages { format: f, usages })
+                        if f == format && usages.contains(wgpu::TextureUsages::STORAGE_BINDING)
+                ));
+            }
+        });
+    }
+}
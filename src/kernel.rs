@@ -1,107 +1,2105 @@
-use crate::{bindings::SetBindings, entry_type::EntryType, *};
+use std::sync::Arc;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    bindings::SetBindings, framework::GpuResult, reflection::check_layouts, shared_set::SharedSet,
+    specialization::specialize, *,
+};
+
+/// Workgroup counts of an indirect dispatch, read by the GPU from a [`GpuBuffer`] when the [`Kernel`] is executed.
+/// See [`Kernel::run_indirect`].
+///
+/// Kernels can write them to decide the workgroup count of the next ones without any readback,
+/// binding the buffer like any other storage buffer:
+/// ```ignore
+/// struct DispatchIndirectArgs {
+///     x: u32,
+///     y: u32,
+///     z: u32,
+/// }
+///
+/// @group(0) @binding(0) var<storage, read_write> args: DispatchIndirectArgs;
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DispatchIndirectArgs {
+    /// Number of workgroups in the X dimension.
+    pub x: u32,
+    /// Number of workgroups in the Y dimension.
+    pub y: u32,
+    /// Number of workgroups in the Z dimension.
+    pub z: u32,
+}
+
+unsafe impl bytemuck::Zeroable for DispatchIndirectArgs {}
+unsafe impl bytemuck::Pod for DispatchIndirectArgs {}
+
+/// Workgroups dispatched by [`Kernel::encode`].
+pub(crate) enum Workgroups<'a> {
+    Count(u32, u32, u32),
+    /// Buffer of the arguments and their byte offset.
+    Indirect(&'a wgpu::Buffer, u64),
+    /// Workgroups in the X dimension of a [`Kernel::run_split`], over several dispatches.
+    Split(u64),
+}
+
+impl<'a> Workgroups<'a> {
+    /// Checks that the arguments `index` of `args` exist and can be read by an indirect dispatch.
+    pub(crate) fn indirect(
+        args: &'a GpuBuffer<DispatchIndirectArgs>,
+        index: u64,
+    ) -> GpuResult<Self> {
+        if !args.buf.usage().contains(wgpu::BufferUsages::INDIRECT) {
+            return Err(GpuError::MissingIndirectUsage);
+        }
+
+        let len = args.capacity();
+        if index >= len {
+            return Err(GpuError::IndirectArgsOutOfRange { index, len });
+        }
+
+        let offset = index * std::mem::size_of::<DispatchIndirectArgs>() as u64;
+
+        Ok(Self::Indirect(&args.buf, offset))
+    }
+}
 
 /// Used to enqueue the execution of a shader with the bidings provided.
 ///
 /// Equivalent to OpenCL's Kernel.
-pub struct Kernel<'a> {
+pub struct Kernel {
     pipeline: wgpu::ComputePipeline,
-    entry_types: Vec<Vec<EntryType>>,
-    layouts: Vec<wgpu::BindGroupLayout>,
-    function_name: &'a str,
+    set_layouts: Vec<SetLayout>,
+    layouts: Vec<Arc<wgpu::BindGroupLayout>>,
+    function_name: String,
+    push_constants_size: u32,
+    /// Reflected `@workgroup_size` of the shader, or the one of [`KernelBuilder::workgroup_size`].
+    workgroup_size: Option<[u32; 3]>,
+    /// Bindings and offset alignments of the dynamic entries of each set, ordered by binding.
+    dynamic_entries: Vec<Vec<(u32, u32)>>,
+    /// Bind groups of [`Kernel::rebind_set`], used by [`Kernel::run_bound`].
+    bound: Vec<Option<Arc<wgpu::BindGroup>>>,
+    /// Sets of [`KernelBuilder::shared_set`], used by [`Kernel::run_bound`] instead of the bound ones.
+    shared: Vec<Option<SharedSet>>,
+    split: Option<SplitDispatch>,
 }
 
-impl<'a> Kernel<'a> {
+/// Set of [`KernelBuilder::split_dispatch`], whose bound buffer holds the base offset of each chunk
+/// of a split dispatch, one per dynamic offset alignment.
+struct SplitDispatch {
+    set: u32,
+    max_elements: u64,
+    /// Workgroups of each chunk, the `max_compute_workgroups_per_dimension` limit.
+    chunk_workgroups: u32,
+    alignment: u32,
+    _bases: wgpu::Buffer,
+}
+
+impl Kernel {
     /// Creates a [`Kernel`] from a [`Program`].
-    pub fn new<'sha, 'res, 'fw>(
-        fw: &'fw Framework,
-        shader: &'sha Shader,
-        function_name: &'a str,
+    ///
+    /// # Panics
+    /// If the layouts do not match the bindings of the shader, see [`Kernel::try_new`].
+    pub fn new(
+        fw: &Framework,
+        shader: &Shader,
+        function_name: &str,
         layouts: Vec<SetLayout>,
     ) -> Self {
-        let entry_types = layouts
-            .iter()
-            .map(|layout| layout.entry_type.clone())
-            .collect();
+        check_storage_access(fw, &layouts).unwrap_or_else(|err| panic!("{}", err));
+        check_layouts(shader, function_name, &layouts).unwrap_or_else(|err| panic!("{}", err));
+
+        Self::create(
+            fw,
+            &shader.module,
+            function_name,
+            layouts,
+            0,
+            vec![],
+            shader.workgroup_size(function_name),
+        )
+    }
+
+    /// Creates a [`Kernel`] whose shader takes `size` bytes of push constants, set on each
+    /// execution by [`Kernel::run_with_push_constants`]. Push constants are faster than a
+    /// [`GpuUniformBuffer`] for small parameters changing on every execution, e.g. a time step.
+    ///
+    /// Push constants need the [`wgpu::Features::PUSH_CONSTANTS`] feature, missing on some
+    /// backends (e.g. on the web), and at most `max_push_constant_size` bytes of
+    /// [`Framework::limits`]. Kernels meant to run everywhere can fall back to a
+    /// [`GpuUniformBuffer`] updated with [`GpuUniformBuffer::write`] when
+    /// `fw.features().contains(wgpu::Features::PUSH_CONSTANTS)` is `false`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// struct Params {
+    ///     frame: u32,
+    ///     time_step: f32,
+    /// }
+    ///
+    /// var<push_constant> params: Params;
+    /// ```
+    ///
+    /// # Panics
+    /// If the device does not support push constants of `size` bytes,
+    /// see [`GpuError::UnsupportedPushConstants`].
+    pub fn with_push_constants(
+        fw: &Framework,
+        shader: &Shader,
+        function_name: &str,
+        layouts: Vec<SetLayout>,
+        size: u32,
+    ) -> Self {
+        check_storage_access(fw, &layouts).unwrap_or_else(|err| panic!("{}", err));
+        check_layouts(shader, function_name, &layouts).unwrap_or_else(|err| panic!("{}", err));
+        check_push_constants(fw, size).unwrap_or_else(|err| panic!("{}", err));
+
+        Self::create(
+            fw,
+            &shader.module,
+            function_name,
+            layouts,
+            size,
+            vec![],
+            shader.workgroup_size(function_name),
+        )
+    }
+
+    /// Creates a [`KernelBuilder`] to configure a [`Kernel`] running the `function_name`
+    /// entry point of `shader`, e.g. with the values of its `override` constants.
+    pub fn builder<'a>(
+        fw: &'a Framework,
+        shader: &'a Shader,
+        function_name: &'a str,
+    ) -> KernelBuilder<'a> {
+        KernelBuilder {
+            fw,
+            shader,
+            function_name,
+            layouts: Vec::new(),
+            push_constants_size: 0,
+            constants: Vec::new(),
+            bindings: Vec::new(),
+            shared_sets: Vec::new(),
+            workgroup_size: None,
+            split: None,
+        }
+    }
 
-        // Compute pipeline bindings
-        let layouts = layouts
+    fn create(
+        fw: &Framework,
+        module: &wgpu::ShaderModule,
+        function_name: &str,
+        layouts: Vec<SetLayout>,
+        push_constants_size: u32,
+        mut bind_group_layouts: Vec<Option<Arc<wgpu::BindGroupLayout>>>,
+        workgroup_size: Option<[u32; 3]>,
+    ) -> Self {
+        // Compute pipeline bindings, reusing the given bind group layouts.
+        let set_layouts = layouts;
+        bind_group_layouts.resize(set_layouts.len(), None);
+        let layouts = set_layouts
             .iter()
-            .map(|layout| {
-                fw.device
-                    .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            .zip(bind_group_layouts)
+            .map(|(layout, bind_group_layout)| match bind_group_layout {
+                Some(bind_group_layout) => bind_group_layout,
+                None => Arc::new(fw.device.create_bind_group_layout(
+                    &wgpu::BindGroupLayoutDescriptor {
                         label: None,
                         entries: &layout.layout_entry,
-                    })
+                    },
+                )),
             })
             .collect::<Vec<_>>();
 
         let mut group_layouts = vec![];
 
         for layout in layouts.iter() {
-            group_layouts.push(layout)
+            group_layouts.push(layout.as_ref())
         }
 
+        let push_constant_ranges = if push_constants_size > 0 {
+            vec![wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::COMPUTE,
+                range: 0..push_constants_size,
+            }]
+        } else {
+            vec![]
+        };
+
         let pipeline_layout = fw
             .device
             .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: None,
                 bind_group_layouts: &group_layouts,
-                push_constant_ranges: &[],
+                push_constant_ranges: &push_constant_ranges,
             });
 
         let pipeline = fw
             .device
             .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
                 label: None,
-                module: &shader.0,
+                module,
                 entry_point: function_name,
                 layout: Some(&pipeline_layout),
             });
 
+        let limits = fw.limits();
+        let dynamic_entries = set_layouts
+            .iter()
+            .map(|layout| {
+                let mut entries = layout
+                    .layout_entry
+                    .iter()
+                    .filter_map(|entry| match entry.ty {
+                        wgpu::BindingType::Buffer {
+                            ty,
+                            has_dynamic_offset: true,
+                            ..
+                        } => {
+                            let alignment = match ty {
+                                wgpu::BufferBindingType::Uniform => {
+                                    limits.min_uniform_buffer_offset_alignment
+                                }
+                                _ => limits.min_storage_buffer_offset_alignment,
+                            };
+
+                            Some((entry.binding, alignment))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                entries.sort_by_key(|&(binding, _)| binding);
+
+                entries
+            })
+            .collect();
+
         Self {
             pipeline,
-            entry_types,
+            bound: layouts.iter().map(|_| None).collect(),
+            shared: layouts.iter().map(|_| None).collect(),
+            set_layouts,
             layouts,
-            function_name,
+            function_name: function_name.to_owned(),
+            push_constants_size,
+            workgroup_size,
+            dynamic_entries,
+            split: None,
         }
     }
 
+    /// Binds the base offsets of the chunks of up to `max_elements` elements to the set `set`,
+    /// whose layout is the one of [`KernelBuilder::split_dispatch`].
+    fn bind_split(&mut self, fw: &Framework, set: u32, max_elements: u64) {
+        let workgroup_size = self.workgroup_size.map_or(1, |size| size[0]) as u64;
+        let limits = fw.limits();
+        let chunk_workgroups = limits.max_compute_workgroups_per_dimension;
+        let alignment = limits.min_uniform_buffer_offset_alignment;
+
+        let chunk_elements = chunk_workgroups as u64 * workgroup_size;
+        let chunks = std::cmp::max(max_elements.div_ceil(chunk_elements), 1);
+
+        let mut bases = vec![0u8; (chunks * alignment as u64) as usize];
+        for chunk in 0..chunks {
+            let start = (chunk * alignment as u64) as usize;
+            let base = (chunk * chunk_elements) as u32;
+            bases[start..start + 4].copy_from_slice(&base.to_ne_bytes());
+        }
+
+        let buffer = fw
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Kernel::split_dispatch"),
+                contents: &bases,
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let bind_group = fw.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Kernel::split_dispatch"),
+            layout: &self.layouts[set as usize],
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &buffer,
+                    offset: 0,
+                    size: std::num::NonZeroU64::new(4),
+                }),
+            }],
+        });
+
+        self.bound[set as usize] = Some(Arc::new(bind_group));
+        self.split = Some(SplitDispatch {
+            set,
+            max_elements,
+            chunk_workgroups,
+            alignment,
+            _bases: buffer,
+        });
+    }
+
+    /// Fallible version of [`Kernel::new`].
+    ///
+    /// Returns [`GpuError::LayoutMismatch`] if the layouts do not match the bindings of the
+    /// shader, naming the first mismatching binding: an entry missing from the layouts or from
+    /// the shader, of the wrong kind of resource, or with the wrong access. Otherwise returns
+    /// the `wgpu` validation error instead of panicking, or [`GpuError::UnsupportedStorageAccess`]
+    /// if the device cannot read one of the storage images of the layouts.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_new(
+        fw: &Framework,
+        shader: &Shader,
+        function_name: &str,
+        layouts: Vec<SetLayout>,
+    ) -> GpuResult<Self> {
+        check_storage_access(fw, &layouts)?;
+        check_layouts(shader, function_name, &layouts)?;
+
+        fw.error_scope(|| {
+            Self::create(
+                fw,
+                &shader.module,
+                function_name,
+                layouts,
+                0,
+                vec![],
+                shader.workgroup_size(function_name),
+            )
+        })
+    }
+
+    /// Fallible version of [`Kernel::with_push_constants`].
+    ///
+    /// Returns the errors of [`Kernel::try_new`], or [`GpuError::UnsupportedPushConstants`]
+    /// if the device does not support push constants of `size` bytes.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_with_push_constants(
+        fw: &Framework,
+        shader: &Shader,
+        function_name: &str,
+        layouts: Vec<SetLayout>,
+        size: u32,
+    ) -> GpuResult<Self> {
+        check_storage_access(fw, &layouts)?;
+        check_layouts(shader, function_name, &layouts)?;
+        check_push_constants(fw, size)?;
+
+        fw.error_scope(|| {
+            Self::create(
+                fw,
+                &shader.module,
+                function_name,
+                layouts,
+                size,
+                vec![],
+                shader.workgroup_size(function_name),
+            )
+        })
+    }
+
+    /// Returns the size in bytes of the push constants of this [`Kernel`], 0 if it has none.
+    /// See [`Kernel::with_push_constants`].
+    pub fn push_constants_size(&self) -> u32 {
+        self.push_constants_size
+    }
+
+    /// Returns the name of the entry point run by this [`Kernel`].
+    pub fn entry_point(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Returns the workgroup size of this [`Kernel`], reflected from the `@workgroup_size` of the shader
+    /// with the constants of [`KernelBuilder::constant`], or set with [`KernelBuilder::workgroup_size`].
+    /// `None` if the shader cannot be reflected.
+    pub fn workgroup_size(&self) -> Option<[u32; 3]> {
+        self.workgroup_size
+    }
+
+    /// Returns the workgroup counts per dimension covering `x`, `y` and `z` elements,
+    /// one invocation per element: the element counts divided by the [`Kernel::workgroup_size`],
+    /// rounded up. The invocations past the elements must be skipped by the shader.
+    ///
+    /// Returns [`GpuError::UnknownWorkgroupSize`] if the workgroup size of the [`Kernel`] is unknown,
+    /// or [`GpuError::TooManyWorkgroups`] if a count exceeds the `max_compute_workgroups_per_dimension` limit.
+    pub fn workgroup_count(&self, fw: &Framework, x: u64, y: u64, z: u64) -> GpuResult<[u32; 3]> {
+        let size = self.workgroup_size.ok_or(GpuError::UnknownWorkgroupSize)?;
+        let limit = fw.limits().max_compute_workgroups_per_dimension;
+        let mut count = [0; 3];
+
+        for (dimension, elements) in [x, y, z].iter().enumerate() {
+            let workgroups = elements.div_ceil(size[dimension] as u64);
+
+            if workgroups > limit as u64 {
+                return Err(GpuError::TooManyWorkgroups {
+                    dimension: ["X", "Y", "Z"][dimension],
+                    count: workgroups,
+                    limit,
+                });
+            }
+
+            count[dimension] = workgroups as u32;
+        }
+
+        Ok(count)
+    }
+
+    /// Binds `bindings` to the set `set` of this [`Kernel`] for [`Kernel::run_bound`], replacing
+    /// the previous bindings of the set. Only the bind group of the set is created again, the
+    /// pipeline of the [`Kernel`] is kept, e.g. to swap double-buffered inputs on each frame:
+    /// ```no_run
+    /// # let fw = gpgpu::Framework::default();
+    /// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+    /// # let layouts = gpgpu::SetLayout::from_shader(&shader, "main").unwrap();
+    /// # let mut kernel = gpgpu::Kernel::new(&fw, &shader, "main", layouts);
+    /// # use gpgpu::BufOps;
+    /// let buffers = [
+    ///     gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 1024),
+    ///     gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 1024),
+    /// ];
+    ///
+    /// for frame in 0..60 {
+    ///     let input = &buffers[frame % 2];
+    ///     let output = &buffers[(frame + 1) % 2];
+    ///
+    ///     kernel.rebind_set(&fw, 0, gpgpu::SetBindings::default().add_buffer(0, input).add_buffer(1, output));
+    ///     kernel.run_bound(&fw, 1024, 1, 1);
+    /// }
+    /// ```
+    ///
+    /// A set given to [`KernelBuilder::shared_set`] stops following the [`SharedSet`].
+    ///
+    /// # Panics
+    /// If the [`Kernel`] has no set `set`, or if the bindings do not match its layout,
+    /// see [`GpuError::BindingMismatch`].
+    pub fn rebind_set(&mut self, fw: &Framework, set: u32, bindings: SetBindings) {
+        let bind_group = self
+            .bind_group(fw, set, &bindings)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        self.bound[set as usize] = Some(Arc::new(bind_group));
+        self.shared[set as usize] = None;
+    }
+
+    /// Fallible version of [`Kernel::rebind_set`].
+    ///
+    /// Returns [`GpuError::BindingMismatch`] if the bindings do not match the layout of the set, or the
+    /// `wgpu` validation error (e.g. a buffer smaller than the shader binding) instead of panicking.
+    /// The previous bindings of the set are kept on failure.
+    ///
+    /// # Panics
+    /// If the [`Kernel`] has no set `set`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_rebind_set(
+        &mut self,
+        fw: &Framework,
+        set: u32,
+        bindings: SetBindings,
+    ) -> GpuResult<()> {
+        let bind_group = fw.error_scope(|| self.bind_group(fw, set, &bindings))??;
+
+        self.bound[set as usize] = Some(Arc::new(bind_group));
+        self.shared[set as usize] = None;
+
+        Ok(())
+    }
+
+    /// Executes this [`Kernel`] with the bindings of [`Kernel::rebind_set`], without creating any bind group.
+    ///
+    /// [`Kernel`] will dispatch `x`, `y` and `z` workgroups per dimension.
+    ///
+    /// # Panics
+    /// If a set of the [`Kernel`] was never bound, see [`GpuError::UnboundEntries`].
+    pub fn run_bound(&self, fw: &Framework, x: u32, y: u32, z: u32) -> Submission {
+        self.record_bound(fw, Workgroups::Count(x, y, z))
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`Kernel::run_bound`].
+    ///
+    /// Returns [`GpuError::UnboundEntries`] if a set of the [`Kernel`] was never bound, the `wgpu`
+    /// validation error instead of panicking, or [`GpuError::DeviceLost`] if the device of `fw` was lost.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_bound_checked(
+        &self,
+        fw: &Framework,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> GpuResult<Submission> {
+        if fw.is_device_lost() {
+            return Err(GpuError::DeviceLost);
+        }
+
+        fw.error_scope(|| self.record_bound(fw, Workgroups::Count(x, y, z)))?
+    }
+
+    /// Executes this [`Kernel`] with the bindings of [`Kernel::rebind_set`] like [`Kernel::run_bound`],
+    /// blocking until the GPU has executed it, and returns its execution time.
+    ///
+    /// The time is measured by timestamp queries if the device supports [`wgpu::Features::TIMESTAMP_QUERY`],
+    /// see [`GpuRecorder::with_timing`], otherwise it is the wall-clock time until [`Framework::wait_for`]
+    /// returns, flagged by [`TimingSource::WallClock`].
+    ///
+    /// Returns [`GpuError::UnboundEntries`] if a set of the [`Kernel`] was never bound, or [`GpuError::Buffer`]
+    /// if the timestamps cannot be read back.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_timed(&self, fw: &Framework, x: u32, y: u32, z: u32) -> GpuResult<Timing> {
+        match fw.create_recorder().with_timing() {
+            Ok(mut recorder) => {
+                recorder.try_dispatch_kernel(self, x, y, z)?;
+                let timings = recorder.submit_timed()?;
+
+                Ok(Timing {
+                    duration: timings[0].gpu_duration,
+                    source: TimingSource::Timestamps,
+                })
+            }
+            Err(GpuError::MissingFeature(_)) => {
+                let mut recorder = fw.create_recorder();
+                recorder.try_dispatch_kernel(self, x, y, z)?;
+
+                let start = std::time::Instant::now();
+                let submission = recorder.submit();
+                fw.wait_for(&submission);
+
+                Ok(Timing {
+                    duration: start.elapsed(),
+                    source: TimingSource::WallClock,
+                })
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Executes this [`Kernel`] with the bindings of [`Kernel::rebind_set`], with one invocation per element
+    /// of the `x` by `y` by `z` elements, e.g. `kernel.run_elements(&fw, len, 1, 1)` for a 1D buffer of `len` elements.
+    ///
+    /// The workgroups covering the elements are computed by [`Kernel::workgroup_count`], so the shader must
+    /// skip the invocations past the last elements:
+    /// ```ignore
+    /// @compute @workgroup_size(64)
+    /// fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    ///     if (global_id.x >= arrayLength(&output)) {
+    ///         return;
+    ///     }
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// If the workgroup counts cannot be computed, see [`Kernel::workgroup_count`], or if a set
+    /// of the [`Kernel`] was never bound, see [`GpuError::UnboundEntries`].
+    pub fn run_elements(&self, fw: &Framework, x: u64, y: u64, z: u64) -> Submission {
+        self.workgroup_count(fw, x, y, z)
+            .and_then(|[x, y, z]| self.record_bound(fw, Workgroups::Count(x, y, z)))
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`Kernel::run_elements`].
+    ///
+    /// Returns the errors of [`Kernel::workgroup_count`] and [`Kernel::run_bound_checked`] instead of panicking.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_elements_checked(
+        &self,
+        fw: &Framework,
+        x: u64,
+        y: u64,
+        z: u64,
+    ) -> GpuResult<Submission> {
+        let [x, y, z] = self.workgroup_count(fw, x, y, z)?;
+
+        self.run_bound_checked(fw, x, y, z)
+    }
+
+    /// Executes this [`Kernel`] with the bindings of [`Kernel::rebind_set`], with one invocation per element
+    /// of the `elements` elements in the X dimension, in as many chunks of `max_compute_workgroups_per_dimension`
+    /// workgroups as needed.
+    ///
+    /// Unlike [`Kernel::run_elements`], the number of elements is not limited by the workgroups of a single
+    /// dispatch: the chunks are dispatched in the same compute pass, each with the index of its first
+    /// element in the uniform `base_offset` of the set of [`KernelBuilder::split_dispatch`].
+    /// The shader must add it to its invocation index:
+    /// ```ignore
+    /// @group(1) @binding(0) var<uniform> base_offset: u32;
+    ///
+    /// @compute @workgroup_size(64)
+    /// fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    ///     let index = base_offset + global_id.x;
+    ///
+    ///     if (index >= arrayLength(&output)) {
+    ///         return;
+    ///     }
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// If the [`Kernel`] was not built with [`KernelBuilder::split_dispatch`], if `elements` exceeds
+    /// its maximum, see [`GpuError::SplitCapacityExceeded`], or if a set of the [`Kernel`] was never bound.
+    pub fn run_split(&self, fw: &Framework, elements: u64) -> Submission {
+        self.split_workgroups(elements)
+            .and_then(|workgroups| self.record_bound(fw, workgroups))
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`Kernel::run_split`].
+    ///
+    /// Returns [`GpuError::SplitCapacityExceeded`] if `elements` exceeds the maximum of
+    /// [`KernelBuilder::split_dispatch`], or the errors of [`Kernel::run_bound_checked`] instead of panicking.
+    ///
+    /// # Panics
+    /// If the [`Kernel`] was not built with [`KernelBuilder::split_dispatch`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_split_checked(&self, fw: &Framework, elements: u64) -> GpuResult<Submission> {
+        if fw.is_device_lost() {
+            return Err(GpuError::DeviceLost);
+        }
+
+        let workgroups = self.split_workgroups(elements)?;
+
+        fw.error_scope(|| self.record_bound(fw, workgroups))?
+    }
+
+    fn split_workgroups(&self, elements: u64) -> GpuResult<Workgroups<'static>> {
+        let split = self
+            .split
+            .as_ref()
+            .expect("The kernel was not built with `KernelBuilder::split_dispatch`");
+
+        if elements > split.max_elements {
+            return Err(GpuError::SplitCapacityExceeded {
+                elements,
+                max: split.max_elements,
+            });
+        }
+
+        let workgroup_size = self.workgroup_size.map_or(1, |size| size[0]) as u64;
+
+        Ok(Workgroups::Split(elements.div_ceil(workgroup_size)))
+    }
+
+    /// Executes this [`Kernel`] with the bindings of [`Kernel::rebind_set`], dispatching the workgroups
+    /// of the arguments at the index `index` of `args`, as read by the GPU when the [`Kernel`] runs.
+    ///
+    /// `args` must be created with [`GpuBuffer::from_indirect_args`], or with the [`wgpu::BufferUsages::INDIRECT`]
+    /// usage. Its arguments can be written by previous kernels.
+    ///
+    /// # Panics
+    /// If a set of the [`Kernel`] was never bound, see [`GpuError::UnboundEntries`], or if `args`
+    /// cannot be read by an indirect dispatch, see [`GpuError::MissingIndirectUsage`] and [`GpuError::IndirectArgsOutOfRange`].
+    pub fn run_indirect(
+        &self,
+        fw: &Framework,
+        args: &GpuBuffer<DispatchIndirectArgs>,
+        index: u64,
+    ) -> Submission {
+        Workgroups::indirect(args, index)
+            .and_then(|workgroups| self.record_bound(fw, workgroups))
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`Kernel::run_indirect`].
+    ///
+    /// Returns the errors of [`Kernel::run_bound_checked`], [`GpuError::MissingIndirectUsage`]
+    /// or [`GpuError::IndirectArgsOutOfRange`] instead of panicking.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_indirect_checked(
+        &self,
+        fw: &Framework,
+        args: &GpuBuffer<DispatchIndirectArgs>,
+        index: u64,
+    ) -> GpuResult<Submission> {
+        if fw.is_device_lost() {
+            return Err(GpuError::DeviceLost);
+        }
+
+        let workgroups = Workgroups::indirect(args, index)?;
+
+        fw.error_scope(|| self.record_bound(fw, workgroups))?
+    }
+
     /// executes this [`Kernel`] with the give bindings.
     ///
     /// [`Kernel`] will dispatch `x`, `y` and `z` workgroups per dimension.
-    pub fn run<'fw>(&self, fw: &'fw Framework, bindings: Vec<SetBindings>, x: u32, y: u32, z: u32) {
-        let mut encoder = fw
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Kernel::enqueue"),
+    ///
+    /// The bind groups of `bindings` are created on each call. Kernels running many times with the
+    /// same bindings can bind them once with [`KernelBuilder::bind_set`] or [`Kernel::rebind_set`]
+    /// and run with [`Kernel::run_bound`] instead.
+    ///
+    /// The dispatch is only submitted: its completion can be awaited with the returned [`Submission`],
+    /// see [`Framework::wait_for`] and [`Framework::is_done`]. The other `run` methods of the [`Kernel`]
+    /// return it too.
+    ///
+    /// # Panics
+    /// If the bindings do not match the layouts of the [`Kernel`], see [`GpuError::BindingMismatch`].
+    pub fn run(
+        &self,
+        fw: &Framework,
+        bindings: Vec<SetBindings>,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> Submission {
+        self.record(fw, &bindings, &[], &[], x, y, z)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Same as [`Kernel::run`], with the dynamic `offsets` in bytes of the entries added with
+    /// [`SetLayout::add_dynamic_buffer`] and [`SetLayout::add_dynamic_uniform_buffer`], ordered
+    /// by set then by binding.
+    ///
+    /// The bindings stay the same whatever the offsets, so a [`Kernel`] can run
+    /// once per object with the parameters of each object packed in a single buffer:
+    /// ```no_run
+    /// # let fw = gpgpu::Framework::default();
+    /// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+    /// # let mut layout = gpgpu::SetLayout::default();
+    /// # layout.add_dynamic_uniform_buffer(0);
+    /// # let kernel = gpgpu::Kernel::new(&fw, &shader, "main", vec![layout]);
+    /// # let objects = 16;
+    /// # type Params = [f32; 4];
+    /// # use gpgpu::BufOps;
+    /// // Each block of parameters is padded to the alignment of the offsets, usually 256 bytes.
+    /// let stride = fw.limits().min_uniform_buffer_offset_alignment;
+    /// let blocks = objects * stride as u64 / std::mem::size_of::<Params>() as u64;
+    /// let params = gpgpu::GpuUniformBuffer::<Params>::with_capacity(&fw, blocks);
+    ///
+    /// for object in 0..objects as u32 {
+    ///     let bindings = gpgpu::SetBindings::default().add_dynamic_uniform_buffer(0, &params);
+    ///     kernel.run_with_offsets(&fw, vec![bindings], &[object * stride], 1, 1, 1);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// If the offsets do not match the dynamic entries of the [`Kernel`] (see
+    /// [`GpuError::DynamicOffsetsMismatch`] and [`GpuError::MisalignedDynamicOffset`]),
+    /// or if the bindings do not match its layouts.
+    pub fn run_with_offsets(
+        &self,
+        fw: &Framework,
+        bindings: Vec<SetBindings>,
+        offsets: &[u32],
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> Submission {
+        self.record(fw, &bindings, &[], offsets, x, y, z)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`Kernel::run_with_offsets`].
+    ///
+    /// Checks the offsets before recording the pass, returning [`GpuError::DynamicOffsetsMismatch`]
+    /// if their number is not the number of dynamic entries of the [`Kernel`], or
+    /// [`GpuError::MisalignedDynamicOffset`], as well as the errors of [`Kernel::run_checked`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_with_offsets_checked(
+        &self,
+        fw: &Framework,
+        bindings: Vec<SetBindings>,
+        offsets: &[u32],
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> GpuResult<Submission> {
+        self.check_offsets(offsets)?;
+
+        if fw.is_device_lost() {
+            return Err(GpuError::DeviceLost);
+        }
+
+        fw.error_scope(|| self.record(fw, &bindings, &[], offsets, x, y, z))?
+    }
+
+    /// Same as [`Kernel::run`], setting the push constants of the [`Kernel`] to `push_constants`.
+    /// See [`Kernel::with_push_constants`].
+    ///
+    /// # Panics
+    /// If the size of `T` is not the push constants size of the [`Kernel`],
+    /// or if the bindings do not match its layouts.
+    pub fn run_with_push_constants<T>(
+        &self,
+        fw: &Framework,
+        bindings: Vec<SetBindings>,
+        push_constants: &T,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> Submission
+    where
+        T: bytemuck::Pod,
+    {
+        let push_constants = self
+            .push_constant_bytes(push_constants)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        self.record(fw, &bindings, push_constants, &[], x, y, z)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`Kernel::run_with_push_constants`].
+    ///
+    /// Returns [`GpuError::PushConstantsMismatch`] if the size of `T` is not the push constants
+    /// size of the [`Kernel`], or the errors of [`Kernel::run_checked`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_with_push_constants_checked<T>(
+        &self,
+        fw: &Framework,
+        bindings: Vec<SetBindings>,
+        push_constants: &T,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> GpuResult<Submission>
+    where
+        T: bytemuck::Pod,
+    {
+        let push_constants = self.push_constant_bytes(push_constants)?;
+
+        if fw.is_device_lost() {
+            return Err(GpuError::DeviceLost);
+        }
+
+        fw.error_scope(|| self.record(fw, &bindings, push_constants, &[], x, y, z))?
+    }
+
+    fn push_constant_bytes<'a, T>(&self, push_constants: &'a T) -> GpuResult<&'a [u8]>
+    where
+        T: bytemuck::Pod,
+    {
+        let size = std::mem::size_of::<T>() as u32;
+
+        if size != self.push_constants_size {
+            return Err(GpuError::PushConstantsMismatch {
+                size,
+                expected: self.push_constants_size,
+            });
+        }
+
+        Ok(bytemuck::bytes_of(push_constants))
+    }
+
+    /// Checks that the dynamic `offsets` match the dynamic entries of the [`Kernel`].
+    fn check_offsets(&self, offsets: &[u32]) -> GpuResult<()> {
+        let expected = self.dynamic_entries.iter().map(Vec::len).sum();
+
+        if offsets.len() != expected {
+            return Err(GpuError::DynamicOffsetsMismatch {
+                count: offsets.len(),
+                expected,
             });
+        }
 
+        for (set, (entries, set_offsets)) in self
+            .dynamic_entries
+            .iter()
+            .zip(self.set_offsets(offsets))
+            .enumerate()
+        {
+            for (&offset, &(binding, alignment)) in set_offsets.iter().zip(entries) {
+                if !offset.is_multiple_of(alignment) {
+                    return Err(GpuError::MisalignedDynamicOffset {
+                        set: set as u32,
+                        binding,
+                        offset,
+                        alignment,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Splits the checked dynamic `offsets` between the sets of the [`Kernel`].
+    fn set_offsets<'o>(&'o self, mut offsets: &'o [u32]) -> impl Iterator<Item = &'o [u32]> + 'o {
+        self.dynamic_entries.iter().map(move |entries| {
+            let (set_offsets, rest) = offsets.split_at(entries.len());
+            offsets = rest;
+
+            set_offsets
+        })
+    }
+
+    /// Checks the dynamic `offsets`, creates the bind groups of `bindings` and dispatches the [`Kernel`].
+    #[allow(clippy::too_many_arguments)]
+    fn record(
+        &self,
+        fw: &Framework,
+        bindings: &[SetBindings],
+        push_constants: &[u8],
+        offsets: &[u32],
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> GpuResult<Submission> {
+        self.check_offsets(offsets)?;
+        let bind_groups = self.bind_groups(fw, bindings)?;
+
+        let mut recorder = fw.create_recorder();
+        recorder.raw(|encoder| {
+            self.encode(
+                encoder,
+                bind_groups.iter().zip(self.set_offsets(offsets)),
+                push_constants,
+                Workgroups::Count(x, y, z),
+                None,
+            )
+        });
+        Ok(recorder.submit())
+    }
+
+    /// Dispatches the [`Kernel`] with the bind groups of [`Kernel::rebind_set`].
+    ///
+    /// Nothing is allocated besides the command encoder, as [`Kernel::run_bound`] may run thousands of times.
+    fn record_bound(&self, fw: &Framework, workgroups: Workgroups) -> GpuResult<Submission> {
+        let mut recorder = fw.create_recorder();
+        recorder.raw(|encoder| self.encode_bound(encoder, workgroups, None))?;
+        Ok(recorder.submit())
+    }
+
+    /// Records the dispatch of the [`Kernel`] with the bind groups of [`Kernel::rebind_set`] into `encoder`,
+    /// measured by the pipeline statistics query `statistics` if any. Nothing is recorded on failure.
+    pub(crate) fn encode_bound(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        workgroups: Workgroups,
+        statistics: Option<(&wgpu::QuerySet, u32)>,
+    ) -> GpuResult<()> {
+        // The split set is bound with the offset of its first chunk, also by the other dispatches.
+        let offsets: &[u32] = match self.split {
+            Some(_) => &[0],
+            None => &[],
+        };
+        self.check_offsets(offsets)?;
+
+        // Only kernels with shared sets allocate, to lock them.
+        let guards = self
+            .shared
+            .iter()
+            .flatten()
+            .map(SharedSet::read)
+            .collect::<Vec<_>>();
+        let bind_groups = || {
+            let mut guards = guards.iter();
+
+            self.bound
+                .iter()
+                .zip(&self.shared)
+                .map(move |(bound, shared)| match shared {
+                    Some(_) => guards.next().and_then(|guard| guard.as_ref()),
+                    None => bound.as_deref(),
+                })
+        };
+
+        if let Some(set) = bind_groups().position(|bind_group| bind_group.is_none()) {
+            return Err(self.set_layouts[set].unbound(set as u32));
+        }
+
+        self.encode(
+            encoder,
+            bind_groups().flatten().zip(self.set_offsets(offsets)),
+            &[],
+            workgroups,
+            statistics,
+        );
+
+        Ok(())
+    }
+
+    /// Creates the bind group of `bindings` for the set `set`, checking it matches its layout.
+    fn bind_group(
+        &self,
+        fw: &Framework,
+        set: u32,
+        bindings: &SetBindings,
+    ) -> GpuResult<wgpu::BindGroup> {
+        let index = set as usize;
+
+        if index >= self.layouts.len() {
+            panic!("The kernel has no set {}", set)
+        }
+
+        bindings.into_bind_group(fw, set, &self.layouts[index], &self.set_layouts[index])
+    }
+
+    /// Creates the bind groups of `bindings`, checking they match the layouts of the [`Kernel`].
+    fn bind_groups(
+        &self,
+        fw: &Framework,
+        bindings: &[SetBindings],
+    ) -> GpuResult<Vec<wgpu::BindGroup>> {
         if bindings.len() != self.layouts.len() {
             panic!("The amount of layouts must match the amount of the binding groups")
         }
 
-        let bind_groups = bindings
+        bindings
             .iter()
             .zip(self.layouts.iter())
-            .zip(self.entry_types.iter())
-            .map(|((binding, layout), entry_type)| binding.into_bind_group(fw, layout, entry_type))
-            .collect::<Vec<_>>();
+            .zip(self.set_layouts.iter())
+            .enumerate()
+            .map(|(set, ((binding, layout), set_layout))| {
+                binding.into_bind_group(fw, set as u32, layout, set_layout)
+            })
+            .collect()
+    }
 
-        {
-            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("Kernel::enqueue"),
+    /// Records the compute pass of the dispatch of the [`Kernel`] into `encoder`, each bind group
+    /// being set with its dynamic offsets, and the dispatch wrapped by the `statistics` query if any.
+    fn encode<'g>(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_groups: impl Iterator<Item = (&'g wgpu::BindGroup, &'g [u32])>,
+        push_constants: &[u8],
+        workgroups: Workgroups,
+        statistics: Option<(&wgpu::QuerySet, u32)>,
+    ) {
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Kernel::enqueue"),
+        });
+
+        cpass.set_pipeline(&self.pipeline);
+
+        for (bind_id, (binds, offsets)) in bind_groups.enumerate() {
+            cpass.set_bind_group(bind_id as u32, binds, offsets)
+        }
+
+        if !push_constants.is_empty() {
+            cpass.set_push_constants(0, push_constants);
+        }
+
+        cpass.insert_debug_marker(&self.function_name);
+        if let Some((query_set, index)) = statistics {
+            cpass.begin_pipeline_statistics_query(query_set, index);
+        }
+
+        match workgroups {
+            Workgroups::Count(x, y, z) => cpass.dispatch_workgroups(x, y, z),
+            Workgroups::Indirect(args, offset) => cpass.dispatch_workgroups_indirect(args, offset),
+            Workgroups::Split(mut workgroups) => {
+                let split = self.split.as_ref().unwrap();
+                let bases = self.bound[split.set as usize].as_ref().unwrap();
+                let mut offset = 0;
+
+                while workgroups > 0 {
+                    let count = std::cmp::min(workgroups, split.chunk_workgroups as u64);
+
+                    cpass.set_bind_group(split.set, bases, &[offset]);
+                    cpass.dispatch_workgroups(count as u32, 1, 1);
+
+                    workgroups -= count;
+                    offset += split.alignment;
+                }
+            }
+        }
+
+        if statistics.is_some() {
+            cpass.end_pipeline_statistics_query();
+        }
+    }
+
+    /// Fallible version of [`Kernel::run`].
+    ///
+    /// Returns [`GpuError::BindingMismatch`] if the bindings do not match the layouts of the [`Kernel`],
+    /// the `wgpu` validation error (e.g. bindings not matching the shader) instead of panicking,
+    /// or [`GpuError::DeviceLost`] if the device of `fw` was lost.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn run_checked(
+        &self,
+        fw: &Framework,
+        bindings: Vec<SetBindings>,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> GpuResult<Submission> {
+        if fw.is_device_lost() {
+            return Err(GpuError::DeviceLost);
+        }
+
+        fw.error_scope(|| self.record(fw, &bindings, &[], &[], x, y, z))?
+    }
+}
+
+/// Configures the creation of a [`Kernel`]. See [`Kernel::builder`].
+///
+/// A builder can be built several times, e.g. to specialize the same shader with
+/// different constants, each [`Kernel`] having its own pipeline.
+///
+/// # Example
+/// ```no_run
+/// # let fw = gpgpu::Framework::default();
+/// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "blur.wgsl").unwrap();
+/// # let layouts = gpgpu::SetLayout::from_shader(&shader, "main").unwrap();
+/// let builder = gpgpu::Kernel::builder(&fw, &shader, "main").layouts(layouts);
+///
+/// let small_blur = builder.clone().constant("RADIUS", 1.0).build().unwrap();
+/// let large_blur = builder.constant("RADIUS", 8.0).build().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct KernelBuilder<'a> {
+    fw: &'a Framework,
+    shader: &'a Shader,
+    function_name: &'a str,
+    layouts: Vec<SetLayout>,
+    push_constants_size: u32,
+    constants: Vec<(String, f64)>,
+    bindings: Vec<(u32, SetBindings<'a>)>,
+    shared_sets: Vec<(u32, SharedSet)>,
+    workgroup_size: Option<[u32; 3]>,
+    split: Option<(u32, u64)>,
+}
+
+impl<'a> KernelBuilder<'a> {
+    /// Sets the entry point of the [`Kernel`], replacing the one given to [`Kernel::builder`].
+    ///
+    /// A builder can be cloned to build kernels of several entry points from the same shader,
+    /// see also [`KernelBuilder::build_many`].
+    pub fn entry_point(mut self, name: &'a str) -> Self {
+        self.function_name = name;
+        self
+    }
+
+    /// Sets the [`SetLayout`]s of the [`Kernel`], one per bind group.
+    pub fn layouts(mut self, layouts: Vec<SetLayout>) -> Self {
+        self.layouts = layouts;
+        self
+    }
+
+    /// Sets the size in bytes of the push constants of the [`Kernel`].
+    /// See [`Kernel::with_push_constants`].
+    pub fn push_constants(mut self, size: u32) -> Self {
+        self.push_constants_size = size;
+        self
+    }
+
+    /// Sets the `override` constant of the shader named `name`, or whose `@id` is `name`, to `value`.
+    ///
+    /// The value is converted to the type of the constant: booleans are `true` unless `value` is 0,
+    /// and integers must be exact. Constants not set keep their default values.
+    ///
+    /// `override` constants are only supported by WGSL shaders, by rewriting them as `const`
+    /// declarations and compiling the shader again.
+    ///
+    /// Integer overrides can set the `@workgroup_size` of the entry points.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// override RADIUS: i32 = 2;
+    /// @id(1) override SCALE: f32;
+    /// override BLOCK_SIZE: u32 = 64u;
+    ///
+    /// @compute @workgroup_size(BLOCK_SIZE)
+    /// fn main() {}
+    /// ```
+    pub fn constant(mut self, name: &str, value: f64) -> Self {
+        self.constants.push((name.to_owned(), value));
+        self
+    }
+
+    /// Sets the workgroup size of the [`Kernel`], used by [`Kernel::run_elements`], for shaders whose
+    /// `@workgroup_size` cannot be reflected, e.g. SPIR-V or GLSL ones.
+    ///
+    /// [`KernelBuilder::build`] fails with [`GpuError::WorkgroupSizeMismatch`] if it does not match
+    /// the reflected `@workgroup_size`.
+    pub fn workgroup_size(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.workgroup_size = Some([x, y, z]);
+        self
+    }
+
+    /// Binds `bindings` to the set `set` of the [`Kernel`], whose bind group is created once by
+    /// [`KernelBuilder::build`]. The [`Kernel`] then runs with [`Kernel::run_bound`] without
+    /// creating any bind group, and the set can be bound again with [`Kernel::rebind_set`].
+    pub fn bind_set(mut self, set: u32, bindings: SetBindings<'a>) -> Self {
+        self.bindings.push((set, bindings));
+        self
+    }
+
+    /// Uses `shared` as the set `set` of the [`Kernel`], replacing its layout by the one of the
+    /// [`SharedSet`]. The [`Kernel`] shares the bind group layout of the set, and runs with its
+    /// current bindings on each [`Kernel::run_bound`].
+    ///
+    /// The layout of the set is checked against the shader like the other layouts
+    /// by [`KernelBuilder::build`], see [`Kernel::try_new`].
+    pub fn shared_set(mut self, set: u32, shared: &SharedSet) -> Self {
+        self.shared_sets.push((set, shared.clone()));
+        self
+    }
+
+    /// Reserves the set `set` of the [`Kernel`] for the base offsets of [`Kernel::run_split`], covering up
+    /// to `max_elements` elements, at most [`u32::MAX`]. The layout of the set is a single dynamic uniform buffer
+    /// at binding 0, bound by the [`Kernel`] itself, which the shader declares as `var<uniform> base_offset: u32`.
+    ///
+    /// [`Kernel::run_bound`], [`Kernel::run_elements`] and [`Kernel::run_indirect`] still run the [`Kernel`]
+    /// as a single dispatch, whose `base_offset` is 0. [`Kernel::run`] and the other executions given
+    /// all the bindings cannot bind the set: they fail with [`GpuError::DynamicOffsetsMismatch`].
+    ///
+    /// The other sets of the [`Kernel`] cannot have dynamic entries. [`KernelBuilder::build`] fails with
+    /// [`GpuError::SplitDynamicEntry`] if they have any, with [`GpuError::UnknownWorkgroupSize`] if the
+    /// workgroup size of the shader is unknown, or with [`GpuError::SplitCapacityExceeded`] if `max_elements`
+    /// exceeds [`u32::MAX`].
+    pub fn split_dispatch(mut self, set: u32, max_elements: u64) -> Self {
+        self.split = Some((set, max_elements));
+        self
+    }
+
+    /// Creates the [`Kernel`].
+    ///
+    /// Fails with [`GpuError::UnknownEntryPoint`] if the shader can be reflected and has no such compute
+    /// entry point, like [`Kernel::try_with_push_constants`], with [`GpuError::InvalidConstant`] if a
+    /// constant is not declared by the shader, does not fit its type, has no value, or sizes a
+    /// `@workgroup_size` without being valued by an integer literal, or like
+    /// [`Kernel::try_rebind_set`] if the bindings of [`KernelBuilder::bind_set`] do not match the layouts.
+    ///
+    /// # Panics
+    /// If a set given to [`KernelBuilder::bind_set`] is not one of the layouts.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build(&self) -> GpuResult<Kernel> {
+        self.check_entry_point(self.function_name)?;
+        self.build_entry(self.function_name, None)
+    }
+
+    /// Creates a [`Kernel`] per entry point of `entry_points`, with the layouts, constants and
+    /// bindings of this builder, e.g. for the passes of a multi-pass algorithm sharing its buffers.
+    ///
+    /// The kernels share their bind group layouts, and the bind groups of [`KernelBuilder::bind_set`], which
+    /// are created once. [`KernelBuilder::workgroup_size`] applies to all the entry points.
+    ///
+    /// Fails like [`KernelBuilder::build`], before creating any kernel if an entry point is unknown.
+    ///
+    /// # Panics
+    /// If a set given to [`KernelBuilder::bind_set`] is not one of the layouts.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # let fw = gpgpu::Framework::default();
+    /// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+    /// let kernels = gpgpu::Kernel::builder(&fw, &shader, "init")
+    ///     .build_many(&["init", "step", "finalize"])
+    ///     .unwrap();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build_many(&self, entry_points: &[&str]) -> GpuResult<Vec<Kernel>> {
+        for entry_point in entry_points {
+            self.check_entry_point(entry_point)?;
+        }
+
+        let mut kernels: Vec<Kernel> = Vec::with_capacity(entry_points.len());
+
+        for entry_point in entry_points {
+            let kernel = self.build_entry(entry_point, kernels.first())?;
+            kernels.push(kernel);
+        }
+
+        Ok(kernels)
+    }
+
+    /// Checks that the shader has the compute `entry_point`, if it can be reflected.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn check_entry_point(&self, entry_point: &str) -> GpuResult<()> {
+        match self.shader.entry_points() {
+            Some(available) if !available.contains(&entry_point) => {
+                Err(GpuError::UnknownEntryPoint {
+                    name: entry_point.to_owned(),
+                    available: available.into_iter().map(str::to_owned).collect(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Creates the [`Kernel`] of `function_name`, reusing the bind group layouts and the bind groups
+    /// of [`KernelBuilder::bind_set`] of `template`, built by this builder.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_entry(&self, function_name: &str, template: Option<&Kernel>) -> GpuResult<Kernel> {
+        let fw = self.fw;
+
+        let mut layouts = self.layouts.clone();
+        let mut shared = vec![None; layouts.len()];
+
+        for (set, shared_set) in &self.shared_sets {
+            let set = *set as usize;
+
+            if layouts.len() <= set {
+                layouts.resize_with(set + 1, SetLayout::default);
+                shared.resize(set + 1, None);
+            }
+
+            layouts[set] = shared_set.layout().clone();
+            shared[set] = Some(shared_set.clone());
+        }
+
+        if let Some((set, _)) = self.split {
+            let set = set as usize;
+
+            if layouts.len() <= set {
+                layouts.resize_with(set + 1, SetLayout::default);
+                shared.resize(set + 1, None);
+            }
+
+            let mut layout = SetLayout::default();
+            layout.add_dynamic_uniform_buffer(0);
+            layouts[set] = layout;
+            shared[set] = None;
+        }
+
+        check_storage_access(fw, &layouts)?;
+        check_layouts(self.shader, function_name, &layouts)?;
+        if self.push_constants_size > 0 {
+            check_push_constants(fw, self.push_constants_size)?;
+        }
+
+        // Shaders without overrides still fail on unknown constants.
+        let specialized = if self.constants.is_empty() && self.shader.overrides.is_empty() {
+            None
+        } else {
+            let source = self.shader.wgsl.as_deref().unwrap_or_default();
+            let specialized = specialize(source, &self.shader.overrides, &self.constants, false)?;
+
+            Some(specialized).filter(|_| !self.constants.is_empty())
+        };
+
+        // Overrides may set the workgroup size.
+        let reflected = match &specialized {
+            Some(source) => naga::front::wgsl::parse_str(source)
+                .ok()
+                .and_then(|module| crate::reflection::workgroup_size(&module, function_name)),
+            None => self.shader.workgroup_size(function_name),
+        };
+        let workgroup_size = match (self.workgroup_size, reflected) {
+            (Some(size), Some(expected)) if size != expected => {
+                return Err(GpuError::WorkgroupSizeMismatch { size, expected })
+            }
+            (size, reflected) => size.or(reflected),
+        };
+
+        if let Some((split_set, max_elements)) = self.split {
+            if workgroup_size.is_none() {
+                return Err(GpuError::UnknownWorkgroupSize);
+            }
+
+            for (set, layout) in layouts.iter().enumerate() {
+                let dynamic = layout.layout_entry.iter().find(|entry| {
+                    matches!(
+                        entry.ty,
+                        wgpu::BindingType::Buffer {
+                            has_dynamic_offset: true,
+                            ..
+                        }
+                    )
+                });
+
+                if let Some(entry) = dynamic.filter(|_| set != split_set as usize) {
+                    return Err(GpuError::SplitDynamicEntry {
+                        set: set as u32,
+                        binding: entry.binding,
+                    });
+                }
+            }
+
+            if max_elements > u32::MAX as u64 {
+                return Err(GpuError::SplitCapacityExceeded {
+                    elements: max_elements,
+                    max: u32::MAX as u64,
+                });
+            }
+        }
+
+        let mut kernel = fw.error_scope(|| {
+            let module = specialized
+                .as_ref()
+                .map(|source| Shader::compile_wgsl(fw, source, Some(function_name)));
+
+            let bind_group_layouts = match template {
+                Some(template) => template.layouts.iter().cloned().map(Some).collect(),
+                None => shared
+                    .iter()
+                    .map(|set| set.as_ref().map(SharedSet::bind_group_layout))
+                    .collect(),
+            };
+
+            let mut kernel = Kernel::create(
+                fw,
+                module.as_ref().unwrap_or(&self.shader.module),
+                function_name,
+                layouts,
+                self.push_constants_size,
+                bind_group_layouts,
+                workgroup_size,
+            );
+            kernel.shared = shared;
+
+            if let Some((set, max_elements)) = self.split {
+                kernel.bind_split(fw, set, max_elements);
+            }
+
+            kernel
+        })?;
+
+        for (set, bindings) in &self.bindings {
+            match template {
+                // Same bind group layouts, so the bind groups are compatible.
+                Some(template) => {
+                    kernel.bound[*set as usize] = template.bound[*set as usize].clone()
+                }
+                None => kernel.try_rebind_set(fw, *set, bindings.clone())?,
+            }
+        }
+
+        Ok(kernel)
+    }
+}
+
+/// Checks that the device supports push constants of `size` bytes.
+fn check_push_constants(fw: &Framework, size: u32) -> GpuResult<()> {
+    let max = fw.limits().max_push_constant_size;
+
+    let reason = if !fw.features().contains(wgpu::Features::PUSH_CONSTANTS) {
+        "the device lacks the `PUSH_CONSTANTS` feature".to_owned()
+    } else if size > max {
+        format!("the device supports up to {} bytes", max)
+    } else if !size.is_multiple_of(wgpu::PUSH_CONSTANT_ALIGNMENT) {
+        format!(
+            "their size must be a multiple of {} bytes",
+            wgpu::PUSH_CONSTANT_ALIGNMENT
+        )
+    } else {
+        return Ok(());
+    };
+
+    Err(GpuError::UnsupportedPushConstants { size, reason })
+}
+
+/// Checks that the device can read the storage images declared with [`StorageAccess::ReadOnly`]
+/// or [`StorageAccess::ReadWrite`], which `wgpu` reports with a less helpful message.
+fn check_storage_access(fw: &Framework, layouts: &[SetLayout]) -> GpuResult<()> {
+    let adapter_formats = fw
+        .features()
+        .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+
+    for (set, layout) in layouts.iter().enumerate() {
+        for entry in &layout.layout_entry {
+            let (access, format) = match entry.ty {
+                wgpu::BindingType::StorageTexture { access, format, .. }
+                    if access != wgpu::StorageTextureAccess::WriteOnly =>
+                {
+                    (access, format)
+                }
+                _ => continue,
+            };
+
+            let reason = if !adapter_formats {
+                "the device lacks the `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` feature"
+            } else if !fw.image_format_capabilities(format).storage_read_write {
+                "the adapter cannot read storage images of this format"
+            } else {
+                continue;
+            };
+
+            return Err(GpuError::UnsupportedStorageAccess {
+                set: set as u32,
+                binding: entry.binding,
+                access,
+                format,
+                reason,
             });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    /// Doubles the elements of the buffer of binding 0.
+    const DOUBLE_SHADER: &str = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            if (id.x < arrayLength(&data)) {
+                data[id.x] = data[id.x] * 2u;
+            }
+        }";
+
+    /// Increments the element of each invocation of the buffer of binding 0, up to its length.
+    const COUNT_SHADER: &str = "
+        @group(0) @binding(0) var<storage, read_write> counts: array<u32>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            if (id.x < arrayLength(&counts)) {
+                counts[id.x] = counts[id.x] + 1u;
+            }
+        }";
+
+    fn shader(fw: &Framework, source: &str) -> Shader {
+        Shader::from_wgsl_string(fw, source.to_owned(), None).unwrap()
+    }
+
+    #[test]
+    fn rebind_set_replaces_bind_group() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, DOUBLE_SHADER);
+            let first = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+            let second = GpuBuffer::from_slice(fw, &[10u32, 20, 30]);
+
+            let mut kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &first))
+                .build()
+                .unwrap();
+            kernel.run_bound(fw, 1, 1, 1);
+
+            kernel.rebind_set(fw, 0, SetBindings::default().add_buffer(0, &second));
+            kernel.run_bound(fw, 1, 1, 1);
+
+            assert_eq!(first.read_vec_blocking().unwrap(), [2, 4, 6]);
+            assert_eq!(second.read_vec_blocking().unwrap(), [20, 40, 60]);
+        });
+    }
+
+    #[test]
+    fn rebind_set_mismatched_type() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, DOUBLE_SHADER);
+            let buffer = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+            let uniform = GpuUniformBuffer::from_slice(fw, &[0u32; 4]);
+
+            let mut kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &buffer))
+                .build()
+                .unwrap();
+            let result = kernel.try_rebind_set(
+                fw,
+                0,
+                SetBindings::default().add_uniform_buffer(0, &uniform),
+            );
+
+            assert!(matches!(
+                result,
+                Err(GpuError::BindingMismatch {
+                    set: 0,
+                    binding: 0,
+                    ..
+                })
+            ));
+
+            // The previous bindings are kept.
+            kernel.run_bound(fw, 1, 1, 1);
+            assert_eq!(buffer.read_vec_blocking().unwrap(), [2, 4, 6]);
+        });
+    }
+
+    #[test]
+    fn run_elements_covers_tail() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, COUNT_SHADER);
 
-            cpass.set_pipeline(&self.pipeline);
+            for &elements in &[1, 63, 64, 65, 1000] {
+                let counts = GpuBuffer::<u32>::with_capacity(fw, elements);
+                let kernel = Kernel::builder(fw, &shader, "main")
+                    .layouts(vec![new_set_layout!(0: Buffer)])
+                    .bind_set(0, SetBindings::default().add_buffer(0, &counts))
+                    .build()
+                    .unwrap();
 
-            for (bind_id, binds) in bind_groups.iter().enumerate() {
-                cpass.set_bind_group(bind_id as u32, &binds, &[])
+                assert_eq!(kernel.workgroup_size(), Some([64, 1, 1]));
+                assert_eq!(
+                    kernel.workgroup_count(fw, elements, 1, 1).unwrap(),
+                    [elements.div_ceil(64) as u32, 1, 1]
+                );
+
+                kernel.run_elements(fw, elements, 1, 1);
+                assert_eq!(
+                    counts.read_vec_blocking().unwrap(),
+                    vec![1; elements as usize],
+                    "{} elements",
+                    elements
+                );
             }
+        });
+    }
 
-            cpass.insert_debug_marker(self.function_name);
-            cpass.dispatch_workgroups(x, y, z);
+    #[test]
+    fn run_elements_2d_covers_tail() {
+        testing::with_framework(|fw| {
+            let (width, height) = (13u32, 9u32);
+            let shader = shader(
+                fw,
+                &format!(
+                    "@group(0) @binding(0) var<storage, read_write> counts: array<u32>;
+
+                    @compute @workgroup_size(8, 8)
+                    fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
+                        if (id.x < {width}u && id.y < {height}u) {{
+                            counts[id.y * {width}u + id.x] = counts[id.y * {width}u + id.x] + 1u;
+                        }}
+                    }}",
+                    width = width,
+                    height = height
+                ),
+            );
+            let counts = GpuBuffer::<u32>::with_capacity(fw, (width * height) as u64);
+
+            let kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &counts))
+                .build()
+                .unwrap();
+            kernel.run_elements(fw, width as u64, height as u64, 1);
+
+            assert_eq!(
+                counts.read_vec_blocking().unwrap(),
+                vec![1; (width * height) as usize]
+            );
+        });
+    }
+
+    #[test]
+    fn too_many_workgroups() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, COUNT_SHADER);
+            let kernel = Kernel::new(fw, &shader, "main", vec![new_set_layout!(0: Buffer)]);
+            let limit = fw.limits().max_compute_workgroups_per_dimension;
+            let elements = (limit as u64 + 1) * 64;
+
+            assert!(matches!(
+                kernel.workgroup_count(fw, elements, 1, 1),
+                Err(GpuError::TooManyWorkgroups {
+                    dimension: "X",
+                    count,
+                    limit: l,
+                }) if count == limit as u64 + 1 && l == limit
+            ));
+        });
+    }
+
+    /// Increments the elements of `counts` from the base offset of the split dispatches.
+    const SPLIT_COUNT_SHADER: &str = "
+        @group(0) @binding(0) var<storage, read_write> counts: array<u32>;
+        @group(1) @binding(0) var<uniform> base_offset: u32;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let index = base_offset + id.x;
+
+            if (index < arrayLength(&counts)) {
+                counts[index] = counts[index] + 1u;
+            }
+        }";
+
+    #[test]
+    fn run_split_touches_every_element_once() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, SPLIT_COUNT_SHADER);
+
+            // More workgroups than a single dispatch can hold, with a partial last workgroup.
+            let limit = fw.limits().max_compute_workgroups_per_dimension as u64;
+            let elements = (limit + 2) * 64 + 3;
+            let counts = GpuBuffer::<u32>::with_capacity(fw, elements);
+
+            let kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &counts))
+                .split_dispatch(1, elements)
+                .build()
+                .unwrap();
+            kernel.run_split(fw, elements);
+
+            let counts = counts.read_vec_blocking().unwrap();
+            let wrong = counts.iter().position(|&count| count != 1);
+            assert_eq!(wrong, None, "{:?}", wrong.map(|index| counts[index]));
+        });
+    }
+
+    #[test]
+    fn run_elements_of_split_kernel() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, SPLIT_COUNT_SHADER);
+            let counts = GpuBuffer::<u32>::with_capacity(fw, 100);
+
+            let kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &counts))
+                .split_dispatch(1, 100)
+                .build()
+                .unwrap();
+
+            // Single dispatches from the base offset 0, of two workgroups and one.
+            kernel.run_elements(fw, 70, 1, 1);
+            kernel.run_bound(fw, 1, 1, 1);
+
+            let counts = counts.read_vec_blocking().unwrap();
+            assert_eq!(counts[..64], [2; 64]);
+            assert_eq!(counts[64..], [1; 36]);
+        });
+    }
+
+    #[test]
+    fn split_dispatch_rejects_dynamic_entries() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, SPLIT_COUNT_SHADER);
+            let mut layout = SetLayout::default();
+            layout.add_dynamic_buffer(0, GpuBufferUsage::ReadWrite);
+
+            let result = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![layout])
+                .split_dispatch(1, 100)
+                .build();
+
+            assert!(matches!(
+                result,
+                Err(GpuError::SplitDynamicEntry { set: 0, binding: 0 })
+            ));
+        });
+    }
+
+    /// Scales and offsets the elements of `data` by the push constants, floats as the GL backend
+    /// only supports them.
+    const PUSH_CONSTANTS_SHADER: &str = "
+        struct Params {
+            scale: f32,
+            offset: f32,
         }
 
-        fw.queue.submit(Some(encoder.finish()));
+        var<push_constant> params: Params;
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            if (id.x < arrayLength(&data)) {
+                data[id.x] = u32(f32(data[id.x]) * params.scale + params.offset);
+            }
+        }";
+
+    #[test]
+    fn run_with_push_constants() {
+        testing::with_framework(|fw| {
+            if !fw.features().contains(wgpu::Features::PUSH_CONSTANTS) {
+                let shader = shader(fw, DOUBLE_SHADER);
+                let result = Kernel::try_with_push_constants(
+                    fw,
+                    &shader,
+                    "main",
+                    vec![new_set_layout!(0: Buffer)],
+                    8,
+                );
+
+                assert!(matches!(
+                    result,
+                    Err(GpuError::UnsupportedPushConstants { size: 8, .. })
+                ));
+                return;
+            }
+
+            let shader = shader(fw, PUSH_CONSTANTS_SHADER);
+            let kernel = Kernel::try_with_push_constants(
+                fw,
+                &shader,
+                "main",
+                vec![new_set_layout!(0: Buffer)],
+                8,
+            )
+            .unwrap();
+            let data = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+            let bindings = || vec![SetBindings::default().add_buffer(0, &data)];
+
+            let result = kernel.run_with_push_constants_checked(
+                fw,
+                bindings(),
+                &[1f32, 0.0, 0.0, 0.0],
+                1,
+                1,
+                1,
+            );
+            assert!(matches!(
+                result,
+                Err(GpuError::PushConstantsMismatch {
+                    size: 16,
+                    expected: 8
+                })
+            ));
+
+            // The GL backend of `wgpu` 0.15 reads the push constants unaligned when setting them,
+            // which aborts debug builds.
+            if cfg!(debug_assertions) && fw.adapter_info().backend == wgpu::Backend::Gl {
+                return;
+            }
+
+            // Each execution has its own values.
+            kernel
+                .run_with_push_constants_checked(fw, bindings(), &[3f32, 10.0], 1, 1, 1)
+                .unwrap();
+            kernel
+                .run_with_push_constants_checked(fw, bindings(), &[2f32, 1.0], 1, 1, 1)
+                .unwrap();
+            assert_eq!(data.read_vec_blocking().unwrap(), [27, 33, 39]);
+        });
+    }
+
+    /// Adds the elements of the regions of `a` and `b`, moved by dynamic offsets, into `sums`.
+    const DYNAMIC_SUM_SHADER: &str = "
+        @group(0) @binding(0) var<storage, read> a: array<u32>;
+        @group(0) @binding(1) var<storage, read> b: array<u32>;
+        @group(0) @binding(2) var<storage, read_write> sums: array<u32>;
+
+        @compute @workgroup_size(4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            sums[id.x] = a[id.x] + b[id.x];
+        }";
+
+    /// Builds the kernel of [`DYNAMIC_SUM_SHADER`], returning it with the alignment of its offsets.
+    fn dynamic_sum(fw: &Framework) -> (Kernel, u32) {
+        let shader = shader(fw, DYNAMIC_SUM_SHADER);
+        let mut layout = SetLayout::default();
+        layout.add_dynamic_buffer(0, GpuBufferUsage::ReadOnly);
+        layout.add_dynamic_buffer(1, GpuBufferUsage::ReadOnly);
+        layout.add_buffer(2, GpuBufferUsage::ReadWrite);
+
+        let kernel = Kernel::new(fw, &shader, "main", vec![layout]);
+        let alignment = fw.limits().min_storage_buffer_offset_alignment;
+
+        (kernel, alignment)
+    }
+
+    #[test]
+    fn run_with_offsets_reads_regions() {
+        testing::with_framework(|fw| {
+            let (kernel, alignment) = dynamic_sum(fw);
+            let stride = alignment / 4;
+
+            // Three blocks, one per offset alignment: the elements of the block `b` start at `100 * b`.
+            let blocks = GpuBuffer::from_iterator(
+                fw,
+                (0..3 * stride).map(|i| (i / stride) * 100 + i % stride),
+            );
+            let sums = GpuBuffer::<u32>::with_capacity(fw, 4);
+
+            let bindings = SetBindings::default()
+                .add_dynamic_buffer(0, &blocks, 4)
+                .add_dynamic_buffer(1, &blocks, 4)
+                .add_buffer(2, &sums);
+            kernel
+                .run_with_offsets_checked(fw, vec![bindings], &[2 * alignment, alignment], 1, 1, 1)
+                .unwrap();
+
+            assert_eq!(sums.read_vec_blocking().unwrap(), [300, 302, 304, 306]);
+        });
+    }
+
+    #[test]
+    fn run_with_offsets_mismatched_count() {
+        testing::with_framework(|fw| {
+            let (kernel, _) = dynamic_sum(fw);
+            let buffer = GpuBuffer::<u32>::with_capacity(fw, 4);
+
+            let bindings = SetBindings::default()
+                .add_dynamic_buffer(0, &buffer, 4)
+                .add_dynamic_buffer(1, &buffer, 4)
+                .add_buffer(2, &buffer);
+            let result = kernel.run_with_offsets_checked(fw, vec![bindings], &[0], 1, 1, 1);
+
+            assert!(matches!(
+                result,
+                Err(GpuError::DynamicOffsetsMismatch {
+                    count: 1,
+                    expected: 2
+                })
+            ));
+        });
+    }
+
+    #[test]
+    fn run_with_offsets_misaligned() {
+        testing::with_framework(|fw| {
+            let (kernel, alignment) = dynamic_sum(fw);
+            let buffer = GpuBuffer::<u32>::with_capacity(fw, alignment as u64);
+
+            let bindings = SetBindings::default()
+                .add_dynamic_buffer(0, &buffer, 4)
+                .add_dynamic_buffer(1, &buffer, 4)
+                .add_buffer(2, &buffer);
+            let result = kernel.run_with_offsets_checked(fw, vec![bindings], &[0, 16], 1, 1, 1);
+
+            assert!(matches!(
+                result,
+                Err(GpuError::MisalignedDynamicOffset {
+                    set: 0,
+                    binding: 1,
+                    offset: 16,
+                    alignment: a,
+                }) if a == alignment
+            ));
+        });
+    }
+
+    #[test]
+    fn run_indirect_with_written_args() {
+        testing::with_framework(|fw| {
+            let args_shader = shader(
+                fw,
+                "
+                struct DispatchIndirectArgs {
+                    x: u32,
+                    y: u32,
+                    z: u32,
+                }
+
+                @group(0) @binding(0) var<storage, read_write> args: DispatchIndirectArgs;
+
+                @compute @workgroup_size(1)
+                fn main() {
+                    args = DispatchIndirectArgs(2u, 1u, 1u);
+                }",
+            );
+            let args = GpuBuffer::from_indirect_args(fw, &[DispatchIndirectArgs::default()]);
+            Kernel::builder(fw, &args_shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &args))
+                .build()
+                .unwrap()
+                .run_bound(fw, 1, 1, 1);
+
+            let count_shader = shader(fw, COUNT_SHADER);
+            let counts = GpuBuffer::<u32>::with_capacity(fw, 200);
+            let kernel = Kernel::builder(fw, &count_shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &counts))
+                .build()
+                .unwrap();
+            kernel.run_indirect(fw, &args, 0);
+            kernel.run_indirect_checked(fw, &args, 0).unwrap();
+
+            let counts = counts.read_vec_blocking().unwrap();
+            assert_eq!(counts[..128], [2; 128]);
+            assert_eq!(counts[128..], [0; 72]);
+
+            assert!(matches!(
+                kernel.run_indirect_checked(fw, &args, 1),
+                Err(GpuError::IndirectArgsOutOfRange { index: 1, len: 1 })
+            ));
+            let without_usage = GpuBuffer::from_slice(fw, &[DispatchIndirectArgs::default()]);
+            assert!(matches!(
+                kernel.run_indirect_checked(fw, &without_usage, 0),
+                Err(GpuError::MissingIndirectUsage)
+            ));
+        });
+    }
+
+    #[test]
+    fn shared_set_follows_rebind() {
+        testing::with_framework(|fw| {
+            let double_shader = shader(fw, DOUBLE_SHADER);
+            let count_shader = shader(fw, COUNT_SHADER);
+            let first = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+            let second = GpuBuffer::from_slice(fw, &[10u32, 20, 30]);
+
+            let shared = SharedSet::new(fw, new_set_layout!(0: Buffer));
+            let double = Kernel::builder(fw, &double_shader, "main")
+                .shared_set(0, &shared)
+                .build()
+                .unwrap();
+            let count = Kernel::builder(fw, &count_shader, "main")
+                .shared_set(0, &shared)
+                .build()
+                .unwrap();
+
+            assert!(matches!(
+                double.run_bound_checked(fw, 1, 1, 1),
+                Err(GpuError::UnboundEntries { .. })
+            ));
+
+            shared.rebind(fw, SetBindings::default().add_buffer(0, &first));
+            double.run_bound(fw, 1, 1, 1);
+            count.run_bound(fw, 1, 1, 1);
+
+            shared.rebind(fw, SetBindings::default().add_buffer(0, &second));
+            count.run_bound(fw, 1, 1, 1);
+            double.run_bound(fw, 1, 1, 1);
+
+            assert_eq!(first.read_vec_blocking().unwrap(), [3, 5, 7]);
+            assert_eq!(second.read_vec_blocking().unwrap(), [22, 42, 62]);
+        });
+    }
+
+    #[test]
+    fn run_timed_source() {
+        testing::with_framework(|fw| {
+            let shader = shader(fw, DOUBLE_SHADER);
+            let data = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+
+            let kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &data))
+                .build()
+                .unwrap();
+            let timing = kernel.run_timed(fw, 1, 1, 1).unwrap();
+
+            // Devices without timestamp queries, e.g. the GL backend, fall back to the wall clock.
+            let source = if fw.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+                TimingSource::Timestamps
+            } else {
+                TimingSource::WallClock
+            };
+            assert_eq!(timing.source, source);
+            if timing.source == TimingSource::WallClock {
+                assert!(timing.duration > std::time::Duration::ZERO);
+            }
+            assert_eq!(data.read_vec_blocking().unwrap(), [2, 4, 6]);
+        });
+    }
+
+    #[test]
+    fn build_many_entry_points() {
+        testing::with_framework(|fw| {
+            let shader = shader(
+                fw,
+                "
+                @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+                @compute @workgroup_size(64)
+                fn double(@builtin(global_invocation_id) id: vec3<u32>) {
+                    if (id.x < arrayLength(&data)) {
+                        data[id.x] = data[id.x] * 2u;
+                    }
+                }
+
+                @compute @workgroup_size(64)
+                fn increment(@builtin(global_invocation_id) id: vec3<u32>) {
+                    if (id.x < arrayLength(&data)) {
+                        data[id.x] = data[id.x] + 1u;
+                    }
+                }",
+            );
+            let data = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+            let builder = Kernel::builder(fw, &shader, "double")
+                .layouts(vec![new_set_layout!(0: Buffer)])
+                .bind_set(0, SetBindings::default().add_buffer(0, &data));
+
+            let kernels = builder.build_many(&["increment", "double"]).unwrap();
+            for kernel in &kernels {
+                kernel.run_bound(fw, 1, 1, 1);
+            }
+            assert_eq!(data.read_vec_blocking().unwrap(), [4, 6, 8]);
+
+            match builder.build_many(&["double", "missing"]) {
+                Err(GpuError::UnknownEntryPoint {
+                    name,
+                    mut available,
+                }) => {
+                    available.sort();
+                    assert_eq!(
+                        (name.as_str(), available),
+                        ("missing", vec!["double".to_owned(), "increment".to_owned()])
+                    );
+                }
+                _ => panic!("`missing` is not an entry point"),
+            }
+        });
     }
 }
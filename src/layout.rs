@@ -1,18 +1,29 @@
-use crate::{entry_type::EntryType, primitives::PixelInfo, GpuBufferUsage};
+use std::collections::HashMap;
+
+use crate::{
+    entry_type::EntryType, primitives::PixelInfo, GpuBufferUsage, GpuError, StorageAccess,
+};
 
 #[derive(Default, Clone)]
 pub struct SetLayout {
     pub(crate) layout_entry: Vec<wgpu::BindGroupLayoutEntry>,
     pub(crate) entry_type: Vec<EntryType>,
+    /// Shader variable names of the entries, by binding. Only known for reflected layouts.
+    pub(crate) names: HashMap<u32, String>,
 }
 
 /// Creates a new [SetLayout]
 ///
 /// Types:
 /// * UniformBuffer: Use it for uniforms
-/// * Buffer: Use it for buffers or arrays
+/// * Buffer: Use it for buffers or arrays, taking a [`GpuBufferUsage`](crate::GpuBufferUsage), read-write by default
 /// * ConstImage: Use it for images that cannot be changed
 /// * Image: Use it for images that can be changed
+/// * ConstImage3d / Image3d: Same as above, for 3D images
+/// * ConstImage1d / Image1d: Same as above, for 1D images
+/// * ConstImageArray / ImageArray: Same as above, for arrays of 2D images
+/// * Image / Image3d / Image1d / ImageArray can take a [`StorageAccess`](crate::StorageAccess), write-only by default
+/// * Sampler: Use it for samplers, with their [`wgpu::SamplerBindingType`]
 ///
 /// Example:
 /// ```
@@ -21,7 +32,8 @@ pub struct SetLayout {
 ///     0: UniformBuffer,
 ///     1: Image<Rgba8Uint>, // Replace Rgba8Uint with anything that implements [PixelInfo]
 ///     2: ConstImage<Rgba8Uint>,
-///     3: Buffer(gpgpu::GpuBufferUsage::ReadOnly)
+///     3: Buffer(gpgpu::GpuBufferUsage::ReadOnly),
+///     4: Image<Rgba8Uint>(gpgpu::StorageAccess::ReadWrite)
 /// );
 /// ```
 #[macro_export]
@@ -29,15 +41,51 @@ macro_rules! new_set_layout {
     (@add_entry $usage:expr, $layout:expr, $id:literal, Buffer) => {
         $layout.add_buffer($id, $usage);
     };
+    (@add_entry $layout:expr, $id:literal, Buffer) => {
+        $layout.add_buffer($id, $crate::GpuBufferUsage::ReadWrite);
+    };
+    (@add_entry $ty:expr, $layout:expr, $id:literal, Sampler) => {
+        $layout.add_sampler($id, $ty);
+    };
     (@add_entry $layout:expr, $id:literal, UniformBuffer) => {
         $layout.add_uniform_buffer($id);
     };
+    (@add_entry $p:ty, $access:expr, $layout:expr, $id:literal, Image) => {
+        $layout.add_image_with_access::<$p>($id, $access);
+    };
+    (@add_entry $p:ty, $access:expr, $layout:expr, $id:literal, Image3d) => {
+        $layout.add_image_3d_with_access::<$p>($id, $access);
+    };
+    (@add_entry $p:ty, $access:expr, $layout:expr, $id:literal, Image1d) => {
+        $layout.add_image_1d_with_access::<$p>($id, $access);
+    };
+    (@add_entry $p:ty, $access:expr, $layout:expr, $id:literal, ImageArray) => {
+        $layout.add_image_array_with_access::<$p>($id, $access);
+    };
     (@add_entry $p:ty, $layout:expr, $id:literal, Image) => {
         $layout.add_image::<$p>($id);
     };
     (@add_entry $p:ty, $layout:expr, $id:literal, ConstImage) => {
         $layout.add_const_image::<$p>($id);
     };
+    (@add_entry $p:ty, $layout:expr, $id:literal, Image3d) => {
+        $layout.add_image_3d::<$p>($id);
+    };
+    (@add_entry $p:ty, $layout:expr, $id:literal, ConstImage3d) => {
+        $layout.add_const_image_3d::<$p>($id);
+    };
+    (@add_entry $p:ty, $layout:expr, $id:literal, Image1d) => {
+        $layout.add_image_1d::<$p>($id);
+    };
+    (@add_entry $p:ty, $layout:expr, $id:literal, ConstImage1d) => {
+        $layout.add_const_image_1d::<$p>($id);
+    };
+    (@add_entry $p:ty, $layout:expr, $id:literal, ImageArray) => {
+        $layout.add_image_array::<$p>($id);
+    };
+    (@add_entry $p:ty, $layout:expr, $id:literal, ConstImageArray) => {
+        $layout.add_const_image_array::<$p>($id);
+    };
     ($($id:literal: $ty:tt$(<$p:ty>)?$(($usage:expr))?),+) => {{
         let mut layout = $crate::layout::SetLayout::default();
 
@@ -50,6 +98,11 @@ macro_rules! new_set_layout {
 }
 
 impl SetLayout {
+    /// Adds a [`GpuBuffer`](crate::GpuBuffer) entry at `bind_id`, bound as a storage buffer.
+    ///
+    /// `usage` must match the access declared by the shader, `read` for [`GpuBufferUsage::ReadOnly`]
+    /// and `read_write` for [`GpuBufferUsage::ReadWrite`], or the creation of the [`Kernel`](crate::Kernel)
+    /// fails with [`GpuError::LayoutMismatch`].
     pub fn add_buffer(&mut self, bind_id: u32, usage: GpuBufferUsage) {
         let entry = wgpu::BindGroupLayoutEntry {
             binding: bind_id,
@@ -68,6 +121,23 @@ impl SetLayout {
         self.entry_type.push(EntryType::Buffer)
     }
 
+    /// Adds a [`GpuUniformBuffer`](crate::GpuUniformBuffer) entry at `bind_id`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// struct Params {
+    ///     origin: vec3<f32>, // 16 bytes aligned!
+    ///     scale: f32,
+    /// };
+    ///
+    /// @group(0) @binding(0) var<uniform> params: Params;
+    /// ```
+    ///
+    /// Uniform buffers follow the WGSL alignment rules, which differ from the Rust ones:
+    /// `vec3<f32>` and `vec4<f32>` are 16 bytes aligned, structs are aligned to their largest
+    /// member and padded to a multiple of its alignment, and array elements are 16 bytes aligned. The matching Rust struct must be `#[repr(C)]`
+    /// with explicit padding where needed, e.g. `origin: [f32; 3]` followed by `scale: f32` above,
+    /// but a `[f32; 3]` followed by a `vec3<f32>` needs an extra `f32` between them.
     pub fn add_uniform_buffer(&mut self, bind_id: u32) {
         let entry = wgpu::BindGroupLayoutEntry {
             binding: bind_id,
@@ -84,22 +154,98 @@ impl SetLayout {
         self.entry_type.push(EntryType::Uniform)
     }
 
-    pub fn add_image<P: PixelInfo>(&mut self, bind_id: u32) {
+    /// Adds a [`GpuBuffer`](crate::GpuBuffer) entry at `bind_id` with a dynamic offset, whose region is
+    /// moved on each execution by [`Kernel::run_with_offsets`](crate::Kernel::run_with_offsets), e.g. to
+    /// select the parameters of an object among many packed in one buffer without creating new bindings.
+    ///
+    /// Bound with [`SetBindings::add_dynamic_buffer`](crate::SetBindings::add_dynamic_buffer). The offsets
+    /// must be multiples of `min_storage_buffer_offset_alignment` of [`Framework::limits`](crate::Framework::limits),
+    /// usually 256 bytes, so the blocks of the buffer are padded to it.
+    pub fn add_dynamic_buffer(&mut self, bind_id: u32, usage: GpuBufferUsage) {
         let entry = wgpu::BindGroupLayoutEntry {
             binding: bind_id,
             visibility: wgpu::ShaderStages::COMPUTE,
-            ty: wgpu::BindingType::StorageTexture {
-                access: wgpu::StorageTextureAccess::WriteOnly,
-                format: P::wgpu_format(),
-                view_dimension: wgpu::TextureViewDimension::D2,
+            ty: wgpu::BindingType::Buffer {
+                has_dynamic_offset: true,
+                min_binding_size: None,
+                ty: wgpu::BufferBindingType::Storage {
+                    read_only: usage == GpuBufferUsage::ReadOnly,
+                },
             },
             count: None,
         };
 
         self.layout_entry.push(entry);
-        self.entry_type.push(EntryType::Image)
+        self.entry_type.push(EntryType::DynamicBuffer)
     }
 
+    /// Adds a [`GpuUniformBuffer`](crate::GpuUniformBuffer) entry at `bind_id` with a dynamic offset.
+    /// See [`SetLayout::add_dynamic_buffer`].
+    ///
+    /// Bound with [`SetBindings::add_dynamic_uniform_buffer`](crate::SetBindings::add_dynamic_uniform_buffer).
+    /// The offsets must be multiples of `min_uniform_buffer_offset_alignment` of
+    /// [`Framework::limits`](crate::Framework::limits), usually 256 bytes.
+    pub fn add_dynamic_uniform_buffer(&mut self, bind_id: u32) {
+        let entry = wgpu::BindGroupLayoutEntry {
+            binding: bind_id,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                has_dynamic_offset: true,
+                min_binding_size: None,
+                ty: wgpu::BufferBindingType::Uniform,
+            },
+            count: None,
+        };
+
+        self.layout_entry.push(entry);
+        self.entry_type.push(EntryType::DynamicUniform)
+    }
+
+    /// Adds a [`GpuImage`](crate::GpuImage) entry at `bind_id`, bound as a write-only storage texture
+    /// whose format is the one of `P`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var output: texture_storage_2d<rgba8unorm, write>;
+    /// ```
+    pub fn add_image<P: PixelInfo>(&mut self, bind_id: u32) {
+        self.add_image_with_access::<P>(bind_id, StorageAccess::WriteOnly)
+    }
+
+    /// Same as [`SetLayout::add_image`], with the `access` of the kernel to the image.
+    /// See [`StorageAccess`].
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var image: texture_storage_2d<r32float, read_write>;
+    /// ```
+    pub fn add_image_with_access<P: PixelInfo>(&mut self, bind_id: u32, access: StorageAccess) {
+        self.add_storage_image::<P>(
+            bind_id,
+            access,
+            wgpu::TextureViewDimension::D2,
+            EntryType::Image,
+        )
+    }
+
+    /// Adds a [`GpuConstImage`](crate::GpuConstImage) entry at `bind_id`, bound as a read-only sampled texture.
+    ///
+    /// The shader only declares the sample type of `P`, not its format: the same kernel reads
+    /// [`Rgba8UintNorm`](crate::primitives::pixels::Rgba8UintNorm),
+    /// [`Rgba8UintNormSrgb`](crate::primitives::pixels::Rgba8UintNormSrgb) or
+    /// [`Rgba32Float`](crate::primitives::pixels::Rgba32Float) images as `vec4<f32>`, sRGB ones being
+    /// converted to linear colors. Integer formats are read as `texture_2d<u32>` or `texture_2d<i32>`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var input: texture_2d<f32>;
+    ///
+    /// // In the kernel, the last argument is the mip level.
+    /// let color = textureLoad(input, vec2<i32>(global_id.xy), 0);
+    /// ```
     pub fn add_const_image<P: PixelInfo>(&mut self, bind_id: u32) {
         let entry = wgpu::BindGroupLayoutEntry {
             binding: bind_id,
@@ -115,4 +261,259 @@ impl SetLayout {
         self.layout_entry.push(entry);
         self.entry_type.push(EntryType::ConstImage)
     }
+
+    /// Adds a [`GpuImage3d`](crate::GpuImage3d) entry at `bind_id`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var volume: texture_storage_3d<rgba32float, write>;
+    /// ```
+    pub fn add_image_3d<P: PixelInfo>(&mut self, bind_id: u32) {
+        self.add_image_3d_with_access::<P>(bind_id, StorageAccess::WriteOnly)
+    }
+
+    /// Same as [`SetLayout::add_image_3d`], with the `access` of the kernel to the image.
+    /// See [`StorageAccess`].
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var image: texture_storage_3d<r32float, read_write>;
+    /// ```
+    pub fn add_image_3d_with_access<P: PixelInfo>(&mut self, bind_id: u32, access: StorageAccess) {
+        self.add_storage_image::<P>(
+            bind_id,
+            access,
+            wgpu::TextureViewDimension::D3,
+            EntryType::Image3d,
+        )
+    }
+
+    /// Adds a [`GpuConstImage3d`](crate::GpuConstImage3d) entry at `bind_id`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var volume: texture_3d<f32>;
+    /// ```
+    pub fn add_const_image_3d<P: PixelInfo>(&mut self, bind_id: u32) {
+        let entry = wgpu::BindGroupLayoutEntry {
+            binding: bind_id,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: P::wgpu_texture_sample(),
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D3,
+            },
+            count: None,
+        };
+
+        self.layout_entry.push(entry);
+        self.entry_type.push(EntryType::ConstImage3d)
+    }
+
+    /// Adds a [`GpuImage1d`](crate::GpuImage1d) entry at `bind_id`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var curve: texture_storage_1d<r32float, write>;
+    /// ```
+    pub fn add_image_1d<P: PixelInfo>(&mut self, bind_id: u32) {
+        self.add_image_1d_with_access::<P>(bind_id, StorageAccess::WriteOnly)
+    }
+
+    /// Same as [`SetLayout::add_image_1d`], with the `access` of the kernel to the image.
+    /// See [`StorageAccess`].
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var image: texture_storage_1d<r32float, read_write>;
+    /// ```
+    pub fn add_image_1d_with_access<P: PixelInfo>(&mut self, bind_id: u32, access: StorageAccess) {
+        self.add_storage_image::<P>(
+            bind_id,
+            access,
+            wgpu::TextureViewDimension::D1,
+            EntryType::Image1d,
+        )
+    }
+
+    /// Adds a [`GpuConstImage1d`](crate::GpuConstImage1d) entry at `bind_id`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var lut: texture_1d<f32>;
+    /// ```
+    pub fn add_const_image_1d<P: PixelInfo>(&mut self, bind_id: u32) {
+        let entry = wgpu::BindGroupLayoutEntry {
+            binding: bind_id,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: P::wgpu_texture_sample(),
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D1,
+            },
+            count: None,
+        };
+
+        self.layout_entry.push(entry);
+        self.entry_type.push(EntryType::ConstImage1d)
+    }
+
+    /// Adds a [`GpuImageArray`](crate::GpuImageArray) entry at `bind_id`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var frames: texture_storage_2d_array<rgba8unorm, write>;
+    /// ```
+    pub fn add_image_array<P: PixelInfo>(&mut self, bind_id: u32) {
+        self.add_image_array_with_access::<P>(bind_id, StorageAccess::WriteOnly)
+    }
+
+    /// Same as [`SetLayout::add_image_array`], with the `access` of the kernel to the image.
+    /// See [`StorageAccess`].
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var image: texture_storage_2d_array<r32float, read_write>;
+    /// ```
+    pub fn add_image_array_with_access<P: PixelInfo>(
+        &mut self,
+        bind_id: u32,
+        access: StorageAccess,
+    ) {
+        self.add_storage_image::<P>(
+            bind_id,
+            access,
+            wgpu::TextureViewDimension::D2Array,
+            EntryType::ImageArray,
+        )
+    }
+
+    /// Adds a [`GpuConstImageArray`](crate::GpuConstImageArray) entry at `bind_id`.
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var frames: texture_2d_array<f32>;
+    /// ```
+    pub fn add_const_image_array<P: PixelInfo>(&mut self, bind_id: u32) {
+        let entry = wgpu::BindGroupLayoutEntry {
+            binding: bind_id,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: P::wgpu_texture_sample(),
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D2Array,
+            },
+            count: None,
+        };
+
+        self.layout_entry.push(entry);
+        self.entry_type.push(EntryType::ConstImageArray)
+    }
+
+    /// Adds a [`GpuSampler`](crate::GpuSampler) entry at `bind_id`, whose binding type is
+    /// the one returned by [`GpuSampler::binding_type`](crate::GpuSampler::binding_type).
+    ///
+    /// Filtering samplers only sample images of filterable formats, e.g. not
+    /// [`Rgba32Float`](crate::primitives::pixels::Rgba32Float).
+    ///
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0)
+    /// var input: texture_2d<f32>;
+    /// @group(0) @binding(1)
+    /// var input_sampler: sampler;
+    ///
+    /// // In the kernel, `uv` are normalized coordinates and the last argument is the mip level.
+    /// let color = textureSampleLevel(input, input_sampler, uv, 0.0);
+    /// ```
+    pub fn add_sampler(&mut self, bind_id: u32, binding_type: wgpu::SamplerBindingType) {
+        let entry = wgpu::BindGroupLayoutEntry {
+            binding: bind_id,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Sampler(binding_type),
+            count: None,
+        };
+
+        self.layout_entry.push(entry);
+        self.entry_type.push(EntryType::Sampler(binding_type))
+    }
+
+    /// Returns the entry type declared at `bind_id`, if any.
+    pub(crate) fn entry_type_of(&self, bind_id: u32) -> Option<EntryType> {
+        self.entry(bind_id).map(|(_, entry_type)| entry_type)
+    }
+
+    /// Returns the entry at `bind_id` with its type, if any.
+    pub(crate) fn entry(&self, bind_id: u32) -> Option<(&wgpu::BindGroupLayoutEntry, EntryType)> {
+        self.layout_entry
+            .iter()
+            .zip(&self.entry_type)
+            .find(|(entry, _)| entry.binding == bind_id)
+            .map(|(entry, &entry_type)| (entry, entry_type))
+    }
+
+    /// Returns the binding of the entry named `name`, if any.
+    pub(crate) fn binding_of(&self, name: &str) -> Option<u32> {
+        self.names
+            .iter()
+            .find(|(_, entry_name)| entry_name.as_str() == name)
+            .map(|(&bind_id, _)| bind_id)
+    }
+
+    /// Reports all the entries of this layout as unbound, for the set `set` of a kernel never bound.
+    pub(crate) fn unbound(&self, set: u32) -> GpuError {
+        GpuError::UnboundEntries {
+            set,
+            unbound: self
+                .layout_entry
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{} at binding {}",
+                        self.describe(entry.binding),
+                        entry.binding
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Names the entry at `bind_id` in errors, by its shader variable name if known.
+    pub(crate) fn describe(&self, bind_id: u32) -> String {
+        match self.names.get(&bind_id) {
+            Some(name) => format!("`{}`", name),
+            None => "the entry".to_owned(),
+        }
+    }
+
+    fn add_storage_image<P: PixelInfo>(
+        &mut self,
+        bind_id: u32,
+        access: StorageAccess,
+        view_dimension: wgpu::TextureViewDimension,
+        entry_type: EntryType,
+    ) {
+        let entry = wgpu::BindGroupLayoutEntry {
+            binding: bind_id,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::StorageTexture {
+                access: access.wgpu_access(),
+                format: P::wgpu_format(),
+                view_dimension,
+            },
+            count: None,
+        };
+
+        self.layout_entry.push(entry);
+        self.entry_type.push(entry_type)
+    }
 }
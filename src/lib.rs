@@ -68,41 +68,115 @@
 
 use std::marker::PhantomData;
 
-pub use bindings::SetBindings;
+pub use bindings::{Bindable, SetBindings};
+#[cfg(feature = "hot-reload")]
+pub use features::hot_reload::{ShaderWatcher, WatchedKernel};
 #[cfg(feature = "integrate-ndarray")]
 pub use features::integrate_ndarray::GpuArray;
-pub use framework::Framework;
-pub use kernel::Kernel;
+pub use framework::{
+    FormatCaps, Framework, FrameworkBuilder, FrameworkError, GpuError, Submission, WorkDone,
+};
+pub use kernel::{DispatchIndirectArgs, Kernel, KernelBuilder};
 pub use layout::SetLayout;
-pub use primitives::{BufOps, ImgOps};
-pub use shader::Shader;
+pub use memory::ResourceKind;
+pub use primitives::{
+    arena::{ArenaBuffer, BufferArena},
+    buffers::ReadbackBatch,
+    images::PingPongImage,
+    samplers::{GpuSampler, SamplerOptions},
+    BufOps, ImgOps,
+};
+pub use profiler::{GpuProfiler, ProfiledScope, ProfilerReport};
+pub use recorder::GpuRecorder;
+pub use reflection::ReflectionError;
+pub use sequence::Sequence;
+pub use shader::{Shader, ShaderError};
+pub use shared_set::SharedSet;
+pub use statistics::{PipelineStats, StatisticsQuery};
+pub use timing::{KernelTiming, Timing, TimingSource};
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bench;
 pub mod bindings;
 pub mod features;
 pub mod framework;
 pub mod kernel;
 pub mod layout;
+pub mod preprocessor;
 pub mod primitives;
+pub mod profiler;
+pub mod recorder;
+pub mod reflection;
+pub mod sequence;
 pub mod shader;
+pub mod shared_set;
+pub mod statistics;
+pub mod timing;
 
 mod entry_type;
+mod memory;
+mod queries;
+mod specialization;
+mod staging;
+#[cfg(test)]
+mod testing;
 
-#[derive(PartialEq, Eq)]
+/// Access of a kernel to a storage buffer, declared with [`SetLayout::add_buffer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GpuBufferUsage {
-    /// Read-only object.
+    /// Read-only object, which drivers can optimize better.
     /// ### Example WGSL syntax:
     /// ```ignore
-    /// [[group(0), binding(0)]] var<storage, read> input: Vector;
+    /// @group(0) @binding(0) var<storage, read> input: Vector;
     /// ```
     ReadOnly,
     /// Read-write object.
     /// ### Example WGSL syntax:
     /// ```ignore
-    /// [[group(0), binding(0)]] var<storage, read_write> input: Vector;
+    /// @group(0) @binding(0) var<storage, read_write> input: Vector;
     /// ```
     ReadWrite,
 }
 
+/// Access of a kernel to a storage image, declared with
+/// [`SetLayout::add_image_with_access`] and its variants.
+///
+/// Reading storage images, with [`StorageAccess::ReadOnly`] or [`StorageAccess::ReadWrite`],
+/// needs the [`wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] feature and a format
+/// the adapter can read and write in storage images. Otherwise [`Kernel::try_new`] fails with
+/// [`GpuError::UnsupportedStorageAccess`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageAccess {
+    /// Read-only image.
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0) var input: texture_storage_2d<r32float, read>;
+    /// ```
+    ReadOnly,
+    /// Write-only image, the access of [`SetLayout::add_image`].
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0) var output: texture_storage_2d<r32float, write>;
+    /// ```
+    WriteOnly,
+    /// Read-write image.
+    /// ### Example WGSL syntax:
+    /// ```ignore
+    /// @group(0) @binding(0) var image: texture_storage_2d<r32float, read_write>;
+    /// ```
+    ReadWrite,
+}
+
+impl StorageAccess {
+    pub(crate) fn wgpu_access(self) -> wgpu::StorageTextureAccess {
+        match self {
+            Self::ReadOnly => wgpu::StorageTextureAccess::ReadOnly,
+            Self::WriteOnly => wgpu::StorageTextureAccess::WriteOnly,
+            Self::ReadWrite => wgpu::StorageTextureAccess::ReadWrite,
+        }
+    }
+}
+
 /// Vector of contiguous homogeneous elements on GPU memory.
 /// Its elements must implement [`bytemuck::Pod`].
 ///
@@ -110,13 +184,28 @@ pub enum GpuBufferUsage {
 ///
 /// More information about its shader representation is
 /// under the [`DescriptorSet::bind_buffer`](crate::DescriptorSet::bind_buffer) documentation.
-pub struct GpuBuffer<'fw, T> {
-    fw: &'fw Framework,
-    buf: wgpu::Buffer,
+///
+/// Its size is limited by the `max_storage_buffer_binding_size` and `max_buffer_size` limits of the device:
+/// [`BufOps::with_capacity`] and [`BufOps::from_slice`] panic above them, while
+/// [`GpuBuffer::try_with_capacity`] and [`GpuBuffer::try_from_slice`] return a [`BufferError::TooLarge`](primitives::buffers::BufferError::TooLarge).
+pub struct GpuBuffer<T> {
+    fw: Framework,
+    // Shared by the handles returned by `GpuBuffer::cast`.
+    buf: std::sync::Arc<wgpu::Buffer>,
     size: u64,
+    _alloc: std::sync::Arc<memory::Allocation>,
+    staging: Option<std::sync::Arc<primitives::buffers::PersistentStaging>>,
     marker: PhantomData<T>,
 }
 
+/// Untyped [`GpuBuffer`] of bytes, for data layouts decided at runtime.
+///
+/// It is a plain `GpuBuffer<u8>`: [`BufOps::with_capacity`] and [`BufOps::from_slice`] take
+/// a size in bytes, the offsets of the range methods like [`GpuBuffer::write_range`] are byte
+/// offsets and it binds like any other [`GpuBuffer`]. [`GpuBuffer::read_at`] and [`GpuBuffer::write_at`]
+/// access single values at arbitrary byte offsets, and [`GpuBuffer::cast`] returns a typed handle.
+pub type GpuByteBuffer = GpuBuffer<u8>;
+
 /// Uniform vector of contiguous homogeneous elements on GPU memory.
 /// Recommended for small, read-only buffers.
 /// Its elements must implement [`bytemuck::Pod`].
@@ -124,11 +213,59 @@ pub struct GpuBuffer<'fw, T> {
 /// Equivalent to OpenCL's Uniform Buffer objects.
 ///
 /// More information about its shader representation is
-/// under the [`DescriptorSet::bind_uniform_buffer`](crate::DescriptorSet::bind_uniform_buffer) documentation.
-pub struct GpuUniformBuffer<'fw, T> {
-    fw: &'fw Framework,
+/// under the [`SetLayout::add_uniform_buffer`](crate::SetLayout::add_uniform_buffer) documentation.
+pub struct GpuUniformBuffer<T> {
+    fw: Framework,
+    buf: wgpu::Buffer,
+    size: u64,
+    _alloc: memory::Allocation,
+    marker: PhantomData<T>,
+}
+
+/// Atomic counter on GPU memory, e.g. an append index or the head of a work queue.
+/// Its element is usually a `u32` or an `i32`.
+///
+/// Derefs to its inner single-element [`GpuBuffer`], so it is bound with
+/// [`SetBindings::add_buffer`](crate::SetBindings::add_buffer) like any other buffer.
+///
+/// ### Example WGSL syntax:
+/// ```ignore
+/// @group(0) @binding(0) var<storage, read_write> counter: atomic<u32>;
+/// ```
+pub struct GpuAtomicBuffer<T = u32> {
+    buf: GpuBuffer<T>,
+}
+
+/// Host-readable vector of contiguous homogeneous elements on GPU memory, the destination
+/// of kernel outputs that are only read back by the CPU.
+/// Its elements must implement [`bytemuck::Pod`].
+///
+/// Unlike a [`GpuBuffer`], it is mapped in place when read, without any staging copy:
+/// [`GpuDownloadBuffer::copy_from`] copies the output of a kernel into it and
+/// [`GpuDownloadBuffer::read`] maps it. It cannot be bound to a kernel, so it is
+/// not accepted by [`SetBindings`].
+pub struct GpuDownloadBuffer<T> {
+    fw: Framework,
+    staging: std::sync::Arc<primitives::buffers::PersistentStaging>,
+    size: u64,
+    marker: PhantomData<T>,
+}
+
+/// Host-writable vector of contiguous homogeneous elements on GPU memory, the source
+/// of data uploaded to [`GpuBuffer`]s.
+/// Its elements must implement [`bytemuck::Pod`].
+///
+/// Unlike [`GpuBuffer::write`], which goes through the staging belt of the queue,
+/// [`GpuUploadBuffer::write`] maps the buffer in place, so its latency is predictable.
+/// [`GpuUploadBuffer::write_with`] fills it in place, e.g. from several threads, and
+/// [`GpuUploadBuffer::copy_to`] pushes it into a [`GpuBuffer`]. It cannot be bound to a kernel.
+pub struct GpuUploadBuffer<T> {
+    fw: Framework,
     buf: wgpu::Buffer,
     size: u64,
+    // Held while the buffer is mapped.
+    mapping: std::sync::Mutex<()>,
+    _alloc: memory::Allocation,
     marker: PhantomData<T>,
 }
 
@@ -137,12 +274,13 @@ pub struct GpuUniformBuffer<'fw, T> {
 /// Equivalent to write-only OpenCL's Image objects.
 ///
 /// More information about its shader representation is
-/// under the [`DescriptorSet::bind_image`](crate::DescriptorSet::bind_image) documentation.
-pub struct GpuImage<'fw, P> {
-    fw: &'fw Framework,
+/// under the [`SetLayout::add_image`](crate::SetLayout::add_image) documentation.
+pub struct GpuImage<P> {
+    fw: Framework,
     texture: wgpu::Texture,
     size: wgpu::Extent3d,
     full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
     pixel: PhantomData<P>,
 }
 
@@ -150,12 +288,113 @@ pub struct GpuImage<'fw, P> {
 ///
 /// Equivalent to read-only OpenCL's Image objects.
 ///
+/// Unlike [`GpuImage`], it is not a storage texture, so formats that cannot be written by shaders
+/// like [`Rgba8UintNormSrgb`](primitives::pixels::Rgba8UintNormSrgb) can be used.
+///
+/// More information about its shader representation is
+/// under the [`SetLayout::add_const_image`](crate::SetLayout::add_const_image) documentation.
+pub struct GpuConstImage<P> {
+    fw: Framework,
+    texture: wgpu::Texture,
+    size: wgpu::Extent3d,
+    full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
+    pixel: PhantomData<P>,
+}
+
+/// 3D-image of homogeneous pixels, e.g. the cells of a volumetric simulation.
+///
+/// Equivalent to write-only OpenCL's 3D Image objects.
+///
 /// More information about its shader representation is
-/// under the [`DescriptorSet::bind_const_image`](crate::DescriptorSet::bind_const_image) documentation.
-pub struct GpuConstImage<'fw, P> {
-    fw: &'fw Framework,
+/// under the [`SetLayout::add_image_3d`](crate::SetLayout::add_image_3d) documentation.
+///
+/// The GL backend of `wgpu` only copies the first slice out of 3D textures:
+/// the following ones are read as zeros.
+pub struct GpuImage3d<P> {
+    fw: Framework,
+    texture: wgpu::Texture,
+    size: wgpu::Extent3d,
+    full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
+    pixel: PhantomData<P>,
+}
+
+/// 3D-image of homogeneous pixels.
+///
+/// Equivalent to read-only OpenCL's 3D Image objects.
+///
+/// More information about its shader representation is
+/// under the [`SetLayout::add_const_image_3d`](crate::SetLayout::add_const_image_3d) documentation.
+pub struct GpuConstImage3d<P> {
+    fw: Framework,
+    texture: wgpu::Texture,
+    size: wgpu::Extent3d,
+    full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
+    pixel: PhantomData<P>,
+}
+
+/// 1D-image of homogeneous pixels.
+///
+/// Equivalent to write-only OpenCL's 1D Image objects.
+///
+/// More information about its shader representation is
+/// under the [`SetLayout::add_image_1d`](crate::SetLayout::add_image_1d) documentation.
+pub struct GpuImage1d<P> {
+    fw: Framework,
+    texture: wgpu::Texture,
+    size: wgpu::Extent3d,
+    full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
+    pixel: PhantomData<P>,
+}
+
+/// 1D-image of homogeneous pixels, e.g. a lookup table or a gradient.
+///
+/// Equivalent to read-only OpenCL's 1D Image objects.
+///
+/// More information about its shader representation is
+/// under the [`SetLayout::add_const_image_1d`](crate::SetLayout::add_const_image_1d) documentation.
+pub struct GpuConstImage1d<P> {
+    fw: Framework,
+    texture: wgpu::Texture,
+    size: wgpu::Extent3d,
+    full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
+    pixel: PhantomData<P>,
+}
+
+/// Array of same-sized 2D-images of homogeneous pixels, e.g. a batch of frames processed by one dispatch.
+///
+/// Equivalent to write-only OpenCL's 2D Image Array objects.
+///
+/// More information about its shader representation is
+/// under the [`SetLayout::add_image_array`](crate::SetLayout::add_image_array) documentation.
+///
+/// On the GL backend, arrays of a single layer cannot be bound as arrays.
+pub struct GpuImageArray<P> {
+    fw: Framework,
+    texture: wgpu::Texture,
+    size: wgpu::Extent3d,
+    full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
+    pixel: PhantomData<P>,
+}
+
+/// Array of same-sized 2D-images of homogeneous pixels.
+///
+/// Equivalent to read-only OpenCL's 2D Image Array objects.
+///
+/// More information about its shader representation is
+/// under the [`SetLayout::add_const_image_array`](crate::SetLayout::add_const_image_array) documentation.
+///
+/// On the GL backend, arrays of a single layer cannot be bound as arrays.
+pub struct GpuConstImageArray<P> {
+    fw: Framework,
     texture: wgpu::Texture,
     size: wgpu::Extent3d,
     full_view: wgpu::TextureView,
+    _alloc: memory::Allocation,
     pixel: PhantomData<P>,
 }
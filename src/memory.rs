@@ -0,0 +1,91 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+/// Kind of the GPU resources tracked by the [`Framework`](crate::Framework) memory accounting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Buffer,
+    UniformBuffer,
+    Image,
+    ConstImage,
+    /// Persistent staging buffers (see [`GpuBuffer::with_persistent_staging`](crate::GpuBuffer::with_persistent_staging)),
+    /// [`GpuDownloadBuffer`](crate::GpuDownloadBuffer) and [`GpuUploadBuffer`](crate::GpuUploadBuffer).
+    ///
+    /// Also the staging buffers of the reads and of [`GpuBuffer::write_async`](crate::GpuBuffer::write_async)
+    /// while they are in use. Those retained by the staging pool are given by
+    /// [`Framework::staging_pool_bytes`](crate::Framework::staging_pool_bytes) instead.
+    Staging,
+}
+
+impl ResourceKind {
+    const COUNT: usize = 5;
+}
+
+type SoftLimitCallback = Box<dyn Fn(u64) + Send + Sync>;
+
+/// Bytes and number of the GPU resources alive, per [`ResourceKind`].
+#[derive(Default)]
+pub(crate) struct MemoryCounters {
+    bytes: [AtomicU64; ResourceKind::COUNT],
+    count: [AtomicU64; ResourceKind::COUNT],
+    soft_limit: Mutex<Option<(u64, SoftLimitCallback)>>,
+}
+
+impl MemoryCounters {
+    /// Records a new allocation of `bytes`. It is released when the returned [`Allocation`] is dropped.
+    pub(crate) fn allocate(self: &Arc<Self>, kind: ResourceKind, bytes: u64) -> Allocation {
+        self.bytes[kind as usize].fetch_add(bytes, Ordering::Relaxed);
+        self.count[kind as usize].fetch_add(1, Ordering::Relaxed);
+
+        if let Some((limit, callback)) = &*self.soft_limit.lock().unwrap() {
+            let total = self.total_bytes();
+
+            if total > *limit {
+                log::warn!(
+                    "GPU memory soft limit exceeded: {} bytes allocated, limit is {} bytes.",
+                    total,
+                    limit
+                );
+                callback(total);
+            }
+        }
+
+        Allocation {
+            counters: Arc::clone(self),
+            kind,
+            bytes,
+        }
+    }
+
+    pub(crate) fn bytes(&self, kind: ResourceKind) -> u64 {
+        self.bytes[kind as usize].load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn total_bytes(&self) -> u64 {
+        self.bytes.iter().map(|b| b.load(Ordering::Relaxed)).sum()
+    }
+
+    pub(crate) fn total_count(&self) -> u64 {
+        self.count.iter().map(|c| c.load(Ordering::Relaxed)).sum()
+    }
+
+    pub(crate) fn set_soft_limit(&self, soft_limit: Option<(u64, SoftLimitCallback)>) {
+        *self.soft_limit.lock().unwrap() = soft_limit;
+    }
+}
+
+/// Record of a live GPU allocation. Releases it from the [`MemoryCounters`] on drop.
+pub(crate) struct Allocation {
+    counters: Arc<MemoryCounters>,
+    kind: ResourceKind,
+    bytes: u64,
+}
+
+impl Drop for Allocation {
+    fn drop(&mut self) {
+        self.counters.bytes[self.kind as usize].fetch_sub(self.bytes, Ordering::Relaxed);
+        self.counters.count[self.kind as usize].fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,687 @@
+//! A small preprocessor for WGSL sources, so one shader file can serve several kernel variants
+//! and share libraries of functions.
+//!
+//! The directives are line comments starting with `//#`, so the WGSL tools still parse the
+//! unprocessed source:
+//! - `//#define NAME VALUE` defines `NAME` as the rest of the line, which can be empty. Defines given
+//!   to [`preprocess`] take precedence, so the ones of the source act as default values.
+//! - `//#if NAME` keeps the following lines if `NAME` is defined with a value other than `0` or
+//!   `false`, and `//#if !NAME` if it is not. Conditionals nest, and end with `//#endif` in the same
+//!   file, optionally after an `//#else` keeping the lines otherwise discarded.
+//! - `//#include "path"` inserts the processed file `path`, found by an [`IncludeResolver`], see
+//!   [`preprocess_with_includes`]. A file is only included once, later includes of it are ignored.
+//!   The defines of an included file apply to the rest of the source.
+//!
+//! Identifiers of the kept lines that are defined are replaced by their values, which are not
+//! expanded again. Directives and discarded lines are emptied, so without includes the lines of
+//! the processed source match the ones of the original in the `naga` errors. The [`SourceMap`] of
+//! [`preprocess_with_includes`] locates them otherwise.
+//!
+//! # Example
+//! ```
+//! let source = "
+//! //#define CHANNELS 1
+//! //#if USE_FAST_MATH
+//! fn norm(v: vec4<f32>) -> f32 { return inverseSqrt(dot(v, v)); }
+//! //#else
+//! fn norm(v: vec4<f32>) -> f32 { return 1.0 / length(v); }
+//! //#endif
+//! const channels = CHANNELS;";
+//!
+//! let processed =
+//!     gpgpu::preprocessor::preprocess(source, &[("USE_FAST_MATH", "1"), ("CHANNELS", "4")]).unwrap();
+//!
+//! assert!(processed.contains("inverseSqrt"));
+//! assert!(!processed.contains("length"));
+//! assert!(processed.contains("const channels = 4;"));
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use thiserror::Error;
+
+pub type PreprocessResult<T> = Result<T, PreprocessError>;
+
+/// Name of the source given to [`preprocess`] in the errors, like in the `naga` ones.
+const SOURCE_NAME: &str = "wgsl";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PreprocessError {
+    /// A conditional is not closed by `//#endif`. Holds the file and line of its `//#if`,
+    /// starting at 1.
+    #[error("{file}:{line}: `//#if` is never closed by `//#endif`.")]
+    UnterminatedIf { file: String, line: usize },
+    /// A directive is malformed or misplaced. Holds its file and line, starting at 1.
+    #[error("{file}:{line}: {reason}.")]
+    InvalidDirective {
+        file: String,
+        line: usize,
+        reason: String,
+    },
+    /// The [`IncludeResolver`] failed to load the `path` included by the directive of `file` on `line`.
+    #[error("{file}:{line}: cannot include `{path}`: {reason}.")]
+    IncludeFailed {
+        file: String,
+        line: usize,
+        path: String,
+        reason: String,
+    },
+    /// A file includes itself, directly or through other files. Holds the names of the files of the
+    /// cycle, starting and ending with the same one.
+    #[error("`//#include` cycle: {}.", .0.join(" -> "))]
+    IncludeCycle(Vec<String>),
+}
+
+/// Loads the files of the `//#include` directives, e.g. from the filesystem with [`FileResolver`],
+/// or from embedded assets.
+///
+/// Closures `Fn(&str) -> std::io::Result<String>` returning the source of the included path
+/// are resolvers naming the files by their path.
+pub trait IncludeResolver {
+    /// Returns the name of the file `path` included by the file named `includer`, which identifies it
+    /// in the errors and to include it once. Defaults to `path`.
+    fn name(&self, includer: &str, path: &str) -> String {
+        let _ = includer;
+        path.to_owned()
+    }
+
+    /// Returns the source of the file named `name`.
+    fn source(&self, name: &str) -> std::io::Result<String>;
+}
+
+impl<F> IncludeResolver for F
+where
+    F: Fn(&str) -> std::io::Result<String>,
+{
+    fn source(&self, name: &str) -> std::io::Result<String> {
+        self(name)
+    }
+}
+
+/// [`IncludeResolver`] reading the included files from the filesystem, relative to the
+/// directory of the including file.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileResolver;
+
+impl IncludeResolver for FileResolver {
+    fn name(&self, includer: &str, path: &str) -> String {
+        let joined = Path::new(includer)
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(path);
+
+        // Lexically, so that the different paths of a file name it the same.
+        let mut normalized = PathBuf::new();
+        for component in joined.components() {
+            match component {
+                Component::CurDir => (),
+                Component::ParentDir
+                    if matches!(
+                        normalized.components().next_back(),
+                        Some(Component::Normal(_))
+                    ) =>
+                {
+                    normalized.pop();
+                }
+                component => normalized.push(component),
+            }
+        }
+
+        normalized.to_string_lossy().into_owned()
+    }
+
+    fn source(&self, name: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(name)
+    }
+}
+
+/// Resolver of [`preprocess`], which does not support includes.
+struct NoIncludes;
+
+impl IncludeResolver for NoIncludes {
+    fn source(&self, _: &str) -> std::io::Result<String> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "includes need a resolver, see `preprocess_with_includes`",
+        ))
+    }
+}
+
+/// Locates the lines of a source processed by [`preprocess_with_includes`] in the original files.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    files: Vec<String>,
+    /// Index of the file and line of each processed line.
+    lines: Vec<(usize, usize)>,
+}
+
+impl SourceMap {
+    /// Returns the name of the file and the line, starting at 1, of the processed `line`,
+    /// starting at 1. `None` if the processed source has no such line.
+    pub fn locate(&self, line: usize) -> Option<(&str, usize)> {
+        let &(file, line) = self.lines.get(line.checked_sub(1)?)?;
+
+        Some((&self.files[file], line))
+    }
+
+    /// Returns the names of the processed files, in the order they were included.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+}
+
+/// A source processed by [`preprocess_with_includes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Processed {
+    pub source: String,
+    pub source_map: SourceMap,
+}
+
+/// Processes the directives of the WGSL `source` with `defines`, the names and values defined
+/// beforehand. See the [module documentation](self) for the syntax.
+///
+/// Fails with [`PreprocessError::UnterminatedIf`] if a conditional is never closed, with
+/// [`PreprocessError::InvalidDirective`] if a directive is unknown, lacks its name, or has no
+/// matching `//#if`, or with [`PreprocessError::IncludeFailed`] on `//#include` directives.
+pub fn preprocess(source: &str, defines: &[(&str, &str)]) -> PreprocessResult<String> {
+    preprocess_with_includes(source, SOURCE_NAME, defines, &NoIncludes)
+        .map(|processed| processed.source)
+}
+
+/// Processes the directives of the WGSL `source` named `name` like [`preprocess`], including the
+/// files of the `//#include` directives loaded by `resolver`.
+///
+/// Fails like [`preprocess`], with [`PreprocessError::IncludeFailed`] if the `resolver` fails to load
+/// a file, or with [`PreprocessError::IncludeCycle`] if a file includes itself.
+///
+/// # Example
+/// ```
+/// use gpgpu::preprocessor::{preprocess_with_includes, FileResolver};
+///
+/// # let root = std::env::temp_dir().join("gpgpu-preprocessor-doc");
+/// # std::fs::create_dir_all(root.join("lib")).unwrap();
+/// # std::fs::write(root.join("lib/math.wgsl"), "fn square(x: f32) -> f32 { return x * x; }").unwrap();
+/// # let path = root.join("kernel.wgsl");
+/// # let path = path.to_str().unwrap();
+/// let source = "//#include \"lib/math.wgsl\"\nconst nine = square(3.0);";
+/// let processed = preprocess_with_includes(source, path, &[], &FileResolver).unwrap();
+///
+/// assert!(processed.source.contains("fn square"));
+/// assert_eq!(processed.source_map.locate(3), Some((path, 2)));
+/// ```
+pub fn preprocess_with_includes(
+    source: &str,
+    name: &str,
+    defines: &[(&str, &str)],
+    resolver: &impl IncludeResolver,
+) -> PreprocessResult<Processed> {
+    let mut preprocessor = Preprocessor {
+        defines,
+        resolver,
+        values: defines
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+            .collect(),
+        source: String::with_capacity(source.len()),
+        source_map: SourceMap::default(),
+        stack: Vec::new(),
+        included: HashSet::new(),
+    };
+
+    preprocessor.process(name.to_owned(), source)?;
+
+    Ok(Processed {
+        source: preprocessor.source,
+        source_map: preprocessor.source_map,
+    })
+}
+
+/// An open `//#if` block.
+struct Conditional {
+    line: usize,
+    /// Whether the enclosing lines are kept.
+    parent: bool,
+    /// Whether the `//#if` branch is taken.
+    taken: bool,
+    /// Line of the `//#else` of the block, if reached.
+    else_line: Option<usize>,
+}
+
+impl Conditional {
+    fn active(&self) -> bool {
+        self.parent && (self.taken == self.else_line.is_none())
+    }
+}
+
+struct Preprocessor<'a, R> {
+    defines: &'a [(&'a str, &'a str)],
+    resolver: &'a R,
+    values: HashMap<String, String>,
+    source: String,
+    source_map: SourceMap,
+    /// Files being processed, each included by the previous one.
+    stack: Vec<String>,
+    included: HashSet<String>,
+}
+
+impl<R: IncludeResolver> Preprocessor<'_, R> {
+    /// Processes the file `name` of `source`, appending its lines to the processed source.
+    fn process(&mut self, name: String, source: &str) -> PreprocessResult<()> {
+        let file = self.source_map.files.len();
+        self.source_map.files.push(name.clone());
+        self.included.insert(name.clone());
+        self.stack.push(name);
+
+        let mut conditionals: Vec<Conditional> = Vec::new();
+
+        for (index, text) in source.lines().enumerate() {
+            let line = index + 1;
+            let active = conditionals.last().is_none_or(Conditional::active);
+            let invalid = |reason: String| PreprocessError::InvalidDirective {
+                file: self.source_map.files[file].clone(),
+                line,
+                reason,
+            };
+
+            let directive = match text.trim_start().strip_prefix("//#") {
+                Some(directive) => directive.trim(),
+                None => {
+                    if active {
+                        self.source.push_str(&substitute(text, &self.values));
+                    }
+                    self.push_line(file, line);
+                    continue;
+                }
+            };
+
+            let (keyword, argument) = directive
+                .split_once(char::is_whitespace)
+                .map_or((directive, ""), |(keyword, argument)| {
+                    (keyword, argument.trim())
+                });
+
+            match keyword {
+                "define" => {
+                    let (name, value) = argument
+                        .split_once(char::is_whitespace)
+                        .map_or((argument, ""), |(name, value)| (name, value.trim()));
+
+                    if !is_identifier(name) {
+                        return Err(invalid("`//#define` needs a name".to_owned()));
+                    }
+
+                    if active && !self.defines.iter().any(|&(defined, _)| defined == name) {
+                        self.values.insert(name.to_owned(), value.to_owned());
+                    }
+                }
+                "if" => {
+                    let (negated, name) = match argument.strip_prefix('!') {
+                        Some(name) => (true, name.trim_start()),
+                        None => (false, argument),
+                    };
+
+                    if !is_identifier(name) {
+                        return Err(invalid("`//#if` needs a single name".to_owned()));
+                    }
+
+                    let defined = self
+                        .values
+                        .get(name)
+                        .is_some_and(|value| value != "0" && value != "false");
+
+                    conditionals.push(Conditional {
+                        line,
+                        parent: active,
+                        taken: defined != negated,
+                        else_line: None,
+                    });
+                }
+                "else" => match conditionals.last_mut() {
+                    Some(Conditional {
+                        else_line: Some(first),
+                        line: if_line,
+                        ..
+                    }) => {
+                        return Err(invalid(format!(
+                            "second `//#else` of the `//#if` of line {}, the first is on line {}",
+                            if_line, first
+                        )))
+                    }
+                    Some(conditional) => conditional.else_line = Some(line),
+                    None => return Err(invalid("`//#else` has no matching `//#if`".to_owned())),
+                },
+                "endif" => {
+                    if conditionals.pop().is_none() {
+                        return Err(invalid("`//#endif` has no matching `//#if`".to_owned()));
+                    }
+                }
+                "include" => {
+                    let path = argument
+                        .strip_prefix('"')
+                        .and_then(|path| path.strip_suffix('"'))
+                        .ok_or_else(|| invalid("`//#include` needs a quoted path".to_owned()))?;
+
+                    self.push_line(file, line);
+
+                    if active {
+                        self.include(file, line, path)?;
+                    }
+                    continue;
+                }
+                _ => return Err(invalid(format!("unknown directive `//#{}`", keyword))),
+            }
+
+            self.push_line(file, line);
+        }
+
+        if let Some(conditional) = conditionals.last() {
+            return Err(PreprocessError::UnterminatedIf {
+                file: self.source_map.files[file].clone(),
+                line: conditional.line,
+            });
+        }
+
+        self.stack.pop();
+
+        Ok(())
+    }
+
+    /// Processes the file `path` included on `line` of the file `file`, unless already included.
+    fn include(&mut self, file: usize, line: usize, path: &str) -> PreprocessResult<()> {
+        let includer = &self.source_map.files[file];
+        let name = self.resolver.name(includer, path);
+
+        if let Some(start) = self.stack.iter().position(|open| open == &name) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(name);
+
+            return Err(PreprocessError::IncludeCycle(cycle));
+        }
+
+        if self.included.contains(&name) {
+            return Ok(());
+        }
+
+        let source = self
+            .resolver
+            .source(&name)
+            .map_err(|err| PreprocessError::IncludeFailed {
+                file: includer.clone(),
+                line,
+                path: path.to_owned(),
+                reason: err.to_string(),
+            })?;
+
+        self.process(name, &source)
+    }
+
+    /// Ends the processed line coming from `line` of the file `file`.
+    fn push_line(&mut self, file: usize, line: usize) {
+        self.source.push('\n');
+        self.source_map.lines.push((file, line));
+    }
+}
+
+/// Replaces the identifiers of `text` named in `values`.
+fn substitute(text: &str, values: &HashMap<String, String>) -> String {
+    let mut substituted = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        // Identifiers do not start inside a number, e.g. the `u` of `1u`.
+        let in_number = rest[..start].ends_with(|c: char| c.is_ascii_alphanumeric());
+        let end = rest[start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .map_or(rest.len(), |end| start + end);
+        let word = &rest[start..end];
+
+        substituted.push_str(&rest[..start]);
+        match values.get(word).filter(|_| !in_number) {
+            Some(value) => substituted.push_str(value),
+            None => substituted.push_str(word),
+        }
+
+        rest = &rest[end..];
+    }
+
+    substituted.push_str(rest);
+    substituted
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the non-empty lines of the processed `source`.
+    fn kept(source: &str, defines: &[(&str, &str)]) -> Vec<String> {
+        preprocess(source, defines)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Returns a resolver closure loading the sources of `files`, named by their path.
+    fn resolver<'a>(
+        files: &'a [(&'a str, &'a str)],
+    ) -> impl Fn(&str) -> std::io::Result<String> + 'a {
+        move |name: &str| {
+            files
+                .iter()
+                .find(|&&(path, _)| path == name)
+                .map(|&(_, source)| source.to_owned())
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))
+        }
+    }
+
+    #[test]
+    fn nested_conditionals() {
+        let source = "
+//#if A
+a
+//#if B
+ab
+//#else
+a_not_b
+//#endif
+//#else
+not_a
+//#if B
+not_a_b
+//#endif
+//#endif
+end";
+
+        assert_eq!(kept(source, &[("A", "1"), ("B", "1")]), ["a", "ab", "end"]);
+        assert_eq!(kept(source, &[("A", "1")]), ["a", "a_not_b", "end"]);
+        assert_eq!(kept(source, &[("B", "true")]), ["not_a", "not_a_b", "end"]);
+        assert_eq!(
+            kept(source, &[("A", "0"), ("B", "false")]),
+            ["not_a", "end"]
+        );
+    }
+
+    #[test]
+    fn discarded_lines_keep_line_numbers() {
+        let source = "first\n//#if A\ndiscarded\n//#endif\nlast";
+        let processed = preprocess(source, &[]).unwrap();
+
+        assert_eq!(
+            processed.lines().collect::<Vec<_>>(),
+            ["first", "", "", "", "last"]
+        );
+    }
+
+    #[test]
+    fn unterminated_if() {
+        let unterminated = |line| PreprocessError::UnterminatedIf {
+            file: SOURCE_NAME.to_owned(),
+            line,
+        };
+
+        assert_eq!(preprocess("a\n\n//#if A\nb", &[]), Err(unterminated(3)));
+        // The innermost open conditional is reported.
+        assert_eq!(
+            preprocess("//#if A\n//#if B\n//#endif\n//#if !C\n//#else", &[]),
+            Err(unterminated(4))
+        );
+        assert_eq!(
+            preprocess("//#if A\n//#if B\n//#else\n", &[("A", "1")]),
+            Err(unterminated(2))
+        );
+    }
+
+    #[test]
+    fn caller_defines_override_defaults() {
+        let source =
+            "//#define SIZE 64\n//#define FAST 0\n//#if FAST\nfast\n//#endif\nsize = SIZE;";
+
+        assert_eq!(kept(source, &[]), ["size = 64;"]);
+        assert_eq!(
+            kept(source, &[("SIZE", "256"), ("FAST", "1")]),
+            ["fast", "size = 256;"]
+        );
+    }
+
+    #[test]
+    fn substitutes_whole_identifiers() {
+        let source = "let x = FOO + FOO_BAR + 1u + FOO1 + _FOO + u(FOO);";
+
+        assert_eq!(
+            kept(source, &[("FOO", "2"), ("u", "3")]),
+            ["let x = 2 + FOO_BAR + 1u + FOO1 + _FOO + 3(2);"]
+        );
+    }
+
+    #[test]
+    fn invalid_directives() {
+        let invalid = |line, reason: &str| PreprocessError::InvalidDirective {
+            file: SOURCE_NAME.to_owned(),
+            line,
+            reason: reason.to_owned(),
+        };
+
+        assert_eq!(
+            preprocess("a\n//#endif", &[]),
+            Err(invalid(2, "`//#endif` has no matching `//#if`"))
+        );
+        assert_eq!(
+            preprocess("//#if A B", &[]),
+            Err(invalid(1, "`//#if` needs a single name"))
+        );
+        assert_eq!(
+            preprocess("//#pragma once", &[]),
+            Err(invalid(1, "unknown directive `//#pragma`"))
+        );
+    }
+
+    #[test]
+    fn source_map_locates_included_lines() {
+        let files = [("lib.wgsl", "//#define SIZE 8\nfn lib() {}")];
+        let source = "first\n//#include \"lib.wgsl\"\nconst size = SIZE;";
+        let processed =
+            preprocess_with_includes(source, "main.wgsl", &[], &resolver(&files)).unwrap();
+
+        assert_eq!(
+            processed.source.lines().collect::<Vec<_>>(),
+            ["first", "", "", "fn lib() {}", "const size = 8;"]
+        );
+        assert_eq!(processed.source_map.files(), ["main.wgsl", "lib.wgsl"]);
+        assert_eq!(processed.source_map.locate(0), None);
+        assert_eq!(processed.source_map.locate(1), Some(("main.wgsl", 1)));
+        assert_eq!(processed.source_map.locate(2), Some(("main.wgsl", 2)));
+        assert_eq!(processed.source_map.locate(3), Some(("lib.wgsl", 1)));
+        assert_eq!(processed.source_map.locate(4), Some(("lib.wgsl", 2)));
+        assert_eq!(processed.source_map.locate(5), Some(("main.wgsl", 3)));
+        assert_eq!(processed.source_map.locate(6), None);
+    }
+
+    #[test]
+    fn includes_files_once() {
+        let files = [
+            ("a.wgsl", "//#include \"common.wgsl\"\nfn a() {}"),
+            ("b.wgsl", "//#include \"common.wgsl\"\nfn b() {}"),
+            ("common.wgsl", "fn common() {}"),
+        ];
+        let source = "//#include \"a.wgsl\"\n//#include \"b.wgsl\"\n//#include \"a.wgsl\"";
+        let processed =
+            preprocess_with_includes(source, "main.wgsl", &[], &resolver(&files)).unwrap();
+
+        assert_eq!(processed.source.matches("fn common()").count(), 1);
+        assert_eq!(processed.source.matches("fn a()").count(), 1);
+        assert_eq!(
+            processed.source_map.files(),
+            ["main.wgsl", "a.wgsl", "common.wgsl", "b.wgsl"]
+        );
+        assert_eq!(processed.source_map.locate(3), Some(("common.wgsl", 1)));
+    }
+
+    #[test]
+    fn include_cycle() {
+        let files = [
+            ("a.wgsl", "//#include \"b.wgsl\""),
+            ("b.wgsl", "fn b() {}\n//#include \"a.wgsl\""),
+        ];
+        let cycle = |names: &[&str]| {
+            PreprocessError::IncludeCycle(names.iter().map(|&name| name.to_owned()).collect())
+        };
+
+        assert_eq!(
+            preprocess_with_includes("//#include \"a.wgsl\"", "main.wgsl", &[], &resolver(&files)),
+            Err(cycle(&["a.wgsl", "b.wgsl", "a.wgsl"]))
+        );
+        assert_eq!(
+            preprocess_with_includes(
+                "//#include \"main.wgsl\"",
+                "main.wgsl",
+                &[],
+                &resolver(&files)
+            ),
+            Err(cycle(&["main.wgsl", "main.wgsl"]))
+        );
+    }
+
+    #[test]
+    fn include_failed() {
+        assert_eq!(
+            preprocess_with_includes(
+                "\n//#include \"missing.wgsl\"",
+                "main.wgsl",
+                &[],
+                &resolver(&[])
+            ),
+            Err(PreprocessError::IncludeFailed {
+                file: "main.wgsl".to_owned(),
+                line: 2,
+                path: "missing.wgsl".to_owned(),
+                reason: std::io::Error::from(std::io::ErrorKind::NotFound).to_string(),
+            })
+        );
+        assert!(matches!(
+            preprocess("//#include \"lib.wgsl\"", &[]),
+            Err(PreprocessError::IncludeFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn file_resolver_normalizes_names() {
+        assert_eq!(FileResolver.name("main.wgsl", "lib.wgsl"), "lib.wgsl");
+        assert_eq!(
+            FileResolver.name("shaders/main.wgsl", "./lib/../common.wgsl"),
+            Path::new("shaders").join("common.wgsl").to_string_lossy()
+        );
+        assert_eq!(
+            FileResolver.name("shaders/main.wgsl", "../../common.wgsl"),
+            Path::new("..").join("common.wgsl").to_string_lossy()
+        );
+    }
+}
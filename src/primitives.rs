@@ -9,19 +9,34 @@
 //! Intended for small, read-only (in the shader)
 //! chunks of data on the GPU.
 //!
+//! ## ReadbackBatch
+//! Intended for reading back several buffers
+//! with a single submission.
+//!
+//! ## BufferArena
+//! Intended for short-lived scratch buffers on the GPU,
+//! reclaimed all at once.
+//!
 //! # Images
 //! ## GpuImage
 //! Intended for write-only (in the shader) images on the GPU.
 //!
 //! ## GpuConstImage
 //! Intended for read-only (in th shader) images on the GPU.
+//!
+//! # Samplers
+//! ## GpuSampler
+//! Intended for filtered reads of `GpuConstImage`s (in the shader).
 
 use crate::Framework;
 
+pub mod arena;
 pub mod buffers;
+pub mod images;
+pub mod samplers;
 
 /// Interface to get information, create and decompose GPU allocated buffers.
-pub trait BufOps<'fw, T>
+pub trait BufOps<T>
 where
     T: bytemuck::Pod,
 {
@@ -35,8 +50,23 @@ where
     /// Returns the number of bytes of the buffer.
     fn size(&self) -> u64;
 
+    /// Returns the number of elements of the buffer. Same as [`BufOps::capacity`].
+    fn len(&self) -> usize {
+        self.capacity() as usize
+    }
+
+    /// Returns the number of bytes of the buffer. Same as [`BufOps::size`].
+    fn size_bytes(&self) -> usize {
+        self.size() as usize
+    }
+
+    /// Returns `true` if the buffer holds no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Returns a [`wgpu::BindingResource`] of all the elements in the buffer.
-    fn as_binding_resource(&self) -> wgpu::BindingResource {
+    fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
         self.as_gpu_buffer().as_entire_binding()
     }
 
@@ -48,12 +78,12 @@ where
     /// Constructs a new zeroed buffer with the specified capacity.
     ///
     /// The buffer will be able to hold exactly `capacity` elements.
-    fn with_capacity(fw: &'fw Framework, capacity: u64) -> Self;
+    fn with_capacity(fw: &Framework, capacity: u64) -> Self;
 
     /// Constructs a new buffer from a slice.
     ///
     /// The buffer `capacity` will be the `slice` length.
-    fn from_slice(fw: &'fw Framework, slice: &[T]) -> Self;
+    fn from_slice(fw: &Framework, slice: &[T]) -> Self;
 
     /// Constructs a new buffer from a [`wgpu::Buffer`] and its byte `size`.
     ///
@@ -62,7 +92,7 @@ where
     /// panic at any time during its usage.
     /// - `size` needs to be less than or equal to the `buf` creation size.
     /// - `size` needs to be multiple of the `T` size.
-    fn from_gpu_parts(fw: &'fw Framework, buf: wgpu::Buffer, size: u64) -> Self;
+    fn from_gpu_parts(fw: &Framework, buf: wgpu::Buffer, size: u64) -> Self;
 
     // --------- Decomposition fns -------------
 
@@ -71,11 +101,11 @@ where
 }
 
 /// Interface to get information, create and decompose GPU allocated images.
-pub trait ImgOps<'fw> {
+pub trait ImgOps {
     // --------- Information fns --------------
 
     /// Returns a [`wgpu::BindingResource`] of the image.
-    fn as_binding_resource(&self) -> wgpu::BindingResource;
+    fn as_binding_resource(&self) -> wgpu::BindingResource<'_>;
 
     /// Returns the [`wgpu::Texture`] that handles the GPU image.
     fn as_gpu_texture(&self) -> &wgpu::Texture;
@@ -89,18 +119,14 @@ pub trait ImgOps<'fw> {
     // ----------- Creation fns ---------------
 
     /// Constructs an empty image with the desired `width` and `height`.
-    fn new(fw: &'fw Framework, width: u32, height: u32) -> Self;
+    fn new(fw: &Framework, width: u32, height: u32) -> Self;
 
     /// Construct a new image from a bytes source `data` and its `width` and `height`.
     ///
     /// If `data` doesn't fit the image perfectly, it panics.
-    fn from_bytes(fw: &'fw Framework, data: &[u8], width: u32, height: u32) -> Self;
+    fn from_bytes(fw: &Framework, data: &[u8], width: u32, height: u32) -> Self;
 
-    fn from_gpu_parts(
-        fw: &'fw Framework,
-        texture: wgpu::Texture,
-        dimensions: wgpu::Extent3d,
-    ) -> Self;
+    fn from_gpu_parts(fw: &Framework, texture: wgpu::Texture, dimensions: wgpu::Extent3d) -> Self;
 
     // -------- Decomposition fns -------------
 
@@ -110,13 +136,17 @@ pub trait ImgOps<'fw> {
 
 /// Gives some information about the pixel format.
 pub trait PixelInfo {
+    /// Host representation of a single pixel, e.g. `[u8; 4]` for [`pixels::Rgba8Uint`]
+    /// or `[f32; 4]` for [`pixels::Rgba32Float`].
+    type Texel: bytemuck::Pod;
+
     fn byte_size() -> usize;
     fn wgpu_format() -> wgpu::TextureFormat;
     fn wgpu_texture_sample() -> wgpu::TextureSampleType;
 }
 
 macro_rules! pixel_info_impl {
-    ($($name:ident, $size:expr, $format:expr, $sample:expr, #[$doc:meta]);+) => {
+    ($($name:ident, $texel:ty, $format:expr, $sample:expr, #[$doc:meta]);+) => {
         use crate::primitives::PixelInfo;
 
         $(
@@ -124,8 +154,10 @@ macro_rules! pixel_info_impl {
             pub struct $name;
 
             impl PixelInfo for $name {
+                type Texel = $texel;
+
                 fn byte_size() -> usize {
-                    $size
+                    std::mem::size_of::<$texel>()
                 }
 
                 fn wgpu_format() -> wgpu::TextureFormat {
@@ -142,11 +174,14 @@ macro_rules! pixel_info_impl {
 
 pub mod pixels {
     pixel_info_impl! {
-        Rgba8Uint, 4, wgpu::TextureFormat::Rgba8Uint, wgpu::TextureSampleType::Uint, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. Unsigned in shader."];
-        Rgba8UintNorm, 4, wgpu::TextureFormat::Rgba8Unorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. [0, 255] converted to/from float [0, 1] in shader."];
-        Rgba8Sint, 4, wgpu::TextureFormat::Rgba8Sint, wgpu::TextureSampleType::Sint, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. Signed in shader."];
-        Rgba8SintNorm, 4, wgpu::TextureFormat::Rgba8Snorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. [-127, 127] converted to/from float [-1, 1] in shader."]
-        // Luma8, 1, wgpu::TextureFormat::R8Uint, wgpu::TextureSampleType::Uint, #[doc = "Grayscale 8 bit integer channel. Unsigned in shader."];
-        // Luma8Norm, 1, wgpu::TextureFormat::R8Unorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Grayscale 8 bit integer channel. Unsigned in shader. [0, 255] converted to/from float [0, 1] in shader."]
+        Rgba8Uint, [u8; 4], wgpu::TextureFormat::Rgba8Uint, wgpu::TextureSampleType::Uint, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. Unsigned in shader."];
+        Rgba8UintNorm, [u8; 4], wgpu::TextureFormat::Rgba8Unorm, wgpu::TextureSampleType::Float { filterable: true }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. [0, 255] converted to/from float [0, 1] in shader."];
+        Rgba8Sint, [i8; 4], wgpu::TextureFormat::Rgba8Sint, wgpu::TextureSampleType::Sint, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. Signed in shader."];
+        Rgba8SintNorm, [i8; 4], wgpu::TextureFormat::Rgba8Snorm, wgpu::TextureSampleType::Float { filterable: true }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. [-127, 127] converted to/from float [-1, 1] in shader."];
+        Rgba8UintNormSrgb, [u8; 4], wgpu::TextureFormat::Rgba8UnormSrgb, wgpu::TextureSampleType::Float { filterable: true }, #[doc = "Red, green, blue, and alpha channels. 8 bit integer per channel. Srgb-color [0, 255] converted to/from linear-color float [0, 1] in shader. Storage access, needed by [`GpuImage`](crate::GpuImage), is not supported by most adapters."];
+        R32Float, f32, wgpu::TextureFormat::R32Float, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red channel only. 32 bit float per channel. Float in shader. Cannot be sampled by filtering samplers."];
+        Rgba32Float, [f32; 4], wgpu::TextureFormat::Rgba32Float, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Red, green, blue, and alpha channels. 32 bit float per channel. Float in shader. Cannot be sampled by filtering samplers."]
+        // Luma8, u8, wgpu::TextureFormat::R8Uint, wgpu::TextureSampleType::Uint, #[doc = "Grayscale 8 bit integer channel. Unsigned in shader."];
+        // Luma8Norm, u8, wgpu::TextureFormat::R8Unorm, wgpu::TextureSampleType::Float { filterable: false }, #[doc = "Grayscale 8 bit integer channel. Unsigned in shader. [0, 255] converted to/from float [0, 1] in shader."]
     }
 }
@@ -0,0 +1,199 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use crate::{
+    primitives::{
+        buffers::{BufferError, BufferResult},
+        BufOps,
+    },
+    GpuBuffer,
+};
+
+/// Allocator of short-lived scratch buffers, e.g. the histograms of a radix sort,
+/// carved out of large backing [`GpuBuffer`]s.
+///
+/// [`BufferArena::alloc`] hands out [`ArenaBuffer`]s from the backing buffers, which are
+/// only allocated when none has room left. [`BufferArena::reset`] reclaims all of them
+/// at once between iterations, without releasing any GPU memory.
+pub struct BufferArena {
+    fw: crate::Framework,
+    block_size: u64,
+    alignment: u64,
+    state: Mutex<ArenaState>,
+}
+
+#[derive(Default)]
+struct ArenaState {
+    blocks: Vec<GpuBuffer<u8>>,
+    /// Index of the block being carved.
+    current: usize,
+    /// First free byte of the current block.
+    cursor: u64,
+}
+
+impl BufferArena {
+    /// Creates an empty [`BufferArena`] whose backing buffers are of `block_size` bytes,
+    /// or larger if a single allocation needs it.
+    pub fn new(fw: &crate::Framework, block_size: u64) -> Self {
+        Self {
+            fw: fw.clone(),
+            block_size,
+            alignment: fw.limits().min_storage_buffer_offset_alignment as u64,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Allocates a buffer of `len` elements, bindable with
+    /// [`SetBindings::add_arena_buffer`](crate::SetBindings::add_arena_buffer).
+    ///
+    /// Allocations are aligned to the `min_storage_buffer_offset_alignment` limit of the device and
+    /// their size is rounded up to 4 bytes. Their elements are left as they were: they are not zeroed,
+    /// see [`ArenaBuffer::clear`].
+    ///
+    /// Fails with [`BufferError::TooLarge`] if a new backing buffer exceeds the device limits.
+    pub fn alloc<T: bytemuck::Pod>(&self, len: u64) -> BufferResult<ArenaBuffer<'_, T>> {
+        let size =
+            len.checked_mul(std::mem::size_of::<T>() as u64)
+                .ok_or(BufferError::TooLarge {
+                    size: u64::MAX,
+                    limit: self.fw.limits().max_storage_buffer_binding_size as u64,
+                })?;
+        // Bindings cannot be empty and must be 4 bytes aligned.
+        let bind_size = wgpu::util::align_to(std::cmp::max(size, 1), wgpu::COPY_BUFFER_ALIGNMENT);
+
+        let mut state = self.state.lock().expect("BufferArena lock poisoned");
+
+        loop {
+            let current = state.current;
+            let start = wgpu::util::align_to(state.cursor, self.alignment);
+
+            match state.blocks.get(current).map(|block| block.size()) {
+                Some(block_size) if start + bind_size <= block_size => {
+                    state.cursor = start + bind_size;
+
+                    return Ok(ArenaBuffer {
+                        block: state.blocks[current].cast::<u8>()?,
+                        offset: start,
+                        len,
+                        bind_size,
+                        marker: PhantomData,
+                    });
+                }
+                Some(_) => {
+                    state.current += 1;
+                    state.cursor = 0;
+                }
+                None => {
+                    let block_size = std::cmp::max(self.block_size, bind_size);
+                    state
+                        .blocks
+                        .push(GpuBuffer::try_with_capacity(&self.fw, block_size)?);
+                }
+            }
+        }
+    }
+
+    /// Reclaims all the allocations of this [`BufferArena`]. The backing buffers are kept for the next ones.
+    pub fn reset(&mut self) {
+        let state = self.state.get_mut().expect("BufferArena lock poisoned");
+        state.current = 0;
+        state.cursor = 0;
+    }
+
+    /// Releases the backing buffers of this [`BufferArena`].
+    pub fn clear(&mut self) {
+        *self.state.get_mut().expect("BufferArena lock poisoned") = ArenaState::default();
+    }
+
+    /// Returns the bytes of the backing buffers of this [`BufferArena`].
+    pub fn capacity_bytes(&self) -> u64 {
+        let state = self.state.lock().expect("BufferArena lock poisoned");
+
+        state.blocks.iter().map(|block| block.size()).sum()
+    }
+}
+
+/// Scratch buffer allocated by a [`BufferArena`], bound with
+/// [`SetBindings::add_arena_buffer`](crate::SetBindings::add_arena_buffer).
+///
+/// It borrows the arena, so the arena cannot be reset while it is alive.
+pub struct ArenaBuffer<'a, T> {
+    // Shares the backing buffer, see `GpuBuffer::cast`.
+    block: GpuBuffer<u8>,
+    offset: u64,
+    len: u64,
+    bind_size: u64,
+    marker: PhantomData<(&'a BufferArena, T)>,
+}
+
+impl<T> ArenaBuffer<'_, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Returns the number of elements of this [`ArenaBuffer`].
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if this [`ArenaBuffer`] has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn size(&self) -> u64 {
+        self.len * std::mem::size_of::<T>() as u64
+    }
+
+    /// Copies `bytes` into a [`Vec`] of aligned elements.
+    fn from_bytes(bytes: &[u8]) -> Vec<T> {
+        let mut elements = vec![T::zeroed(); bytes.len() / std::mem::size_of::<T>()];
+        bytemuck::cast_slice_mut::<T, u8>(&mut elements).copy_from_slice(bytes);
+
+        elements
+    }
+
+    /// Returns a [`wgpu::BindingResource`] of the allocation in its backing buffer.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer: self.block.as_gpu_buffer(),
+            offset: self.offset,
+            size: std::num::NonZeroU64::new(self.bind_size),
+        })
+    }
+
+    /// Writes `buf` at the start of this [`ArenaBuffer`]. The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if `buf` is longer than the [`ArenaBuffer`].
+    pub fn write(&self, buf: &[T]) -> BufferResult<()> {
+        let len = buf.len() as u64;
+
+        if len > self.len {
+            return Err(BufferError::OutOfRange {
+                offset: 0,
+                len,
+                capacity: self.len,
+            });
+        }
+
+        self.block
+            .write_range(self.offset, bytemuck::cast_slice(buf))
+    }
+
+    /// Sets all the elements of this [`ArenaBuffer`] to zero on the GPU. The operation is instantly offloaded.
+    pub fn clear(&self) -> BufferResult<()> {
+        self.block.clear_range(self.offset, self.size())
+    }
+
+    /// Asynchronously reads the elements of this [`ArenaBuffer`] into a [`Vec`].
+    pub async fn read_vec(&self) -> BufferResult<Vec<T>> {
+        let bytes = self.block.read_range(self.offset, self.size()).await?;
+
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    /// Blocking version of `ArenaBuffer::read_vec()`.
+    pub fn read_vec_blocking(&self) -> BufferResult<Vec<T>> {
+        let bytes = self.block.read_range_blocking(self.offset, self.size())?;
+
+        Ok(Self::from_bytes(&bytes))
+    }
+}
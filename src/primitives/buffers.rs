@@ -1,20 +1,35 @@
-use std::marker::PhantomData;
+use std::{
+    future::Future,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use futures::channel::oneshot::Receiver;
 
 use thiserror::Error;
 use wgpu::{util::DeviceExt, MapMode};
 
-use crate::{GpuBuffer, GpuUniformBuffer};
+use crate::{
+    memory::{Allocation, ResourceKind},
+    staging::PooledBuffer,
+    DispatchIndirectArgs, GpuAtomicBuffer, GpuBuffer, GpuDownloadBuffer, GpuUniformBuffer,
+    GpuUploadBuffer,
+};
 
 use super::BufOps;
 
 // TODO https://github.com/bitflags/bitflags/issues/180
-// TODO Unsure wether MAP_READ and MAP_WRITE should only be present on certain buffers
+// Storage buffers cannot be mapped on most backends, they are read through a staging buffer.
 const GPU_BUFFER_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_truncate(
     wgpu::BufferUsages::STORAGE.bits()
         | wgpu::BufferUsages::COPY_SRC.bits()
-        | wgpu::BufferUsages::COPY_DST.bits()
-        | wgpu::BufferUsages::MAP_READ.bits()
-        | wgpu::BufferUsages::MAP_WRITE.bits(),
+        | wgpu::BufferUsages::COPY_DST.bits(),
+);
+const UPLOAD_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_truncate(
+    wgpu::BufferUsages::MAP_WRITE.bits() | wgpu::BufferUsages::COPY_SRC.bits(),
 );
 const GPU_UNIFORM_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_truncate(
     wgpu::BufferUsages::UNIFORM.bits() | wgpu::BufferUsages::COPY_DST.bits(),
@@ -22,13 +37,84 @@ const GPU_UNIFORM_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_tru
 
 pub type BufferResult<T> = Result<T, BufferError>;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum BufferError {
     #[error(transparent)]
     AsyncMapError(#[from] wgpu::BufferAsyncError),
+    #[error("the device was lost.")]
+    DeviceLost,
+    #[error(
+        "range of {len} elements at offset {offset} is out of the buffer capacity ({capacity})."
+    )]
+    OutOfRange {
+        offset: u64,
+        len: u64,
+        capacity: u64,
+    },
+    #[error("destination buffer capacity ({capacity}) is smaller than the {len} elements copied.")]
+    DestinationTooSmall { len: u64, capacity: u64 },
+    #[error("buffers belong to different frameworks, use `GpuBuffer::copy_to_framework` instead.")]
+    DifferentFramework,
+    #[error("copy source range of {len} elements at offset {offset} is out of the buffer capacity ({capacity}).")]
+    SourceOutOfRange {
+        offset: u64,
+        len: u64,
+        capacity: u64,
+    },
+    #[error("copy destination range of {len} elements at offset {offset} is out of the buffer capacity ({capacity}).")]
+    DestinationOutOfRange {
+        offset: u64,
+        len: u64,
+        capacity: u64,
+    },
+    #[error("copy source and destination ranges overlap in the same buffer.")]
+    OverlappingCopy,
+    #[error("copy of {0} bytes cannot be recorded, its size must be a multiple of 4 bytes or fill the destination buffer.")]
+    UnalignedCopy(u64),
+    #[error("the persistent staging buffer is still mapped by a previous read.")]
+    StagingBusy,
+    #[error("usages {0:?} cannot be added to a storage buffer.")]
+    InvalidUsage(wgpu::BufferUsages),
+    #[error("buffer of {size} bytes cannot be cast to elements of {element_size} bytes.")]
+    InvalidCast { size: u64, element_size: u64 },
+    #[error("slice byte offset {offset} is not a multiple of the storage buffer offset alignment {alignment}.")]
+    UnalignedSliceOffset { offset: u64, alignment: u64 },
+    #[error(
+        "slice of {0} bytes cannot be bound, its size must be a non-zero multiple of 4 bytes."
+    )]
+    InvalidSliceSize(u64),
+    #[error("buffer of {size} bytes exceeds the {limit} bytes allowed by the device limits `max_storage_buffer_binding_size` and `max_buffer_size`, they can be raised with `FrameworkBuilder::limits`.")]
+    TooLarge { size: u64, limit: u64 },
+}
+
+/// Dedicated staging buffer of a [`GpuBuffer`] or a [`GpuDownloadBuffer`].
+/// See [`GpuBuffer::with_persistent_staging`].
+pub(crate) struct PersistentStaging {
+    buf: wgpu::Buffer,
+    busy: AtomicBool,
+    _alloc: Allocation,
+}
+
+impl PersistentStaging {
+    /// Creates a staging buffer of at least `size` bytes.
+    fn new(fw: &crate::Framework, size: u64, label: &str) -> Self {
+        let size = wgpu::util::align_to(size, wgpu::COPY_BUFFER_ALIGNMENT);
+        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            buf,
+            busy: AtomicBool::new(false),
+            _alloc: fw.memory.allocate(ResourceKind::Staging, size),
+        }
+    }
 }
 
-impl<'fw, T> BufOps<'fw, T> for GpuBuffer<'fw, T>
+impl<T> BufOps<T> for GpuBuffer<T>
 where
     T: bytemuck::Pod,
 {
@@ -40,219 +126,2566 @@ where
         &self.buf
     }
 
-    fn with_capacity(fw: &'fw crate::Framework, capacity: u64) -> Self {
-        let size = capacity * std::mem::size_of::<T>() as u64;
-        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("GpuBuffer::with_capacity"),
-            size,
-            usage: GPU_BUFFER_USAGES,
-            mapped_at_creation: false,
-        });
+    fn with_capacity(fw: &crate::Framework, capacity: u64) -> Self {
+        Self::try_with_capacity(fw, capacity).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn from_slice(fw: &crate::Framework, slice: &[T]) -> Self {
+        Self::try_from_slice(fw, slice).unwrap_or_else(|err| panic!("{}", err))
+    }
 
+    fn from_gpu_parts(fw: &crate::Framework, buf: wgpu::Buffer, size: u64) -> Self {
         Self {
-            fw,
-            buf,
+            fw: fw.clone(),
+            buf: Arc::new(buf),
             size,
+            _alloc: Arc::new(fw.memory.allocate(ResourceKind::Buffer, size)),
+            staging: None,
             marker: PhantomData,
         }
     }
 
-    fn from_slice(fw: &'fw crate::Framework, slice: &[T]) -> Self {
-        let size = (slice.len() * std::mem::size_of::<T>()) as u64;
+    /// # Panics
+    /// If the [`wgpu::Buffer`] is still shared with another handle returned by [`GpuBuffer::cast`].
+    fn into_gpu_parts(self) -> (wgpu::Buffer, u64) {
+        let buf = Arc::try_unwrap(self.buf)
+            .unwrap_or_else(|_| panic!("GpuBuffer is still shared with a cast handle"));
+
+        (buf, self.size)
+    }
+}
+
+impl<T> GpuBuffer<T>
+where
+    T: bytemuck::Pod,
+{
+    /// Fallible version of [`BufOps::with_capacity`].
+    ///
+    /// Fails with [`BufferError::TooLarge`] if the buffer exceeds the `max_storage_buffer_binding_size`
+    /// or `max_buffer_size` limits of the device. The default limits only allow 128 MiB storage
+    /// bindings, higher ones can be requested with [`FrameworkBuilder::limits`](crate::FrameworkBuilder::limits).
+    pub fn try_with_capacity(fw: &crate::Framework, capacity: u64) -> BufferResult<Self> {
+        Self::create(fw, capacity, GPU_BUFFER_USAGES)
+    }
+
+    /// Fallible version of [`BufOps::from_slice`]. See [`GpuBuffer::try_with_capacity`].
+    pub fn try_from_slice(fw: &crate::Framework, slice: &[T]) -> BufferResult<Self> {
+        Self::create_init(fw, slice, GPU_BUFFER_USAGES)
+    }
+
+    /// Returns the [`wgpu::Buffer`] of the [`GpuBuffer`], e.g. to use it in a render pipeline of the same device.
+    ///
+    /// Same as [`BufOps::as_gpu_buffer`]. Buffers created with [`GpuBuffer::with_capacity_and_usage`]
+    /// can take other usages, like [`wgpu::BufferUsages::VERTEX`].
+    pub fn as_wgpu_buffer(&self) -> &wgpu::Buffer {
+        &self.buf
+    }
+
+    /// Constructs a new zeroed [`GpuBuffer`] with the specified capacity, adding the `extra` usages
+    /// to the default ones, e.g. [`wgpu::BufferUsages::VERTEX`] to use it in a render pipeline
+    /// through [`BufOps::as_gpu_buffer`].
+    ///
+    /// Fails with [`BufferError::InvalidUsage`] if `extra` contains [`wgpu::BufferUsages::MAP_READ`]
+    /// or [`wgpu::BufferUsages::MAP_WRITE`], which cannot be combined with storage buffers,
+    /// and with [`BufferError::TooLarge`] like [`GpuBuffer::try_with_capacity`].
+    pub fn with_capacity_and_usage(
+        fw: &crate::Framework,
+        capacity: u64,
+        extra: wgpu::BufferUsages,
+    ) -> BufferResult<Self> {
+        Self::create(fw, capacity, Self::usage(extra)?)
+    }
+
+    /// Constructs a new [`GpuBuffer`] from a slice, adding the `extra` usages to the default ones.
+    /// See [`GpuBuffer::with_capacity_and_usage`].
+    pub fn from_slice_with_usage(
+        fw: &crate::Framework,
+        slice: &[T],
+        extra: wgpu::BufferUsages,
+    ) -> BufferResult<Self> {
+        Self::create_init(fw, slice, Self::usage(extra)?)
+    }
+
+    /// Constructs a new [`GpuBuffer`] from the elements of `iter`, without collecting them first.
+    ///
+    /// The elements are uploaded in chunks of at most [`Framework::staging_chunk_size`] bytes.
+    /// If the length of `iter` is known (see [`Iterator::size_hint`]) the buffer is allocated up front,
+    /// otherwise it grows on the GPU (see [`GpuBuffer::resize`]) and is shrunk to fit at the end.
+    ///
+    /// [`Framework::staging_chunk_size`]: crate::Framework::staging_chunk_size
+    pub fn from_iterator<I>(fw: &crate::Framework, iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        let element_size = std::mem::size_of::<T>() as u64;
+        // Multiple of 4 elements, so every chunk is 4 bytes aligned.
+        let chunk_len = std::cmp::max(fw.staging_chunk_size() / element_size / 4 * 4, 4);
+
+        let (lower, upper) = iter.size_hint();
+        let initial = match upper {
+            Some(upper) if upper == lower => lower as u64,
+            _ => std::cmp::max(lower as u64, chunk_len),
+        };
+
+        let mut buffer = Self::with_capacity(fw, initial);
+        let mut chunk = Vec::with_capacity(std::cmp::min(chunk_len, initial.max(1)) as usize);
+        let mut len = 0;
+
+        loop {
+            chunk.clear();
+            chunk.extend(iter.by_ref().take(chunk_len as usize));
+
+            if chunk.is_empty() {
+                break;
+            }
+
+            let end = len + chunk.len() as u64;
+            if end > buffer.capacity() {
+                let grown = std::cmp::max(end, buffer.capacity() * 2);
+                buffer.resize(grown).expect("GpuBuffer growing error!");
+            }
+
+            buffer
+                .write_range(len, &chunk)
+                .expect("GpuBuffer writing error!");
+            len = end;
+        }
+
+        if len != buffer.capacity() {
+            buffer.resize(len).expect("GpuBuffer shrinking error!");
+        }
+
+        buffer
+    }
+
+    fn usage(extra: wgpu::BufferUsages) -> BufferResult<wgpu::BufferUsages> {
+        let map = wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::MAP_WRITE;
+
+        if extra.intersects(map) {
+            return Err(BufferError::InvalidUsage(extra & map));
+        }
+
+        Ok(GPU_BUFFER_USAGES | extra)
+    }
+
+    /// Checks that a buffer of `size` bytes (`None` if it overflows) fits in the device limits.
+    fn check_size(fw: &crate::Framework, size: Option<u64>) -> BufferResult<u64> {
+        let limits = fw.device.limits();
+        let limit = std::cmp::min(
+            limits.max_storage_buffer_binding_size as u64,
+            limits.max_buffer_size,
+        );
+
+        match size {
+            Some(size) if size <= limit => Ok(size),
+            size => Err(BufferError::TooLarge {
+                size: size.unwrap_or(u64::MAX),
+                limit,
+            }),
+        }
+    }
+
+    fn create(
+        fw: &crate::Framework,
+        capacity: u64,
+        usage: wgpu::BufferUsages,
+    ) -> BufferResult<Self> {
+        let size = Self::check_size(fw, capacity.checked_mul(std::mem::size_of::<T>() as u64))?;
+        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuBuffer::with_capacity"),
+            // Padded like `create_buffer_init` does, so the whole buffer can be copied.
+            size: wgpu::util::align_to(size, wgpu::COPY_BUFFER_ALIGNMENT),
+            usage,
+            mapped_at_creation: false,
+        });
+
+        Ok(Self::from_gpu_parts(fw, buf, size))
+    }
+
+    fn create_init(
+        fw: &crate::Framework,
+        slice: &[T],
+        usage: wgpu::BufferUsages,
+    ) -> BufferResult<Self> {
+        let size = Self::check_size(fw, Some(std::mem::size_of_val(slice) as u64))?;
         let buf = fw
             .device
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("GpuBuffer::from_slice"),
                 contents: bytemuck::cast_slice(slice),
-                usage: GPU_BUFFER_USAGES,
+                usage,
             });
 
-        Self {
-            fw,
-            buf,
-            size,
-            marker: PhantomData,
-        }
+        Ok(Self::from_gpu_parts(fw, buf, size))
     }
 
-    fn from_gpu_parts(fw: &'fw crate::Framework, buf: wgpu::Buffer, size: u64) -> Self {
-        Self {
-            fw,
-            buf,
-            size,
-            marker: PhantomData,
+    /// Pulls some elements from the [`GpuBuffer`] into `buf`, returning how many elements were read.
+    ///
+    /// The elements are copied into a staging buffer which is then mapped. If the polling thread
+    /// of the [`Framework`] is not running, the device is polled until the mapping is resolved.
+    ///
+    /// Fails with [`BufferError::DeviceLost`] if the device of the [`Framework`] was lost.
+    pub async fn read(&self, buf: &mut [T]) -> BufferResult<u64> {
+        self.read_staged(buf, !self.fw.is_polling()).await
+    }
+
+    async fn read_staged(&self, buf: &mut [T], wait: bool) -> BufferResult<u64> {
+        let len = std::cmp::min(buf.len() as u64, self.capacity());
+
+        match self.download(0, len)? {
+            Some(mut download) => {
+                if wait {
+                    download.wait();
+                }
+                download.mapped().await?;
+                download.copy_into(&mut buf[..len as usize]);
+
+                Ok(len)
+            }
+            None => Ok(0),
         }
     }
 
-    fn into_gpu_parts(self) -> (wgpu::Buffer, u64) {
-        (self.buf, self.size)
+    /// Copies `len` elements starting at `offset` into a new staging buffer and requests its mapping.
+    ///
+    /// Returns `None` if there is nothing to download.
+    fn download(&self, offset: u64, len: u64) -> BufferResult<Option<Download>> {
+        let element_size = std::mem::size_of::<T>() as u64;
+
+        self.download_bytes(offset * element_size, len * element_size, true)
     }
-}
 
-impl<'fw, T> GpuBuffer<'fw, T>
-where
-    T: bytemuck::Pod,
-{
-    /// Pulls some elements from the [`GpuBuffer`] into `buf`, returning how many elements were read.
-    pub async fn read(&self, buf: &mut [T]) -> BufferResult<u64> {
-        let output_size = (buf.len() * std::mem::size_of::<T>()) as u64;
-        let download_size = if output_size > self.size {
-            self.size
-        } else {
-            output_size
+    /// Byte version of [`GpuBuffer::download`]. The persistent staging buffer is used if `persistent` is set,
+    /// the staging pool of the [`Framework`](crate::Framework) otherwise.
+    fn download_bytes(
+        &self,
+        start: u64,
+        size: u64,
+        persistent: bool,
+    ) -> BufferResult<Option<Download>> {
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        if size == 0 {
+            return Ok(None);
+        }
+
+        // Copies must be 4 bytes aligned, so the range is widened and sliced on the CPU.
+        // Buffers are padded, so the source is large enough.
+        let copy_start = start - start % wgpu::COPY_BUFFER_ALIGNMENT;
+        let copy_end = wgpu::util::align_to(start + size, wgpu::COPY_BUFFER_ALIGNMENT);
+        let staging_size = copy_end - copy_start;
+        let staging = match self.staging.as_ref().filter(|_| persistent) {
+            Some(staging) => {
+                if staging.busy.swap(true, Ordering::Acquire) {
+                    return Err(BufferError::StagingBusy);
+                }
+
+                Staging::Persistent(Arc::clone(staging))
+            }
+            None => Staging::Pooled(self.fw.staging.acquire(&self.fw, staging_size)),
         };
 
-        let download = self.buf.slice(..download_size as u64);
+        Ok(Some(Download::copy(
+            &self.fw,
+            &self.buf,
+            copy_start,
+            staging,
+            staging_size,
+            start - copy_start,
+            size,
+        )))
+    }
+
+    /// Pulls all the elements from the [`GpuBuffer`] into a [`Vec`], without blocking.
+    ///
+    /// Unlike [`GpuBuffer::read_vec`], the device is never polled by the returned future:
+    /// it resolves once the device is polled by the polling thread of the [`Framework`],
+    /// [`Framework::poll`] or [`Framework::blocking_poll`].
+    ///
+    /// The copy is submitted when this function is called. The returned future owns
+    /// its staging buffer, so it can be dropped at any time to cancel the read.
+    ///
+    /// [`Framework`]: crate::Framework
+    /// [`Framework::poll`]: crate::Framework::poll
+    /// [`Framework::blocking_poll`]: crate::Framework::blocking_poll
+    pub fn read_async(&self) -> impl Future<Output = BufferResult<Vec<T>>> {
+        let download = self.download(0, self.capacity());
+        let len = self.len();
 
-        let (tx, rx) = futures::channel::oneshot::channel();
-        download.map_async(MapMode::Read, |result| {
-            tx.send(result).expect("GpuBuffer reading error!");
-        });
-        rx.await
-            .expect("GpuBuffer futures::channel::oneshot error")?;
+        async move {
+            let mut buf = vec![T::zeroed(); len];
 
-        buf.copy_from_slice(bytemuck::cast_slice(&download.get_mapped_range()));
+            if let Some(mut download) = download? {
+                download.mapped().await?;
+                download.copy_into(&mut buf);
+            }
 
-        Ok(download_size)
+            Ok(buf)
+        }
     }
 
     /// Pulls all the elements from the [`GpuBuffer`] into a [`Vec`].
     pub async fn read_vec(&self) -> BufferResult<Vec<T>> {
-        // Safety: Since T is Pod: Zeroed + ... it is safe to use zeroed() to init it.
-        let mut buf = vec![unsafe { std::mem::zeroed() }; self.capacity() as usize];
+        let mut buf = vec![T::zeroed(); self.len()];
         self.read(&mut buf).await?;
 
         Ok(buf)
     }
 
-    /// Blocking version of `GpuBuffer::read()`.
-    pub fn read_blocking(&self, buf: &mut [T]) -> BufferResult<u64> {
-        futures::executor::block_on(self.read(buf))
+    /// Pulls `len` elements starting at the element `offset` from the [`GpuBuffer`] into a [`Vec`].
+    ///
+    /// Only the requested range is copied and mapped. Fails with [`BufferError::OutOfRange`]
+    /// if the range exceeds the buffer capacity.
+    pub async fn read_range(&self, offset: u64, len: u64) -> BufferResult<Vec<T>> {
+        self.read_range_staged(offset, len, !self.fw.is_polling())
+            .await
     }
 
-    /// Blocking version of `GpuBuffer::read_vec()`.
-    pub fn read_vec_blocking(&self) -> BufferResult<Vec<T>> {
-        futures::executor::block_on(self.read_vec())
+    async fn read_range_staged(&self, offset: u64, len: u64, wait: bool) -> BufferResult<Vec<T>> {
+        self.check_range(offset, len)?;
+
+        let mut buf = vec![T::zeroed(); len as usize];
+
+        if let Some(mut download) = self.download(offset, len)? {
+            if wait {
+                download.wait();
+            }
+            download.mapped().await?;
+            download.copy_into(&mut buf);
+        }
+
+        Ok(buf)
     }
 
-    /// Writes a buffer into this [`GpuBuffer`], returning how many elements were written. The operation is instantly offloaded.
+    /// Blocking version of `GpuBuffer::read_range()`.
+    pub fn read_range_blocking(&self, offset: u64, len: u64) -> BufferResult<Vec<T>> {
+        futures::executor::block_on(self.read_range_staged(offset, len, true))
+    }
+
+    /// Maps all the elements of the [`GpuBuffer`] for reading, blocking until they are available.
     ///
-    /// This function will attempt to write the entire contents of `buf` unless its capacity
-    /// exceeds the one of the source buffer, in which case `GpuBuffer::capacity()` elements are written.
-    pub fn write(&self, buf: &[T]) -> BufferResult<u64> {
-        let input_size = (buf.len() * std::mem::size_of::<T>()) as u64;
-        let upload_size = if input_size > self.size {
-            self.size
-        } else {
-            input_size
+    /// Unlike [`GpuBuffer::read_vec_blocking`], the elements are not copied into a [`Vec`]: the returned
+    /// [`MappedGuard`] derefs to the mapped staging memory, which is unmapped and reused once it is dropped.
+    ///
+    /// # Panics
+    /// If the alignment of `T` is larger than [`wgpu::MAP_ALIGNMENT`].
+    pub fn read_mapped(&self) -> BufferResult<MappedGuard<'_, T>> {
+        let download = match self.download(0, self.capacity())? {
+            Some(download) => download,
+            None => return Ok(MappedGuard::empty()),
         };
 
-        self.fw
-            .queue
-            .write_buffer(&self.buf, 0, bytemuck::cast_slice(buf));
+        // Boxed so the staging buffer keeps its address while the view borrows it.
+        let mut download = Box::new(download);
+        download.wait();
+        futures::executor::block_on(download.mapped())?;
 
-        let encoder = self
-            .fw
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("GpuBuffer::write"),
-            });
-        self.fw.queue.submit(Some(encoder.finish()));
+        let view = download
+            .staging()
+            .slice(..download.staging_size)
+            .get_mapped_range();
+        // Safety: the view borrows the staging buffer owned by the boxed download, which is
+        // neither moved nor modified while the guard is alive and is dropped after the view.
+        let view: wgpu::BufferView<'static> = unsafe { std::mem::transmute(view) };
+        let start = download.skip as usize;
 
-        Ok(upload_size)
+        Ok(MappedGuard {
+            view: Some(view),
+            range: start..start + download.size as usize,
+            _download: Some(download),
+            marker: PhantomData,
+        })
     }
-}
 
-impl<'fw, T> BufOps<'fw, T> for GpuUniformBuffer<'fw, T>
-where
-    T: bytemuck::Pod,
-{
-    fn size(&self) -> u64 {
-        self.size
-    }
+    /// Reads the [`GpuBuffer`] one chunk of at most `chunk_bytes` bytes at a time, blocking until all
+    /// the chunks are read. `f` is called with the element offset and the elements of every chunk.
+    ///
+    /// A single staging buffer of `chunk_bytes` is reused, so buffers larger than the available host or
+    /// staging memory can be streamed, e.g. to a file. Chunks hold a multiple of 4 elements, at least 4.
+    pub fn read_chunked<F>(&self, chunk_bytes: u64, mut f: F) -> BufferResult<()>
+    where
+        F: FnMut(u64, &[T]),
+    {
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
 
-    fn as_gpu_buffer(&self) -> &wgpu::Buffer {
-        &self.buf
-    }
+        let element_size = std::mem::size_of::<T>() as u64;
+        let capacity = self.capacity();
+        // Multiple of 4 elements, so every chunk starts 4 bytes aligned.
+        let chunk_len = std::cmp::min(
+            std::cmp::max(chunk_bytes / element_size / 4 * 4, 4),
+            capacity,
+        );
 
-    fn with_capacity(fw: &'fw crate::Framework, capacity: u64) -> Self {
-        let size = capacity * std::mem::size_of::<T>() as u64;
+        if chunk_len == 0 {
+            return Ok(());
+        }
 
-        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("GpuUniformBuffer::with_capacity"),
-            size,
-            usage: GPU_UNIFORM_USAGES,
-            mapped_at_creation: false,
-        });
+        let staging_size =
+            wgpu::util::align_to(chunk_len * element_size, wgpu::COPY_BUFFER_ALIGNMENT);
+        let staging = self.fw.staging.acquire(&self.fw, staging_size);
+        let read = self.read_chunks(&staging, chunk_len, &mut f);
+        // Also released if a chunk failed to map, leaving it unmapped.
+        self.fw.staging.release(staging);
 
-        Self {
-            fw,
-            buf,
-            size,
-            marker: PhantomData,
-        }
+        read
     }
 
-    fn from_slice(fw: &'fw crate::Framework, slice: &[T]) -> Self {
-        let size = (slice.len() * std::mem::size_of::<T>()) as u64;
-        let buf = fw
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("GpuUniformBuffer::from_slice"),
-                contents: bytemuck::cast_slice(slice),
-                usage: GPU_UNIFORM_USAGES,
+    /// Reads the chunks of [`GpuBuffer::read_chunked`] of `chunk_len` elements through `staging`.
+    fn read_chunks<F>(&self, staging: &wgpu::Buffer, chunk_len: u64, f: &mut F) -> BufferResult<()>
+    where
+        F: FnMut(u64, &[T]),
+    {
+        let element_size = std::mem::size_of::<T>() as u64;
+        let capacity = self.capacity();
+
+        let mut offset = 0;
+        while offset < capacity {
+            let len = std::cmp::min(chunk_len, capacity - offset);
+            let size = len * element_size;
+            let copy_size = wgpu::util::align_to(size, wgpu::COPY_BUFFER_ALIGNMENT);
+
+            let mut encoder =
+                self.fw
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("GpuBuffer::read_chunked"),
+                    });
+            encoder.copy_buffer_to_buffer(&self.buf, offset * element_size, staging, 0, copy_size);
+            let index = self.fw.queue.submit(Some(encoder.finish()));
+
+            let (tx, rx) = futures::channel::oneshot::channel();
+            let chunk = staging.slice(..copy_size);
+            chunk.map_async(MapMode::Read, |result| {
+                let _ = tx.send(result);
             });
+            self.fw
+                .device
+                .poll(wgpu::Maintain::WaitForSubmissionIndex(index));
+            futures::executor::block_on(rx).expect("GpuBuffer futures::channel::oneshot error")?;
 
-        Self {
-            fw,
-            buf,
-            size,
-            marker: PhantomData,
+            {
+                let mapped = chunk.get_mapped_range();
+                f(offset, bytemuck::cast_slice(&mapped[..size as usize]));
+            }
+            staging.unmap();
+
+            offset += len;
         }
+
+        Ok(())
     }
 
-    fn from_gpu_parts(fw: &'fw crate::Framework, buf: wgpu::Buffer, size: u64) -> Self {
-        Self {
-            fw,
-            buf,
-            size,
-            marker: PhantomData,
+    /// Blocking version of `GpuBuffer::read()`.
+    ///
+    /// The device is polled until the read is completed, so it does not depend on the polling thread.
+    pub fn read_blocking(&self, buf: &mut [T]) -> BufferResult<u64> {
+        futures::executor::block_on(self.read_staged(buf, true))
+    }
+
+    /// Pulls the elements from the [`GpuBuffer`] into the preallocated `target`, blocking
+    /// until the read is completed. Returns how many elements were written into `target`.
+    ///
+    /// If `target` is shorter than the buffer, only its length is read. If it is longer,
+    /// only its first [`BufOps::capacity`] elements are written.
+    ///
+    /// Reuse `target` to avoid allocating a new [`Vec`] on every read like [`GpuBuffer::read_vec_blocking`].
+    pub fn read_into(&self, target: &mut [T]) -> BufferResult<usize> {
+        self.read_blocking(target).map(|len| len as usize)
+    }
+
+    /// Blocking version of `GpuBuffer::read_vec()`.
+    ///
+    /// The device is polled until the read is completed, so it does not depend on the polling thread.
+    pub fn read_vec_blocking(&self) -> BufferResult<Vec<T>> {
+        let mut buf = vec![T::zeroed(); self.len()];
+        self.read_blocking(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Copies all the elements of this [`GpuBuffer`] into the beginning of `dst` on the GPU.
+    /// The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::DestinationTooSmall`] if `dst` cannot hold all the elements,
+    /// or with [`BufferError::DifferentFramework`] if `dst` was not created by the same [`Framework`](crate::Framework).
+    ///
+    /// Copies must be 4 bytes aligned: if the size of the buffer is not a multiple of 4 bytes
+    /// and `dst` is larger, its last bytes are transferred through host memory, which blocks.
+    pub fn copy_to(&self, dst: &GpuBuffer<T>) -> BufferResult<()> {
+        let len = self.capacity();
+        let capacity = dst.capacity();
+
+        if len > capacity {
+            return Err(BufferError::DestinationTooSmall { len, capacity });
         }
+
+        self.copy_bytes(0, dst, 0, self.size)
     }
 
-    fn into_gpu_parts(self) -> (wgpu::Buffer, u64) {
-        (self.buf, self.size)
+    /// Copies `len` elements starting at the element `src_offset` into `dst` at the element `dst_offset`
+    /// on the GPU. The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::SourceOutOfRange`] or [`BufferError::DestinationOutOfRange`] if a range
+    /// exceeds the capacity of its buffer, and with [`BufferError::OverlappingCopy`] if `dst` shares the memory
+    /// of this [`GpuBuffer`], e.g. through [`GpuBuffer::cast`], and both ranges overlap. Fails like [`GpuBuffer::copy_to`] otherwise.
+    ///
+    /// Copies must be 4 bytes aligned: the bytes of the ranges outside of it are transferred through host
+    /// memory, which blocks. Copies between disjoint ranges of the same buffer go through host memory too.
+    pub fn copy_range_to(
+        &self,
+        src_offset: u64,
+        dst: &GpuBuffer<T>,
+        dst_offset: u64,
+        len: u64,
+    ) -> BufferResult<()> {
+        self.check_range(src_offset, len)
+            .map_err(|_| BufferError::SourceOutOfRange {
+                offset: src_offset,
+                len,
+                capacity: self.capacity(),
+            })?;
+        dst.check_range(dst_offset, len)
+            .map_err(|_| BufferError::DestinationOutOfRange {
+                offset: dst_offset,
+                len,
+                capacity: dst.capacity(),
+            })?;
+
+        let element_size = std::mem::size_of::<T>() as u64;
+
+        self.copy_bytes(
+            src_offset * element_size,
+            dst,
+            dst_offset * element_size,
+            len * element_size,
+        )
     }
-}
 
-impl<'fw, T> GpuUniformBuffer<'fw, T>
-where
-    T: bytemuck::Pod,
-{
-    /// Writes a buffer into this [`GpuUniformBuffer`], returning how many elements were written. The operation is instantly offloaded.
+    /// Creates a new [`GpuBuffer`] with the same capacity and elements as this one, copied on the GPU.
+    pub fn duplicate(&self) -> GpuBuffer<T> {
+        let dst = GpuBuffer::with_capacity(&self.fw, self.capacity());
+        self.copy_to(&dst).expect("GpuBuffer duplication error!");
+
+        dst
+    }
+
+    /// Copies `size` bytes at the byte offset `src_start` into `dst` at the byte offset `dst_start`.
     ///
-    /// This function will attempt to write the entire contents of `buf` unless its capacity
-    /// exceeds the one of the source buffer, in which case `GpuBuffer::capacity()` elements are written.
-    pub fn write(&self, buf: &[T]) -> BufferResult<u64> {
-        let input_size = (buf.len() * std::mem::size_of::<T>()) as u64;
-        let upload_size = if input_size > self.size {
-            self.size
+    /// The bytes outside the 4 bytes aligned part of the range are transferred through host memory.
+    fn copy_bytes(
+        &self,
+        src_start: u64,
+        dst: &GpuBuffer<T>,
+        dst_start: u64,
+        size: u64,
+    ) -> BufferResult<()> {
+        if !Arc::ptr_eq(&self.fw.device, &dst.fw.device) {
+            return Err(BufferError::DifferentFramework);
+        }
+
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        if size == 0 {
+            return Ok(());
+        }
+
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+        let (src_end, dst_end) = (src_start + size, dst_start + size);
+
+        // Handles returned by `GpuBuffer::cast` share the same buffer.
+        if Arc::ptr_eq(&self.buf, &dst.buf) {
+            if src_start < dst_end && dst_start < src_end {
+                return Err(BufferError::OverlappingCopy);
+            }
+
+            // `wgpu` does not allow copies within the same buffer.
+            let bytes = self.read_bytes(src_start, size)?;
+            return dst.write_bytes(dst_start, &bytes);
+        }
+
+        if src_start % align != dst_start % align {
+            // No aligned part can be copied on the GPU.
+            let bytes = self.read_bytes(src_start, size)?;
+            return dst.write_bytes(dst_start, &bytes);
+        }
+
+        let head = (align - src_start % align) % align;
+        // The padding after the end of both buffers can be overwritten.
+        let tail = if src_end == self.size && dst_end == dst.size {
+            0
         } else {
-            input_size
+            src_end % align
         };
 
-        self.fw
-            .queue
-            .write_buffer(&self.buf, 0, bytemuck::cast_slice(buf));
+        if head + tail >= size {
+            let bytes = self.read_bytes(src_start, size)?;
+            return dst.write_bytes(dst_start, &bytes);
+        }
 
-        let encoder = self
+        let mut encoder = self
             .fw
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("GpuUniformBuffer::write"),
+                label: Some("GpuBuffer::copy_to"),
             });
+        encoder.copy_buffer_to_buffer(
+            &self.buf,
+            src_start + head,
+            &dst.buf,
+            dst_start + head,
+            wgpu::util::align_to(size - head - tail, align),
+        );
         self.fw.queue.submit(Some(encoder.finish()));
 
-        Ok(upload_size)
+        if head > 0 {
+            let bytes = self.read_bytes(src_start, head)?;
+            dst.write_bytes(dst_start, &bytes)?;
+        }
+
+        if tail > 0 {
+            let bytes = self.read_bytes(src_end - tail, tail)?;
+            dst.write_bytes(dst_end - tail, &bytes)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads `size` bytes at the byte offset `start`, blocking until the read is completed.
+    fn read_bytes(&self, start: u64, size: u64) -> BufferResult<Vec<u8>> {
+        let mut bytes = vec![0u8; size as usize];
+
+        if let Some(mut download) = self.download_bytes(start, size, false)? {
+            download.wait();
+            futures::executor::block_on(download.mapped())?;
+            download.copy_into(&mut bytes);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Resizes this [`GpuBuffer`] to hold `new_len` elements, keeping the first `min(capacity, new_len)` ones.
+    /// The new elements are zeroed.
+    ///
+    /// A new buffer is allocated with the same usages, e.g. the ones of [`GpuBuffer::with_capacity_and_usage`],
+    /// and the elements are copied on the GPU (see [`GpuBuffer::copy_range_to`]), then the previous
+    /// allocation is released. Its persistent staging buffer, if any, is recreated.
+    ///
+    /// Fails with [`BufferError::TooLarge`] like [`GpuBuffer::try_with_capacity`].
+    ///
+    /// Bind groups are created by [`Kernel::run`](crate::Kernel::run) from the [`SetBindings`](crate::SetBindings)
+    /// of each call, so these kernels use the resized buffer once it is added to new bindings. The bind groups
+    /// created once, by [`KernelBuilder::bind_set`](crate::KernelBuilder::bind_set) and [`Kernel::rebind_set`](crate::Kernel::rebind_set)
+    /// or by a [`SharedSet`](crate::SharedSet), keep the previous allocation alive: the kernels run with
+    /// [`Kernel::run_bound`](crate::Kernel::run_bound) keep using it until the set is bound again with
+    /// [`Kernel::rebind_set`](crate::Kernel::rebind_set) or [`SharedSet::rebind`](crate::SharedSet::rebind).
+    pub fn resize(&mut self, new_len: u64) -> BufferResult<()> {
+        let mut resized = Self::create(&self.fw, new_len, self.buf.usage())?;
+        self.copy_range_to(0, &resized, 0, std::cmp::min(self.capacity(), new_len))?;
+
+        if self.staging.is_some() {
+            resized = resized.with_persistent_staging();
+        }
+        *self = resized;
+
+        Ok(())
+    }
+
+    /// Returns a view of `len` elements starting at the element `offset`, that can be bound
+    /// with [`SetBindings::add_buffer_slice`](crate::SetBindings::add_buffer_slice) so a kernel only sees that region.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if the range exceeds the [`GpuBuffer`] capacity,
+    /// with [`BufferError::UnalignedSliceOffset`] if the byte offset is not a multiple of the
+    /// `min_storage_buffer_offset_alignment` limit of the device (at most 256 bytes) and with
+    /// [`BufferError::InvalidSliceSize`] if the slice is empty or, unless it ends the buffer,
+    /// its byte size is not a multiple of 4.
+    pub fn slice(&self, offset: u64, len: u64) -> BufferResult<GpuBufferSlice<'_, T>> {
+        self.check_range(offset, len)?;
+
+        let element_size = std::mem::size_of::<T>() as u64;
+        let start = offset * element_size;
+        let size = len * element_size;
+
+        let alignment = self.fw.device.limits().min_storage_buffer_offset_alignment as u64;
+        if !start.is_multiple_of(alignment) {
+            return Err(BufferError::UnalignedSliceOffset {
+                offset: start,
+                alignment,
+            });
+        }
+
+        // The padding after the end of the buffer can be bound.
+        let bind_size = if start + size == self.size {
+            wgpu::util::align_to(size, wgpu::COPY_BUFFER_ALIGNMENT)
+        } else {
+            size
+        };
+        let bind_size = std::num::NonZeroU64::new(bind_size)
+            .filter(|size| size.get().is_multiple_of(4))
+            .ok_or(BufferError::InvalidSliceSize(size))?;
+
+        Ok(GpuBufferSlice {
+            buf: self,
+            offset,
+            len,
+            bind_size,
+        })
+    }
+
+    /// Returns a handle to the same GPU memory with elements of type `U`, e.g. to read as `f32`
+    /// the bit patterns written as `u32` by a previous kernel, or to read its raw bytes as `u8`.
+    ///
+    /// Both handles share the underlying [`wgpu::Buffer`], which is released once all of them
+    /// are dropped: writes through one of them are visible through the others, and both can be
+    /// bound to kernels or read back. [`GpuBuffer::resize`] replaces the storage of the resized
+    /// handle only, detaching it from the others.
+    ///
+    /// Fails with [`BufferError::InvalidCast`] if the byte size of this [`GpuBuffer`]
+    /// is not a multiple of the size of `U`.
+    pub fn cast<U: bytemuck::Pod>(&self) -> BufferResult<GpuBuffer<U>> {
+        let element_size = std::mem::size_of::<U>() as u64;
+
+        if element_size == 0 || !self.size.is_multiple_of(element_size) {
+            return Err(BufferError::InvalidCast {
+                size: self.size,
+                element_size,
+            });
+        }
+
+        Ok(GpuBuffer {
+            fw: self.fw.clone(),
+            buf: Arc::clone(&self.buf),
+            size: self.size,
+            _alloc: Arc::clone(&self._alloc),
+            staging: self.staging.clone(),
+            marker: PhantomData,
+        })
+    }
+
+    /// Attaches a dedicated staging buffer of the same size to this [`GpuBuffer`], used by all its reads.
+    ///
+    /// Intended for buffers read back very often: reads neither allocate nor go through the staging pool
+    /// of the [`Framework`](crate::Framework), so their latency is deterministic. The staging buffer is
+    /// accounted as [`ResourceKind::Staging`].
+    ///
+    /// Only one read can be in flight: reading while a previous read future is pending
+    /// fails with [`BufferError::StagingBusy`].
+    pub fn with_persistent_staging(mut self) -> Self {
+        self.staging = Some(Arc::new(PersistentStaging::new(
+            &self.fw,
+            self.size,
+            "GpuBuffer persistent staging",
+        )));
+
+        self
+    }
+
+    /// Copies this [`GpuBuffer`] into a new [`GpuBuffer`] of another [`Framework`](crate::Framework),
+    /// e.g. one created on a different GPU.
+    ///
+    /// The data is transferred through host memory.
+    pub async fn copy_to_framework(&self, fw: &crate::Framework) -> BufferResult<GpuBuffer<T>> {
+        let data = self.read_vec().await?;
+
+        Ok(GpuBuffer::from_slice(fw, &data))
+    }
+
+    /// Blocking version of `GpuBuffer::copy_to_framework()`.
+    pub fn copy_to_framework_blocking(&self, fw: &crate::Framework) -> BufferResult<GpuBuffer<T>> {
+        futures::executor::block_on(self.copy_to_framework(fw))
+    }
+
+    /// Writes a buffer into this [`GpuBuffer`], returning how many elements were written. The operation is instantly offloaded.
+    ///
+    /// The elements after the length of `buf` are left untouched. Fails with
+    /// [`BufferError::OutOfRange`] if `buf` is longer than the [`GpuBuffer`] capacity.
+    ///
+    /// Writes must be 4 bytes aligned: if the size of `buf` is not a multiple of 4 bytes
+    /// (e.g. 3 `u8`), the last bytes of the [`GpuBuffer`] are read back first, which blocks.
+    pub fn write(&self, buf: &[T]) -> BufferResult<u64> {
+        let len = buf.len() as u64;
+        self.check_range(0, len)?;
+
+        self.write_bytes(0, bytemuck::cast_slice(buf))?;
+
+        Ok(len)
+    }
+
+    /// Writes a buffer into this [`GpuBuffer`] through its own staging buffer, returning how
+    /// many elements were written. Intended for very large uploads.
+    ///
+    /// Unlike [`GpuBuffer::write`], which copies `buf` into the internal staging memory of the queue,
+    /// the upload is split into chunks of at most [`Framework::staging_chunk_size`] bytes that reuse
+    /// a single staging buffer, so the peak memory usage stays bounded. The future resolves once
+    /// the last chunk is submitted; later GPU operations see the new contents.
+    ///
+    /// The staging buffer is remapped by device polling between chunks (see [`GpuBuffer::read`]).
+    /// Fails like [`GpuBuffer::write`].
+    ///
+    /// [`Framework::staging_chunk_size`]: crate::Framework::staging_chunk_size
+    pub async fn write_async(&self, buf: &[T]) -> BufferResult<u64> {
+        let len = buf.len() as u64;
+        self.check_range(0, len)?;
+
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        let bytes: &[u8] = bytemuck::cast_slice(buf);
+        // The unaligned tail is written through the queue.
+        let (aligned, tail) =
+            bytes.split_at(bytes.len() - bytes.len() % wgpu::COPY_BUFFER_ALIGNMENT as usize);
+
+        if !aligned.is_empty() {
+            let chunk_size = std::cmp::min(self.fw.staging_chunk_size(), aligned.len() as u64);
+            let staging = self.fw.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("GpuBuffer::write_async staging"),
+                size: chunk_size,
+                usage: UPLOAD_USAGES,
+                mapped_at_creation: true,
+            });
+            let _alloc = self.fw.memory.allocate(ResourceKind::Staging, chunk_size);
+
+            let mut chunks = aligned.chunks(chunk_size as usize).enumerate().peekable();
+
+            while let Some((idx, chunk)) = chunks.next() {
+                let chunk_len = chunk.len() as u64;
+
+                staging
+                    .slice(..chunk_len)
+                    .get_mapped_range_mut()
+                    .copy_from_slice(chunk);
+                staging.unmap();
+
+                let mut encoder =
+                    self.fw
+                        .device
+                        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                            label: Some("GpuBuffer::write_async"),
+                        });
+                encoder.copy_buffer_to_buffer(
+                    &staging,
+                    0,
+                    &self.buf,
+                    idx as u64 * chunk_size,
+                    chunk_len,
+                );
+                let index = self.fw.queue.submit(Some(encoder.finish()));
+
+                if chunks.peek().is_some() {
+                    let (tx, rx) = futures::channel::oneshot::channel();
+                    staging.slice(..).map_async(MapMode::Write, |result| {
+                        let _ = tx.send(result);
+                    });
+
+                    if !self.fw.is_polling() {
+                        self.fw
+                            .device
+                            .poll(wgpu::Maintain::WaitForSubmissionIndex(index));
+                    }
+
+                    rx.await
+                        .expect("GpuBuffer futures::channel::oneshot error")?;
+                }
+            }
+        }
+
+        if !tail.is_empty() {
+            self.write_bytes_staged(aligned.len() as u64, tail, !self.fw.is_polling())
+                .await?;
+        }
+
+        Ok(len)
+    }
+
+    /// Writes `buf` into this [`GpuBuffer`] starting at the element `offset`. The operation is instantly offloaded.
+    ///
+    /// The other elements are left untouched. Fails with [`BufferError::OutOfRange`]
+    /// if the range exceeds the [`GpuBuffer`] capacity.
+    ///
+    /// Writes must be 4 bytes aligned: if the range does not start and end at multiples of 4 bytes
+    /// (e.g. `u8` or `u16` elements at odd offsets), the bytes around it are read back first, which blocks.
+    pub fn write_range(&self, offset: u64, buf: &[T]) -> BufferResult<()> {
+        self.check_range(offset, buf.len() as u64)?;
+
+        self.write_bytes(
+            offset * std::mem::size_of::<T>() as u64,
+            bytemuck::cast_slice(buf),
+        )
+    }
+
+    /// Sets all the elements of this [`GpuBuffer`] to zero on the GPU. The operation is instantly offloaded.
+    pub fn clear(&self) -> BufferResult<()> {
+        self.clear_range(0, self.capacity())
+    }
+
+    /// Sets `len` elements starting at the element `offset` to zero on the GPU. The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if the range exceeds the [`GpuBuffer`] capacity.
+    ///
+    /// Clears must be 4 bytes aligned: the bytes of the range outside of it are written
+    /// like [`GpuBuffer::write_range`] does.
+    pub fn clear_range(&self, offset: u64, len: u64) -> BufferResult<()> {
+        self.check_range(offset, len)?;
+
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+        let element_size = std::mem::size_of::<T>() as u64;
+        let start = offset * element_size;
+        let end = start + len * element_size;
+
+        let clear_start = wgpu::util::align_to(start, align);
+        // The padding after the end of the buffer can be cleared.
+        let clear_end = if end == self.size {
+            wgpu::util::align_to(end, align)
+        } else {
+            end - end % align
+        };
+
+        if clear_start >= clear_end {
+            return self.write_bytes(start, &vec![0; (end - start) as usize]);
+        }
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuBuffer::clear"),
+            });
+        encoder.clear_buffer(
+            &self.buf,
+            clear_start,
+            wgpu::BufferSize::new(clear_end - clear_start),
+        );
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        if start < clear_start {
+            self.write_bytes(start, &vec![0; (clear_start - start) as usize])?;
+        }
+
+        if clear_end < end {
+            self.write_bytes(clear_end, &vec![0; (end - clear_end) as usize])?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets all the elements of this [`GpuBuffer`] to `value`. The operation is instantly offloaded.
+    ///
+    /// The elements are uploaded through the queue in chunks of at most
+    /// [`Framework::staging_chunk_size`](crate::Framework::staging_chunk_size) bytes.
+    pub fn fill(&self, value: T) -> BufferResult<()> {
+        let element_size = std::mem::size_of::<T>() as u64;
+        let capacity = self.capacity();
+
+        if capacity == 0 {
+            return Ok(());
+        }
+
+        // Multiple of 4 elements, so every chunk but the last one is 4 bytes aligned.
+        let chunk_len = std::cmp::max(self.fw.staging_chunk_size() / element_size / 4 * 4, 4);
+        let chunk = vec![value; std::cmp::min(chunk_len, capacity) as usize];
+
+        let mut offset = 0;
+        while offset < capacity {
+            let len = std::cmp::min(chunk_len, capacity - offset);
+            self.write_bytes(
+                offset * element_size,
+                bytemuck::cast_slice(&chunk[..len as usize]),
+            )?;
+
+            offset += len;
+        }
+
+        Ok(())
+    }
+
+    /// Fails with [`BufferError::OutOfRange`] if `len` elements at `offset` exceed the capacity.
+    fn check_range(&self, offset: u64, len: u64) -> BufferResult<()> {
+        let capacity = self.capacity();
+
+        if offset.checked_add(len).is_none_or(|end| end > capacity) {
+            return Err(BufferError::OutOfRange {
+                offset,
+                len,
+                capacity,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Writes `bytes` at the byte offset `start` through the queue.
+    ///
+    /// Unaligned ranges are widened to 4 bytes, reading back the bytes around them.
+    fn write_bytes(&self, start: u64, bytes: &[u8]) -> BufferResult<()> {
+        futures::executor::block_on(self.write_bytes_staged(start, bytes, true))
+    }
+
+    /// Async version of [`GpuBuffer::write_bytes`]. The device is polled until the read back is
+    /// completed if `wait` is set.
+    async fn write_bytes_staged(&self, start: u64, bytes: &[u8], wait: bool) -> BufferResult<()> {
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        let size = bytes.len() as u64;
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+
+        if start.is_multiple_of(align) && size.is_multiple_of(align) {
+            self.fw.queue.write_buffer(&self.buf, start, bytes);
+        } else {
+            let write_start = start - start % align;
+            let write_end = wgpu::util::align_to(start + size, align);
+
+            let mut widened = vec![0u8; (write_end - write_start) as usize];
+
+            if let Some(mut download) =
+                self.download_bytes(write_start, write_end - write_start, false)?
+            {
+                if wait {
+                    download.wait();
+                }
+                download.mapped().await?;
+                download.copy_into(&mut widened);
+            }
+
+            let skip = (start - write_start) as usize;
+            widened[skip..skip + bytes.len()].copy_from_slice(bytes);
+
+            self.fw.queue.write_buffer(&self.buf, write_start, &widened);
+        }
+
+        let encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuBuffer::write"),
+            });
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+}
+
+impl GpuBuffer<DispatchIndirectArgs> {
+    /// Constructs a new [`GpuBuffer`] of indirect dispatch arguments, for [`Kernel::run_indirect`](crate::Kernel::run_indirect).
+    ///
+    /// It is a storage buffer with the [`wgpu::BufferUsages::INDIRECT`] usage, so kernels can write its arguments.
+    pub fn from_indirect_args(fw: &crate::Framework, args: &[DispatchIndirectArgs]) -> Self {
+        Self::from_slice_with_usage(fw, args, wgpu::BufferUsages::INDIRECT)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+/// Region of a [`GpuBuffer`], bound with [`SetBindings::add_buffer_slice`](crate::SetBindings::add_buffer_slice).
+/// See [`GpuBuffer::slice`].
+pub struct GpuBufferSlice<'a, T> {
+    buf: &'a GpuBuffer<T>,
+    offset: u64,
+    len: u64,
+    bind_size: std::num::NonZeroU64,
+}
+
+impl<T> Clone for GpuBufferSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GpuBufferSlice<'_, T> {}
+
+impl<'a, T> GpuBufferSlice<'a, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Returns the [`GpuBuffer`] of this [`GpuBufferSlice`].
+    pub fn buffer(&self) -> &'a GpuBuffer<T> {
+        self.buf
+    }
+
+    /// Returns the offset of this [`GpuBufferSlice`] in elements.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the length of this [`GpuBufferSlice`] in elements.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if this [`GpuBufferSlice`] has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a [`wgpu::BindingResource`] of the region.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'a> {
+        wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer: &self.buf.buf,
+            offset: self.offset * std::mem::size_of::<T>() as u64,
+            size: Some(self.bind_size),
+        })
+    }
+
+    /// Asynchronously reads the elements of this region. See [`GpuBuffer::read_range`].
+    pub async fn read_vec(&self) -> BufferResult<Vec<T>> {
+        self.buf.read_range(self.offset, self.len).await
+    }
+
+    /// Blocking version of `GpuBufferSlice::read_vec()`.
+    pub fn read_vec_blocking(&self) -> BufferResult<Vec<T>> {
+        self.buf.read_range_blocking(self.offset, self.len)
+    }
+
+    /// Writes `buf` at the start of this region. See [`GpuBuffer::write_range`].
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if `buf` is longer than the region.
+    pub fn write(&self, buf: &[T]) -> BufferResult<()> {
+        let len = buf.len() as u64;
+
+        if len > self.len {
+            return Err(BufferError::OutOfRange {
+                offset: 0,
+                len,
+                capacity: self.len,
+            });
+        }
+
+        self.buf.write_range(self.offset, buf)
+    }
+}
+
+/// Readback of several [`GpuBuffer`]s, possibly of different element types, in a single submission.
+///
+/// All the staging copies are recorded into one command encoder and submitted at once, then all
+/// the mappings are resolved after a single poll, instead of one submission and poll per buffer.
+///
+/// ```ignore
+/// let mut batch = ReadbackBatch::new(&fw);
+/// let positions = batch.add(&gpu_positions);
+/// let count = batch.add(&gpu_count);
+/// let results = batch.submit();
+///
+/// let positions: Vec<[f32; 4]> = results.get(positions)?;
+/// let count: Vec<u32> = results.get(count)?;
+/// ```
+pub struct ReadbackBatch<'a> {
+    fw: crate::Framework,
+    entries: Vec<BatchEntry<'a>>,
+}
+
+struct BatchEntry<'a> {
+    buf: &'a wgpu::Buffer,
+    size: u64,
+    same_framework: bool,
+}
+
+/// Handle of a [`GpuBuffer`] added to a [`ReadbackBatch`], to get its elements from the [`ReadbackResults`].
+pub struct Readback<T> {
+    index: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> Clone for Readback<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Readback<T> {}
+
+impl<'a> ReadbackBatch<'a> {
+    /// Creates an empty [`ReadbackBatch`].
+    pub fn new(fw: &crate::Framework) -> Self {
+        Self {
+            fw: fw.clone(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds all the elements of `buffer` to the readback.
+    pub fn add<T: bytemuck::Pod>(&mut self, buffer: &'a GpuBuffer<T>) -> Readback<T> {
+        self.entries.push(BatchEntry {
+            buf: &buffer.buf,
+            size: buffer.size,
+            same_framework: Arc::ptr_eq(&self.fw.device, &buffer.fw.device),
+        });
+
+        Readback {
+            index: self.entries.len() - 1,
+            marker: PhantomData,
+        }
+    }
+
+    /// Submits all the copies at once and waits for their mappings.
+    ///
+    /// Failures are reported per buffer by [`ReadbackResults::get`], e.g. [`BufferError::DifferentFramework`]
+    /// for a buffer that was not created by the [`Framework`](crate::Framework) of the batch.
+    pub fn submit(self) -> ReadbackResults {
+        if self.fw.is_device_lost() {
+            return ReadbackResults {
+                downloads: self
+                    .entries
+                    .iter()
+                    .map(|_| Err(BufferError::DeviceLost))
+                    .collect(),
+            };
+        }
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("ReadbackBatch::submit"),
+            });
+
+        let copies = self
+            .entries
+            .iter()
+            .map(|entry| {
+                if !entry.same_framework {
+                    return Err(BufferError::DifferentFramework);
+                }
+
+                if entry.size == 0 {
+                    return Ok(None);
+                }
+
+                // Buffers are padded, so the copy can be rounded up.
+                let staging_size = wgpu::util::align_to(entry.size, wgpu::COPY_BUFFER_ALIGNMENT);
+                let staging = self.fw.staging.acquire(&self.fw, staging_size);
+                encoder.copy_buffer_to_buffer(entry.buf, 0, &staging, 0, staging_size);
+
+                Ok(Some((staging, staging_size, entry.size)))
+            })
+            .collect::<Vec<_>>();
+
+        let index = self.fw.queue.submit(Some(encoder.finish()));
+
+        let mut downloads = copies
+            .into_iter()
+            .map(|copy| {
+                copy.map(|copy| {
+                    copy.map(|(staging, staging_size, size)| {
+                        Download::map(
+                            &self.fw,
+                            Staging::Pooled(staging),
+                            staging_size,
+                            0,
+                            size,
+                            index.clone(),
+                        )
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // A single poll resolves all the mappings.
+        self.fw
+            .device
+            .poll(wgpu::Maintain::WaitForSubmissionIndex(index));
+
+        for download in downloads.iter_mut() {
+            if let Ok(Some(mapping)) = download {
+                if let Err(err) = futures::executor::block_on(mapping.mapped()) {
+                    *download = Err(err);
+                }
+            }
+        }
+
+        ReadbackResults { downloads }
+    }
+}
+
+/// Mapped staging buffers of a submitted [`ReadbackBatch`], released on drop.
+pub struct ReadbackResults {
+    downloads: Vec<BufferResult<Option<Download>>>,
+}
+
+impl ReadbackResults {
+    /// Copies the elements of the buffer of `handle` into a [`Vec`], or returns the error of its readback.
+    ///
+    /// # Panics
+    /// If `handle` was returned by another [`ReadbackBatch`] with fewer buffers.
+    pub fn get<T: bytemuck::Pod>(&self, handle: Readback<T>) -> BufferResult<Vec<T>> {
+        match &self.downloads[handle.index] {
+            Ok(Some(download)) => {
+                let mut buf =
+                    vec![T::zeroed(); (download.size / std::mem::size_of::<T>() as u64) as usize];
+                download.copy_into(&mut buf);
+
+                Ok(buf)
+            }
+            Ok(None) => Ok(Vec::new()),
+            Err(err) => Err(err.clone()),
+        }
+    }
+}
+
+/// Elements of a [`GpuBuffer`] mapped in host memory. See [`GpuBuffer::read_mapped`].
+///
+/// Derefs to a slice of the elements. The staging memory is unmapped and returned to its pool on drop.
+pub struct MappedGuard<'a, T> {
+    // Borrows the staging buffer of `_download`, so it must be declared (i.e. dropped) first.
+    view: Option<wgpu::BufferView<'static>>,
+    range: std::ops::Range<usize>,
+    _download: Option<Box<Download>>,
+    marker: PhantomData<&'a GpuBuffer<T>>,
+}
+
+impl<T> MappedGuard<'_, T> {
+    fn empty() -> Self {
+        Self {
+            view: None,
+            range: 0..0,
+            _download: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> std::ops::Deref for MappedGuard<'_, T>
+where
+    T: bytemuck::Pod,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match &self.view {
+            Some(view) => bytemuck::cast_slice(&view[self.range.clone()]),
+            None => &[],
+        }
+    }
+}
+
+/// Staging buffer of a [`Download`].
+pub(crate) enum Staging {
+    Pooled(PooledBuffer),
+    Persistent(Arc<PersistentStaging>),
+}
+
+impl Staging {
+    fn buffer(&self) -> &wgpu::Buffer {
+        match self {
+            Self::Pooled(buf) => buf,
+            Self::Persistent(staging) => &staging.buf,
+        }
+    }
+}
+
+/// Staging buffer of a pending [`GpuBuffer`] read, released on drop.
+pub(crate) struct Download {
+    fw: crate::Framework,
+    staging: Option<Staging>,
+    mapped: bool,
+    staging_size: u64,
+    skip: u64,
+    size: u64,
+    index: wgpu::SubmissionIndex,
+    mapping: Receiver<Result<(), wgpu::BufferAsyncError>>,
+}
+
+impl Download {
+    /// Requests the mapping of the first `staging_size` bytes of `staging`, once the submission `index` is done.
+    pub(crate) fn map(
+        fw: &crate::Framework,
+        staging: Staging,
+        staging_size: u64,
+        skip: u64,
+        size: u64,
+        index: wgpu::SubmissionIndex,
+    ) -> Self {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        staging
+            .buffer()
+            .slice(..staging_size)
+            .map_async(MapMode::Read, |result| {
+                // The receiver is dropped if the read was cancelled.
+                let _ = tx.send(result);
+            });
+
+        Self {
+            fw: fw.clone(),
+            staging: Some(staging),
+            mapped: false,
+            staging_size,
+            skip,
+            size,
+            index,
+            mapping: rx,
+        }
+    }
+
+    /// Copies `staging_size` bytes of `buf` starting at the byte `copy_start` into `staging`, then requests
+    /// its mapping like [`Download::map`].
+    fn copy(
+        fw: &crate::Framework,
+        buf: &wgpu::Buffer,
+        copy_start: u64,
+        staging: Staging,
+        staging_size: u64,
+        skip: u64,
+        size: u64,
+    ) -> Self {
+        let mut encoder = fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuBuffer::read"),
+            });
+        encoder.copy_buffer_to_buffer(buf, copy_start, staging.buffer(), 0, staging_size);
+        let index = fw.queue.submit(Some(encoder.finish()));
+
+        Self::map(fw, staging, staging_size, skip, size, index)
+    }
+
+    /// Polls the device until the copy into the staging buffer is completed.
+    pub(crate) fn wait(&self) {
+        self.fw
+            .device
+            .poll(wgpu::Maintain::WaitForSubmissionIndex(self.index.clone()));
+    }
+
+    /// Waits for the staging buffer to be mapped.
+    async fn mapped(&mut self) -> BufferResult<()> {
+        Ok(self.mapped_raw().await?)
+    }
+
+    /// Waits for the staging buffer to be mapped, returning the raw mapping error.
+    pub(crate) async fn mapped_raw(&mut self) -> Result<(), wgpu::BufferAsyncError> {
+        (&mut self.mapping)
+            .await
+            .expect("GpuBuffer futures::channel::oneshot error")?;
+        self.mapped = true;
+
+        Ok(())
+    }
+
+    /// Returns the mapped bytes of the staging buffer.
+    pub(crate) fn mapped_range(&self) -> wgpu::BufferView<'_> {
+        self.staging().slice(..self.staging_size).get_mapped_range()
+    }
+
+    fn staging(&self) -> &wgpu::Buffer {
+        self.staging
+            .as_ref()
+            .expect("Download staging buffer taken")
+            .buffer()
+    }
+
+    fn copy_into<T: bytemuck::Pod>(&self, buf: &mut [T]) {
+        let mapped = self.staging().slice(..self.staging_size).get_mapped_range();
+        let start = self.skip as usize;
+        buf.copy_from_slice(bytemuck::cast_slice(
+            &mapped[start..start + self.size as usize],
+        ));
+    }
+}
+
+impl Drop for Download {
+    fn drop(&mut self) {
+        match self.staging.take() {
+            // A buffer whose mapping is still pending (i.e. the read was cancelled) is not reused.
+            Some(Staging::Pooled(buf)) if self.mapped => {
+                buf.unmap();
+                self.fw.staging.release(buf);
+            }
+            Some(Staging::Persistent(staging)) => {
+                // Also aborts a pending mapping.
+                staging.buf.unmap();
+                staging.busy.store(false, Ordering::Release);
+            }
+            _ => (),
+        }
+    }
+}
+
+impl GpuBuffer<u8> {
+    /// Reads the value of type `U` stored at the byte `offset`, which does not need to be aligned.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if the value exceeds the [`GpuBuffer`] capacity.
+    pub fn read_at<U: bytemuck::Pod>(&self, offset: u64) -> BufferResult<U> {
+        let bytes = self.read_range_blocking(offset, std::mem::size_of::<U>() as u64)?;
+
+        Ok(bytemuck::pod_read_unaligned(&bytes))
+    }
+
+    /// Writes `value` at the byte `offset`, which does not need to be aligned. The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if the value exceeds the [`GpuBuffer`] capacity.
+    pub fn write_at<U: bytemuck::Pod>(&self, offset: u64, value: &U) -> BufferResult<()> {
+        self.write_range(offset, bytemuck::bytes_of(value))
+    }
+}
+
+impl<T> BufOps<T> for GpuUniformBuffer<T>
+where
+    T: bytemuck::Pod,
+{
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn as_gpu_buffer(&self) -> &wgpu::Buffer {
+        &self.buf
+    }
+
+    fn with_capacity(fw: &crate::Framework, capacity: u64) -> Self {
+        let size = capacity * std::mem::size_of::<T>() as u64;
+
+        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuUniformBuffer::with_capacity"),
+            size: wgpu::util::align_to(size, wgpu::COPY_BUFFER_ALIGNMENT),
+            usage: GPU_UNIFORM_USAGES,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            fw: fw.clone(),
+            buf,
+            size,
+            _alloc: fw.memory.allocate(ResourceKind::UniformBuffer, size),
+            marker: PhantomData,
+        }
+    }
+
+    fn from_slice(fw: &crate::Framework, slice: &[T]) -> Self {
+        let size = std::mem::size_of_val(slice) as u64;
+        let buf = fw
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("GpuUniformBuffer::from_slice"),
+                contents: bytemuck::cast_slice(slice),
+                usage: GPU_UNIFORM_USAGES,
+            });
+
+        Self {
+            fw: fw.clone(),
+            buf,
+            size,
+            _alloc: fw.memory.allocate(ResourceKind::UniformBuffer, size),
+            marker: PhantomData,
+        }
+    }
+
+    fn from_gpu_parts(fw: &crate::Framework, buf: wgpu::Buffer, size: u64) -> Self {
+        Self {
+            fw: fw.clone(),
+            buf,
+            size,
+            _alloc: fw.memory.allocate(ResourceKind::UniformBuffer, size),
+            marker: PhantomData,
+        }
+    }
+
+    fn into_gpu_parts(self) -> (wgpu::Buffer, u64) {
+        (self.buf, self.size)
+    }
+}
+
+impl<T> GpuUniformBuffer<T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new [`GpuUniformBuffer`] holding a single `value`, e.g. the parameters of a kernel.
+    ///
+    /// The bytes of `value` are uploaded as they are laid out in Rust, so `T` must follow the
+    /// alignment rules of uniform buffers. See [`SetLayout::add_uniform_buffer`](crate::SetLayout::add_uniform_buffer).
+    pub fn from_value(fw: &crate::Framework, value: &T) -> Self {
+        Self::from_slice(fw, std::slice::from_ref(value))
+    }
+
+    /// Returns the [`wgpu::Buffer`] of the [`GpuUniformBuffer`]. Same as [`BufOps::as_gpu_buffer`].
+    pub fn as_wgpu_buffer(&self) -> &wgpu::Buffer {
+        &self.buf
+    }
+
+    /// Replaces the first element of this [`GpuUniformBuffer`] with `value`. The operation is instantly offloaded.
+    pub fn update(&self, value: &T) -> BufferResult<()> {
+        self.write(std::slice::from_ref(value)).map(|_| ())
+    }
+
+    /// Writes a buffer into this [`GpuUniformBuffer`], returning how many elements were written. The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if `buf` is longer than the [`GpuUniformBuffer`] capacity.
+    /// Writes must be 4 bytes aligned: if the size of `buf` is not a multiple of 4 bytes,
+    /// the following bytes up to the next multiple of 4 are zeroed.
+    pub fn write(&self, buf: &[T]) -> BufferResult<u64> {
+        let len = buf.len() as u64;
+        let capacity = self.capacity();
+
+        if len > capacity {
+            return Err(BufferError::OutOfRange {
+                offset: 0,
+                len,
+                capacity,
+            });
+        }
+
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        let bytes: &[u8] = bytemuck::cast_slice(buf);
+        let aligned_size = wgpu::util::align_to(bytes.len(), wgpu::COPY_BUFFER_ALIGNMENT as usize);
+
+        if aligned_size == bytes.len() {
+            self.fw.queue.write_buffer(&self.buf, 0, bytes);
+        } else {
+            let mut padded = bytes.to_vec();
+            padded.resize(aligned_size, 0);
+            self.fw.queue.write_buffer(&self.buf, 0, &padded);
+        }
+
+        let encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuUniformBuffer::write"),
+            });
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        Ok(len)
+    }
+}
+
+impl<T> GpuAtomicBuffer<T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new [`GpuAtomicBuffer`] set to zero.
+    ///
+    /// It has a persistent staging buffer (see [`GpuBuffer::with_persistent_staging`]),
+    /// so reading it back does not go through the staging pool.
+    pub fn new(fw: &crate::Framework) -> Self {
+        Self::from_value(fw, T::zeroed())
+    }
+
+    /// Constructs a new [`GpuAtomicBuffer`] set to `value`.
+    pub fn from_value(fw: &crate::Framework, value: T) -> Self {
+        Self {
+            buf: GpuBuffer::from_slice(fw, &[value]).with_persistent_staging(),
+        }
+    }
+
+    /// Sets the counter to zero on the GPU. The operation is instantly offloaded.
+    pub fn reset(&self) -> BufferResult<()> {
+        self.buf.clear()
+    }
+
+    /// Sets the counter to `value`. The operation is instantly offloaded.
+    pub fn set(&self, value: T) -> BufferResult<()> {
+        self.buf.write(&[value]).map(|_| ())
+    }
+
+    /// Reads the counter back, blocking until the previously submitted work is done.
+    ///
+    /// Fails with [`BufferError::StagingBusy`] if another read of this [`GpuAtomicBuffer`] is in flight.
+    pub fn read(&self) -> BufferResult<T> {
+        let mut value = [T::zeroed()];
+        self.buf.read_blocking(&mut value)?;
+
+        Ok(value[0])
+    }
+
+    /// Returns the inner single-element [`GpuBuffer`].
+    pub fn into_inner(self) -> GpuBuffer<T> {
+        self.buf
+    }
+}
+
+impl<T> std::ops::Deref for GpuAtomicBuffer<T> {
+    type Target = GpuBuffer<T>;
+
+    fn deref(&self) -> &GpuBuffer<T> {
+        &self.buf
+    }
+}
+
+impl<T> GpuDownloadBuffer<T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new zeroed [`GpuDownloadBuffer`] with the specified capacity.
+    pub fn with_capacity(fw: &crate::Framework, capacity: u64) -> Self {
+        let size = capacity * std::mem::size_of::<T>() as u64;
+
+        Self {
+            fw: fw.clone(),
+            staging: Arc::new(PersistentStaging::new(fw, size, "GpuDownloadBuffer")),
+            size,
+            marker: PhantomData,
+        }
+    }
+
+    /// Obtains the number of elements the [`GpuDownloadBuffer`] can hold.
+    pub fn capacity(&self) -> u64 {
+        self.size / std::mem::size_of::<T>() as u64
+    }
+
+    /// Copies all the elements of `src` into the beginning of this [`GpuDownloadBuffer`] on the GPU.
+    /// The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::DestinationTooSmall`] if this [`GpuDownloadBuffer`] cannot hold all the elements,
+    /// with [`BufferError::DifferentFramework`] if `src` was not created by the same [`Framework`](crate::Framework)
+    /// and with [`BufferError::StagingBusy`] while a read is in flight.
+    ///
+    /// Copies must be 4 bytes aligned: if `src` is smaller than this [`GpuDownloadBuffer`] and its size is not
+    /// a multiple of 4 bytes, its last bytes are transferred through host memory, which blocks.
+    pub fn copy_from(&self, src: &GpuBuffer<T>) -> BufferResult<()> {
+        let len = src.capacity();
+        let capacity = self.capacity();
+
+        if len > capacity {
+            return Err(BufferError::DestinationTooSmall { len, capacity });
+        }
+
+        if !Arc::ptr_eq(&self.fw.device, &src.fw.device) {
+            return Err(BufferError::DifferentFramework);
+        }
+
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        if self.staging.busy.load(Ordering::Acquire) {
+            return Err(BufferError::StagingBusy);
+        }
+
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+        // The padding after the end of both buffers can be overwritten.
+        let tail = if src.size == self.size {
+            0
+        } else {
+            src.size % align
+        };
+        let aligned = wgpu::util::align_to(src.size - tail, align);
+
+        if tail > 0 {
+            // The last word is merged with the bytes of this buffer after the copied ones.
+            let mut word = vec![0u8; align as usize];
+
+            if self.staging.busy.swap(true, Ordering::Acquire) {
+                return Err(BufferError::StagingBusy);
+            }
+
+            {
+                let mut download = Download::map(
+                    &self.fw,
+                    Staging::Persistent(Arc::clone(&self.staging)),
+                    aligned + align,
+                    aligned,
+                    align,
+                    self.fw.queue.submit(None),
+                );
+                download.wait();
+                futures::executor::block_on(download.mapped())?;
+                download.copy_into(&mut word);
+            }
+
+            let bytes = src.read_bytes(aligned, tail)?;
+            word[..tail as usize].copy_from_slice(&bytes);
+            self.fw
+                .queue
+                .write_buffer(&self.staging.buf, aligned, &word);
+        }
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuDownloadBuffer::copy_from"),
+            });
+        if aligned > 0 {
+            encoder.copy_buffer_to_buffer(&src.buf, 0, &self.staging.buf, 0, aligned);
+        }
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+
+    /// Pulls some elements from the [`GpuDownloadBuffer`] into `buf`, returning how many elements were read.
+    /// The buffer is mapped in place, once the previously submitted copies are done.
+    ///
+    /// Fails with [`BufferError::StagingBusy`] if another read is in flight.
+    /// See [`GpuBuffer::read`] about polling.
+    pub async fn read(&self, buf: &mut [T]) -> BufferResult<u64> {
+        self.read_staged(buf, !self.fw.is_polling()).await
+    }
+
+    /// Blocking version of `GpuDownloadBuffer::read()`.
+    pub fn read_blocking(&self, buf: &mut [T]) -> BufferResult<u64> {
+        futures::executor::block_on(self.read_staged(buf, true))
+    }
+
+    /// Pulls all the elements from the [`GpuDownloadBuffer`] into a [`Vec`].
+    pub async fn read_vec(&self) -> BufferResult<Vec<T>> {
+        let mut buf = vec![T::zeroed(); self.capacity() as usize];
+        self.read(&mut buf).await?;
+
+        Ok(buf)
+    }
+
+    /// Blocking version of `GpuDownloadBuffer::read_vec()`.
+    pub fn read_vec_blocking(&self) -> BufferResult<Vec<T>> {
+        let mut buf = vec![T::zeroed(); self.capacity() as usize];
+        self.read_blocking(&mut buf)?;
+
+        Ok(buf)
+    }
+
+    async fn read_staged(&self, buf: &mut [T], wait: bool) -> BufferResult<u64> {
+        let len = std::cmp::min(buf.len() as u64, self.capacity());
+
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        if len == 0 {
+            return Ok(0);
+        }
+
+        if self.staging.busy.swap(true, Ordering::Acquire) {
+            return Err(BufferError::StagingBusy);
+        }
+
+        let size = len * std::mem::size_of::<T>() as u64;
+        // Nothing to copy, the empty submission tracks the previous ones.
+        let index = self.fw.queue.submit(None);
+        let mut download = Download::map(
+            &self.fw,
+            Staging::Persistent(Arc::clone(&self.staging)),
+            wgpu::util::align_to(size, wgpu::COPY_BUFFER_ALIGNMENT),
+            0,
+            size,
+            index,
+        );
+
+        if wait {
+            download.wait();
+        }
+        download.mapped().await?;
+        download.copy_into(&mut buf[..len as usize]);
+
+        Ok(len)
+    }
+}
+
+impl<T> GpuUploadBuffer<T>
+where
+    T: bytemuck::Pod,
+{
+    /// Constructs a new zeroed [`GpuUploadBuffer`] with the specified capacity.
+    pub fn with_capacity(fw: &crate::Framework, capacity: u64) -> Self {
+        Self::create(fw, capacity, false)
+    }
+
+    /// Constructs a new [`GpuUploadBuffer`] from a slice. It is mapped at creation, so no copy is involved.
+    pub fn from_slice(fw: &crate::Framework, slice: &[T]) -> Self {
+        Self::with_capacity_init(fw, slice.len() as u64, |mapped| {
+            mapped.copy_from_slice(slice)
+        })
+    }
+
+    /// Constructs a new [`GpuUploadBuffer`] with the specified capacity, whose elements
+    /// are initialized in place by `f` while the buffer is mapped at creation.
+    ///
+    /// # Panics
+    /// If the alignment of `T` is larger than [`wgpu::MAP_ALIGNMENT`].
+    pub fn with_capacity_init<F>(fw: &crate::Framework, capacity: u64, f: F) -> Self
+    where
+        F: FnOnce(&mut [T]),
+    {
+        let buffer = Self::create(fw, capacity, true);
+
+        {
+            let mut mapped = buffer.buf.slice(..).get_mapped_range_mut();
+            f(bytemuck::cast_slice_mut(
+                &mut mapped[..buffer.size as usize],
+            ));
+        }
+        buffer.buf.unmap();
+
+        buffer
+    }
+
+    fn create(fw: &crate::Framework, capacity: u64, mapped_at_creation: bool) -> Self {
+        let size = capacity * std::mem::size_of::<T>() as u64;
+        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuUploadBuffer"),
+            size: wgpu::util::align_to(size, wgpu::COPY_BUFFER_ALIGNMENT),
+            usage: UPLOAD_USAGES,
+            mapped_at_creation,
+        });
+
+        Self {
+            fw: fw.clone(),
+            buf,
+            size,
+            mapping: std::sync::Mutex::new(()),
+            _alloc: fw.memory.allocate(ResourceKind::Staging, size),
+            marker: PhantomData,
+        }
+    }
+
+    /// Obtains the number of elements the [`GpuUploadBuffer`] can hold.
+    pub fn capacity(&self) -> u64 {
+        self.size / std::mem::size_of::<T>() as u64
+    }
+
+    /// Writes a buffer into the beginning of this [`GpuUploadBuffer`], returning how many elements were written.
+    /// The buffer is mapped in place, blocking until its previous copies are done.
+    ///
+    /// Fails with [`BufferError::OutOfRange`] if `buf` is longer than the [`GpuUploadBuffer`] capacity.
+    pub fn write(&self, buf: &[T]) -> BufferResult<u64> {
+        let len = buf.len() as u64;
+        let capacity = self.capacity();
+
+        if len > capacity {
+            return Err(BufferError::OutOfRange {
+                offset: 0,
+                len,
+                capacity,
+            });
+        }
+
+        self.write_with(|mapped| mapped[..buf.len()].copy_from_slice(buf))?;
+
+        Ok(len)
+    }
+
+    /// Maps this [`GpuUploadBuffer`] and lets `f` modify its elements in place, blocking until
+    /// its previous copies are done. The buffer is unmapped once `f` returns.
+    ///
+    /// `f` can split the slice (e.g. with [`slice::chunks_mut`]) to fill it from several threads.
+    ///
+    /// # Panics
+    /// If the alignment of `T` is larger than [`wgpu::MAP_ALIGNMENT`].
+    pub fn write_with<F, R>(&self, f: F) -> BufferResult<R>
+    where
+        F: FnOnce(&mut [T]) -> R,
+    {
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        let _mapping = self.mapping.lock().expect("GpuUploadBuffer lock poisoned");
+
+        let (tx, rx) = futures::channel::oneshot::channel();
+        self.buf.slice(..).map_async(MapMode::Write, |result| {
+            let _ = tx.send(result);
+        });
+        self.fw.device.poll(wgpu::Maintain::Wait);
+        futures::executor::block_on(rx)
+            .expect("GpuUploadBuffer futures::channel::oneshot error")?;
+
+        let result = {
+            let mut mapped = self.buf.slice(..).get_mapped_range_mut();
+            f(bytemuck::cast_slice_mut(&mut mapped[..self.size as usize]))
+        };
+        self.buf.unmap();
+
+        Ok(result)
+    }
+
+    /// Copies all the elements of this [`GpuUploadBuffer`] into the beginning of `dst` on the GPU.
+    /// The operation is instantly offloaded.
+    ///
+    /// Fails with [`BufferError::DestinationTooSmall`] if `dst` cannot hold all the elements,
+    /// or with [`BufferError::DifferentFramework`] if `dst` was not created by the same [`Framework`](crate::Framework).
+    ///
+    /// Copies must be 4 bytes aligned: if `dst` is larger than this [`GpuUploadBuffer`] and its size is not
+    /// a multiple of 4 bytes, its last bytes are transferred through host memory, which blocks.
+    pub fn copy_to(&self, dst: &GpuBuffer<T>) -> BufferResult<()> {
+        let len = self.capacity();
+        let capacity = dst.capacity();
+
+        if len > capacity {
+            return Err(BufferError::DestinationTooSmall { len, capacity });
+        }
+
+        if !Arc::ptr_eq(&self.fw.device, &dst.fw.device) {
+            return Err(BufferError::DifferentFramework);
+        }
+
+        if self.fw.is_device_lost() {
+            return Err(BufferError::DeviceLost);
+        }
+
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+        // The padding after the end of both buffers can be overwritten.
+        let tail = if self.size == dst.size {
+            0
+        } else {
+            self.size % align
+        };
+        let aligned = wgpu::util::align_to(self.size - tail, align);
+
+        let _mapping = self.mapping.lock().expect("GpuUploadBuffer lock poisoned");
+
+        if aligned > 0 {
+            let mut encoder =
+                self.fw
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("GpuUploadBuffer::copy_to"),
+                    });
+            encoder.copy_buffer_to_buffer(&self.buf, 0, &dst.buf, 0, aligned);
+            self.fw.queue.submit(Some(encoder.finish()));
+        }
+
+        if tail > 0 {
+            let mut bytes = vec![0u8; tail as usize];
+
+            {
+                let staging = Staging::Pooled(self.fw.staging.acquire(&self.fw, align));
+                let mut download =
+                    Download::copy(&self.fw, &self.buf, aligned, staging, align, 0, tail);
+                download.wait();
+                futures::executor::block_on(download.mapped())?;
+                download.copy_into(&mut bytes);
+            }
+
+            dst.write_bytes(aligned, &bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn from_exact_size_iterator() {
+        testing::with_framework(|fw| {
+            let len = testing::STAGING_CHUNK_SIZE as u32 + 3;
+            let buffer = GpuBuffer::from_iterator(fw, 0..len);
+
+            assert_eq!(buffer.capacity(), len as u64);
+            assert!(buffer.read_vec_blocking().unwrap().into_iter().eq(0..len));
+        });
+    }
+
+    #[test]
+    fn from_unsized_iterator() {
+        testing::with_framework(|fw| {
+            let len = testing::STAGING_CHUNK_SIZE as u32 + 3;
+            // Grown while uploading, then shrunk to fit.
+            let buffer = GpuBuffer::from_iterator(fw, (0..).take_while(|i| *i < len));
+
+            assert_eq!(buffer.capacity(), len as u64);
+            assert!(buffer.read_vec_blocking().unwrap().into_iter().eq(0..len));
+        });
+    }
+
+    #[test]
+    fn from_empty_iterator() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::<u32>::from_iterator(fw, std::iter::empty());
+
+            assert_eq!(buffer.capacity(), 0);
+        });
+    }
+
+    #[test]
+    fn read_chunked() {
+        testing::with_framework(|fw| {
+            let data = (0..1003).collect::<Vec<u32>>();
+            let buffer = GpuBuffer::from_slice(fw, &data);
+
+            let mut chunks = Vec::new();
+            buffer
+                .read_chunked(1024, |offset, chunk| chunks.push((offset, chunk.to_vec())))
+                .unwrap();
+
+            let offsets = chunks.iter().map(|(offset, _)| *offset).collect::<Vec<_>>();
+            assert_eq!(offsets, [0, 256, 512, 768]);
+            let elements = chunks.into_iter().flat_map(|(_, chunk)| chunk);
+            assert_eq!(elements.collect::<Vec<_>>(), data);
+        });
+    }
+
+    #[test]
+    fn write_async_chunks() {
+        testing::with_framework(|fw| {
+            let len = testing::STAGING_CHUNK_SIZE as usize * 5 / 2;
+            let data = (0..len as u32).collect::<Vec<_>>();
+            let buffer = GpuBuffer::<u32>::with_capacity(fw, data.len() as u64);
+
+            let written = futures::executor::block_on(buffer.write_async(&data)).unwrap();
+
+            assert_eq!(written, data.len() as u64);
+            assert_eq!(buffer.read_vec_blocking().unwrap(), data);
+        });
+    }
+
+    #[test]
+    fn write_async_unaligned_tail() {
+        testing::with_framework(|fw| {
+            let len = testing::STAGING_CHUNK_SIZE as usize * 2 + 3;
+            let data = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+            let buffer = GpuBuffer::<u8>::with_capacity(fw, data.len() as u64);
+
+            futures::executor::block_on(buffer.write_async(&data)).unwrap();
+
+            assert_eq!(buffer.read_vec_blocking().unwrap(), data);
+        });
+    }
+
+    #[test]
+    fn write_disjoint_ranges() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &[0u32; 16]);
+
+            buffer.write_range(2, &[1, 2, 3]).unwrap();
+            buffer.write_range(10, &[4, 5]).unwrap();
+
+            let mut expected = [0u32; 16];
+            expected[2..5].copy_from_slice(&[1, 2, 3]);
+            expected[10..12].copy_from_slice(&[4, 5]);
+            assert_eq!(buffer.read_vec_blocking().unwrap(), expected);
+        });
+    }
+
+    #[test]
+    fn write_unaligned_ranges() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &[0u8; 15]);
+
+            buffer.write_range(1, &[1, 2]).unwrap();
+            buffer.write_range(6, &[3, 4, 5, 6, 7]).unwrap();
+            buffer.write_range(14, &[8]).unwrap();
+
+            assert_eq!(
+                buffer.read_vec_blocking().unwrap(),
+                [0, 1, 2, 0, 0, 0, 3, 4, 5, 6, 7, 0, 0, 0, 8]
+            );
+        });
+    }
+
+    #[test]
+    fn copy_range_within_buffer() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &(0..8).collect::<Vec<u32>>());
+
+            buffer.copy_range_to(0, &buffer, 5, 3).unwrap();
+
+            assert_eq!(
+                buffer.read_vec_blocking().unwrap(),
+                [0, 1, 2, 3, 4, 0, 1, 2]
+            );
+        });
+    }
+
+    #[test]
+    fn copy_range_within_cast_buffer() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &(0..8).collect::<Vec<u32>>());
+            let view = buffer.cast::<u32>().unwrap();
+
+            buffer.copy_range_to(4, &view, 0, 4).unwrap();
+
+            assert_eq!(
+                buffer.read_vec_blocking().unwrap(),
+                [4, 5, 6, 7, 4, 5, 6, 7]
+            );
+        });
+    }
+
+    #[test]
+    fn copy_range_overlapping() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &(0..8).collect::<Vec<u32>>());
+            let view = buffer.cast::<u32>().unwrap();
+
+            assert!(matches!(
+                buffer.copy_range_to(0, &buffer, 2, 4),
+                Err(BufferError::OverlappingCopy)
+            ));
+            assert!(matches!(
+                buffer.copy_range_to(2, &view, 0, 4),
+                Err(BufferError::OverlappingCopy)
+            ));
+            assert_eq!(
+                buffer.read_vec_blocking().unwrap(),
+                (0..8).collect::<Vec<_>>()
+            );
+        });
+    }
+
+    #[test]
+    fn copy_range_out_of_range() {
+        testing::with_framework(|fw| {
+            let src = GpuBuffer::from_slice(fw, &[0u32; 4]);
+            let dst = GpuBuffer::from_slice(fw, &[0u32; 8]);
+
+            assert!(matches!(
+                src.copy_range_to(2, &dst, 0, 3),
+                Err(BufferError::SourceOutOfRange { .. })
+            ));
+            assert!(matches!(
+                src.copy_range_to(0, &dst, 6, 3),
+                Err(BufferError::DestinationOutOfRange { .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn resize_grow() {
+        testing::with_framework(|fw| {
+            let mut buffer = GpuBuffer::from_slice(fw, &[1u32, 2, 3]);
+
+            buffer.resize(6).unwrap();
+
+            assert_eq!(buffer.read_vec_blocking().unwrap(), [1, 2, 3, 0, 0, 0]);
+        });
+    }
+
+    #[test]
+    fn resize_shrink() {
+        testing::with_framework(|fw| {
+            let mut buffer = GpuBuffer::from_slice(fw, &[1u8, 2, 3, 4, 5, 6]);
+
+            buffer.resize(3).unwrap();
+
+            assert_eq!(buffer.capacity(), 3);
+            assert_eq!(buffer.read_vec_blocking().unwrap(), [1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn copy_to_other_framework() {
+        testing::with_frameworks(|fw, other| {
+            let data = (0..1003u32).collect::<Vec<_>>();
+            let buffer = GpuBuffer::from_slice(fw, &data);
+
+            let copy = buffer.copy_to_framework_blocking(other).unwrap();
+            assert_eq!(copy.read_vec_blocking().unwrap(), data);
+
+            let copy = futures::executor::block_on(copy.copy_to_framework(fw)).unwrap();
+            assert_eq!(copy.read_vec_blocking().unwrap(), data);
+        });
+    }
+
+    #[test]
+    fn staging_buffers_are_accounted() {
+        testing::with_framework(|fw| {
+            let staging = || fw.allocated_bytes_of(ResourceKind::Staging);
+            let before = staging();
+            let buffer = GpuBuffer::from_slice(fw, &[7u32; 1000]);
+
+            let read = buffer.read_async();
+            assert!(staging() >= before + 4000);
+            assert_eq!(futures::executor::block_on(read).unwrap(), [7; 1000]);
+            assert_eq!(staging(), before);
+
+            let mut chunks = 0;
+            buffer
+                .read_chunked(256, |_, _| {
+                    assert!(staging() >= before + 256);
+                    chunks += 1;
+                })
+                .unwrap();
+            assert_eq!((chunks, staging()), (16, before));
+
+            futures::executor::block_on(buffer.write_async(&[3; 1000])).unwrap();
+            assert_eq!(staging(), before);
+            assert_eq!(buffer.read_vec_blocking().unwrap(), [3; 1000]);
+        });
+    }
+
+    #[test]
+    fn resize_keeps_usages() {
+        testing::with_framework(|fw| {
+            let extra = wgpu::BufferUsages::INDIRECT | wgpu::BufferUsages::VERTEX;
+            let mut buffer = GpuBuffer::from_slice_with_usage(fw, &[1u32, 2, 3], extra).unwrap();
+            let usage = buffer.as_wgpu_buffer().usage();
+
+            buffer.resize(8).unwrap();
+
+            assert!(usage.contains(extra));
+            assert_eq!(buffer.as_wgpu_buffer().usage(), usage);
+            assert_eq!(
+                buffer.read_vec_blocking().unwrap(),
+                [1, 2, 3, 0, 0, 0, 0, 0]
+            );
+        });
+    }
+
+    #[test]
+    fn resize_rebind() {
+        testing::with_framework(|fw| {
+            let shader = crate::Shader::from_wgsl_string(
+                fw,
+                "@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+                @compute @workgroup_size(1)
+                fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                    data[id.x] += 1u;
+                }"
+                .to_owned(),
+                None,
+            )
+            .unwrap();
+
+            let mut buffer = GpuBuffer::from_slice(fw, &[0u32; 2]);
+            let previous = buffer.cast::<u32>().unwrap();
+            let mut kernel = crate::Kernel::builder(fw, &shader, "main")
+                .layouts(vec![crate::new_set_layout!(0: Buffer)])
+                .bind_set(0, crate::SetBindings::default().add_buffer(0, &buffer))
+                .build()
+                .unwrap();
+
+            buffer.resize(4).unwrap();
+
+            // The bind group of the kernel still references the previous allocation.
+            kernel.run_bound(fw, 2, 1, 1);
+            assert_eq!(previous.read_vec_blocking().unwrap(), [1, 1]);
+            assert_eq!(buffer.read_vec_blocking().unwrap(), [0, 0, 0, 0]);
+
+            kernel.rebind_set(fw, 0, crate::SetBindings::default().add_buffer(0, &buffer));
+            kernel.run_bound(fw, 4, 1, 1);
+            assert_eq!(buffer.read_vec_blocking().unwrap(), [1, 1, 1, 1]);
+            assert_eq!(previous.read_vec_blocking().unwrap(), [1, 1]);
+        });
+    }
+
+    /// `Params` of the uniform buffer test, following the WGSL alignment rules.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Params {
+        origin: [f32; 3],
+        // `vec3<f32>` is 16 bytes aligned, so `direction` does not follow `origin` directly.
+        _padding: f32,
+        direction: [f32; 3],
+        scale: f32,
+        count: u32,
+        // The size of the struct is a multiple of its 16 bytes alignment.
+        _tail: [u32; 3],
+    }
+
+    unsafe impl bytemuck::Zeroable for Params {}
+    unsafe impl bytemuck::Pod for Params {}
+
+    #[test]
+    fn uniform_buffer_layout() {
+        testing::with_framework(|fw| {
+            let shader = crate::Shader::from_wgsl_string(
+                fw,
+                "struct Params {
+                    origin: vec3<f32>,
+                    direction: vec3<f32>,
+                    scale: f32,
+                    count: u32,
+                }
+
+                @group(0) @binding(0) var<uniform> params: Params;
+                @group(0) @binding(1) var<storage, read_write> fields: array<f32, 8>;
+
+                @compute @workgroup_size(1)
+                fn main() {
+                    fields[0] = params.origin.x;
+                    fields[1] = params.origin.y;
+                    fields[2] = params.origin.z;
+                    fields[3] = params.direction.x;
+                    fields[4] = params.direction.y;
+                    fields[5] = params.direction.z;
+                    fields[6] = params.scale;
+                    fields[7] = f32(params.count);
+                }"
+                .to_owned(),
+                None,
+            )
+            .unwrap();
+
+            let mut params = Params {
+                origin: [1.0, 2.0, 3.0],
+                _padding: 0.0,
+                direction: [4.0, 5.0, 6.0],
+                scale: 7.0,
+                count: 8,
+                _tail: [0; 3],
+            };
+            let uniform = GpuUniformBuffer::from_value(fw, &params);
+            let fields = GpuBuffer::<f32>::with_capacity(fw, 8);
+            let kernel = crate::Kernel::builder(fw, &shader, "main")
+                .layouts(vec![crate::new_set_layout!(0: UniformBuffer, 1: Buffer)])
+                .bind_set(
+                    0,
+                    crate::SetBindings::default()
+                        .add_uniform_buffer(0, &uniform)
+                        .add_buffer(1, &fields),
+                )
+                .build()
+                .unwrap();
+
+            kernel.run_bound(fw, 1, 1, 1);
+            assert_eq!(
+                fields.read_vec_blocking().unwrap(),
+                [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]
+            );
+
+            params.count = 9;
+            uniform.update(&params).unwrap();
+            kernel.run_bound(fw, 1, 1, 1);
+            assert_eq!(fields.read_vec_blocking().unwrap()[7], 9.0);
+        });
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Rgb {
+        r: u8,
+        g: u8,
+        b: u8,
+    }
+
+    unsafe impl bytemuck::Zeroable for Rgb {}
+    unsafe impl bytemuck::Pod for Rgb {}
+
+    #[test]
+    fn clear() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &(1..100).collect::<Vec<u32>>());
+
+            buffer.clear().unwrap();
+
+            assert_eq!(buffer.read_vec_blocking().unwrap(), [0; 99]);
+        });
+    }
+
+    #[test]
+    fn clear_unaligned_range() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &[1u8; 15]);
+
+            buffer.clear_range(3, 10).unwrap();
+
+            assert_eq!(
+                buffer.read_vec_blocking().unwrap(),
+                [1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1]
+            );
+        });
+    }
+
+    #[test]
+    fn fill_struct() {
+        testing::with_framework(|fw| {
+            let value = Rgb { r: 1, g: 2, b: 3 };
+            // Several chunks, with elements straddling their bounds.
+            let len = testing::STAGING_CHUNK_SIZE * 2 / 3 + 1;
+            let buffer = GpuBuffer::<Rgb>::with_capacity(fw, len);
+
+            buffer.fill(value).unwrap();
+
+            let elements = buffer.read_vec_blocking().unwrap();
+            assert_eq!(elements.len() as u64, len);
+            assert!(elements.iter().all(|element| *element == value));
+        });
+    }
+
+    #[test]
+    fn download_copy_from() {
+        testing::with_framework(|fw| {
+            let data = (0..1000).collect::<Vec<u32>>();
+            let download = GpuDownloadBuffer::<u32>::with_capacity(fw, 1000);
+
+            download
+                .copy_from(&GpuBuffer::from_slice(fw, &data))
+                .unwrap();
+
+            assert_eq!(download.read_vec_blocking().unwrap(), data);
+        });
+    }
+
+    #[test]
+    fn download_copy_from_unaligned() {
+        testing::with_framework(|fw| {
+            let download = GpuDownloadBuffer::<u8>::with_capacity(fw, 7);
+            download
+                .copy_from(&GpuBuffer::from_slice(fw, &[9; 7]))
+                .unwrap();
+
+            download
+                .copy_from(&GpuBuffer::from_slice(fw, &[1, 2, 3, 4, 5]))
+                .unwrap();
+
+            assert_eq!(download.read_vec_blocking().unwrap(), [1, 2, 3, 4, 5, 9, 9]);
+        });
+    }
+
+    #[test]
+    fn upload_copy_to() {
+        testing::with_framework(|fw| {
+            let data = (0..1000).collect::<Vec<u32>>();
+            let upload = GpuUploadBuffer::<u32>::with_capacity(fw, 1000);
+            let uploaded = GpuBuffer::<u32>::with_capacity(fw, 1000);
+            let written = GpuBuffer::<u32>::with_capacity(fw, 1000);
+
+            upload.write(&data).unwrap();
+            upload.copy_to(&uploaded).unwrap();
+            written.write(&data).unwrap();
+
+            assert_eq!(uploaded.read_vec_blocking().unwrap(), data);
+            assert_eq!(
+                uploaded.read_vec_blocking().unwrap(),
+                written.read_vec_blocking().unwrap()
+            );
+        });
+    }
+
+    #[test]
+    fn upload_copy_to_unaligned() {
+        testing::with_framework(|fw| {
+            let upload = GpuUploadBuffer::from_slice(fw, &[1u8, 2, 3, 4, 5]);
+            let dst = GpuBuffer::from_slice(fw, &[9u8; 7]);
+
+            upload.copy_to(&dst).unwrap();
+
+            assert_eq!(dst.read_vec_blocking().unwrap(), [1, 2, 3, 4, 5, 9, 9]);
+        });
+    }
+
+    #[test]
+    fn upload_write_with() {
+        testing::with_framework(|fw| {
+            let upload = GpuUploadBuffer::<u16>::with_capacity(fw, 5);
+            let dst = GpuBuffer::<u16>::with_capacity(fw, 5);
+
+            upload
+                .write_with(|elements| {
+                    for (i, element) in elements.iter_mut().enumerate() {
+                        *element = i as u16 * 3;
+                    }
+                })
+                .unwrap();
+            upload.copy_to(&dst).unwrap();
+
+            assert_eq!(dst.read_vec_blocking().unwrap(), [0, 3, 6, 9, 12]);
+        });
+    }
+
+    #[test]
+    fn write_range_out_of_range() {
+        testing::with_framework(|fw| {
+            let buffer = GpuBuffer::from_slice(fw, &[0u32; 4]);
+
+            assert!(matches!(
+                buffer.write_range(3, &[1, 2]),
+                Err(BufferError::OutOfRange { .. })
+            ));
+        });
     }
 }
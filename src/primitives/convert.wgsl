@@ -0,0 +1,20 @@
+// Converts the texels of an image into the format of another one of the same size.
+// `STORAGE_FORMAT` is replaced by the WGSL storage format of the destination image.
+
+@group(0) @binding(0)
+var src: texture_2d<f32>;
+@group(0) @binding(1)
+var dst: texture_storage_2d<STORAGE_FORMAT, write>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<u32>(textureDimensions(dst));
+
+    if (id.x >= size.x || id.y >= size.y) {
+        return;
+    }
+
+    // Loads decode sRGB texels and fill missing channels with (0, 0, 1),
+    // stores keep the channels of the destination format.
+    textureStore(dst, vec2<i32>(id.xy), textureLoad(src, vec2<i32>(id.xy), 0));
+}
@@ -0,0 +1,2741 @@
+use std::{future::Future, marker::PhantomData, sync::Arc};
+
+use bytemuck::Zeroable;
+
+use thiserror::Error;
+
+use crate::{
+    memory::ResourceKind, BufOps, GpuBuffer, GpuConstImage, GpuConstImage1d, GpuConstImage3d,
+    GpuConstImageArray, GpuImage, GpuImage1d, GpuImage3d, GpuImageArray,
+};
+
+use super::{
+    buffers::{Download, Staging},
+    ImgOps, PixelInfo,
+};
+
+// TODO https://github.com/gfx-rs/wgpu/issues/2297
+const GPU_IMAGE_USAGES: wgpu::TextureUsages = wgpu::TextureUsages::from_bits_truncate(
+    wgpu::TextureUsages::STORAGE_BINDING.bits()
+        | wgpu::TextureUsages::COPY_SRC.bits()
+        | wgpu::TextureUsages::COPY_DST.bits(),
+);
+const GPU_CONST_IMAGE_USAGES: wgpu::TextureUsages = wgpu::TextureUsages::from_bits_truncate(
+    wgpu::TextureUsages::TEXTURE_BINDING.bits()
+        | wgpu::TextureUsages::COPY_SRC.bits()
+        | wgpu::TextureUsages::COPY_DST.bits(),
+);
+
+const MIPMAP_SHADER: &str = include_str!("mipmap.wgsl");
+const CONVERT_SHADER: &str = include_str!("convert.wgsl");
+/// Workgroup size of both dimensions of `mipmap.wgsl` and `convert.wgsl`.
+const MIPMAP_WORKGROUP_SIZE: u32 = 8;
+
+pub type ImageInputResult<T> = Result<T, ImageInputError>;
+pub type ImageOutputResult<T> = Result<T, ImageOutputError>;
+
+#[derive(Error, Debug)]
+pub enum ImageInputError {
+    #[error("the device was lost.")]
+    DeviceLost,
+    #[error("input of {len} bytes is smaller than the {size} bytes of the pixels written.")]
+    BufferTooSmall { len: usize, size: usize },
+    #[error("region of {width}x{height} pixels at ({x}, {y}) is out of the image bounds ({image_width}x{image_height}).")]
+    RegionOutOfBounds {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        image_width: u32,
+        image_height: u32,
+    },
+    #[error("pixels of format {0:?} cannot be written from the CPU.")]
+    UnsupportedFormat(wgpu::TextureFormat),
+    #[error("input of {len} bytes does not match the {size} bytes of the image.")]
+    InvalidLength { len: usize, size: usize },
+    #[error("image of {width}x{height}x{depth} pixels is empty or exceeds the texture dimension limit ({limit}).")]
+    InvalidDimensions {
+        width: u32,
+        height: u32,
+        depth: u32,
+        limit: u32,
+    },
+    #[error("format {format:?} does not support the usages {usages:?} of the image on this device, see `Framework::image_format_capabilities`.")]
+    UnsupportedUsages {
+        format: wgpu::TextureFormat,
+        usages: wgpu::TextureUsages,
+    },
+    #[error("layer {layer} is out of the {layers} layers of the image array.")]
+    LayerOutOfRange { layer: u32, layers: u32 },
+    #[error("{levels} mip levels is not between 1 and the {max} levels of the full mip chain of the image.")]
+    InvalidMipLevels { levels: u32, max: u32 },
+    #[error("mip level {level} is out of the {levels} mip levels of the image.")]
+    MipLevelOutOfRange { level: u32, levels: u32 },
+    #[error(
+        "view of {count} {kind} from {base} is empty or out of the {total} {kind} of the image."
+    )]
+    ViewOutOfRange {
+        kind: &'static str,
+        base: u32,
+        count: u32,
+        total: u32,
+    },
+    #[error("mipmaps of format {0:?} cannot be generated.")]
+    MipmapsUnsupported(wgpu::TextureFormat),
+    #[error("images of format {from:?} cannot be converted into format {to:?}.")]
+    UnsupportedConversion {
+        from: wgpu::TextureFormat,
+        to: wgpu::TextureFormat,
+    },
+    #[error("destination image ({dst_width}x{dst_height}) does not match the {width}x{height} pixels converted.")]
+    DimensionsMismatch {
+        width: u32,
+        height: u32,
+        dst_width: u32,
+        dst_height: u32,
+    },
+    #[error("destination image ({dst_width}x{dst_height}) is smaller than the {width}x{height} pixels copied.")]
+    DestinationTooSmall {
+        width: u32,
+        height: u32,
+        dst_width: u32,
+        dst_height: u32,
+    },
+    #[error("images belong to different frameworks.")]
+    DifferentFramework,
+    #[error("buffer of {len} bytes cannot hold the {size} bytes of the image.")]
+    DestinationBufferTooSmall { len: usize, size: usize },
+    #[error("texels of {texel_size} bytes cannot be copied from or into buffer elements of {element_size} bytes.")]
+    ElementSizeMismatch {
+        texel_size: usize,
+        element_size: usize,
+    },
+    #[error("region of {width}x{height} pixels at ({x}, {y}) is not aligned to the {block_width}x{block_height} blocks of the format.")]
+    UnalignedRegion {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        block_width: u32,
+        block_height: u32,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum ImageOutputError {
+    #[error(transparent)]
+    AsyncMapError(#[from] wgpu::BufferAsyncError),
+    #[error("the device was lost.")]
+    DeviceLost,
+    #[error("output buffer of {len} bytes is smaller than the {size} bytes of the image.")]
+    BufferTooSmall { len: usize, size: usize },
+    #[error("output buffer of {len} bytes does not match the {size} bytes of the image.")]
+    InvalidLength { len: usize, size: usize },
+    #[error("layer {layer} is out of the {layers} layers of the image array.")]
+    LayerOutOfRange { layer: u32, layers: u32 },
+    #[error("mip level {level} is out of the {levels} mip levels of the image.")]
+    MipLevelOutOfRange { level: u32, levels: u32 },
+    #[error(
+        "pixels of format {format:?} cannot be read from a texture of format {texture_format:?}."
+    )]
+    FormatMismatch {
+        format: wgpu::TextureFormat,
+        texture_format: wgpu::TextureFormat,
+    },
+}
+
+/// Creates a texture of `P` pixels, checking its size against the device limits of its `dimension`
+/// and its usages against the ones supported by the format.
+///
+/// The `depth_or_array_layers` of [`wgpu::TextureDimension::D2`] textures are array layers,
+/// limited by `max_texture_array_layers`.
+fn create_texture<P: PixelInfo>(
+    fw: &crate::Framework,
+    size: wgpu::Extent3d,
+    dimension: wgpu::TextureDimension,
+    usage: wgpu::TextureUsages,
+    mip_level_count: u32,
+    label: &str,
+) -> ImageInputResult<wgpu::Texture> {
+    let limits = fw.limits();
+    let limit = match dimension {
+        wgpu::TextureDimension::D1 => limits.max_texture_dimension_1d,
+        wgpu::TextureDimension::D2 => limits.max_texture_dimension_2d,
+        wgpu::TextureDimension::D3 => limits.max_texture_dimension_3d,
+    };
+    let depth = size.depth_or_array_layers;
+    let depth_limit = match dimension {
+        wgpu::TextureDimension::D2 => limits.max_texture_array_layers,
+        _ => limit,
+    };
+    let invalid = |dim: u32, limit: u32| dim == 0 || dim > limit;
+
+    if invalid(size.width, limit) || invalid(size.height, limit) || invalid(depth, depth_limit) {
+        return Err(ImageInputError::InvalidDimensions {
+            width: size.width,
+            height: size.height,
+            depth,
+            limit: if invalid(depth, depth_limit) {
+                depth_limit
+            } else {
+                limit
+            },
+        });
+    }
+
+    let format = P::wgpu_format();
+    let allowed_usages = fw.image_format_capabilities(format).usages;
+
+    if !allowed_usages.contains(usage) {
+        return Err(ImageInputError::UnsupportedUsages {
+            format,
+            usages: usage - allowed_usages,
+        });
+    }
+
+    let max_mip_levels = size.max_mips(dimension);
+
+    if mip_level_count == 0 || mip_level_count > max_mip_levels {
+        return Err(ImageInputError::InvalidMipLevels {
+            levels: mip_level_count,
+            max: max_mip_levels,
+        });
+    }
+
+    Ok(fw.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size,
+        mip_level_count,
+        sample_count: 1,
+        dimension,
+        format,
+        usage,
+        view_formats: &[],
+    }))
+}
+
+/// Fails with [`ImageInputError::InvalidLength`] if `data` does not hold exactly the pixels of an image of `size`.
+fn check_length<P: PixelInfo>(data: &[u8], size: wgpu::Extent3d) -> ImageInputResult<()> {
+    let image_size = image_bytes::<P>(size);
+
+    if data.len() != image_size {
+        return Err(ImageInputError::InvalidLength {
+            len: data.len(),
+            size: image_size,
+        });
+    }
+
+    Ok(())
+}
+
+fn extent(width: u32, height: u32) -> wgpu::Extent3d {
+    wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    }
+}
+
+fn extent_3d(width: u32, height: u32, depth: u32) -> wgpu::Extent3d {
+    wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: depth,
+    }
+}
+
+/// Returns the pixels of an image of `size`.
+fn image_pixels(size: wgpu::Extent3d) -> usize {
+    size.width as usize * size.height as usize * size.depth_or_array_layers as usize
+}
+
+/// Returns the bytes of an image of `P` pixels.
+fn image_bytes<P: PixelInfo>(size: wgpu::Extent3d) -> usize {
+    image_pixels(size) * P::byte_size()
+}
+
+/// Fails with [`ImageInputError::UnsupportedFormat`] for block-compressed and depth-stencil formats,
+/// which `wgpu::Queue::write_texture` cannot write as tightly packed pixels.
+fn check_writable(format: wgpu::TextureFormat) -> ImageInputResult<()> {
+    let info = format.describe();
+    let depth_stencil = matches!(info.sample_type, wgpu::TextureSampleType::Depth)
+        || matches!(format, wgpu::TextureFormat::Stencil8);
+
+    if info.block_dimensions != (1, 1) || depth_stencil {
+        return Err(ImageInputError::UnsupportedFormat(format));
+    }
+
+    Ok(())
+}
+
+/// Writes tightly packed pixels into the region of `texture` of `size` at `origin`,
+/// returning how many pixels were written.
+fn write_texture<P: PixelInfo>(
+    fw: &crate::Framework,
+    texture: &wgpu::Texture,
+    origin: wgpu::Origin3d,
+    size: wgpu::Extent3d,
+    buf: &[u8],
+) -> ImageInputResult<usize> {
+    check_writable(P::wgpu_format())?;
+
+    let image_size = image_bytes::<P>(size);
+
+    if buf.len() < image_size {
+        return Err(ImageInputError::BufferTooSmall {
+            len: buf.len(),
+            size: image_size,
+        });
+    }
+
+    if fw.is_device_lost() {
+        return Err(ImageInputError::DeviceLost);
+    }
+
+    if image_size == 0 {
+        return Ok(0);
+    }
+
+    fw.queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &buf[..image_size],
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(size.width * P::byte_size() as u32),
+            rows_per_image: std::num::NonZeroU32::new(size.height),
+        },
+        size,
+    );
+
+    Ok(image_pixels(size))
+}
+
+/// Copies the region of `size` of `source` into a staging buffer whose rows are padded to
+/// [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`], and requests its mapping.
+///
+/// Returns the pending mapping and the padded bytes per row, or `None` if the image is empty.
+fn download_texture<P: PixelInfo>(
+    fw: &crate::Framework,
+    source: wgpu::ImageCopyTexture<'_>,
+    size: wgpu::Extent3d,
+) -> ImageOutputResult<Option<(Download, usize)>> {
+    if fw.is_device_lost() {
+        return Err(ImageOutputError::DeviceLost);
+    }
+
+    // Images constructed from the parts of another texture may not hold `P` pixels.
+    if source.texture.format() != P::wgpu_format() {
+        return Err(ImageOutputError::FormatMismatch {
+            format: P::wgpu_format(),
+            texture_format: source.texture.format(),
+        });
+    }
+
+    if image_bytes::<P>(size) == 0 {
+        return Ok(None);
+    }
+
+    let bytes_per_row = size.width * P::byte_size() as u32;
+    let padded_bytes_per_row =
+        wgpu::util::align_to(bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    // Layers follow each other without padding, since `rows_per_image` is the height.
+    let rows = size.height as u64 * size.depth_or_array_layers as u64;
+    let staging_size = padded_bytes_per_row as u64 * rows;
+    let staging = fw.staging.acquire(fw, staging_size);
+
+    let mut encoder = fw
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("GpuImage::read"),
+        });
+    encoder.copy_texture_to_buffer(
+        source,
+        wgpu::ImageCopyBuffer {
+            buffer: &staging,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+                rows_per_image: std::num::NonZeroU32::new(size.height),
+            },
+        },
+        size,
+    );
+    let index = fw.queue.submit(Some(encoder.finish()));
+
+    let download = Download::map(
+        fw,
+        Staging::Pooled(staging),
+        staging_size,
+        0,
+        staging_size,
+        index,
+    );
+
+    Ok(Some((download, padded_bytes_per_row as usize)))
+}
+
+/// Removes the row padding of a mapped download into `buf`, whose rows are `bytes_per_row` bytes.
+fn copy_rows(
+    download: &Download,
+    bytes_per_row: usize,
+    padded_bytes_per_row: usize,
+    buf: &mut [u8],
+) {
+    let mapped = download.mapped_range();
+
+    // Copies of images whose rows need no padding are copied at once.
+    if bytes_per_row == padded_bytes_per_row {
+        buf.copy_from_slice(&mapped[..buf.len()]);
+        return;
+    }
+
+    for (src, dst) in mapped
+        .chunks(padded_bytes_per_row)
+        .zip(buf.chunks_mut(bytes_per_row))
+    {
+        dst.copy_from_slice(&src[..bytes_per_row]);
+    }
+}
+
+/// Pulls the pixels of the region of `size` of `source` into `buf`,
+/// polling the device until the copy is done if `wait`.
+async fn read_texture<P: PixelInfo>(
+    fw: &crate::Framework,
+    source: wgpu::ImageCopyTexture<'_>,
+    size: wgpu::Extent3d,
+    buf: &mut [u8],
+    wait: bool,
+) -> ImageOutputResult<usize> {
+    let image_size = image_bytes::<P>(size);
+
+    if buf.len() < image_size {
+        return Err(ImageOutputError::BufferTooSmall {
+            len: buf.len(),
+            size: image_size,
+        });
+    }
+
+    if let Some((mut download, padded_bytes_per_row)) = download_texture::<P>(fw, source, size)? {
+        if wait {
+            download.wait();
+        }
+        download.mapped_raw().await?;
+        copy_rows(
+            &download,
+            size.width as usize * P::byte_size(),
+            padded_bytes_per_row,
+            &mut buf[..image_size],
+        );
+    }
+
+    Ok(image_size)
+}
+
+/// Pulls the pixels of the region of `size` of `source` into a [`Vec`], without polling the device.
+///
+/// The copy is submitted before the future is returned, which owns its staging buffer
+/// and does not borrow the image.
+fn read_texture_async<P: PixelInfo>(
+    fw: &crate::Framework,
+    source: wgpu::ImageCopyTexture<'_>,
+    size: wgpu::Extent3d,
+) -> impl Future<Output = ImageOutputResult<Vec<u8>>> {
+    let download = download_texture::<P>(fw, source, size);
+    let bytes_per_row = size.width as usize * P::byte_size();
+    let image_size = image_bytes::<P>(size);
+
+    async move {
+        let mut buf = vec![0u8; image_size];
+
+        if let Some((mut download, padded_bytes_per_row)) = download? {
+            download.mapped_raw().await?;
+            copy_rows(&download, bytes_per_row, padded_bytes_per_row, &mut buf);
+        }
+
+        Ok(buf)
+    }
+}
+
+/// Copies tightly packed `bytes` into pixels of `P`, which may be more aligned than the bytes.
+fn texels<P: PixelInfo>(bytes: &[u8]) -> Vec<P::Texel> {
+    let mut pixels = vec![P::Texel::zeroed(); bytes.len() / P::byte_size()];
+    bytemuck::cast_slice_mut(&mut pixels).copy_from_slice(bytes);
+
+    pixels
+}
+
+/// Returns the bytes of the `levels` first mip levels of an image of `P` pixels of `size` and `dimension`.
+fn mip_chain_bytes<P: PixelInfo>(
+    size: wgpu::Extent3d,
+    dimension: wgpu::TextureDimension,
+    levels: u32,
+) -> usize {
+    (0..levels)
+        .map(|level| image_bytes::<P>(size.mip_level_size(level, dimension)))
+        .sum()
+}
+
+/// Creates a `D2` view of the single mip `level` of `texture`.
+fn mip_view(texture: &wgpu::Texture, level: u32) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some("GpuImage::mip_view"),
+        dimension: Some(wgpu::TextureViewDimension::D2),
+        base_mip_level: level,
+        mip_level_count: std::num::NonZeroU32::new(1),
+        ..Default::default()
+    })
+}
+
+/// Returns the WGSL storage texel format of `format`, if its texels can be written as floats,
+/// e.g. to average them into mipmaps.
+fn float_storage_format(format: wgpu::TextureFormat) -> Option<&'static str> {
+    match format {
+        wgpu::TextureFormat::Rgba8Unorm => Some("rgba8unorm"),
+        wgpu::TextureFormat::Rgba8Snorm => Some("rgba8snorm"),
+        wgpu::TextureFormat::Rgba16Float => Some("rgba16float"),
+        wgpu::TextureFormat::R32Float => Some("r32float"),
+        wgpu::TextureFormat::Rg32Float => Some("rg32float"),
+        wgpu::TextureFormat::Rgba32Float => Some("rgba32float"),
+        _ => None,
+    }
+}
+
+/// Creates the pipeline of a shader reading a float texture at binding 0
+/// and writing a storage texture of `format` at binding 1.
+fn float_image_pipeline(
+    device: &wgpu::Device,
+    label: &str,
+    source: &str,
+    format: wgpu::TextureFormat,
+) -> (wgpu::BindGroupLayout, wgpu::ComputePipeline) {
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    // An explicit layout, since derived layouts expect filterable float textures.
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(label),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::StorageTexture {
+                    access: wgpu::StorageTextureAccess::WriteOnly,
+                    format,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                },
+                count: None,
+            },
+        ],
+    });
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(label),
+        bind_group_layouts: &[&layout],
+        push_constant_ranges: &[],
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some(label),
+        layout: Some(&pipeline_layout),
+        module: &module,
+        entry_point: "main",
+    });
+
+    (layout, pipeline)
+}
+
+/// Converts the mip level 0 of `src`, of `src_format` and `size`, into the pixels of `dst`.
+/// See [`GpuImage::convert_into`].
+fn convert_texture<Q: PixelInfo>(
+    fw: &crate::Framework,
+    (src, src_format): (&wgpu::Texture, wgpu::TextureFormat),
+    size: wgpu::Extent3d,
+    dst: &GpuImage<Q>,
+) -> ImageInputResult<()> {
+    if !Arc::ptr_eq(&fw.device, &dst.fw.device) {
+        return Err(ImageInputError::DifferentFramework);
+    }
+
+    if fw.is_device_lost() {
+        return Err(ImageInputError::DeviceLost);
+    }
+
+    if size != dst.size {
+        return Err(ImageInputError::DimensionsMismatch {
+            width: size.width,
+            height: size.height,
+            dst_width: dst.size.width,
+            dst_height: dst.size.height,
+        });
+    }
+
+    let dst_format = Q::wgpu_format();
+    let device = &fw.device;
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("GpuImage::convert_into"),
+    });
+
+    if src_format == dst_format {
+        encode_image_copy(
+            &mut encoder,
+            (src, wgpu::Origin3d::ZERO),
+            (&dst.texture, wgpu::Origin3d::ZERO),
+            0,
+            size,
+        );
+        fw.queue.submit(Some(encoder.finish()));
+
+        return Ok(());
+    }
+
+    let storage_format = match src_format.describe().sample_type {
+        wgpu::TextureSampleType::Float { .. } => float_storage_format(dst_format),
+        _ => None,
+    }
+    .ok_or(ImageInputError::UnsupportedConversion {
+        from: src_format,
+        to: dst_format,
+    })?;
+
+    // Storage images cannot be sampled: their pixels are copied into a sampled texture first.
+    let sampled = if src.usage().contains(wgpu::TextureUsages::TEXTURE_BINDING) {
+        None
+    } else {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("GpuImage::convert_into"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: src_format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        encode_image_copy(
+            &mut encoder,
+            (src, wgpu::Origin3d::ZERO),
+            (&texture, wgpu::Origin3d::ZERO),
+            0,
+            size,
+        );
+
+        Some(texture)
+    };
+    let src_view = mip_view(sampled.as_ref().unwrap_or(src), 0);
+
+    let (layout, pipeline) = float_image_pipeline(
+        device,
+        "GpuImage::convert_into",
+        &CONVERT_SHADER.replace("STORAGE_FORMAT", storage_format),
+        dst_format,
+    );
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("GpuImage::convert_into"),
+        layout: &layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&src_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&dst.full_view),
+            },
+        ],
+    });
+
+    {
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("GpuImage::convert_into"),
+        });
+        cpass.set_pipeline(&pipeline);
+        cpass.set_bind_group(0, &bind_group, &[]);
+        cpass.dispatch_workgroups(
+            size.width.div_ceil(MIPMAP_WORKGROUP_SIZE),
+            size.height.div_ceil(MIPMAP_WORKGROUP_SIZE),
+            1,
+        );
+    }
+
+    fw.queue.submit(Some(encoder.finish()));
+
+    Ok(())
+}
+
+/// Records the copy of the region of `size` at `src_origin` of the mip `level` of `src`
+/// into the same mip level of `dst` at `dst_origin`.
+pub(crate) fn encode_image_copy(
+    encoder: &mut wgpu::CommandEncoder,
+    (src, src_origin): (&wgpu::Texture, wgpu::Origin3d),
+    (dst, dst_origin): (&wgpu::Texture, wgpu::Origin3d),
+    level: u32,
+    size: wgpu::Extent3d,
+) {
+    let copy = |texture, origin| wgpu::ImageCopyTexture {
+        texture,
+        mip_level: level,
+        origin,
+        aspect: wgpu::TextureAspect::All,
+    };
+
+    encoder.copy_texture_to_texture(copy(src, src_origin), copy(dst, dst_origin), size);
+}
+
+/// Fails with [`ImageInputError::ElementSizeMismatch`] unless a texel of `P` is made of whole elements `T`,
+/// or an element `T` of whole texels.
+fn check_element_size<P: PixelInfo, T>() -> ImageInputResult<()> {
+    let (texel_size, element_size) = (P::byte_size(), std::mem::size_of::<T>());
+
+    if texel_size % element_size != 0 && element_size % texel_size != 0 {
+        return Err(ImageInputError::ElementSizeMismatch {
+            texel_size,
+            element_size,
+        });
+    }
+
+    Ok(())
+}
+
+/// Buffer layout of images of `size` whose rows are `bytes_per_row` bytes.
+fn buffer_layout(bytes_per_row: u32, size: wgpu::Extent3d) -> wgpu::ImageDataLayout {
+    wgpu::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: std::num::NonZeroU32::new(bytes_per_row),
+        rows_per_image: std::num::NonZeroU32::new(size.height),
+    }
+}
+
+/// Records the copies between the tightly packed pixels of `buffer` and the mip level 0 of `texture`
+/// of `size`, in the direction of `to_buffer`.
+///
+/// Rows that are not [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`] aligned go through a padded buffer, row by row.
+fn encode_buffer_copy<P: PixelInfo>(
+    fw: &crate::Framework,
+    encoder: &mut wgpu::CommandEncoder,
+    texture: &wgpu::Texture,
+    buffer: &wgpu::Buffer,
+    size: wgpu::Extent3d,
+    to_buffer: bool,
+) -> ImageInputResult<()> {
+    let bytes_per_row = size.width * P::byte_size() as u32;
+    let texture_copy = texture.as_image_copy();
+
+    if bytes_per_row.is_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) {
+        let buffer_copy = wgpu::ImageCopyBuffer {
+            buffer,
+            layout: buffer_layout(bytes_per_row, size),
+        };
+
+        if to_buffer {
+            encoder.copy_texture_to_buffer(texture_copy, buffer_copy, size);
+        } else {
+            encoder.copy_buffer_to_texture(buffer_copy, texture_copy, size);
+        }
+
+        return Ok(());
+    }
+
+    // Rows are copied between buffers with 4 bytes aligned copies.
+    if !(bytes_per_row as u64).is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) {
+        return Err(ImageInputError::UnsupportedFormat(P::wgpu_format()));
+    }
+
+    let padded_bytes_per_row =
+        wgpu::util::align_to(bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    let rows = size.height as u64 * size.depth_or_array_layers as u64;
+    let padded = fw.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("GpuImage::buffer_copy"),
+        size: padded_bytes_per_row as u64 * rows,
+        usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let padded_copy = wgpu::ImageCopyBuffer {
+        buffer: &padded,
+        layout: buffer_layout(padded_bytes_per_row, size),
+    };
+
+    if to_buffer {
+        encoder.copy_texture_to_buffer(texture_copy, padded_copy, size);
+    }
+
+    for row in 0..rows {
+        let packed_offset = row * bytes_per_row as u64;
+        let padded_offset = row * padded_bytes_per_row as u64;
+
+        if to_buffer {
+            encoder.copy_buffer_to_buffer(
+                &padded,
+                padded_offset,
+                buffer,
+                packed_offset,
+                bytes_per_row as u64,
+            );
+        } else {
+            encoder.copy_buffer_to_buffer(
+                buffer,
+                packed_offset,
+                &padded,
+                padded_offset,
+                bytes_per_row as u64,
+            );
+        }
+    }
+
+    if !to_buffer {
+        encoder.copy_buffer_to_texture(padded_copy, texture_copy, size);
+    }
+
+    Ok(())
+}
+
+/// Fails with [`ImageInputError::UnalignedRegion`] if the region of `(width, height)` pixels at `(x, y)`
+/// does not start and end on the blocks of `format`, which copies of block-compressed formats need.
+fn check_block_aligned(
+    format: wgpu::TextureFormat,
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+) -> ImageInputResult<()> {
+    let (block_width, block_height) = format.describe().block_dimensions;
+    let (block_width, block_height) = (block_width as u32, block_height as u32);
+    let aligned = [x, width].iter().all(|v| v % block_width == 0)
+        && [y, height].iter().all(|v| v % block_height == 0);
+
+    if !aligned {
+        return Err(ImageInputError::UnalignedRegion {
+            x,
+            y,
+            width,
+            height,
+            block_width,
+            block_height,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the origin and size of the region of `(width, height)` pixels at `(x, y)`,
+/// or [`ImageInputError::RegionOutOfBounds`] if it does not fit in an image of `image_size`.
+fn image_region(
+    image_size: wgpu::Extent3d,
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+) -> ImageInputResult<(wgpu::Origin3d, wgpu::Extent3d)> {
+    let fits =
+        |start: u32, len: u32, max: u32| start.checked_add(len).is_some_and(|end| end <= max);
+
+    if !fits(x, width, image_size.width) || !fits(y, height, image_size.height) {
+        return Err(ImageInputError::RegionOutOfBounds {
+            x,
+            y,
+            width,
+            height,
+            image_width: image_size.width,
+            image_height: image_size.height,
+        });
+    }
+
+    Ok((
+        wgpu::Origin3d { x, y, z: 0 },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    ))
+}
+
+/// Implements the constructors and the writes shared by the image types.
+///
+/// Each `$image` holds a texture of `$dimension` created with `$usages`, accounted as a `$kind` resource
+/// and bound with the `view` of the texture, whose first `mip_levels` are accounted. The image of the
+/// dimensions `$dim` given to its constructors is of `size`. `pixels`, `limits` and `layout` complete the docs: the pixels of the image, the
+/// device limits of its dimensions, and the order of its tightly packed pixels.
+macro_rules! image_impl {
+    ($(
+        $image:ident {
+            dimension: $dimension:expr,
+            usages: $usages:expr,
+            kind: $kind:expr,
+            view: |$texture:ident| $view:expr,
+            mip_levels: |$levels_texture:ident| $levels:expr,
+            size: |$($dim:ident),+| $size:expr,
+            pixels: $pixels:literal,
+            limits: $limits:literal,
+            layout: $layout:literal,
+        }
+    )+) => {
+        $(
+            impl<P> $image<P>
+            where
+                P: PixelInfo,
+            {
+                #[doc = concat!("Constructs an empty [`", stringify!($image), "`] of ", $pixels, ".")]
+                ///
+                /// Fails with [`ImageInputError::InvalidDimensions`] if the image is empty or exceeds the
+                #[doc = concat!($limits, " of the device, and with [`ImageInputError::UnsupportedUsages`]")]
+                /// if the format of `P` does not support the usages of the image on the device, see
+                /// [`Framework::image_format_capabilities`](crate::Framework::image_format_capabilities).
+                pub fn try_new(fw: &crate::Framework, $($dim: u32),+) -> ImageInputResult<Self> {
+                    let size = $size;
+                    let texture = create_texture::<P>(
+                        fw,
+                        size,
+                        $dimension,
+                        $usages,
+                        1,
+                        concat!(stringify!($image), "::new"),
+                    )?;
+
+                    Ok(Self::from_texture(fw, texture, size))
+                }
+
+                #[doc = concat!("Constructs a new [`", stringify!($image), "`] holding the tightly packed pixels of `data`, ", $layout, ".")]
+                ///
+                #[doc = concat!("Fails with [`ImageInputError::InvalidLength`] if `data` does not hold exactly the ", $pixels, ",")]
+                #[doc = concat!("and like [`", stringify!($image), "::try_new`].")]
+                pub fn try_from_bytes(
+                    fw: &crate::Framework,
+                    data: &[u8],
+                    $($dim: u32),+
+                ) -> ImageInputResult<Self> {
+                    check_length::<P>(data, $size)?;
+
+                    let img = Self::try_new(fw, $($dim),+)?;
+                    img.write(data)?;
+
+                    Ok(img)
+                }
+
+                image_impl!(@from_pixels $image, $($dim),+);
+
+                /// Wraps `texture` of `size`.
+                fn from_texture(fw: &crate::Framework, texture: wgpu::Texture, size: wgpu::Extent3d) -> Self {
+                    let full_view = {
+                        let $texture = &texture;
+                        $view
+                    };
+                    let levels = {
+                        let $levels_texture = &texture;
+                        $levels
+                    };
+                    let bytes = mip_chain_bytes::<P>(size, $dimension, levels);
+
+                    Self {
+                        fw: fw.clone(),
+                        texture,
+                        size,
+                        full_view,
+                        _alloc: fw.memory.allocate($kind, bytes as u64),
+                        pixel: PhantomData,
+                    }
+                }
+
+                #[doc = concat!("Returns the [`wgpu::TextureFormat`] of the [`", stringify!($image), "`] pixels.")]
+                pub fn format(&self) -> wgpu::TextureFormat {
+                    P::wgpu_format()
+                }
+
+                #[doc = concat!("Writes tightly packed pixels, ", $layout, ", into this [`", stringify!($image), "`],")]
+                /// returning how many pixels were written. The operation is instantly offloaded.
+                ///
+                /// Fails with [`ImageInputError::BufferTooSmall`] if `buf` does not hold all the pixels of the image.
+                /// If it holds more, only the pixels of the image are written.
+                pub fn write(&self, buf: &[u8]) -> ImageInputResult<usize> {
+                    write_texture::<P>(
+                        &self.fw,
+                        &self.texture,
+                        wgpu::Origin3d::ZERO,
+                        self.size,
+                        buf,
+                    )
+                }
+
+                image_impl!(@write_pixels $image, $($dim),+);
+            }
+        )+
+    };
+    // Image arrays have no typed writes.
+    (@write_pixels $image:ident, width, height, layers) => {};
+    (@write_pixels $image:ident, $($dim:ident),+) => {
+        #[doc = concat!("Typed version of `", stringify!($image), "::write()`.")]
+        pub fn write_pixels(&self, pixels: &[P::Texel]) -> ImageInputResult<usize> {
+            self.write(bytemuck::cast_slice(pixels))
+        }
+    };
+    // 1D images have one pixel per element of `pixels`, e.g. the entries of a lookup table.
+    (@from_pixels $image:ident, $width:ident) => {
+        #[doc = concat!("Constructs a new [`", stringify!($image), "`] of one pixel per element of `pixels`.")]
+        pub fn from_pixels(fw: &crate::Framework, pixels: &[P::Texel]) -> ImageInputResult<Self> {
+            Self::try_from_bytes(fw, bytemuck::cast_slice(pixels), pixels.len() as u32)
+        }
+    };
+    (@from_pixels $image:ident, $($dim:ident),+) => {
+        #[doc = concat!("Constructs a new [`", stringify!($image), "`] from typed pixels, e.g. `&[[f32; 4]]` for")]
+        /// [`Rgba32Float`](crate::primitives::pixels::Rgba32Float) images.
+        ///
+        #[doc = concat!("Fails like [`", stringify!($image), "::try_from_bytes`].")]
+        pub fn from_pixels(
+            fw: &crate::Framework,
+            pixels: &[P::Texel],
+            $($dim: u32),+
+        ) -> ImageInputResult<Self> {
+            Self::try_from_bytes(fw, bytemuck::cast_slice(pixels), $($dim),+)
+        }
+    };
+}
+
+/// Implements [`ImgOps`] for the 2D image types.
+macro_rules! img_ops_impl {
+    ($($image:ident),+) => {
+        $(
+            impl<P> ImgOps for $image<P>
+            where
+                P: PixelInfo,
+            {
+                fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+                    wgpu::BindingResource::TextureView(&self.full_view)
+                }
+
+                fn as_gpu_texture(&self) -> &wgpu::Texture {
+                    &self.texture
+                }
+
+                fn get_wgpu_extent3d(&self) -> wgpu::Extent3d {
+                    self.size
+                }
+
+                fn dimensions(&self) -> (u32, u32) {
+                    (self.size.width, self.size.height)
+                }
+
+                fn new(fw: &crate::Framework, width: u32, height: u32) -> Self {
+                    Self::try_new(fw, width, height).unwrap_or_else(|err| panic!("{}", err))
+                }
+
+                fn from_bytes(fw: &crate::Framework, data: &[u8], width: u32, height: u32) -> Self {
+                    Self::try_from_bytes(fw, data, width, height).unwrap_or_else(|err| panic!("{}", err))
+                }
+
+                fn from_gpu_parts(
+                    fw: &crate::Framework,
+                    texture: wgpu::Texture,
+                    dimensions: wgpu::Extent3d,
+                ) -> Self {
+                    Self::from_texture(fw, texture, dimensions)
+                }
+
+                fn into_gpu_parts(self) -> (wgpu::Texture, wgpu::Extent3d) {
+                    (self.texture, self.size)
+                }
+            }
+        )+
+    };
+}
+
+/// Implements the methods of [`ImgOps`] on the image types of other dimensions, which cannot implement
+/// it, and the accessors of their dimensions: `$image` is constructed from the dimensions `$dim`, and from
+/// a texture described by the `texture` docs.
+macro_rules! image_parts_impl {
+    ($($image:ident($($dim:ident),+), texture: $texture:literal);+) => {
+        $(
+            impl<P> $image<P>
+            where
+                P: PixelInfo,
+            {
+                #[doc = concat!("Panicking version of `", stringify!($image), "::try_new()`.")]
+                pub fn new(fw: &crate::Framework, $($dim: u32),+) -> Self {
+                    Self::try_new(fw, $($dim),+).unwrap_or_else(|err| panic!("{}", err))
+                }
+
+                #[doc = concat!("Constructs a [`", stringify!($image), "`] from ", $texture, ".")]
+                pub fn from_gpu_parts(
+                    fw: &crate::Framework,
+                    texture: wgpu::Texture,
+                    dimensions: wgpu::Extent3d,
+                ) -> Self {
+                    Self::from_texture(fw, texture, dimensions)
+                }
+
+                #[doc = concat!("Decomposes a [`", stringify!($image), "`] into a [`wgpu::Texture`] and its [`wgpu::Extent3d`].")]
+                pub fn into_gpu_parts(self) -> (wgpu::Texture, wgpu::Extent3d) {
+                    (self.texture, self.size)
+                }
+
+                /// Returns a [`wgpu::BindingResource`] of the image.
+                pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+                    wgpu::BindingResource::TextureView(&self.full_view)
+                }
+
+                /// Returns the [`wgpu::Texture`] that handles the GPU image.
+                pub fn as_gpu_texture(&self) -> &wgpu::Texture {
+                    &self.texture
+                }
+
+                image_parts_impl!(@dimensions $image, $($dim),+);
+            }
+        )+
+    };
+    (@dimensions $image:ident, width) => {
+        #[doc = concat!("Returns the width in pixels of the [`", stringify!($image), "`].")]
+        pub fn width(&self) -> u32 {
+            self.size.width
+        }
+    };
+    (@dimensions $image:ident, width, height, depth) => {
+        /// Returns the width, height and depth of the image.
+        pub fn dimensions(&self) -> (u32, u32, u32) {
+            (
+                self.size.width,
+                self.size.height,
+                self.size.depth_or_array_layers,
+            )
+        }
+    };
+    (@dimensions $image:ident, width, height, layers) => {
+        #[doc = concat!("Returns the width and height of the layers of the [`", stringify!($image), "`].")]
+        pub fn dimensions(&self) -> (u32, u32) {
+            (self.size.width, self.size.height)
+        }
+
+        #[doc = concat!("Returns the number of layers of the [`", stringify!($image), "`].")]
+        pub fn layers(&self) -> u32 {
+            self.size.depth_or_array_layers
+        }
+
+        #[doc = concat!("Writes tightly packed pixels into `layer` of this [`", stringify!($image), "`], returning how many")]
+        /// pixels were written. The operation is instantly offloaded.
+        ///
+        #[doc = concat!("Fails with [`ImageInputError::LayerOutOfRange`] if `layer` is not a layer of the [`", stringify!($image), "`],")]
+        #[doc = concat!("and like [`", stringify!($image), "::write`].")]
+        pub fn write_layer(&self, layer: u32, buf: &[u8]) -> ImageInputResult<usize> {
+            let (origin, size) =
+                array_layer(self.size, layer).ok_or(ImageInputError::LayerOutOfRange {
+                    layer,
+                    layers: self.layers(),
+                })?;
+
+            write_texture::<P>(&self.fw, &self.texture, origin, size, buf)
+        }
+    };
+}
+
+/// Implements the reads of all the pixels of the image types written by shaders,
+/// tightly packed like the ones of their `write`.
+macro_rules! image_read_impl {
+    ($($image:ident),+) => {
+        $(
+            impl<P> $image<P>
+            where
+                P: PixelInfo,
+            {
+                #[doc = concat!("Pulls all the pixels from the [`", stringify!($image), "`] into `buf`, tightly packed like the ones")]
+                #[doc = concat!("of [`", stringify!($image), "::write`], returning how many bytes were read.")]
+                ///
+                /// Fails with [`ImageOutputError::BufferTooSmall`] if `buf` cannot hold all the pixels.
+                /// If the polling thread of the [`Framework`](crate::Framework) is stopped, the device is polled
+                /// until the read is completed, like [`GpuBuffer::read`](crate::GpuBuffer::read) does.
+                pub async fn read(&self, buf: &mut [u8]) -> ImageOutputResult<usize> {
+                    read_texture::<P>(
+                        &self.fw,
+                        self.texture.as_image_copy(),
+                        self.size,
+                        buf,
+                        !self.fw.is_polling(),
+                    )
+                    .await
+                }
+
+                #[doc = concat!("Blocking version of `", stringify!($image), "::read()`.")]
+                pub fn read_blocking(&self, buf: &mut [u8]) -> ImageOutputResult<usize> {
+                    futures::executor::block_on(read_texture::<P>(
+                        &self.fw,
+                        self.texture.as_image_copy(),
+                        self.size,
+                        buf,
+                        true,
+                    ))
+                }
+
+                #[doc = concat!("Pulls all the pixels from the [`", stringify!($image), "`] into a [`Vec`]. See [`", stringify!($image), "::read`].")]
+                pub async fn read_vec(&self) -> ImageOutputResult<Vec<u8>> {
+                    let mut buf = vec![0u8; image_bytes::<P>(self.size)];
+                    self.read(&mut buf).await?;
+
+                    Ok(buf)
+                }
+
+                #[doc = concat!("Blocking version of `", stringify!($image), "::read_vec()`.")]
+                pub fn read_vec_blocking(&self) -> ImageOutputResult<Vec<u8>> {
+                    let mut buf = vec![0u8; image_bytes::<P>(self.size)];
+                    self.read_blocking(&mut buf)?;
+
+                    Ok(buf)
+                }
+
+                #[doc = concat!("Pulls all the pixels from the [`", stringify!($image), "`] into a [`Vec`], without blocking.")]
+                ///
+                /// Like [`GpuBuffer::read_async`](crate::GpuBuffer::read_async), the device is never polled
+                /// by the returned future: it resolves once the device is polled by the polling thread of the
+                /// [`Framework`](crate::Framework) or by [`Framework::poll`](crate::Framework::poll).
+                /// The copy is submitted when this function is called, and the returned future owns its staging
+                /// buffer, so it can be dropped at any time to cancel the read.
+                pub fn read_async(&self) -> impl Future<Output = ImageOutputResult<Vec<u8>>> {
+                    read_texture_async::<P>(&self.fw, self.texture.as_image_copy(), self.size)
+                }
+
+                #[doc = concat!("Typed version of `", stringify!($image), "::read_vec()`.")]
+                pub async fn read_pixels(&self) -> ImageOutputResult<Vec<P::Texel>> {
+                    let mut pixels = vec![P::Texel::zeroed(); image_pixels(self.size)];
+                    self.read(bytemuck::cast_slice_mut(&mut pixels)).await?;
+
+                    Ok(pixels)
+                }
+
+                #[doc = concat!("Blocking version of `", stringify!($image), "::read_pixels()`.")]
+                pub fn read_pixels_blocking(&self) -> ImageOutputResult<Vec<P::Texel>> {
+                    let mut pixels = vec![P::Texel::zeroed(); image_pixels(self.size)];
+                    self.read_blocking(bytemuck::cast_slice_mut(&mut pixels))?;
+
+                    Ok(pixels)
+                }
+            }
+        )+
+    };
+}
+
+image_impl! {
+    GpuImage {
+        dimension: wgpu::TextureDimension::D2,
+        usages: GPU_IMAGE_USAGES,
+        kind: ResourceKind::Image,
+        // Storage bindings take a single mip level.
+        view: |texture| mip_view(texture, 0),
+        mip_levels: |texture| texture.mip_level_count(),
+        size: |width, height| extent(width, height),
+        pixels: "`width` x `height` pixels",
+        limits: "`max_texture_dimension_2d` limit",
+        layout: "row after row",
+    }
+    GpuConstImage {
+        dimension: wgpu::TextureDimension::D2,
+        usages: GPU_CONST_IMAGE_USAGES,
+        kind: ResourceKind::ConstImage,
+        view: |texture| texture.create_view(&wgpu::TextureViewDescriptor::default()),
+        // Only `GpuImage` has mip levels of its own.
+        mip_levels: |_texture| 1,
+        size: |width, height| extent(width, height),
+        pixels: "`width` x `height` pixels",
+        limits: "`max_texture_dimension_2d` limit",
+        layout: "row after row",
+    }
+    GpuImage3d {
+        dimension: wgpu::TextureDimension::D3,
+        usages: GPU_IMAGE_USAGES,
+        kind: ResourceKind::Image,
+        view: |texture| texture.create_view(&wgpu::TextureViewDescriptor::default()),
+        mip_levels: |_texture| 1,
+        size: |width, height, depth| extent_3d(width, height, depth),
+        pixels: "`width` x `height` x `depth` pixels",
+        limits: "`max_texture_dimension_3d` limit",
+        layout: "row after row and slice after slice",
+    }
+    GpuConstImage3d {
+        dimension: wgpu::TextureDimension::D3,
+        usages: GPU_CONST_IMAGE_USAGES,
+        kind: ResourceKind::ConstImage,
+        view: |texture| texture.create_view(&wgpu::TextureViewDescriptor::default()),
+        mip_levels: |_texture| 1,
+        size: |width, height, depth| extent_3d(width, height, depth),
+        pixels: "`width` x `height` x `depth` pixels",
+        limits: "`max_texture_dimension_3d` limit",
+        layout: "row after row and slice after slice",
+    }
+    GpuImage1d {
+        dimension: wgpu::TextureDimension::D1,
+        usages: GPU_IMAGE_USAGES,
+        kind: ResourceKind::Image,
+        view: |texture| texture.create_view(&wgpu::TextureViewDescriptor::default()),
+        mip_levels: |_texture| 1,
+        size: |width| extent(width, 1),
+        pixels: "`width` pixels",
+        limits: "`max_texture_dimension_1d` limit",
+        layout: "from left to right",
+    }
+    GpuConstImage1d {
+        dimension: wgpu::TextureDimension::D1,
+        usages: GPU_CONST_IMAGE_USAGES,
+        kind: ResourceKind::ConstImage,
+        view: |texture| texture.create_view(&wgpu::TextureViewDescriptor::default()),
+        mip_levels: |_texture| 1,
+        size: |width| extent(width, 1),
+        pixels: "`width` pixels",
+        limits: "`max_texture_dimension_1d` limit",
+        layout: "from left to right",
+    }
+    GpuImageArray {
+        dimension: wgpu::TextureDimension::D2,
+        usages: GPU_IMAGE_USAGES,
+        kind: ResourceKind::Image,
+        view: |texture| array_view(texture),
+        mip_levels: |_texture| 1,
+        size: |width, height, layers| extent_3d(width, height, layers),
+        pixels: "`layers` images of `width` x `height` pixels",
+        limits: "`max_texture_dimension_2d` and `max_texture_array_layers` limits",
+        layout: "row after row and layer after layer",
+    }
+    GpuConstImageArray {
+        dimension: wgpu::TextureDimension::D2,
+        usages: GPU_CONST_IMAGE_USAGES,
+        kind: ResourceKind::ConstImage,
+        view: |texture| array_view(texture),
+        mip_levels: |_texture| 1,
+        size: |width, height, layers| extent_3d(width, height, layers),
+        pixels: "`layers` images of `width` x `height` pixels",
+        limits: "`max_texture_dimension_2d` and `max_texture_array_layers` limits",
+        layout: "row after row and layer after layer",
+    }
+}
+
+img_ops_impl!(GpuImage, GpuConstImage);
+
+image_parts_impl! {
+    GpuImage3d(width, height, depth), texture: "a [`wgpu::Texture`] of [`wgpu::TextureDimension::D3`] and its size";
+    GpuConstImage3d(width, height, depth), texture: "a [`wgpu::Texture`] of [`wgpu::TextureDimension::D3`] and its size";
+    GpuImage1d(width), texture: "a [`wgpu::Texture`] of [`wgpu::TextureDimension::D1`] and its size";
+    GpuConstImage1d(width), texture: "a [`wgpu::Texture`] of [`wgpu::TextureDimension::D1`] and its size";
+    GpuImageArray(width, height, layers),
+        texture: "a [`wgpu::Texture`] of [`wgpu::TextureDimension::D2`] and its size, whose `depth_or_array_layers` are the layers";
+    GpuConstImageArray(width, height, layers),
+        texture: "a [`wgpu::Texture`] of [`wgpu::TextureDimension::D2`] and its size, whose `depth_or_array_layers` are the layers"
+}
+
+image_read_impl!(GpuImage, GpuImage3d, GpuImage1d, GpuImageArray);
+
+impl<P> GpuImage<P>
+where
+    P: PixelInfo,
+{
+    /// Returns the width in pixels of the [`GpuImage`].
+    pub fn width(&self) -> u32 {
+        self.size.width
+    }
+
+    /// Returns the height in pixels of the [`GpuImage`].
+    pub fn height(&self) -> u32 {
+        self.size.height
+    }
+
+    /// Returns the size of the [`GpuImage`] as a [`wgpu::Extent3d`], of depth 1.
+    pub fn extent(&self) -> wgpu::Extent3d {
+        self.size
+    }
+
+    /// Returns the [`wgpu::Texture`] of the [`GpuImage`], e.g. to draw it in a render pass of the same device.
+    ///
+    /// Same as [`ImgOps::as_gpu_texture`].
+    pub fn as_wgpu_texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    /// Returns the [`wgpu::TextureView`] the [`GpuImage`] is bound with, of its first mip level.
+    pub fn full_view(&self) -> &wgpu::TextureView {
+        &self.full_view
+    }
+
+    /// Returns the bytes of all the pixels of the [`GpuImage`] tightly packed, i.e. the size of the buffers
+    /// read by [`GpuImage::read_into`] and written by [`GpuImage::write`].
+    pub fn packed_size_bytes(&self) -> usize {
+        image_bytes::<P>(self.size)
+    }
+
+    /// Pulls all the pixels from the [`GpuImage`] into `target`, tightly packed row after row,
+    /// e.g. to read every frame of a video into the same buffer without allocating.
+    ///
+    /// Fails with [`ImageOutputError::InvalidLength`] unless `target` has the length returned by
+    /// [`GpuImage::packed_size_bytes`]. Like [`GpuImage::read`], the pixels are copied through a staging
+    /// buffer of the pool of the [`Framework`](crate::Framework), which is reused by the next reads.
+    pub async fn read_into(&self, target: &mut [u8]) -> ImageOutputResult<()> {
+        self.check_packed_size(target)?;
+        self.read(target).await.map(|_| ())
+    }
+
+    /// Blocking version of `GpuImage::read_into()`.
+    pub fn read_into_blocking(&self, target: &mut [u8]) -> ImageOutputResult<()> {
+        self.check_packed_size(target)?;
+        self.read_blocking(target).map(|_| ())
+    }
+
+    fn check_packed_size(&self, target: &[u8]) -> ImageOutputResult<()> {
+        if target.len() != self.packed_size_bytes() {
+            return Err(ImageOutputError::InvalidLength {
+                len: target.len(),
+                size: self.packed_size_bytes(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Writes tightly packed pixels into the region of `size` (width, height) pixels whose top-left
+    /// corner is at `origin` (x, y), e.g. a tile of a larger image. The operation is instantly offloaded.
+    ///
+    /// Fails with [`ImageInputError::RegionOutOfBounds`] if the region exceeds the [`GpuImage`] and with
+    /// [`ImageInputError::BufferTooSmall`] if `buf` does not hold all the pixels of the region.
+    pub fn write_region(
+        &self,
+        origin: (u32, u32),
+        size: (u32, u32),
+        buf: &[u8],
+    ) -> ImageInputResult<usize> {
+        let (origin, size) = image_region(self.size, origin, size)?;
+
+        write_texture::<P>(&self.fw, &self.texture, origin, size, buf)
+    }
+
+    /// Typed version of `GpuImage::read_async()`, e.g. `f32` pixels of [`R32Float`](crate::primitives::pixels::R32Float)
+    /// images or `[f32; 4]` ones of [`Rgba32Float`](crate::primitives::pixels::Rgba32Float) images.
+    pub fn read_pixels_async(&self) -> impl Future<Output = ImageOutputResult<Vec<P::Texel>>> {
+        let read = self.read_async();
+
+        async move { read.await.map(|bytes| texels::<P>(&bytes)) }
+    }
+
+    /// Typed version of `GpuImage::read_into()`: `target` must hold exactly the pixels of the image.
+    pub async fn read_pixels_into(&self, target: &mut [P::Texel]) -> ImageOutputResult<()> {
+        self.read_into(bytemuck::cast_slice_mut(target)).await
+    }
+
+    /// Blocking version of `GpuImage::read_pixels_into()`.
+    pub fn read_pixels_into_blocking(&self, target: &mut [P::Texel]) -> ImageOutputResult<()> {
+        self.read_into_blocking(bytemuck::cast_slice_mut(target))
+    }
+
+    /// Typed version of `GpuImage::read_mip()`.
+    pub async fn read_mip_pixels(&self, level: u32) -> ImageOutputResult<Vec<P::Texel>> {
+        self.read_mip(level).await.map(|bytes| texels::<P>(&bytes))
+    }
+
+    /// Blocking version of `GpuImage::read_mip_pixels()`.
+    pub fn read_mip_pixels_blocking(&self, level: u32) -> ImageOutputResult<Vec<P::Texel>> {
+        self.read_mip_blocking(level)
+            .map(|bytes| texels::<P>(&bytes))
+    }
+
+    /// Constructs an empty [`GpuImage`] with `mip_levels` mip levels, e.g. for multi-scale kernels.
+    /// Level 0 is `width` x `height` pixels and each following level halves it.
+    ///
+    /// Fails with [`ImageInputError::InvalidMipLevels`] if `mip_levels` is zero or longer than the full
+    /// mip chain, of `floor(log2(max(width, height))) + 1` levels, and like [`GpuImage::try_new`].
+    ///
+    /// Unlike other [`GpuImage`]s, its mip levels can also be sampled through views,
+    /// see [`GpuImage::create_view`].
+    pub fn try_with_mip_levels(
+        fw: &crate::Framework,
+        width: u32,
+        height: u32,
+        mip_levels: u32,
+    ) -> ImageInputResult<Self> {
+        let size = extent(width, height);
+        let texture = create_texture::<P>(
+            fw,
+            size,
+            wgpu::TextureDimension::D2,
+            GPU_IMAGE_USAGES | wgpu::TextureUsages::TEXTURE_BINDING,
+            mip_levels,
+            "GpuImage::with_mip_levels",
+        )?;
+
+        Ok(Self::from_gpu_parts(fw, texture, size))
+    }
+
+    /// Constructs an empty [`GpuImage`] with its full mip chain. See [`GpuImage::try_with_mip_levels`].
+    pub fn try_with_mipmaps(
+        fw: &crate::Framework,
+        width: u32,
+        height: u32,
+    ) -> ImageInputResult<Self> {
+        let mip_levels = extent(width, height).max_mips(wgpu::TextureDimension::D2);
+
+        Self::try_with_mip_levels(fw, width, height, mip_levels)
+    }
+
+    /// Returns the number of mip levels of the [`GpuImage`], 1 unless created with
+    /// [`GpuImage::try_with_mip_levels`] or [`GpuImage::try_with_mipmaps`].
+    ///
+    /// Reads, writes and [`SetBindings::add_image`](crate::SetBindings::add_image) only use level 0.
+    pub fn mip_levels(&self) -> u32 {
+        self.texture.mip_level_count()
+    }
+
+    /// Returns the width and height of the mip `level` of the [`GpuImage`], or `None` if it is out of range.
+    pub fn mip_dimensions(&self, level: u32) -> Option<(u32, u32)> {
+        if level >= self.mip_levels() {
+            return None;
+        }
+
+        let size = self.size.mip_level_size(level, wgpu::TextureDimension::D2);
+
+        Some((size.width, size.height))
+    }
+
+    /// Returns a view of the single mip `level`, bound as a storage image with
+    /// [`SetBindings::add_image_mip`](crate::SetBindings::add_image_mip) to an entry added with
+    /// [`SetLayout::add_image`](crate::SetLayout::add_image).
+    ///
+    /// Fails with [`ImageInputError::MipLevelOutOfRange`] if `level` is not a mip level of the [`GpuImage`].
+    pub fn mip_view(&self, level: u32) -> ImageInputResult<GpuImageMip<'_, P>> {
+        if level >= self.mip_levels() {
+            return Err(ImageInputError::MipLevelOutOfRange {
+                level,
+                levels: self.mip_levels(),
+            });
+        }
+
+        Ok(GpuImageMip {
+            image: self,
+            level,
+            view: mip_view(&self.texture, level),
+        })
+    }
+
+    /// Returns a view of the mip levels and layers selected by `options`, bound with
+    /// [`SetBindings::add_image_view`](crate::SetBindings::add_image_view) as a storage image or with
+    /// [`SetBindings::add_const_image_view`](crate::SetBindings::add_const_image_view) as a sampled one,
+    /// e.g. to sample a mip level while writing the next one.
+    ///
+    /// Fails with [`ImageInputError::ViewOutOfRange`] if the selected mip levels or layers are empty
+    /// or exceed the ones of the [`GpuImage`].
+    pub fn create_view(&self, options: &ImageViewOptions) -> ImageInputResult<GpuImageView<'_, P>> {
+        GpuImageView::new(&self.texture, options, wgpu::TextureViewDimension::D2)
+    }
+
+    /// Pulls all the pixels of the mip `level` from the [`GpuImage`] into a [`Vec`],
+    /// of the size returned by [`GpuImage::mip_dimensions`].
+    ///
+    /// Fails with [`ImageOutputError::MipLevelOutOfRange`] if `level` is not a mip level of the [`GpuImage`].
+    pub async fn read_mip(&self, level: u32) -> ImageOutputResult<Vec<u8>> {
+        self.read_mip_inner(level, !self.fw.is_polling()).await
+    }
+
+    /// Blocking version of `GpuImage::read_mip()`.
+    pub fn read_mip_blocking(&self, level: u32) -> ImageOutputResult<Vec<u8>> {
+        futures::executor::block_on(self.read_mip_inner(level, true))
+    }
+
+    /// Pulls all the pixels of the mip `level` from the [`GpuImage`] into a [`Vec`], without blocking.
+    /// See [`GpuImage::read_mip`] and [`GpuImage::read_async`].
+    pub fn read_mip_async(&self, level: u32) -> impl Future<Output = ImageOutputResult<Vec<u8>>> {
+        let read = self
+            .mip_source(level)
+            .map(|(source, size)| read_texture_async::<P>(&self.fw, source, size));
+
+        async move { read?.await }
+    }
+
+    /// Returns the copy source and the size of the mip `level`.
+    fn mip_source(
+        &self,
+        level: u32,
+    ) -> ImageOutputResult<(wgpu::ImageCopyTexture<'_>, wgpu::Extent3d)> {
+        if level >= self.mip_levels() {
+            return Err(ImageOutputError::MipLevelOutOfRange {
+                level,
+                levels: self.mip_levels(),
+            });
+        }
+
+        let source = wgpu::ImageCopyTexture {
+            texture: &self.texture,
+            mip_level: level,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        };
+
+        Ok((
+            source,
+            self.size.mip_level_size(level, wgpu::TextureDimension::D2),
+        ))
+    }
+
+    async fn read_mip_inner(&self, level: u32, wait: bool) -> ImageOutputResult<Vec<u8>> {
+        let (source, size) = self.mip_source(level)?;
+
+        let mut buf = vec![0u8; image_bytes::<P>(size)];
+        read_texture::<P>(&self.fw, source, size, &mut buf, wait).await?;
+
+        Ok(buf)
+    }
+
+    /// Fills every mip level of the [`GpuImage`] from level 0, each texel averaging the 2x2 texels
+    /// of the previous level. The operation is instantly offloaded.
+    ///
+    /// Fails with [`ImageInputError::MipmapsUnsupported`] unless `P` is a float or normalized format
+    /// writable from WGSL, e.g. [`Rgba8UintNorm`](crate::primitives::pixels::Rgba8UintNorm) or
+    /// [`Rgba32Float`](crate::primitives::pixels::Rgba32Float).
+    pub fn generate_mipmaps(&self) -> ImageInputResult<()> {
+        let format = P::wgpu_format();
+        let storage_format =
+            float_storage_format(format).ok_or(ImageInputError::MipmapsUnsupported(format))?;
+
+        if self.fw.is_device_lost() {
+            return Err(ImageInputError::DeviceLost);
+        }
+
+        if self.mip_levels() == 1 {
+            return Ok(());
+        }
+
+        let device = &self.fw.device;
+        let (layout, pipeline) = float_image_pipeline(
+            device,
+            "GpuImage::generate_mipmaps",
+            &MIPMAP_SHADER.replace("STORAGE_FORMAT", storage_format),
+            format,
+        );
+
+        // Each source level is copied into its own texture first: the GL backend ignores
+        // the base mip level of sampled views.
+        let levels = (1..self.mip_levels())
+            .map(|level| {
+                let src_size = self
+                    .size
+                    .mip_level_size(level - 1, wgpu::TextureDimension::D2);
+                let src = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("GpuImage::generate_mipmaps"),
+                    size: src_size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    view_formats: &[],
+                });
+                let src_view = src.create_view(&wgpu::TextureViewDescriptor::default());
+                let dst_view = mip_view(&self.texture, level);
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("GpuImage::generate_mipmaps"),
+                    layout: &layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&src_view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&dst_view),
+                        },
+                    ],
+                });
+
+                (level, src, src_size, bind_group)
+            })
+            .collect::<Vec<_>>();
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("GpuImage::generate_mipmaps"),
+        });
+
+        for (level, src, src_size, bind_group) in &levels {
+            encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &self.texture,
+                    mip_level: level - 1,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                src.as_image_copy(),
+                *src_size,
+            );
+
+            let size = self.size.mip_level_size(*level, wgpu::TextureDimension::D2);
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("GpuImage::generate_mipmaps"),
+            });
+            cpass.set_pipeline(&pipeline);
+            cpass.set_bind_group(0, bind_group, &[]);
+            cpass.dispatch_workgroups(
+                size.width.div_ceil(MIPMAP_WORKGROUP_SIZE),
+                size.height.div_ceil(MIPMAP_WORKGROUP_SIZE),
+                1,
+            );
+        }
+
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+
+    /// Copies all the pixels of this [`GpuImage`] into the top-left corner of `dst` on the GPU,
+    /// e.g. to snapshot it before a kernel writing it in place. The operation is instantly offloaded.
+    ///
+    /// Only the mip level 0 is copied. Fails with [`ImageInputError::DestinationTooSmall`] if `dst`
+    /// cannot hold all the pixels, or with [`ImageInputError::DifferentFramework`] if `dst` was not created
+    /// by the same [`Framework`](crate::Framework).
+    pub fn copy_to(&self, dst: &GpuImage<P>) -> ImageInputResult<()> {
+        self.check_copy_to(dst)?;
+        self.copy_levels(dst, 1)
+    }
+
+    /// Checks that `dst` can hold all the pixels of this [`GpuImage`] and belongs to the same device.
+    pub(crate) fn check_copy_to(&self, dst: &GpuImage<P>) -> ImageInputResult<()> {
+        if self.size.width > dst.size.width || self.size.height > dst.size.height {
+            return Err(ImageInputError::DestinationTooSmall {
+                width: self.size.width,
+                height: self.size.height,
+                dst_width: dst.size.width,
+                dst_height: dst.size.height,
+            });
+        }
+
+        if !Arc::ptr_eq(&self.fw.device, &dst.fw.device) {
+            return Err(ImageInputError::DifferentFramework);
+        }
+
+        Ok(())
+    }
+
+    /// Creates a new [`GpuImage`] with the same size, mip levels and pixels as this one, copied on the GPU.
+    pub fn duplicate(&self) -> ImageInputResult<GpuImage<P>> {
+        let dst = Self::try_with_mip_levels(
+            &self.fw,
+            self.size.width,
+            self.size.height,
+            self.mip_levels(),
+        )?;
+        self.copy_levels(&dst, self.mip_levels())?;
+
+        Ok(dst)
+    }
+
+    /// Converts the pixels of this [`GpuImage`] into the format of `dst`, e.g. 8 bit normalized pixels
+    /// into 32 bit floats before processing them. The operation is instantly offloaded.
+    ///
+    /// Pixels are converted on the GPU as kernels read and write them: sRGB pixels are decoded into
+    /// linear colors, missing green, blue and alpha channels are filled with 0, 0 and 1, extra channels
+    /// are dropped, and normalized destinations clamp the values they cannot hold. Images of the same
+    /// format are copied.
+    ///
+    /// Only float, normalized and sRGB pixels can be converted, into float or normalized ones: fails with
+    /// [`ImageInputError::UnsupportedConversion`] otherwise, with [`ImageInputError::DimensionsMismatch`]
+    /// if `dst` has not the size of this image, and with [`ImageInputError::DifferentFramework`]
+    /// if `dst` was not created by the same [`Framework`](crate::Framework).
+    pub fn convert_into<Q: PixelInfo>(&self, dst: &GpuImage<Q>) -> ImageInputResult<()> {
+        convert_texture(&self.fw, (&self.texture, P::wgpu_format()), self.size, dst)
+    }
+
+    /// Creates a new [`GpuImage`] of the same size with the pixels of this one converted into `Q` pixels.
+    /// See [`GpuImage::convert_into`].
+    pub fn convert<Q: PixelInfo>(&self) -> ImageInputResult<GpuImage<Q>> {
+        let dst = GpuImage::try_new(&self.fw, self.size.width, self.size.height)?;
+        self.convert_into(&dst)?;
+
+        Ok(dst)
+    }
+
+    /// Resizes this [`GpuImage`] to `new_width` x `new_height` pixels, keeping the pixels of the region
+    /// shared by both sizes: shrinking crops the image, growing adds zeroed pixels to its right and bottom.
+    ///
+    /// A new image with the same format and usages is created and the pixels are copied on the GPU
+    /// (see [`GpuImage::copy_region_to`]), then the previous texture is released. Mip levels are kept,
+    /// up to the mip levels of the new size, each one keeping its shared region.
+    ///
+    /// Fails like [`GpuImage::try_new`] with the new size, leaving the image unchanged.
+    ///
+    /// Bind groups are created by [`Kernel::run`](crate::Kernel::run) from the [`SetBindings`](crate::SetBindings)
+    /// of each call, so the kernels use the resized image once it is added to new bindings.
+    pub fn resize(&mut self, new_width: u32, new_height: u32) -> ImageInputResult<()> {
+        if self.fw.is_device_lost() {
+            return Err(ImageInputError::DeviceLost);
+        }
+
+        let size = extent(new_width, new_height);
+        let levels = std::cmp::min(self.mip_levels(), size.max_mips(wgpu::TextureDimension::D2));
+        let texture = create_texture::<P>(
+            &self.fw,
+            size,
+            wgpu::TextureDimension::D2,
+            self.texture.usage(),
+            levels,
+            "GpuImage::resize",
+        )?;
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuImage::resize"),
+            });
+
+        for level in 0..levels {
+            let old = self.size.mip_level_size(level, wgpu::TextureDimension::D2);
+            let new = size.mip_level_size(level, wgpu::TextureDimension::D2);
+
+            encode_image_copy(
+                &mut encoder,
+                (&self.texture, wgpu::Origin3d::ZERO),
+                (&texture, wgpu::Origin3d::ZERO),
+                level,
+                extent(
+                    std::cmp::min(old.width, new.width),
+                    std::cmp::min(old.height, new.height),
+                ),
+            );
+        }
+
+        self.fw.queue.submit(Some(encoder.finish()));
+        *self = Self::from_gpu_parts(&self.fw, texture, size);
+
+        Ok(())
+    }
+
+    /// Copies the region of `extent` (width, height) pixels whose top-left corner is at `src_origin` (x, y)
+    /// into `dst` at `dst_origin` on the GPU, e.g. a tile of a larger image. The operation is instantly offloaded.
+    ///
+    /// Fails with [`ImageInputError::RegionOutOfBounds`] if a region exceeds its image, with
+    /// [`ImageInputError::UnalignedRegion`] if a region is not aligned to the blocks of the format,
+    /// and like [`GpuImage::copy_to`] otherwise.
+    ///
+    /// `wgpu` does not copy within the same image: if `dst` is this [`GpuImage`], the region is copied
+    /// through a temporary image, so overlapping regions are copied as if they were disjoint.
+    pub fn copy_region_to(
+        &self,
+        src_origin: (u32, u32),
+        dst: &GpuImage<P>,
+        dst_origin: (u32, u32),
+        extent: (u32, u32),
+    ) -> ImageInputResult<()> {
+        let (src_origin_3d, size) = image_region(self.size, src_origin, extent)?;
+        let (dst_origin_3d, _) = image_region(dst.size, dst_origin, extent)?;
+        check_block_aligned(P::wgpu_format(), src_origin, extent)?;
+        check_block_aligned(P::wgpu_format(), dst_origin, extent)?;
+
+        if !Arc::ptr_eq(&self.fw.device, &dst.fw.device) {
+            return Err(ImageInputError::DifferentFramework);
+        }
+
+        if self.fw.is_device_lost() {
+            return Err(ImageInputError::DeviceLost);
+        }
+
+        if image_pixels(size) == 0 {
+            return Ok(());
+        }
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuImage::copy_region_to"),
+            });
+
+        if std::ptr::eq(self, dst) {
+            let temp = self.fw.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("GpuImage::copy_region_to"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: P::wgpu_format(),
+                usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+
+            encode_image_copy(
+                &mut encoder,
+                (&self.texture, src_origin_3d),
+                (&temp, wgpu::Origin3d::ZERO),
+                0,
+                size,
+            );
+            encode_image_copy(
+                &mut encoder,
+                (&temp, wgpu::Origin3d::ZERO),
+                (&dst.texture, dst_origin_3d),
+                0,
+                size,
+            );
+        } else {
+            encode_image_copy(
+                &mut encoder,
+                (&self.texture, src_origin_3d),
+                (&dst.texture, dst_origin_3d),
+                0,
+                size,
+            );
+        }
+
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+
+    /// Copies all the pixels of this [`GpuImage`] into the beginning of `dst` on the GPU, tightly packed
+    /// row after row like [`GpuImage::read`] returns them, e.g. for kernels addressing them linearly.
+    /// The operation is instantly offloaded.
+    ///
+    /// Rows whose size is not a multiple of [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`] (256 bytes) are copied
+    /// through a temporary padded buffer. Only the mip level 0 is copied.
+    ///
+    /// Fails with [`ImageInputError::ElementSizeMismatch`] if the texels of `P` and the elements `T`
+    /// cannot be made of each other, e.g. `[f32; 4]` texels and `[f32; 3]` elements, with
+    /// [`ImageInputError::DestinationBufferTooSmall`] if `dst` cannot hold all the pixels,
+    /// and with [`ImageInputError::DifferentFramework`] if `dst` was not created by the same
+    /// [`Framework`](crate::Framework).
+    pub fn copy_to_buffer<T>(&self, dst: &GpuBuffer<T>) -> ImageInputResult<()>
+    where
+        T: bytemuck::Pod,
+    {
+        check_element_size::<P, T>()?;
+
+        let image_size = image_bytes::<P>(self.size);
+
+        if (dst.size() as usize) < image_size {
+            return Err(ImageInputError::DestinationBufferTooSmall {
+                len: dst.size() as usize,
+                size: image_size,
+            });
+        }
+
+        self.buffer_copy(dst, true)
+    }
+
+    /// Copies the tightly packed pixels at the beginning of `src` into this [`GpuImage`] on the GPU,
+    /// row after row like [`GpuImage::write`] takes them. The operation is instantly offloaded.
+    ///
+    /// Fails with [`ImageInputError::BufferTooSmall`] if `src` does not hold all the pixels of the image,
+    /// and like [`GpuImage::copy_to_buffer`] otherwise.
+    pub fn copy_from_buffer<T>(&self, src: &GpuBuffer<T>) -> ImageInputResult<()>
+    where
+        T: bytemuck::Pod,
+    {
+        check_element_size::<P, T>()?;
+        check_writable(P::wgpu_format())?;
+
+        let image_size = image_bytes::<P>(self.size);
+
+        if (src.size() as usize) < image_size {
+            return Err(ImageInputError::BufferTooSmall {
+                len: src.size() as usize,
+                size: image_size,
+            });
+        }
+
+        self.buffer_copy(src, false)
+    }
+
+    fn buffer_copy<T>(&self, buffer: &GpuBuffer<T>, to_buffer: bool) -> ImageInputResult<()>
+    where
+        T: bytemuck::Pod,
+    {
+        if !Arc::ptr_eq(&self.fw.device, &buffer.fw.device) {
+            return Err(ImageInputError::DifferentFramework);
+        }
+
+        if self.fw.is_device_lost() {
+            return Err(ImageInputError::DeviceLost);
+        }
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuImage::buffer_copy"),
+            });
+        encode_buffer_copy::<P>(
+            &self.fw,
+            &mut encoder,
+            &self.texture,
+            buffer.as_gpu_buffer(),
+            self.size,
+            to_buffer,
+        )?;
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+
+    /// Copies the `levels` first mip levels into the top-left corner of the ones of `dst`, which holds them.
+    fn copy_levels(&self, dst: &GpuImage<P>, levels: u32) -> ImageInputResult<()> {
+        if !Arc::ptr_eq(&self.fw.device, &dst.fw.device) {
+            return Err(ImageInputError::DifferentFramework);
+        }
+
+        if self.fw.is_device_lost() {
+            return Err(ImageInputError::DeviceLost);
+        }
+
+        // An image copied into itself is left unchanged.
+        if std::ptr::eq(self, dst) {
+            return Ok(());
+        }
+
+        let mut encoder = self
+            .fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuImage::copy_to"),
+            });
+
+        self.encode_copy(&mut encoder, dst, levels);
+        self.fw.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+
+    /// Records the copy of the `levels` first mip levels into `dst`, another image, without any check.
+    pub(crate) fn encode_copy(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        dst: &GpuImage<P>,
+        levels: u32,
+    ) {
+        for level in 0..levels {
+            encode_image_copy(
+                encoder,
+                (&self.texture, wgpu::Origin3d::ZERO),
+                (&dst.texture, wgpu::Origin3d::ZERO),
+                level,
+                self.size.mip_level_size(level, wgpu::TextureDimension::D2),
+            );
+        }
+    }
+}
+
+/// Single mip level of a [`GpuImage`], bound with [`SetBindings::add_image_mip`](crate::SetBindings::add_image_mip).
+/// See [`GpuImage::mip_view`].
+pub struct GpuImageMip<'a, P> {
+    image: &'a GpuImage<P>,
+    level: u32,
+    view: wgpu::TextureView,
+}
+
+impl<'a, P> GpuImageMip<'a, P>
+where
+    P: PixelInfo,
+{
+    /// Returns the [`GpuImage`] of this [`GpuImageMip`].
+    pub fn image(&self) -> &'a GpuImage<P> {
+        self.image
+    }
+
+    /// Returns the mip level of this [`GpuImageMip`].
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// Returns a [`wgpu::BindingResource`] of the mip level.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::TextureView(&self.view)
+    }
+}
+
+impl<P> GpuConstImage<P>
+where
+    P: PixelInfo,
+{
+    /// Returns the width in pixels of the [`GpuConstImage`].
+    pub fn width(&self) -> u32 {
+        self.size.width
+    }
+
+    /// Returns the height in pixels of the [`GpuConstImage`].
+    pub fn height(&self) -> u32 {
+        self.size.height
+    }
+
+    /// Returns the size of the [`GpuConstImage`] as a [`wgpu::Extent3d`], of depth 1.
+    pub fn extent(&self) -> wgpu::Extent3d {
+        self.size
+    }
+
+    /// Returns the [`wgpu::Texture`] of the [`GpuConstImage`], e.g. to draw it in a render pass of the same device.
+    ///
+    /// Same as [`ImgOps::as_gpu_texture`].
+    pub fn as_wgpu_texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    /// Returns the [`wgpu::TextureView`] the [`GpuConstImage`] is bound with, of the whole image.
+    pub fn full_view(&self) -> &wgpu::TextureView {
+        &self.full_view
+    }
+
+    /// Converts the pixels of this [`GpuConstImage`] into the format of `dst`. See [`GpuImage::convert_into`].
+    pub fn convert_into<Q: PixelInfo>(&self, dst: &GpuImage<Q>) -> ImageInputResult<()> {
+        convert_texture(&self.fw, (&self.texture, P::wgpu_format()), self.size, dst)
+    }
+
+    /// Creates a new [`GpuImage`] of the same size with the pixels of this [`GpuConstImage`] converted
+    /// into `Q` pixels, e.g. an input image into the format of the kernels processing it.
+    /// See [`GpuImage::convert_into`].
+    pub fn convert<Q: PixelInfo>(&self) -> ImageInputResult<GpuImage<Q>> {
+        let dst = GpuImage::try_new(&self.fw, self.size.width, self.size.height)?;
+        self.convert_into(&dst)?;
+
+        Ok(dst)
+    }
+
+    /// Writes tightly packed pixels into the region of `size` (width, height) pixels at `origin` (x, y).
+    /// The operation is instantly offloaded. See [`GpuImage::write_region`].
+    pub fn write_region(
+        &self,
+        origin: (u32, u32),
+        size: (u32, u32),
+        buf: &[u8],
+    ) -> ImageInputResult<usize> {
+        let (origin, size) = image_region(self.size, origin, size)?;
+
+        write_texture::<P>(&self.fw, &self.texture, origin, size, buf)
+    }
+}
+
+/// Returns the origin and size of the `layer` of an image array of `size`, or `None` if it is out of range.
+fn array_layer(size: wgpu::Extent3d, layer: u32) -> Option<(wgpu::Origin3d, wgpu::Extent3d)> {
+    if layer >= size.depth_or_array_layers {
+        return None;
+    }
+
+    Some((
+        wgpu::Origin3d {
+            x: 0,
+            y: 0,
+            z: layer,
+        },
+        extent(size.width, size.height),
+    ))
+}
+
+/// Creates a view of `texture` of the `D2Array` dimension, even if it has a single layer.
+fn array_view(texture: &wgpu::Texture) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        dimension: Some(wgpu::TextureViewDimension::D2Array),
+        ..Default::default()
+    })
+}
+
+/// Creates a `D2` view of the single `layer` of `texture`.
+fn layer_view(texture: &wgpu::Texture, layer: u32) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some("GpuImageArray::layer_view"),
+        dimension: Some(wgpu::TextureViewDimension::D2),
+        base_array_layer: layer,
+        array_layer_count: std::num::NonZeroU32::new(1),
+        ..Default::default()
+    })
+}
+
+impl<P> GpuImageArray<P>
+where
+    P: PixelInfo,
+{
+    /// Returns a view of the single `layer`, bound with
+    /// [`SetBindings::add_image_layer`](crate::SetBindings::add_image_layer) to kernels expecting a [`GpuImage`].
+    ///
+    /// Fails with [`ImageInputError::LayerOutOfRange`] if `layer` is not a layer of the [`GpuImageArray`].
+    pub fn layer_view(&self, layer: u32) -> ImageInputResult<GpuImageLayer<'_, P>> {
+        if array_layer(self.size, layer).is_none() {
+            return Err(ImageInputError::LayerOutOfRange {
+                layer,
+                layers: self.layers(),
+            });
+        }
+
+        Ok(GpuImageLayer {
+            array: self,
+            layer,
+            view: layer_view(&self.texture, layer),
+        })
+    }
+
+    /// Returns a view of the layers selected by `options`, as an image array.
+    /// See [`GpuImage::create_view`].
+    pub fn create_view(&self, options: &ImageViewOptions) -> ImageInputResult<GpuImageView<'_, P>> {
+        GpuImageView::new(&self.texture, options, wgpu::TextureViewDimension::D2Array)
+    }
+
+    /// Typed version of `GpuImageArray::read_layer()`.
+    pub async fn read_layer_pixels(&self, layer: u32) -> ImageOutputResult<Vec<P::Texel>> {
+        self.read_layer(layer)
+            .await
+            .map(|bytes| texels::<P>(&bytes))
+    }
+
+    /// Blocking version of `GpuImageArray::read_layer_pixels()`.
+    pub fn read_layer_pixels_blocking(&self, layer: u32) -> ImageOutputResult<Vec<P::Texel>> {
+        self.read_layer_blocking(layer)
+            .map(|bytes| texels::<P>(&bytes))
+    }
+
+    /// Pulls all the pixels of `layer` from the [`GpuImageArray`] into a [`Vec`].
+    ///
+    /// Fails with [`ImageOutputError::LayerOutOfRange`] if `layer` is not a layer of the [`GpuImageArray`].
+    pub async fn read_layer(&self, layer: u32) -> ImageOutputResult<Vec<u8>> {
+        self.read_layer_inner(layer, !self.fw.is_polling()).await
+    }
+
+    /// Blocking version of `GpuImageArray::read_layer()`.
+    pub fn read_layer_blocking(&self, layer: u32) -> ImageOutputResult<Vec<u8>> {
+        futures::executor::block_on(self.read_layer_inner(layer, true))
+    }
+
+    async fn read_layer_inner(&self, layer: u32, wait: bool) -> ImageOutputResult<Vec<u8>> {
+        let (origin, size) =
+            array_layer(self.size, layer).ok_or(ImageOutputError::LayerOutOfRange {
+                layer,
+                layers: self.layers(),
+            })?;
+
+        let mut buf = vec![0u8; image_bytes::<P>(size)];
+        let source = wgpu::ImageCopyTexture {
+            texture: &self.texture,
+            mip_level: 0,
+            origin,
+            aspect: wgpu::TextureAspect::All,
+        };
+        read_texture::<P>(&self.fw, source, size, &mut buf, wait).await?;
+
+        Ok(buf)
+    }
+}
+
+impl<P> GpuConstImageArray<P>
+where
+    P: PixelInfo,
+{
+    /// Returns a view of the single `layer`, bound with
+    /// [`SetBindings::add_const_image_layer`](crate::SetBindings::add_const_image_layer) to kernels
+    /// expecting a [`GpuConstImage`].
+    ///
+    /// The GL backend has no texture views: kernels read zeroes from the layer instead.
+    ///
+    /// Fails with [`ImageInputError::LayerOutOfRange`] if `layer` is not a layer of the [`GpuConstImageArray`].
+    pub fn layer_view(&self, layer: u32) -> ImageInputResult<GpuConstImageLayer<'_, P>> {
+        if array_layer(self.size, layer).is_none() {
+            return Err(ImageInputError::LayerOutOfRange {
+                layer,
+                layers: self.layers(),
+            });
+        }
+
+        Ok(GpuConstImageLayer {
+            array: self,
+            layer,
+            view: layer_view(&self.texture, layer),
+        })
+    }
+}
+
+/// Single layer of a [`GpuImageArray`], bound with [`SetBindings::add_image_layer`](crate::SetBindings::add_image_layer).
+/// See [`GpuImageArray::layer_view`].
+pub struct GpuImageLayer<'a, P> {
+    array: &'a GpuImageArray<P>,
+    layer: u32,
+    view: wgpu::TextureView,
+}
+
+impl<'a, P> GpuImageLayer<'a, P>
+where
+    P: PixelInfo,
+{
+    /// Returns the [`GpuImageArray`] of this [`GpuImageLayer`].
+    pub fn array(&self) -> &'a GpuImageArray<P> {
+        self.array
+    }
+
+    /// Returns the index of this [`GpuImageLayer`] in its [`GpuImageArray`].
+    pub fn layer(&self) -> u32 {
+        self.layer
+    }
+
+    /// Returns a [`wgpu::BindingResource`] of the layer.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::TextureView(&self.view)
+    }
+}
+
+/// Single layer of a [`GpuConstImageArray`], bound with
+/// [`SetBindings::add_const_image_layer`](crate::SetBindings::add_const_image_layer).
+/// See [`GpuConstImageArray::layer_view`].
+pub struct GpuConstImageLayer<'a, P> {
+    array: &'a GpuConstImageArray<P>,
+    layer: u32,
+    view: wgpu::TextureView,
+}
+
+impl<'a, P> GpuConstImageLayer<'a, P>
+where
+    P: PixelInfo,
+{
+    /// Returns the [`GpuConstImageArray`] of this [`GpuConstImageLayer`].
+    pub fn array(&self) -> &'a GpuConstImageArray<P> {
+        self.array
+    }
+
+    /// Returns the index of this [`GpuConstImageLayer`] in its [`GpuConstImageArray`].
+    pub fn layer(&self) -> u32 {
+        self.layer
+    }
+
+    /// Returns a [`wgpu::BindingResource`] of the layer.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::TextureView(&self.view)
+    }
+}
+
+/// Mip levels and layers selected by a [`GpuImageView`]. The default options select all of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImageViewOptions {
+    /// First mip level of the view.
+    pub base_mip_level: u32,
+    /// Number of mip levels of the view, all the following ones if `None`.
+    pub mip_level_count: Option<u32>,
+    /// First layer of the view.
+    pub base_array_layer: u32,
+    /// Number of layers of the view, all the following ones if `None`.
+    pub array_layer_count: Option<u32>,
+}
+
+impl ImageViewOptions {
+    /// Options selecting the single mip `level`.
+    pub fn mip_level(level: u32) -> Self {
+        Self {
+            base_mip_level: level,
+            mip_level_count: Some(1),
+            ..Default::default()
+        }
+    }
+}
+
+/// View of some mip levels and layers of an image, bound with
+/// [`SetBindings::add_image_view`](crate::SetBindings::add_image_view) or
+/// [`SetBindings::add_const_image_view`](crate::SetBindings::add_const_image_view).
+/// See [`GpuImage::create_view`].
+pub struct GpuImageView<'a, P> {
+    texture: &'a wgpu::Texture,
+    view: wgpu::TextureView,
+    dimension: wgpu::TextureViewDimension,
+    mip_level_count: u32,
+    array_layer_count: u32,
+    pixel: PhantomData<P>,
+}
+
+impl<'a, P> GpuImageView<'a, P>
+where
+    P: PixelInfo,
+{
+    fn new(
+        texture: &'a wgpu::Texture,
+        options: &ImageViewOptions,
+        dimension: wgpu::TextureViewDimension,
+    ) -> ImageInputResult<Self> {
+        let check_range = |kind, base: u32, count: Option<u32>, total: u32| {
+            let count = count.unwrap_or_else(|| total.saturating_sub(base));
+            let fits = count > 0 && base.checked_add(count).is_some_and(|end| end <= total);
+
+            if !fits {
+                return Err(ImageInputError::ViewOutOfRange {
+                    kind,
+                    base,
+                    count,
+                    total,
+                });
+            }
+
+            Ok(count)
+        };
+
+        let mip_level_count = check_range(
+            "mip levels",
+            options.base_mip_level,
+            options.mip_level_count,
+            texture.mip_level_count(),
+        )?;
+        let array_layer_count = check_range(
+            "layers",
+            options.base_array_layer,
+            options.array_layer_count,
+            texture.depth_or_array_layers(),
+        )?;
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("GpuImageView"),
+            dimension: Some(dimension),
+            base_mip_level: options.base_mip_level,
+            mip_level_count: std::num::NonZeroU32::new(mip_level_count),
+            base_array_layer: options.base_array_layer,
+            array_layer_count: std::num::NonZeroU32::new(array_layer_count),
+            ..Default::default()
+        });
+
+        Ok(Self {
+            texture,
+            view,
+            dimension,
+            mip_level_count,
+            array_layer_count,
+            pixel: PhantomData,
+        })
+    }
+
+    /// Returns the number of mip levels of the view.
+    pub fn mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+
+    /// Returns the number of layers of the view.
+    pub fn array_layer_count(&self) -> u32 {
+        self.array_layer_count
+    }
+
+    /// Returns the [`wgpu::TextureViewDimension`] of the view, `D2Array` for views of image arrays.
+    pub fn dimension(&self) -> wgpu::TextureViewDimension {
+        self.dimension
+    }
+
+    /// Returns `true` if the view can be sampled, which needs the image to be created with
+    /// [`GpuImage::try_with_mip_levels`] or [`GpuImage::try_with_mipmaps`].
+    pub fn is_sampled(&self) -> bool {
+        self.texture
+            .usage()
+            .contains(wgpu::TextureUsages::TEXTURE_BINDING)
+    }
+
+    /// Returns a [`wgpu::BindingResource`] of the view.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::TextureView(&self.view)
+    }
+}
+
+/// Pair of [`GpuImage`]s swapped every iteration of an iterative filter, e.g. a Jacobi solver:
+/// kernels read the [`PingPongImage::front`] image and write the [`PingPongImage::back`] one,
+/// then [`PingPongImage::swap`] makes the written image the next input.
+///
+/// Both images can be sampled, see [`SetBindings::add_ping_pong_image`](crate::SetBindings::add_ping_pong_image).
+pub struct PingPongImage<P> {
+    images: [GpuImage<P>; 2],
+    front: usize,
+}
+
+impl<P> PingPongImage<P>
+where
+    P: PixelInfo,
+{
+    /// Constructs a pair of empty images of `width` x `height` pixels.
+    ///
+    /// Fails like [`GpuImage::try_new`].
+    pub fn try_new(fw: &crate::Framework, width: u32, height: u32) -> ImageInputResult<Self> {
+        Ok(Self {
+            images: [
+                GpuImage::try_with_mip_levels(fw, width, height, 1)?,
+                GpuImage::try_with_mip_levels(fw, width, height, 1)?,
+            ],
+            front: 0,
+        })
+    }
+
+    /// Constructs a pair of empty images of `width` x `height` pixels.
+    ///
+    /// # Panics
+    /// Like [`GpuImage::new`].
+    pub fn new(fw: &crate::Framework, width: u32, height: u32) -> Self {
+        Self::try_new(fw, width, height).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns the image read by the next iteration, holding the result of the last one.
+    pub fn front(&self) -> &GpuImage<P> {
+        &self.images[self.front]
+    }
+
+    /// Returns the image written by the next iteration.
+    pub fn back(&self) -> &GpuImage<P> {
+        &self.images[1 - self.front]
+    }
+
+    /// Swaps the [`PingPongImage::front`] and [`PingPongImage::back`] images, once a kernel wrote the back one.
+    pub fn swap(&mut self) {
+        self.front = 1 - self.front;
+    }
+
+    /// Returns the width and height of both images.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.front().dimensions()
+    }
+
+    /// Decomposes the pair into its front and back images.
+    pub fn into_images(self) -> (GpuImage<P>, GpuImage<P>) {
+        let [first, second] = self.images;
+
+        if self.front == 0 {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        new_set_layout, primitives::pixels::Rgba8Uint, testing, Kernel, SetBindings, Shader,
+    };
+
+    /// Distinct bytes for each byte of an image of `len` bytes.
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 251) as u8).collect()
+    }
+
+    /// Copies `input` into a new [`GpuImage`] of the same size with a kernel.
+    fn copy_with_kernel(
+        fw: &crate::Framework,
+        input: &GpuConstImage<Rgba8Uint>,
+    ) -> GpuImage<Rgba8Uint> {
+        let shader = Shader::from_wgsl_string(
+            fw,
+            "@group(0) @binding(0) var input: texture_2d<u32>;
+            @group(0) @binding(1) var output: texture_storage_2d<rgba8uint, write>;
+
+            @compute @workgroup_size(8, 8)
+            fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                let coords = vec2<i32>(id.xy);
+                let size = textureDimensions(input);
+                if (coords.x < size.x && coords.y < size.y) {
+                    textureStore(output, coords, textureLoad(input, coords, 0));
+                }
+            }"
+            .to_owned(),
+            None,
+        )
+        .unwrap();
+
+        let output = GpuImage::new(fw, input.width(), input.height());
+        let kernel = Kernel::builder(fw, &shader, "main")
+            .layouts(vec![
+                new_set_layout!(0: ConstImage<Rgba8Uint>, 1: Image<Rgba8Uint>),
+            ])
+            .bind_set(
+                0,
+                SetBindings::default()
+                    .add_const_image(0, input)
+                    .add_image(1, &output),
+            )
+            .build()
+            .unwrap();
+        kernel.run_elements(fw, input.width() as u64, input.height() as u64, 1);
+
+        output
+    }
+
+    #[test]
+    fn read_round_trip() {
+        testing::with_framework(|fw| {
+            // Rows of 4, 12, 256 and 260 bytes, padded to 256 bytes in the staging buffer but one.
+            for &(width, height) in &[(1, 7), (3, 5), (64, 3), (65, 4)] {
+                let data = pattern(width as usize * height as usize * 4);
+                let image = GpuImage::<Rgba8Uint>::from_bytes(fw, &data, width, height);
+
+                assert_eq!(
+                    image.read_vec_blocking().unwrap(),
+                    data,
+                    "{}x{}",
+                    width,
+                    height
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn read_round_trip_through_kernel() {
+        testing::with_framework(|fw| {
+            for &(width, height) in &[(1, 9), (65, 3)] {
+                let data = pattern(width as usize * height as usize * 4);
+                let input = GpuConstImage::<Rgba8Uint>::from_bytes(fw, &data, width, height);
+                let output = copy_with_kernel(fw, &input);
+
+                assert_eq!(
+                    output.read_vec_blocking().unwrap(),
+                    data,
+                    "{}x{}",
+                    width,
+                    height
+                );
+                assert_eq!(
+                    futures::executor::block_on(output.read_async()).unwrap(),
+                    data,
+                    "{}x{}",
+                    width,
+                    height
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn volume_round_trip() {
+        testing::with_framework(|fw| {
+            let data = pattern(8 * 8 * 8 * 4);
+            let volume = GpuImage3d::<Rgba8Uint>::try_from_bytes(fw, &data, 8, 8, 8).unwrap();
+            let read = volume.read_vec_blocking().unwrap();
+
+            assert_eq!(volume.dimensions(), (8, 8, 8));
+            // The GL backend only copies the first slice out of 3D textures.
+            if fw.adapter_info().backend == wgpu::Backend::Gl {
+                assert_eq!(read[..8 * 8 * 4], data[..8 * 8 * 4]);
+            } else {
+                assert_eq!(read, data);
+            }
+        });
+    }
+
+    #[test]
+    fn volume_write_read_by_kernel() {
+        testing::with_framework(|fw| {
+            let shader = Shader::from_wgsl_string(
+                fw,
+                "@group(0) @binding(0) var input: texture_3d<u32>;
+                @group(0) @binding(1) var<storage, read_write> output: array<vec4<u32>>;
+
+                @compute @workgroup_size(4, 4, 4)
+                fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                    output[(id.z * 8u + id.y) * 8u + id.x] = textureLoad(input, vec3<i32>(id), 0);
+                }"
+                .to_owned(),
+                None,
+            )
+            .unwrap();
+
+            let data = pattern(8 * 8 * 8 * 4);
+            let volume = GpuConstImage3d::<Rgba8Uint>::try_from_bytes(fw, &data, 8, 8, 8).unwrap();
+            let output = GpuBuffer::<[u32; 4]>::with_capacity(fw, 8 * 8 * 8);
+
+            let kernel = Kernel::builder(fw, &shader, "main")
+                .layouts(vec![new_set_layout!(0: ConstImage3d<Rgba8Uint>, 1: Buffer)])
+                .bind_set(
+                    0,
+                    SetBindings::default()
+                        .add_const_image_3d(0, &volume)
+                        .add_buffer(1, &output),
+                )
+                .build()
+                .unwrap();
+            kernel.run_elements(fw, 8, 8, 8);
+
+            let texels = output
+                .read_vec_blocking()
+                .unwrap()
+                .iter()
+                .flat_map(|texel| texel.iter().map(|&channel| channel as u8))
+                .collect::<Vec<_>>();
+            assert_eq!(texels, data);
+        });
+    }
+
+    #[test]
+    fn copy_region_at_odd_offset() {
+        testing::with_framework(|fw| {
+            let (width, height) = (11, 13);
+            let src_data = pattern(width * height * 4);
+            let dst_data = vec![0xaa; width * height * 4];
+            let src = GpuImage::<Rgba8Uint>::from_bytes(fw, &src_data, width as u32, height as u32);
+            let dst = GpuImage::<Rgba8Uint>::from_bytes(fw, &dst_data, width as u32, height as u32);
+
+            src.copy_region_to((1, 2), &dst, (5, 7), (3, 5)).unwrap();
+
+            let mut expected = dst_data;
+            for y in 0..5 {
+                for x in 0..3 {
+                    let src_texel = ((2 + y) * width + 1 + x) * 4;
+                    let dst_texel = ((7 + y) * width + 5 + x) * 4;
+                    expected[dst_texel..dst_texel + 4]
+                        .copy_from_slice(&src_data[src_texel..src_texel + 4]);
+                }
+            }
+            assert_eq!(dst.read_vec_blocking().unwrap(), expected);
+            assert_eq!(src.read_vec_blocking().unwrap(), src_data);
+        });
+    }
+
+    #[test]
+    fn copy_region_out_of_bounds() {
+        testing::with_framework(|fw| {
+            let src = GpuImage::<Rgba8Uint>::new(fw, 8, 8);
+            let dst = GpuImage::<Rgba8Uint>::new(fw, 4, 4);
+
+            assert!(matches!(
+                src.copy_region_to((6, 0), &dst, (0, 0), (3, 1)),
+                Err(ImageInputError::RegionOutOfBounds { x: 6, width: 3, .. })
+            ));
+            assert!(matches!(
+                src.copy_region_to((0, 0), &dst, (2, 2), (3, 1)),
+                Err(ImageInputError::RegionOutOfBounds { x: 2, y: 2, .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn buffer_round_trip() {
+        testing::with_framework(|fw| {
+            // Rows of 20 bytes, copied through a padded buffer.
+            let (width, height) = (5, 3);
+            let data = (0..width * height).collect::<Vec<u32>>();
+            let src = GpuBuffer::<u32>::from_slice(fw, &data);
+            let dst = GpuBuffer::<u32>::with_capacity(fw, data.len() as u64);
+            let image = GpuImage::<Rgba8Uint>::new(fw, width, height);
+
+            // Each `u32` holds a texel of 4 bytes.
+            image.copy_from_buffer(&src).unwrap();
+            assert_eq!(
+                image.read_vec_blocking().unwrap(),
+                bytemuck::cast_slice::<_, u8>(&data)
+            );
+
+            image.copy_to_buffer(&dst).unwrap();
+            assert_eq!(dst.read_vec_blocking().unwrap(), data);
+        });
+    }
+
+    #[test]
+    fn buffer_copy_element_size_mismatch() {
+        testing::with_framework(|fw| {
+            let image = GpuImage::<Rgba8Uint>::new(fw, 4, 4);
+            let buffer = GpuBuffer::<[u8; 3]>::with_capacity(fw, 64);
+
+            assert!(matches!(
+                image.copy_to_buffer(&buffer),
+                Err(ImageInputError::ElementSizeMismatch {
+                    texel_size: 4,
+                    element_size: 3
+                })
+            ));
+        });
+    }
+
+    #[test]
+    fn read_into_wrong_length() {
+        testing::with_framework(|fw| {
+            let image = GpuImage::<Rgba8Uint>::new(fw, 3, 2);
+            let mut target = vec![0; 3 * 2 * 4 + 1];
+
+            assert!(matches!(
+                image.read_into_blocking(&mut target),
+                Err(ImageOutputError::InvalidLength { len: 25, size: 24 })
+            ));
+        });
+    }
+}
@@ -0,0 +1,27 @@
+// Downsamples a mip level into the next one with a 2x2 box filter.
+// `STORAGE_FORMAT` is replaced by the WGSL storage format of the image.
+
+@group(0) @binding(0)
+var src: texture_2d<f32>;
+@group(0) @binding(1)
+var dst: texture_storage_2d<STORAGE_FORMAT, write>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<u32>(textureDimensions(dst));
+
+    if (id.x >= size.x || id.y >= size.y) {
+        return;
+    }
+
+    // Odd sizes clamp the last texel of the source level.
+    let last = vec2<i32>(textureDimensions(src)) - vec2<i32>(1, 1);
+    let base = vec2<i32>(id.xy) * 2;
+
+    let sum = textureLoad(src, base, 0)
+        + textureLoad(src, min(base + vec2<i32>(1, 0), last), 0)
+        + textureLoad(src, min(base + vec2<i32>(0, 1), last), 0)
+        + textureLoad(src, min(base + vec2<i32>(1, 1), last), 0);
+
+    textureStore(dst, vec2<i32>(id.xy), sum / 4.0);
+}
@@ -0,0 +1,164 @@
+use std::num::NonZeroU8;
+
+use thiserror::Error;
+
+pub type SamplerResult<T> = Result<T, SamplerError>;
+
+#[derive(Error, Debug)]
+pub enum SamplerError {
+    #[error("anisotropic filtering needs linear magnification, minification and mipmap filters.")]
+    AnisotropyWithoutLinearFilters,
+    #[error("anisotropy clamp {0} is not a power of two between 1 and 16.")]
+    InvalidAnisotropy(u8),
+}
+
+/// Options of a [`GpuSampler`]. The default options sample the nearest texel
+/// and clamp the coordinates to the edges of the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerOptions {
+    /// How to address the texels beyond the edges of the image, per axis (u, v, w).
+    pub address_modes: [wgpu::AddressMode; 3],
+    /// Filter used when the image is magnified.
+    pub mag_filter: wgpu::FilterMode,
+    /// Filter used when the image is minified.
+    pub min_filter: wgpu::FilterMode,
+    /// Filter used between mip levels.
+    pub mipmap_filter: wgpu::FilterMode,
+    /// Lowest mip level that can be sampled.
+    pub lod_min_clamp: f32,
+    /// Highest mip level that can be sampled.
+    pub lod_max_clamp: f32,
+    /// Maximum anisotropy of the anisotropic filtering, disabled if `None`.
+    pub anisotropy_clamp: Option<NonZeroU8>,
+}
+
+impl Default for SamplerOptions {
+    fn default() -> Self {
+        let desc = wgpu::SamplerDescriptor::default();
+
+        Self {
+            address_modes: [
+                desc.address_mode_u,
+                desc.address_mode_v,
+                desc.address_mode_w,
+            ],
+            mag_filter: desc.mag_filter,
+            min_filter: desc.min_filter,
+            mipmap_filter: desc.mipmap_filter,
+            lod_min_clamp: desc.lod_min_clamp,
+            lod_max_clamp: desc.lod_max_clamp,
+            anisotropy_clamp: None,
+        }
+    }
+}
+
+impl SamplerOptions {
+    /// Options interpolating linearly between the texels and mip levels, e.g. for bilinear sampling.
+    pub fn linear() -> Self {
+        Self {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the address mode of all the axes.
+    pub fn address_mode(mut self, address_mode: wgpu::AddressMode) -> Self {
+        self.address_modes = [address_mode; 3];
+        self
+    }
+
+    /// Returns how a sampler of these options must be declared in a [`SetLayout`](crate::SetLayout):
+    /// filtering if any of its filters is linear.
+    pub fn binding_type(&self) -> wgpu::SamplerBindingType {
+        let filters = [self.mag_filter, self.min_filter, self.mipmap_filter];
+
+        if filters.contains(&wgpu::FilterMode::Linear) {
+            wgpu::SamplerBindingType::Filtering
+        } else {
+            wgpu::SamplerBindingType::NonFiltering
+        }
+    }
+}
+
+/// Sampler of [`GpuConstImage`](crate::GpuConstImage)s, e.g. for bilinear filtering in shaders.
+///
+/// More information about its shader representation is
+/// under the [`SetLayout::add_sampler`](crate::SetLayout::add_sampler) documentation.
+pub struct GpuSampler {
+    sampler: wgpu::Sampler,
+    options: SamplerOptions,
+}
+
+impl GpuSampler {
+    /// Constructs a new [`GpuSampler`] of `options`.
+    ///
+    /// Fails with [`SamplerError::InvalidAnisotropy`] if the anisotropy clamp is not 1, 2, 4, 8 or 16,
+    /// and with [`SamplerError::AnisotropyWithoutLinearFilters`] if it is enabled with any nearest filter.
+    pub fn try_new(fw: &crate::Framework, options: &SamplerOptions) -> SamplerResult<Self> {
+        if let Some(anisotropy) = options.anisotropy_clamp {
+            let anisotropy = anisotropy.get();
+
+            if !anisotropy.is_power_of_two() || anisotropy > 16 {
+                return Err(SamplerError::InvalidAnisotropy(anisotropy));
+            }
+
+            let filters = [
+                options.mag_filter,
+                options.min_filter,
+                options.mipmap_filter,
+            ];
+
+            if filters.contains(&wgpu::FilterMode::Nearest) {
+                return Err(SamplerError::AnisotropyWithoutLinearFilters);
+            }
+        }
+
+        let [address_mode_u, address_mode_v, address_mode_w] = options.address_modes;
+        let sampler = fw.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("GpuSampler::new"),
+            address_mode_u,
+            address_mode_v,
+            address_mode_w,
+            mag_filter: options.mag_filter,
+            min_filter: options.min_filter,
+            mipmap_filter: options.mipmap_filter,
+            lod_min_clamp: options.lod_min_clamp,
+            lod_max_clamp: options.lod_max_clamp,
+            anisotropy_clamp: options.anisotropy_clamp,
+            ..Default::default()
+        });
+
+        Ok(Self {
+            sampler,
+            options: *options,
+        })
+    }
+
+    /// Panicking version of `GpuSampler::try_new()`.
+    pub fn new(fw: &crate::Framework, options: &SamplerOptions) -> Self {
+        Self::try_new(fw, options).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns the [`SamplerOptions`] of the [`GpuSampler`].
+    pub fn options(&self) -> &SamplerOptions {
+        &self.options
+    }
+
+    /// Returns how the [`GpuSampler`] must be declared in a [`SetLayout`](crate::SetLayout).
+    /// See [`SamplerOptions::binding_type`].
+    pub fn binding_type(&self) -> wgpu::SamplerBindingType {
+        self.options.binding_type()
+    }
+
+    /// Returns a [`wgpu::BindingResource`] of the sampler.
+    pub fn as_binding_resource(&self) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::Sampler(&self.sampler)
+    }
+
+    /// Returns the [`wgpu::Sampler`] of the [`GpuSampler`].
+    pub fn as_gpu_sampler(&self) -> &wgpu::Sampler {
+        &self.sampler
+    }
+}
@@ -0,0 +1,361 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    framework::GpuResult,
+    timing::{self, Timestamps},
+    Framework,
+};
+
+/// Default of [`GpuProfiler::frames_in_flight`].
+const FRAMES_IN_FLIGHT: usize = 3;
+
+/// Profiler timing the scopes and kernel dispatches of the [`GpuRecorder`](crate::GpuRecorder)s
+/// and [`Sequence`](crate::Sequence)s it is handed to, with timestamp queries, and reporting them per frame.
+///
+/// Each dispatch recorded through a profiled recorder is a scope labelled by the entry point of its kernel.
+/// [`GpuRecorder::begin_scope`](crate::GpuRecorder::begin_scope) and [`GpuRecorder::end_scope`](crate::GpuRecorder::end_scope)
+/// open and close labelled scopes around them, which can nest.
+///
+/// The scopes submitted until [`GpuProfiler::end_frame`] make a frame. Its timestamps are read back
+/// without blocking: [`GpuProfiler::report`] returns the [`ProfilerReport`] of the last frame whose
+/// timestamps were read, once the device is polled by the polling thread, [`Framework::poll`] or
+/// [`Framework::blocking_poll`]. At most [`GpuProfiler::frames_in_flight`] frames wait for their
+/// timestamps, the older ones are discarded.
+///
+/// Cloning a [`GpuProfiler`] returns another handle to the same profiler.
+///
+/// # Example
+/// ```no_run
+/// # let fw = gpgpu::Framework::default();
+/// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+/// # let (blur, sharpen) = (gpgpu::Kernel::builder(&fw, &shader, "blur").build().unwrap(), gpgpu::Kernel::builder(&fw, &shader, "sharpen").build().unwrap());
+/// let profiler = gpgpu::GpuProfiler::new(&fw).unwrap();
+///
+/// loop {
+///     let mut recorder = fw.create_recorder().profile(&profiler).unwrap();
+///     recorder.begin_scope("filters");
+///     recorder.dispatch_kernel(&blur, 64, 1, 1);
+///     recorder.dispatch_kernel(&sharpen, 64, 1, 1);
+///     recorder.end_scope();
+///     recorder.submit();
+///
+///     profiler.end_frame();
+///     if let Some(report) = profiler.report() {
+///         for scope in &report.scopes {
+///             println!("{:indent$}{}: {:?}", "", scope.label, scope.duration, indent = 2 * scope.depth);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct GpuProfiler {
+    inner: Arc<Mutex<ProfilerState>>,
+}
+
+struct ProfilerState {
+    /// Nanoseconds per timestamp tick of the queue.
+    period: f32,
+    frames_in_flight: usize,
+    /// Submissions of the current frame.
+    submitted: Vec<ProfiledSubmission>,
+    /// Ended frames waiting for their timestamps, from the oldest.
+    frames: VecDeque<Vec<ProfiledSubmission>>,
+    report: Option<ProfilerReport>,
+}
+
+/// Timed scope of a [`ProfilerReport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfiledScope {
+    /// Label of the scope, the entry point of the kernel for dispatches.
+    pub label: String,
+    /// Number of scopes enclosing this one.
+    pub depth: usize,
+    /// Time elapsed on the GPU between the beginning and the end of the scope.
+    pub duration: Duration,
+}
+
+/// Timings of the scopes of a frame of a [`GpuProfiler`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProfilerReport {
+    /// Scopes of the frame, in the order they were opened: nested scopes follow the scope enclosing them.
+    pub scopes: Vec<ProfiledScope>,
+    /// Sum of the durations of the outermost scopes.
+    pub total: Duration,
+}
+
+impl ProfilerReport {
+    /// Returns the summed duration of the scopes of each label, in the order the labels first appear,
+    /// e.g. the time spent by each kernel dispatched several times in the frame.
+    pub fn totals(&self) -> Vec<(String, Duration)> {
+        let mut totals: Vec<(String, Duration)> = Vec::new();
+
+        for scope in &self.scopes {
+            match totals.iter_mut().find(|(label, _)| label == &scope.label) {
+                Some((_, total)) => *total += scope.duration,
+                None => totals.push((scope.label.clone(), scope.duration)),
+            }
+        }
+
+        totals
+    }
+}
+
+impl GpuProfiler {
+    /// Creates a [`GpuProfiler`] for the recorders of `fw`.
+    ///
+    /// Fails with [`GpuError::MissingFeature`](crate::GpuError::MissingFeature) if the device does not support
+    /// [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub fn new(fw: &Framework) -> GpuResult<Self> {
+        Timestamps::new(fw)?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(ProfilerState {
+                period: fw.queue.get_timestamp_period(),
+                frames_in_flight: FRAMES_IN_FLIGHT,
+                submitted: Vec::new(),
+                frames: VecDeque::new(),
+                report: None,
+            })),
+        })
+    }
+
+    /// Sets how many ended frames can wait for their timestamps, 3 by default.
+    ///
+    /// When a frame ends with more frames waiting, the oldest one is discarded: reports lag behind
+    /// by a few frames instead of stalling the GPU.
+    pub fn frames_in_flight(self, frames: usize) -> Self {
+        self.inner.lock().unwrap().frames_in_flight = std::cmp::max(frames, 1);
+        self
+    }
+
+    /// Ends the current frame, made of the profiled submissions since the previous call.
+    pub fn end_frame(&self) {
+        let mut state = self.inner.lock().unwrap();
+
+        let frame = std::mem::take(&mut state.submitted);
+        state.frames.push_back(frame);
+
+        while state.frames.len() > state.frames_in_flight {
+            state.frames.pop_front();
+        }
+
+        state.collect();
+    }
+
+    /// Returns the [`ProfilerReport`] of the last frame whose timestamps were read back, without blocking.
+    ///
+    /// `None` until the timestamps of a frame are read, once the device was polled after its submission.
+    pub fn report(&self) -> Option<ProfilerReport> {
+        let mut state = self.inner.lock().unwrap();
+        state.collect();
+
+        state.report.clone()
+    }
+
+    fn push(&self, submission: ProfiledSubmission) {
+        self.inner.lock().unwrap().submitted.push(submission);
+    }
+}
+
+impl ProfilerState {
+    /// Builds the reports of the ended frames whose timestamps were read, in order.
+    fn collect(&mut self) {
+        while let Some(frame) = self.frames.front() {
+            let mut failed = false;
+
+            for submission in frame {
+                match *submission.mapped.lock().unwrap() {
+                    None => return,
+                    Some(Ok(())) => (),
+                    Some(Err(_)) => failed = true,
+                }
+            }
+
+            // Frames whose timestamps cannot be read, e.g. once the device is lost, are discarded.
+            let frame = self.frames.pop_front().unwrap();
+            if failed {
+                continue;
+            }
+
+            let mut scopes = Vec::new();
+            for submission in frame {
+                submission.read(self.period, &mut scopes);
+            }
+
+            let total = scopes
+                .iter()
+                .filter(|scope| scope.depth == 0)
+                .map(|scope| scope.duration)
+                .sum();
+
+            self.report = Some(ProfilerReport { scopes, total });
+        }
+    }
+}
+
+/// Scope recorded by a profiled recorder, between the timestamps `start` and `end`.
+struct RecordedScope {
+    label: String,
+    depth: usize,
+    start: u32,
+    end: u32,
+}
+
+/// Submitted scopes of a profiled recorder, whose timestamps are read back from `staging`.
+struct ProfiledSubmission {
+    scopes: Vec<RecordedScope>,
+    staging: wgpu::Buffer,
+    /// Result of the mapping of `staging`, `None` until it is resolved.
+    mapped: Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>,
+}
+
+impl ProfiledSubmission {
+    /// Appends the scopes of the submission to `scopes`, with the mapped timestamps.
+    fn read(self, period: f32, scopes: &mut Vec<ProfiledScope>) {
+        {
+            let view = self.staging.slice(..).get_mapped_range();
+            let ticks: &[u64] = bytemuck::cast_slice(&view);
+
+            scopes.extend(self.scopes.into_iter().map(|scope| ProfiledScope {
+                label: scope.label,
+                depth: scope.depth,
+                duration: timing::elapsed(
+                    period,
+                    ticks[scope.start as usize],
+                    ticks[scope.end as usize],
+                ),
+            }));
+        }
+
+        self.staging.unmap();
+    }
+}
+
+/// Scopes and timestamps of a [`GpuRecorder`](crate::GpuRecorder) handed a [`GpuProfiler`].
+pub(crate) struct ProfiledCommands {
+    profiler: GpuProfiler,
+    timestamps: Timestamps,
+    scopes: Vec<RecordedScope>,
+    /// Indices of the open scopes in `scopes`, from the outermost.
+    open: Vec<usize>,
+}
+
+impl ProfiledCommands {
+    /// Fails with [`GpuError::MissingFeature`](crate::GpuError::MissingFeature) if the device of `fw`
+    /// does not support [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub(crate) fn new(fw: &Framework, profiler: &GpuProfiler) -> GpuResult<Self> {
+        Ok(Self {
+            profiler: profiler.clone(),
+            timestamps: Timestamps::new(fw)?,
+            scopes: Vec::new(),
+            open: Vec::new(),
+        })
+    }
+
+    /// Opens a scope labelled `label`, nested in the open scopes.
+    pub(crate) fn begin(
+        &mut self,
+        fw: &Framework,
+        encoder: &mut wgpu::CommandEncoder,
+        label: String,
+    ) {
+        let start = self.timestamps.write(fw, encoder);
+
+        self.open.push(self.scopes.len());
+        self.scopes.push(RecordedScope {
+            label,
+            depth: self.open.len() - 1,
+            start,
+            end: start,
+        });
+    }
+
+    /// Closes the innermost open scope.
+    ///
+    /// # Panics
+    /// If no scope is open.
+    pub(crate) fn end(&mut self, fw: &Framework, encoder: &mut wgpu::CommandEncoder) {
+        let scope = self
+            .open
+            .pop()
+            .expect("No profiler scope is open, call `GpuRecorder::begin_scope` first");
+
+        self.scopes[scope].end = self.timestamps.write(fw, encoder);
+    }
+
+    /// Removes the innermost open scope, e.g. the one of a failed dispatch. Its start timestamp is left unused.
+    pub(crate) fn cancel(&mut self) {
+        if let Some(scope) = self.open.pop() {
+            self.scopes.truncate(scope);
+        }
+    }
+
+    /// Records the resolution of the timestamps into a mappable buffer, returning the scopes
+    /// to hand to the profiler by [`PendingScopes::submitted`]. `None` without scopes.
+    ///
+    /// # Panics
+    /// If a scope is still open.
+    pub(crate) fn finish(
+        self,
+        fw: &Framework,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Option<(GpuProfiler, PendingScopes)> {
+        if !self.open.is_empty() {
+            panic!(
+                "{} profiler scopes are still open, close them with `GpuRecorder::end_scope`",
+                self.open.len()
+            )
+        }
+
+        if self.scopes.is_empty() {
+            return None;
+        }
+
+        let staging = fw.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuProfiler"),
+            size: self.timestamps.len() as u64 * 8,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.timestamps.resolve_into(encoder, &staging);
+
+        Some((
+            self.profiler,
+            PendingScopes {
+                scopes: self.scopes,
+                staging,
+            },
+        ))
+    }
+}
+
+/// Scopes resolved by [`ProfiledCommands::finish`], handed to the profiler once submitted.
+pub(crate) struct PendingScopes {
+    scopes: Vec<RecordedScope>,
+    staging: wgpu::Buffer,
+}
+
+impl PendingScopes {
+    /// Maps the timestamps of the scopes, resolved when the device is polled, and adds them
+    /// to the current frame of `profiler`. Must be called once their commands are submitted.
+    pub(crate) fn submitted(self, profiler: &GpuProfiler) {
+        let mapped = Arc::new(Mutex::new(None));
+        let callback_mapped = Arc::clone(&mapped);
+
+        self.staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                *callback_mapped.lock().unwrap() = Some(result);
+            });
+
+        profiler.push(ProfiledSubmission {
+            scopes: self.scopes,
+            staging: self.staging,
+            mapped,
+        });
+    }
+}
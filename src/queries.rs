@@ -0,0 +1,93 @@
+use crate::{
+    framework::{GpuError, GpuResult},
+    Framework, GpuBuffer,
+};
+
+/// Queries per [`wgpu::QuerySet`] of [`QuerySets`], whose results are resolved at offsets
+/// of `QUERY_SET_SIZE * 8` bytes, a multiple of [`wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT`].
+const QUERY_SET_SIZE: u32 = 64;
+
+/// Queries of a single type with a single result each, allocated in query sets of
+/// [`QUERY_SET_SIZE`] queries as they are used, so their number is not bounded by the query set limit.
+pub(crate) struct QuerySets {
+    ty: wgpu::QueryType,
+    sets: Vec<wgpu::QuerySet>,
+    count: u32,
+}
+
+impl QuerySets {
+    /// Fails with [`GpuError::MissingFeature`] if the device does not support `feature`, required by `ty`.
+    pub(crate) fn new(
+        fw: &Framework,
+        ty: wgpu::QueryType,
+        feature: wgpu::Features,
+    ) -> GpuResult<Self> {
+        if !fw.features().contains(feature) {
+            return Err(GpuError::MissingFeature(feature));
+        }
+
+        Ok(Self {
+            ty,
+            sets: Vec::new(),
+            count: 0,
+        })
+    }
+
+    /// Returns the number of queries used so far.
+    pub(crate) fn len(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns the query set of the next query and its index in the set, allocating the set if needed.
+    ///
+    /// The query is only counted by [`QuerySets::advance`], once it was written: resolving queries
+    /// that were never written is not allowed.
+    pub(crate) fn slot(&mut self, fw: &Framework) -> (&wgpu::QuerySet, u32) {
+        if self.count == self.sets.len() as u32 * QUERY_SET_SIZE {
+            self.sets
+                .push(fw.device.create_query_set(&wgpu::QuerySetDescriptor {
+                    label: Some("QuerySets"),
+                    ty: self.ty,
+                    count: QUERY_SET_SIZE,
+                }));
+        }
+
+        (&self.sets[self.sets.len() - 1], self.count % QUERY_SET_SIZE)
+    }
+
+    /// Counts the query of the last [`QuerySets::slot`], returning its index among all the queries.
+    pub(crate) fn advance(&mut self) -> u32 {
+        self.count += 1;
+        self.count - 1
+    }
+
+    /// Records the resolution of all the used queries into a buffer, read by [`read_results`]
+    /// once the commands of `encoder` are submitted.
+    pub(crate) fn resolve(
+        &self,
+        fw: &Framework,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> GpuResult<GpuBuffer<u64>> {
+        // Query sets are resolved into buffers with the `COPY_DST` usage of all the buffers.
+        let resolved = GpuBuffer::try_with_capacity(fw, std::cmp::max(self.count, 1) as u64)?;
+        self.resolve_into(encoder, &resolved.buf);
+
+        Ok(resolved)
+    }
+
+    /// Records the resolution of all the used queries into `buffer`, which must have the `COPY_DST`
+    /// usage and hold 8 bytes per query.
+    pub(crate) fn resolve_into(&self, encoder: &mut wgpu::CommandEncoder, buffer: &wgpu::Buffer) {
+        for (i, set) in self.sets.iter().enumerate() {
+            let first = i as u32 * QUERY_SET_SIZE;
+            let count = std::cmp::min(self.count - first, QUERY_SET_SIZE);
+
+            encoder.resolve_query_set(set, 0..count, buffer, first as u64 * 8);
+        }
+    }
+}
+
+/// Reads the results resolved by [`QuerySets::resolve`], blocking until they are available.
+pub(crate) fn read_results(resolved: &GpuBuffer<u64>) -> GpuResult<Vec<u64>> {
+    Ok(resolved.read_vec_blocking()?)
+}
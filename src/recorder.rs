@@ -0,0 +1,443 @@
+use std::sync::Arc;
+
+use crate::{
+    framework::{GpuError, GpuResult, Submission, WorkDone},
+    kernel::Workgroups,
+    primitives::{
+        buffers::{BufferError, BufferResult},
+        images::{ImageInputError, ImageInputResult},
+        PixelInfo,
+    },
+    profiler::ProfiledCommands,
+    queries::QuerySets,
+    timing::{self, Timestamps},
+    BufOps, DispatchIndirectArgs, Framework, GpuBuffer, GpuImage, GpuProfiler, Kernel,
+    KernelTiming, StatisticsQuery,
+};
+
+/// Manual recording of GPU commands into a [`wgpu::CommandEncoder`], created by [`Framework::create_recorder`].
+///
+/// Kernel dispatches, buffer and image copies and raw `wgpu` commands are recorded in order,
+/// each one seeing the writes of the previous ones, and executed at once by [`GpuRecorder::submit`].
+/// Dropping a [`GpuRecorder`] without submitting it discards its commands.
+///
+/// [`Kernel::run`] and [`Sequence`](crate::Sequence) record their commands with a [`GpuRecorder`].
+///
+/// # Example
+/// ```no_run
+/// # let fw = gpgpu::Framework::default();
+/// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+/// # use gpgpu::BufOps;
+/// # let (data, snapshot) = (gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 64), gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 64));
+/// let kernel = gpgpu::Kernel::builder(&fw, &shader, "step")
+///     .layouts(vec![gpgpu::new_set_layout!(0: Buffer)])
+///     .bind_set(0, gpgpu::SetBindings::default().add_buffer(0, &data))
+///     .build()
+///     .unwrap();
+///
+/// let mut recorder = fw.create_recorder();
+/// recorder.dispatch_kernel(&kernel, 64, 1, 1);
+/// recorder.copy_buffer(&data, &snapshot);
+/// recorder.raw(|encoder| encoder.insert_debug_marker("second step"));
+/// recorder.dispatch_kernel(&kernel, 64, 1, 1);
+/// recorder.submit();
+/// ```
+pub struct GpuRecorder<'fw> {
+    fw: &'fw Framework,
+    encoder: wgpu::CommandEncoder,
+    /// Timestamps of [`GpuRecorder::with_timing`], and the entry point and first timestamp of each dispatch.
+    timing: Option<(Timestamps, Vec<(String, u32)>)>,
+    /// Pipeline statistics queries of the dispatches since [`GpuRecorder::begin_statistics`], one per dispatch.
+    statistics: Option<QuerySets>,
+    /// Scopes of the profiler of [`GpuRecorder::profile`].
+    profile: Option<ProfiledCommands>,
+}
+
+impl<'fw> GpuRecorder<'fw> {
+    pub(crate) fn new(fw: &'fw Framework) -> Self {
+        let encoder = fw
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuRecorder"),
+            });
+
+        Self {
+            fw,
+            encoder,
+            timing: None,
+            statistics: None,
+            profile: None,
+        }
+    }
+
+    /// Measures the GPU execution time of each kernel dispatch recorded afterwards with timestamp queries,
+    /// returned by [`GpuRecorder::submit_timed`].
+    ///
+    /// Fails with [`GpuError::MissingFeature`] if the device does not support [`wgpu::Features::TIMESTAMP_QUERY`],
+    /// see [`FrameworkBuilder::optional_features`](crate::FrameworkBuilder::optional_features).
+    pub fn with_timing(mut self) -> GpuResult<Self> {
+        if self.timing.is_none() {
+            self.timing = Some((Timestamps::new(self.fw)?, Vec::new()));
+        }
+
+        Ok(self)
+    }
+
+    /// Records the dispatch of `kernel` with the bindings of [`Kernel::rebind_set`], with `x`, `y` and `z`
+    /// workgroups per dimension.
+    ///
+    /// # Panics
+    /// If a set of `kernel` was never bound, see [`GpuError::UnboundEntries`].
+    pub fn dispatch_kernel(&mut self, kernel: &Kernel, x: u32, y: u32, z: u32) {
+        self.try_dispatch_kernel(kernel, x, y, z)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`GpuRecorder::dispatch_kernel`].
+    ///
+    /// Returns [`GpuError::UnboundEntries`] if a set of `kernel` was never bound.
+    /// The `wgpu` validation errors are only reported by [`GpuRecorder::try_submit`].
+    pub fn try_dispatch_kernel(
+        &mut self,
+        kernel: &Kernel,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> GpuResult<()> {
+        self.encode_dispatch(kernel, Workgroups::Count(x, y, z))
+    }
+
+    /// Records the indirect dispatch of `kernel`, with the workgroups of the arguments at the index `index`
+    /// of `args`, as read by the GPU when the dispatch is executed. See [`Kernel::run_indirect`].
+    ///
+    /// # Panics
+    /// If the dispatch fails like [`GpuRecorder::try_dispatch_kernel_indirect`].
+    pub fn dispatch_kernel_indirect(
+        &mut self,
+        kernel: &Kernel,
+        args: &GpuBuffer<DispatchIndirectArgs>,
+        index: u64,
+    ) {
+        self.try_dispatch_kernel_indirect(kernel, args, index)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`GpuRecorder::dispatch_kernel_indirect`].
+    ///
+    /// Returns [`GpuError::UnboundEntries`] if a set of `kernel` was never bound, [`GpuError::MissingIndirectUsage`]
+    /// or [`GpuError::IndirectArgsOutOfRange`] if `args` cannot be read by an indirect dispatch.
+    pub fn try_dispatch_kernel_indirect(
+        &mut self,
+        kernel: &Kernel,
+        args: &GpuBuffer<DispatchIndirectArgs>,
+        index: u64,
+    ) -> GpuResult<()> {
+        let workgroups = Workgroups::indirect(args, index)?;
+
+        self.encode_dispatch(kernel, workgroups)
+    }
+
+    /// Records the dispatch of `kernel`, between two timestamps if the recorder is timed, in a scope
+    /// of its profiler if any, and measured by a pipeline statistics query during [`GpuRecorder::begin_statistics`].
+    fn encode_dispatch(&mut self, kernel: &Kernel, workgroups: Workgroups<'_>) -> GpuResult<()> {
+        let fw = self.fw;
+        let encoder = &mut self.encoder;
+
+        // The start timestamp of a failed dispatch is left unused.
+        let start = self
+            .timing
+            .as_mut()
+            .map(|(timestamps, _)| timestamps.write(fw, encoder));
+
+        if let Some(profile) = &mut self.profile {
+            profile.begin(fw, encoder, kernel.entry_point().to_owned());
+        }
+
+        let statistics = self.statistics.as_mut().map(|queries| queries.slot(fw));
+        if let Err(err) = kernel.encode_bound(encoder, workgroups, statistics) {
+            if let Some(profile) = &mut self.profile {
+                profile.cancel();
+            }
+
+            return Err(err);
+        }
+
+        if let Some(profile) = &mut self.profile {
+            profile.end(fw, encoder);
+        }
+
+        if let Some(queries) = &mut self.statistics {
+            queries.advance();
+        }
+
+        if let (Some(start), Some((timestamps, kernels))) = (start, &mut self.timing) {
+            timestamps.write(fw, encoder);
+            kernels.push((kernel.entry_point().to_owned(), start));
+        }
+
+        Ok(())
+    }
+
+    /// Times the scopes and kernel dispatches recorded afterwards with `profiler`, reported once its
+    /// frame ends. See [`GpuProfiler`].
+    ///
+    /// Fails with [`GpuError::MissingFeature`] if the device does not support [`wgpu::Features::TIMESTAMP_QUERY`].
+    ///
+    /// # Panics
+    /// If the recorder is already profiled.
+    pub fn profile(mut self, profiler: &GpuProfiler) -> GpuResult<Self> {
+        if self.profile.is_some() {
+            panic!("The recorder is already profiled")
+        }
+
+        self.profile = Some(ProfiledCommands::new(self.fw, profiler)?);
+
+        Ok(self)
+    }
+
+    /// Opens a scope labelled `label` in the profiler of [`GpuRecorder::profile`], enclosing the
+    /// commands recorded until [`GpuRecorder::end_scope`]. Scopes can nest.
+    ///
+    /// Does nothing if the recorder is not profiled, so scopes can stay in place when profiling is disabled.
+    pub fn begin_scope(&mut self, label: &str) {
+        if let Some(profile) = &mut self.profile {
+            profile.begin(self.fw, &mut self.encoder, label.to_owned());
+        }
+    }
+
+    /// Closes the innermost scope opened by [`GpuRecorder::begin_scope`].
+    ///
+    /// Does nothing if the recorder is not profiled.
+    ///
+    /// # Panics
+    /// If no scope is open. Submitting the recorder also panics if a scope is left open.
+    pub fn end_scope(&mut self) {
+        if let Some(profile) = &mut self.profile {
+            profile.end(self.fw, &mut self.encoder);
+        }
+    }
+
+    /// Starts measuring the pipeline statistics of the kernel dispatches recorded afterwards,
+    /// until [`GpuRecorder::end_statistics`].
+    ///
+    /// Fails with [`GpuError::MissingFeature`] if the device does not support
+    /// [`wgpu::Features::PIPELINE_STATISTICS_QUERY`].
+    ///
+    /// # Panics
+    /// If the statistics are already being measured.
+    pub fn begin_statistics(&mut self) -> GpuResult<()> {
+        if self.statistics.is_some() {
+            panic!("Pipeline statistics are already being measured, call `GpuRecorder::end_statistics` first")
+        }
+
+        self.statistics = Some(QuerySets::new(
+            self.fw,
+            wgpu::QueryType::PipelineStatistics(
+                wgpu::PipelineStatisticsTypes::COMPUTE_SHADER_INVOCATIONS,
+            ),
+            wgpu::Features::PIPELINE_STATISTICS_QUERY,
+        )?);
+
+        Ok(())
+    }
+
+    /// Stops measuring the pipeline statistics started by [`GpuRecorder::begin_statistics`],
+    /// returning the [`StatisticsQuery`] to read them once the recorder is submitted.
+    ///
+    /// # Panics
+    /// If the statistics are not being measured.
+    pub fn end_statistics(&mut self) -> StatisticsQuery {
+        let queries = self
+            .statistics
+            .take()
+            .expect("Pipeline statistics are not being measured, call `GpuRecorder::begin_statistics` first");
+
+        let resolved = queries
+            .resolve(self.fw, &mut self.encoder)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        StatisticsQuery::new(resolved, queries.len())
+    }
+
+    /// Records the copy of all the elements of `src` into the beginning of `dst`.
+    ///
+    /// Unlike [`GpuBuffer::copy_to`], the copy cannot go through host memory: its size must
+    /// be a multiple of 4 bytes, unless `src` and `dst` have the same size.
+    ///
+    /// # Panics
+    /// If the copy fails like [`GpuRecorder::try_copy_buffer`].
+    pub fn copy_buffer<T>(&mut self, src: &GpuBuffer<T>, dst: &GpuBuffer<T>)
+    where
+        T: bytemuck::Pod,
+    {
+        self.try_copy_buffer(src, dst)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`GpuRecorder::copy_buffer`].
+    ///
+    /// Fails with [`BufferError::DestinationTooSmall`] if `dst` cannot hold all the elements,
+    /// with [`BufferError::DifferentFramework`] if a buffer was not created by the [`Framework`] of the recorder,
+    /// with [`BufferError::OverlappingCopy`] if `src` and `dst` are the same buffer or with
+    /// [`BufferError::UnalignedCopy`] if the copy is not 4 bytes aligned.
+    pub fn try_copy_buffer<T>(&mut self, src: &GpuBuffer<T>, dst: &GpuBuffer<T>) -> BufferResult<()>
+    where
+        T: bytemuck::Pod,
+    {
+        let len = src.capacity();
+        let capacity = dst.capacity();
+
+        if len > capacity {
+            return Err(BufferError::DestinationTooSmall { len, capacity });
+        }
+
+        if !Arc::ptr_eq(&self.fw.device, &src.fw.device)
+            || !Arc::ptr_eq(&self.fw.device, &dst.fw.device)
+        {
+            return Err(BufferError::DifferentFramework);
+        }
+
+        if Arc::ptr_eq(&src.buf, &dst.buf) {
+            return Err(BufferError::OverlappingCopy);
+        }
+
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+
+        // The padding after the end of both buffers can be overwritten.
+        if !src.size.is_multiple_of(align) && src.size != dst.size {
+            return Err(BufferError::UnalignedCopy(src.size));
+        }
+
+        let size = wgpu::util::align_to(src.size, align);
+
+        if size != 0 {
+            self.encoder
+                .copy_buffer_to_buffer(&src.buf, 0, &dst.buf, 0, size);
+        }
+
+        Ok(())
+    }
+
+    /// Records the copy of all the pixels of `src` into the top-left corner of `dst`, like [`GpuImage::copy_to`].
+    ///
+    /// # Panics
+    /// If the copy fails like [`GpuRecorder::try_copy_image`].
+    pub fn copy_image<P>(&mut self, src: &GpuImage<P>, dst: &GpuImage<P>)
+    where
+        P: PixelInfo,
+    {
+        self.try_copy_image(src, dst)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Fallible version of [`GpuRecorder::copy_image`].
+    ///
+    /// Only the mip level 0 is copied. Fails with [`ImageInputError::DestinationTooSmall`] if `dst` cannot hold
+    /// all the pixels, or with [`ImageInputError::DifferentFramework`] if an image was not created by the
+    /// [`Framework`] of the recorder.
+    pub fn try_copy_image<P>(
+        &mut self,
+        src: &GpuImage<P>,
+        dst: &GpuImage<P>,
+    ) -> ImageInputResult<()>
+    where
+        P: PixelInfo,
+    {
+        src.check_copy_to(dst)?;
+
+        if !Arc::ptr_eq(&self.fw.device, &src.fw.device) {
+            return Err(ImageInputError::DifferentFramework);
+        }
+
+        // An image copied into itself is left unchanged.
+        if !std::ptr::eq(src, dst) {
+            src.encode_copy(&mut self.encoder, dst, 1);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` with the [`wgpu::CommandEncoder`] of the recorder, to record any `wgpu` command
+    /// between the ones of the recorder.
+    pub fn raw<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut wgpu::CommandEncoder) -> R,
+    {
+        f(&mut self.encoder)
+    }
+
+    /// Submits all the recorded commands at once, returning their [`Submission`].
+    pub fn submit(mut self) -> Submission {
+        let fw = self.fw;
+        let pending = self
+            .profile
+            .take()
+            .and_then(|profile| profile.finish(fw, &mut self.encoder));
+
+        let submission = fw.submit(self.encoder.finish());
+
+        if let Some((profiler, scopes)) = pending {
+            scopes.submitted(&profiler);
+        }
+
+        submission
+    }
+
+    /// Submits all the recorded commands at once, returning a [`WorkDone`] future resolved once
+    /// the GPU has completed them. See [`Framework::wait_async`].
+    pub fn submit_async(self) -> WorkDone {
+        let fw = self.fw;
+        let submission = self.submit();
+
+        fw.wait_async(&submission)
+    }
+
+    /// Submits all the recorded commands at once, blocking until the GPU has executed them,
+    /// and returns the GPU execution time of each kernel dispatch, in the order they were recorded.
+    ///
+    /// Returns no timings if the recorder was not created [`GpuRecorder::with_timing`].
+    /// Fails with [`GpuError::Buffer`] if the timestamps cannot be read back.
+    pub fn submit_timed(mut self) -> GpuResult<Vec<KernelTiming>> {
+        let fw = self.fw;
+        let (timestamps, kernels) = match self.timing.take() {
+            Some(timing) => timing,
+            None => {
+                self.submit();
+                return Ok(Vec::new());
+            }
+        };
+
+        let resolved = timestamps.resolve(fw, &mut self.encoder)?;
+        self.submit();
+
+        let ticks = timing::read_ticks(&resolved)?;
+        let period = fw.queue.get_timestamp_period();
+
+        Ok(kernels
+            .into_iter()
+            .map(|(kernel, start)| KernelTiming {
+                kernel,
+                gpu_duration: timing::elapsed(
+                    period,
+                    ticks[start as usize],
+                    ticks[start as usize + 1],
+                ),
+            })
+            .collect())
+    }
+
+    /// Fallible version of [`GpuRecorder::submit`].
+    ///
+    /// Returns the `wgpu` validation error of any recorded command instead of panicking,
+    /// in which case nothing is executed, or [`GpuError::DeviceLost`] if the device was lost.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_submit(self) -> GpuResult<Submission> {
+        if self.fw.is_device_lost() {
+            return Err(GpuError::DeviceLost);
+        }
+
+        let fw = self.fw;
+
+        fw.error_scope(|| self.submit())
+    }
+}
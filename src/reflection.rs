@@ -0,0 +1,747 @@
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::{
+    entry_type::EntryType,
+    framework::{GpuError, GpuResult},
+    Framework, Kernel, SetLayout, Shader,
+};
+
+pub type ReflectionResult<T> = Result<T, ReflectionError>;
+
+#[derive(Error, Debug)]
+pub enum ReflectionError {
+    #[error("the shader cannot be reflected, its source failed to parse.")]
+    MissingModule,
+    #[error("the shader has no compute entry point named `{0}`.")]
+    MissingEntryPoint(String),
+    #[error("the shader failed to validate: {0}")]
+    Invalid(String),
+    #[error("binding {binding} of set {set} (`{name}`) cannot be reflected: {reason}.")]
+    UnsupportedBinding {
+        set: u32,
+        binding: u32,
+        name: String,
+        reason: &'static str,
+    },
+    #[error(transparent)]
+    Gpu(#[from] GpuError),
+}
+
+impl SetLayout {
+    /// Builds the [`SetLayout`]s of the `entry_point` of `shader` from its `@group` and `@binding`
+    /// declarations, one per group up to the last one used by the entry point. Bindings not used by
+    /// the entry point are left out, as well as the groups without any, which must then be bound
+    /// with an empty [`SetBindings`](crate::SetBindings).
+    ///
+    /// The entries are named after the shader variables, which name the bindings in the
+    /// errors of [`Kernel::run`] and [`Kernel::run_checked`].
+    ///
+    /// Storage images keep the format and access declared by the shader. Sampled images read
+    /// with a sampler are declared as filterable, and their samplers as filtering (or comparison)
+    /// samplers, so images of non-filterable formats like
+    /// [`Rgba32Float`](crate::primitives::pixels::Rgba32Float) can only be read with `textureLoad`.
+    /// Use [`SetLayout::add_const_image`] and [`SetLayout::add_sampler`] otherwise.
+    pub fn from_shader(shader: &Shader, entry_point: &str) -> ReflectionResult<Vec<SetLayout>> {
+        let module = shader.naga.as_ref().ok_or(ReflectionError::MissingModule)?;
+
+        Self::from_module(module, entry_point)
+    }
+
+    /// Builds the [`SetLayout`]s of the `entry_point` of `module`, see [`SetLayout::from_shader`].
+    fn from_module(module: &naga::Module, entry_point: &str) -> ReflectionResult<Vec<SetLayout>> {
+        let (index, info) = entry_point_info(module, entry_point)?;
+        let info = info.get_entry_point(index);
+
+        let sampled = info
+            .sampling_set
+            .iter()
+            .map(|key| key.image)
+            .collect::<HashSet<_>>();
+
+        let mut layouts = Vec::<SetLayout>::new();
+
+        for (handle, var) in module.global_variables.iter() {
+            let binding = match &var.binding {
+                Some(binding) if !info[handle].is_empty() => binding,
+                _ => continue,
+            };
+
+            let unsupported = |reason| ReflectionError::UnsupportedBinding {
+                set: binding.group,
+                binding: binding.binding,
+                name: var.name.clone().unwrap_or_default(),
+                reason,
+            };
+
+            let (ty, entry_type) = match (var.space, &module.types[var.ty].inner) {
+                (naga::AddressSpace::Storage { access }, _) => (
+                    wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage {
+                            read_only: !access.contains(naga::StorageAccess::STORE),
+                        },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    EntryType::Buffer,
+                ),
+                (naga::AddressSpace::Uniform, _) => (
+                    wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    EntryType::Uniform,
+                ),
+                (naga::AddressSpace::Handle, naga::TypeInner::Sampler { comparison }) => {
+                    let binding_type = if *comparison {
+                        wgpu::SamplerBindingType::Comparison
+                    } else {
+                        wgpu::SamplerBindingType::Filtering
+                    };
+
+                    (
+                        wgpu::BindingType::Sampler(binding_type),
+                        EntryType::Sampler(binding_type),
+                    )
+                }
+                (
+                    naga::AddressSpace::Handle,
+                    naga::TypeInner::Image {
+                        dim,
+                        arrayed,
+                        class,
+                    },
+                ) => {
+                    let storage = matches!(class, naga::ImageClass::Storage { .. });
+                    let (view_dimension, entry_type) = match (dim, arrayed, storage) {
+                        (naga::ImageDimension::D1, false, false) => {
+                            (wgpu::TextureViewDimension::D1, EntryType::ConstImage1d)
+                        }
+                        (naga::ImageDimension::D1, false, true) => {
+                            (wgpu::TextureViewDimension::D1, EntryType::Image1d)
+                        }
+                        (naga::ImageDimension::D2, false, false) => {
+                            (wgpu::TextureViewDimension::D2, EntryType::ConstImage)
+                        }
+                        (naga::ImageDimension::D2, false, true) => {
+                            (wgpu::TextureViewDimension::D2, EntryType::Image)
+                        }
+                        (naga::ImageDimension::D2, true, false) => (
+                            wgpu::TextureViewDimension::D2Array,
+                            EntryType::ConstImageArray,
+                        ),
+                        (naga::ImageDimension::D2, true, true) => {
+                            (wgpu::TextureViewDimension::D2Array, EntryType::ImageArray)
+                        }
+                        (naga::ImageDimension::D3, false, false) => {
+                            (wgpu::TextureViewDimension::D3, EntryType::ConstImage3d)
+                        }
+                        (naga::ImageDimension::D3, false, true) => {
+                            (wgpu::TextureViewDimension::D3, EntryType::Image3d)
+                        }
+                        _ => {
+                            return Err(unsupported(
+                                "only 1D, 2D, 3D and 2D array images are supported",
+                            ))
+                        }
+                    };
+
+                    let ty = match *class {
+                        naga::ImageClass::Sampled { kind, multi: false } => {
+                            let sample_type = match kind {
+                                naga::ScalarKind::Float => wgpu::TextureSampleType::Float {
+                                    filterable: sampled.contains(&handle),
+                                },
+                                naga::ScalarKind::Sint => wgpu::TextureSampleType::Sint,
+                                naga::ScalarKind::Uint => wgpu::TextureSampleType::Uint,
+                                naga::ScalarKind::Bool => {
+                                    return Err(unsupported("images cannot hold booleans"))
+                                }
+                            };
+
+                            wgpu::BindingType::Texture {
+                                sample_type,
+                                view_dimension,
+                                multisampled: false,
+                            }
+                        }
+                        naga::ImageClass::Storage { format, access } => {
+                            let access = if !access.contains(naga::StorageAccess::STORE) {
+                                wgpu::StorageTextureAccess::ReadOnly
+                            } else if !access.contains(naga::StorageAccess::LOAD) {
+                                wgpu::StorageTextureAccess::WriteOnly
+                            } else {
+                                wgpu::StorageTextureAccess::ReadWrite
+                            };
+
+                            wgpu::BindingType::StorageTexture {
+                                access,
+                                format: storage_format(format),
+                                view_dimension,
+                            }
+                        }
+                        _ => {
+                            return Err(unsupported(
+                                "multisampled and depth images are not supported",
+                            ))
+                        }
+                    };
+
+                    (ty, entry_type)
+                }
+                _ => return Err(unsupported("its type is not a resource supported by gpgpu")),
+            };
+
+            let set = binding.group as usize;
+            if layouts.len() <= set {
+                layouts.resize_with(set + 1, SetLayout::default);
+            }
+
+            let layout = &mut layouts[set];
+            layout.layout_entry.push(wgpu::BindGroupLayoutEntry {
+                binding: binding.binding,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty,
+                count: None,
+            });
+            layout.entry_type.push(entry_type);
+
+            if let Some(name) = &var.name {
+                layout.names.insert(binding.binding, name.clone());
+            }
+        }
+
+        for layout in &mut layouts {
+            let mut entries = layout
+                .layout_entry
+                .drain(..)
+                .zip(layout.entry_type.drain(..))
+                .collect::<Vec<_>>();
+            entries.sort_by_key(|(entry, _)| entry.binding);
+
+            for (entry, entry_type) in entries {
+                layout.layout_entry.push(entry);
+                layout.entry_type.push(entry_type);
+            }
+        }
+
+        Ok(layouts)
+    }
+}
+
+/// Validates `module`, returning the index of its compute `entry_point` with the validation info.
+fn entry_point_info(
+    module: &naga::Module,
+    entry_point: &str,
+) -> ReflectionResult<(usize, naga::valid::ModuleInfo)> {
+    let index = module
+        .entry_points
+        .iter()
+        .position(|entry| entry.name == entry_point && entry.stage == naga::ShaderStage::Compute)
+        .ok_or_else(|| ReflectionError::MissingEntryPoint(entry_point.to_owned()))?;
+
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(module)
+    .map_err(|err| ReflectionError::Invalid(err.into_inner().to_string()))?;
+
+    Ok((index, info))
+}
+
+/// Returns the `@workgroup_size` of the compute `entry_point` of `module`.
+pub(crate) fn workgroup_size(module: &naga::Module, entry_point: &str) -> Option<[u32; 3]> {
+    module
+        .entry_points
+        .iter()
+        .find(|entry| entry.name == entry_point && entry.stage == naga::ShaderStage::Compute)
+        .map(|entry| entry.workgroup_size)
+}
+
+/// Checks `layouts` against the bindings the `entry_point` of `shader` uses, which `wgpu` reports
+/// with a less helpful message: the entries must exist and match the declared resources, and must
+/// not be missing from the shader. Shaders that cannot be reflected are left to the `wgpu` validation.
+pub(crate) fn check_layouts(
+    shader: &Shader,
+    entry_point: &str,
+    layouts: &[SetLayout],
+) -> GpuResult<()> {
+    match &shader.naga {
+        Some(module) => check_module_layouts(module, entry_point, layouts),
+        None => Ok(()),
+    }
+}
+
+/// Checks `layouts` against the bindings the `entry_point` of `module` uses, see [`check_layouts`].
+fn check_module_layouts(
+    module: &naga::Module,
+    entry_point: &str,
+    layouts: &[SetLayout],
+) -> GpuResult<()> {
+    let reflected = match SetLayout::from_module(module, entry_point) {
+        Ok(reflected) => reflected,
+        Err(_) => return Ok(()),
+    };
+
+    let declared = |set: usize, binding: u32| {
+        module.global_variables.iter().find_map(|(_, var)| {
+            var.binding
+                .as_ref()
+                .filter(|bind| bind.group as usize == set && bind.binding == binding)
+                .map(|_| var.name.as_deref().unwrap_or_default())
+        })
+    };
+
+    for set in 0..reflected.len().max(layouts.len()) {
+        let expected = reflected.get(set);
+        let actual = layouts.get(set);
+
+        let mut bindings = expected
+            .into_iter()
+            .chain(actual)
+            .flat_map(|layout| layout.layout_entry.iter().map(|entry| entry.binding))
+            .collect::<Vec<_>>();
+        bindings.sort_unstable();
+        bindings.dedup();
+
+        for binding in bindings {
+            let name = match declared(set, binding) {
+                Some(name) if !name.is_empty() => format!("`{}`", name),
+                _ => "the entry".to_owned(),
+            };
+
+            let reason = match (
+                expected.and_then(|layout| layout.entry(binding)),
+                actual.and_then(|layout| layout.entry(binding)),
+            ) {
+                (Some(_), None) => format!(
+                    "{} is used by the shader, but not declared by the layout",
+                    name
+                ),
+                (None, Some(_)) if declared(set, binding).is_none() => {
+                    "the layout declares an entry, but the shader declares no binding there"
+                        .to_owned()
+                }
+                (Some(expected), Some(actual)) => match entry_mismatch(expected, actual) {
+                    Some(reason) => format!("{} {}", name, reason),
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            return Err(GpuError::LayoutMismatch {
+                set: set as u32,
+                binding,
+                reason,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes how the layout entry `actual` differs from the `expected` one reflected from the shader.
+fn entry_mismatch(
+    (expected, expected_type): (&wgpu::BindGroupLayoutEntry, EntryType),
+    (actual, actual_type): (&wgpu::BindGroupLayoutEntry, EntryType),
+) -> Option<String> {
+    use wgpu::BindingType as Bt;
+
+    let dynamic = matches!(
+        (expected_type, actual_type),
+        (EntryType::Buffer, EntryType::DynamicBuffer)
+            | (EntryType::Uniform, EntryType::DynamicUniform)
+    );
+
+    if !dynamic && !expected_type.accepts(actual_type) {
+        return Some(format!(
+            "is declared as a {:?} by the shader, but as a {:?} by the layout",
+            expected_type, actual_type
+        ));
+    }
+
+    let buffer_access = |read_only| if read_only { "`read`" } else { "`read_write`" };
+
+    match (expected.ty, actual.ty) {
+        (
+            Bt::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                ..
+            },
+            Bt::Buffer {
+                ty:
+                    wgpu::BufferBindingType::Storage {
+                        read_only: layout_read_only,
+                    },
+                ..
+            },
+        ) if read_only != layout_read_only => Some(format!(
+            "is declared {} by the shader, but {} by the layout{}",
+            buffer_access(read_only),
+            buffer_access(layout_read_only),
+            if read_only {
+                " (use `GpuBufferUsage::ReadOnly`)"
+            } else {
+                ""
+            }
+        )),
+        (
+            Bt::StorageTexture { format, access, .. },
+            Bt::StorageTexture {
+                format: layout_format,
+                access: layout_access,
+                ..
+            },
+        ) if format != layout_format || access != layout_access => Some(format!(
+            "is declared as a {:?} {:?} storage image by the shader, but as a {:?} {:?} one by the layout",
+            access, format, layout_access, layout_format
+        )),
+        _ => None,
+    }
+}
+
+impl Kernel {
+    /// Creates a [`Kernel`] running the `function_name` entry point of `shader`, with
+    /// the [`SetLayout`]s reflected from the shader by [`SetLayout::from_shader`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_reflection(
+        fw: &Framework,
+        shader: &Shader,
+        function_name: &str,
+    ) -> ReflectionResult<Self> {
+        let layouts = SetLayout::from_shader(shader, function_name)?;
+
+        Ok(Self::try_new(fw, shader, function_name, layouts)?)
+    }
+}
+
+/// Texture format of a storage image declared with the `naga` `format`.
+fn storage_format(format: naga::StorageFormat) -> wgpu::TextureFormat {
+    use naga::StorageFormat as Sf;
+    use wgpu::TextureFormat as Tf;
+
+    match format {
+        Sf::R8Unorm => Tf::R8Unorm,
+        Sf::R8Snorm => Tf::R8Snorm,
+        Sf::R8Uint => Tf::R8Uint,
+        Sf::R8Sint => Tf::R8Sint,
+        Sf::R16Uint => Tf::R16Uint,
+        Sf::R16Sint => Tf::R16Sint,
+        Sf::R16Float => Tf::R16Float,
+        Sf::Rg8Unorm => Tf::Rg8Unorm,
+        Sf::Rg8Snorm => Tf::Rg8Snorm,
+        Sf::Rg8Uint => Tf::Rg8Uint,
+        Sf::Rg8Sint => Tf::Rg8Sint,
+        Sf::R32Uint => Tf::R32Uint,
+        Sf::R32Sint => Tf::R32Sint,
+        Sf::R32Float => Tf::R32Float,
+        Sf::Rg16Uint => Tf::Rg16Uint,
+        Sf::Rg16Sint => Tf::Rg16Sint,
+        Sf::Rg16Float => Tf::Rg16Float,
+        Sf::Rgba8Unorm => Tf::Rgba8Unorm,
+        Sf::Rgba8Snorm => Tf::Rgba8Snorm,
+        Sf::Rgba8Uint => Tf::Rgba8Uint,
+        Sf::Rgba8Sint => Tf::Rgba8Sint,
+        Sf::Rgb10a2Unorm => Tf::Rgb10a2Unorm,
+        Sf::Rg11b10Float => Tf::Rg11b10Float,
+        Sf::Rg32Uint => Tf::Rg32Uint,
+        Sf::Rg32Sint => Tf::Rg32Sint,
+        Sf::Rg32Float => Tf::Rg32Float,
+        Sf::Rgba16Uint => Tf::Rgba16Uint,
+        Sf::Rgba16Sint => Tf::Rgba16Sint,
+        Sf::Rgba16Float => Tf::Rgba16Float,
+        Sf::Rgba32Uint => Tf::Rgba32Uint,
+        Sf::Rgba32Sint => Tf::Rgba32Sint,
+        Sf::Rgba32Float => Tf::Rgba32Float,
+        Sf::R16Unorm => Tf::R16Unorm,
+        Sf::R16Snorm => Tf::R16Snorm,
+        Sf::Rg16Unorm => Tf::Rg16Unorm,
+        Sf::Rg16Snorm => Tf::Rg16Snorm,
+        Sf::Rgba16Unorm => Tf::Rgba16Unorm,
+        Sf::Rgba16Snorm => Tf::Rgba16Snorm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{primitives::pixels, GpuBufferUsage, StorageAccess};
+
+    /// Uses every kind of entry, over three sets.
+    const SHADER: &str = "
+        @group(0) @binding(0) var<storage, read> input: array<f32>;
+        @group(0) @binding(1) var<storage, read_write> output: array<f32>;
+        @group(0) @binding(2) var<uniform> scale: vec4<f32>;
+
+        @group(1) @binding(0) var sampled: texture_2d<f32>;
+        @group(1) @binding(1) var loaded: texture_2d<f32>;
+        @group(1) @binding(2) var linear: sampler;
+        @group(1) @binding(3) var shadow: sampler_comparison;
+
+        @group(2) @binding(0) var source: texture_storage_2d<r32float, read>;
+        @group(2) @binding(1) var written: texture_storage_2d<rgba8uint, write>;
+
+        fn compare(s: sampler_comparison) {}
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let uv = vec2<f32>(0.5);
+            let color = textureSampleLevel(sampled, linear, uv, 0.0) + textureLoad(loaded, vec2<i32>(id.xy), 0);
+            compare(shadow);
+            let value = textureLoad(source, vec2<i32>(id.xy)).x;
+            textureStore(written, vec2<i32>(id.xy), vec4<u32>(u32(value)));
+            output[id.x] = input[id.x] * scale.x + color.x;
+        }";
+
+    fn module(source: &str) -> naga::Module {
+        naga::front::wgsl::parse_str(source).unwrap()
+    }
+
+    fn reflect(source: &str) -> Vec<SetLayout> {
+        SetLayout::from_module(&module(source), "main").unwrap()
+    }
+
+    fn ty(layout: &SetLayout, binding: u32) -> wgpu::BindingType {
+        layout.entry(binding).unwrap().0.ty
+    }
+
+    /// Checks `layouts` against [`SHADER`], returning the binding and reason of the mismatch.
+    fn mismatch(layouts: &[SetLayout]) -> Option<(u32, u32, String)> {
+        match check_module_layouts(&module(SHADER), "main", layouts) {
+            Ok(()) => None,
+            Err(GpuError::LayoutMismatch {
+                set,
+                binding,
+                reason,
+            }) => Some((set, binding, reason)),
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+
+    /// Layouts declared by hand to match [`SHADER`].
+    fn declared() -> Vec<SetLayout> {
+        let mut buffers = SetLayout::default();
+        buffers.add_buffer(0, GpuBufferUsage::ReadOnly);
+        buffers.add_buffer(1, GpuBufferUsage::ReadWrite);
+        buffers.add_uniform_buffer(2);
+
+        let mut sampled = SetLayout::default();
+        sampled.add_const_image::<pixels::Rgba8UintNorm>(0);
+        sampled.add_const_image::<pixels::Rgba32Float>(1);
+        sampled.add_sampler(2, wgpu::SamplerBindingType::Filtering);
+        sampled.add_sampler(3, wgpu::SamplerBindingType::Comparison);
+
+        let mut storage = SetLayout::default();
+        storage.add_image_with_access::<pixels::R32Float>(0, StorageAccess::ReadOnly);
+        storage.add_image::<pixels::Rgba8Uint>(1);
+
+        vec![buffers, sampled, storage]
+    }
+
+    #[test]
+    fn reflects_entry_kinds() {
+        let layouts = reflect(SHADER);
+        assert_eq!(layouts.len(), 3);
+
+        let kinds = |layout: &SetLayout| layout.entry_type.clone();
+        assert_eq!(
+            kinds(&layouts[0]),
+            [EntryType::Buffer, EntryType::Buffer, EntryType::Uniform]
+        );
+        assert_eq!(
+            kinds(&layouts[1]),
+            [
+                EntryType::ConstImage,
+                EntryType::ConstImage,
+                EntryType::Sampler(wgpu::SamplerBindingType::Filtering),
+                EntryType::Sampler(wgpu::SamplerBindingType::Comparison),
+            ]
+        );
+        assert_eq!(kinds(&layouts[2]), [EntryType::Image, EntryType::Image]);
+
+        assert_eq!(layouts[0].names[&0], "input");
+        assert_eq!(layouts[2].names[&1], "written");
+    }
+
+    #[test]
+    fn reflects_buffer_access() {
+        let layouts = reflect(SHADER);
+
+        let read_only = |binding| match ty(&layouts[0], binding) {
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                ..
+            } => read_only,
+            ty => panic!("unexpected binding type {:?}", ty),
+        };
+        assert!(read_only(0));
+        assert!(!read_only(1));
+    }
+
+    #[test]
+    fn sampled_images_are_filterable() {
+        let layouts = reflect(SHADER);
+
+        let filterable = |binding| match ty(&layouts[1], binding) {
+            wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            } => filterable,
+            ty => panic!("unexpected binding type {:?}", ty),
+        };
+        assert!(filterable(0));
+        assert!(!filterable(1));
+    }
+
+    #[test]
+    fn reflects_storage_images() {
+        let layouts = reflect(SHADER);
+
+        let storage = |binding| match ty(&layouts[2], binding) {
+            wgpu::BindingType::StorageTexture { access, format, .. } => (access, format),
+            ty => panic!("unexpected binding type {:?}", ty),
+        };
+        assert_eq!(
+            storage(0),
+            (
+                wgpu::StorageTextureAccess::ReadOnly,
+                wgpu::TextureFormat::R32Float
+            )
+        );
+        assert_eq!(
+            storage(1),
+            (
+                wgpu::StorageTextureAccess::WriteOnly,
+                wgpu::TextureFormat::Rgba8Uint
+            )
+        );
+    }
+
+    #[test]
+    fn unused_bindings_are_left_out() {
+        let layouts = reflect(
+            "
+            @group(0) @binding(0) var<storage, read_write> unused: array<u32>;
+            @group(1) @binding(3) var<storage, read_write> used: array<u32>;
+
+            @compute @workgroup_size(1)
+            fn main() {
+                used[0] = 1u;
+            }",
+        );
+
+        assert_eq!(layouts.len(), 2);
+        assert!(layouts[0].layout_entry.is_empty());
+        assert_eq!(layouts[1].entry_type, [EntryType::Buffer]);
+        assert!(layouts[1].entry(3).is_some());
+    }
+
+    #[test]
+    fn missing_entry_point() {
+        let result = SetLayout::from_module(&module(SHADER), "other");
+
+        assert!(matches!(result, Err(ReflectionError::MissingEntryPoint(name)) if name == "other"));
+    }
+
+    #[test]
+    fn matching_layouts() {
+        assert_eq!(mismatch(&declared()), None);
+        assert_eq!(mismatch(&reflect(SHADER)), None);
+    }
+
+    #[test]
+    fn mismatch_missing_entry() {
+        let mut layouts = declared();
+        layouts[1] = SetLayout::default();
+        layouts[1].add_const_image::<pixels::Rgba8UintNorm>(0);
+        layouts[1].add_const_image::<pixels::Rgba32Float>(1);
+        layouts[1].add_sampler(2, wgpu::SamplerBindingType::Filtering);
+
+        let (set, binding, reason) = mismatch(&layouts).unwrap();
+        assert_eq!((set, binding), (1, 3));
+        assert_eq!(
+            reason,
+            "`shadow` is used by the shader, but not declared by the layout"
+        );
+    }
+
+    #[test]
+    fn mismatch_extra_entry() {
+        let mut layouts = declared();
+        layouts[0].add_uniform_buffer(5);
+
+        let (set, binding, reason) = mismatch(&layouts).unwrap();
+        assert_eq!((set, binding), (0, 5));
+        assert_eq!(
+            reason,
+            "the layout declares an entry, but the shader declares no binding there"
+        );
+    }
+
+    #[test]
+    fn mismatch_wrong_kind() {
+        let mut layouts = declared();
+        layouts[0] = SetLayout::default();
+        layouts[0].add_buffer(0, GpuBufferUsage::ReadOnly);
+        layouts[0].add_buffer(1, GpuBufferUsage::ReadWrite);
+        layouts[0].add_buffer(2, GpuBufferUsage::ReadOnly);
+
+        let (set, binding, reason) = mismatch(&layouts).unwrap();
+        assert_eq!((set, binding), (0, 2));
+        assert_eq!(
+            reason,
+            "`scale` is declared as a Uniform by the shader, but as a Buffer by the layout"
+        );
+    }
+
+    #[test]
+    fn mismatch_buffer_access() {
+        let mut layouts = declared();
+        layouts[0] = SetLayout::default();
+        layouts[0].add_buffer(0, GpuBufferUsage::ReadWrite);
+        layouts[0].add_buffer(1, GpuBufferUsage::ReadWrite);
+        layouts[0].add_uniform_buffer(2);
+
+        let (set, binding, reason) = mismatch(&layouts).unwrap();
+        assert_eq!((set, binding), (0, 0));
+        assert_eq!(
+            reason,
+            "`input` is declared `read` by the shader, but `read_write` by the layout (use `GpuBufferUsage::ReadOnly`)"
+        );
+    }
+
+    #[test]
+    fn mismatch_storage_image_access() {
+        let mut layouts = declared();
+        layouts[2] = SetLayout::default();
+        layouts[2].add_image::<pixels::R32Float>(0);
+        layouts[2].add_image::<pixels::Rgba8Uint>(1);
+
+        let (set, binding, reason) = mismatch(&layouts).unwrap();
+        assert_eq!((set, binding), (2, 0));
+        assert_eq!(
+            reason,
+            "`source` is declared as a ReadOnly R32Float storage image by the shader, but as a WriteOnly R32Float one by the layout"
+        );
+    }
+
+    #[test]
+    fn mismatch_storage_image_format() {
+        let mut layouts = declared();
+        layouts[2] = SetLayout::default();
+        layouts[2].add_image_with_access::<pixels::R32Float>(0, StorageAccess::ReadOnly);
+        layouts[2].add_image::<pixels::Rgba8Sint>(1);
+
+        let (set, binding, reason) = mismatch(&layouts).unwrap();
+        assert_eq!((set, binding), (2, 1));
+        assert_eq!(
+            reason,
+            "`written` is declared as a WriteOnly Rgba8Uint storage image by the shader, but as a WriteOnly Rgba8Sint one by the layout"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    framework::{GpuResult, Submission},
+    primitives::buffers::BufferResult,
+    DispatchIndirectArgs, Framework, GpuBuffer, GpuProfiler, GpuRecorder, Kernel,
+};
+
+/// Chain of kernel dispatches and buffer copies recorded into a single [`wgpu::CommandEncoder`],
+/// created by [`Framework::sequence`]. It is a chainable [`GpuRecorder`].
+///
+/// Nothing is executed until [`Sequence::submit`], which submits all the commands at once:
+/// for pipelines of many small kernels, it avoids the cost of a submission per
+/// [`Kernel::run_bound`]. The commands are executed in order, each one seeing the writes of the previous ones.
+///
+/// The kernels run with the bindings of [`Kernel::rebind_set`], [`KernelBuilder::bind_set`](crate::KernelBuilder::bind_set)
+/// and [`KernelBuilder::shared_set`](crate::KernelBuilder::shared_set), as with [`Kernel::run_bound`].
+///
+/// # Example
+/// ```no_run
+/// # let fw = gpgpu::Framework::default();
+/// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+/// # use gpgpu::BufOps;
+/// # let (input, output, result) = (gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 64), gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 64), gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 64));
+/// let bindings = gpgpu::SetBindings::default().add_buffer(0, &input).add_buffer(1, &output);
+/// let layout = gpgpu::new_set_layout!(
+///     0: Buffer(gpgpu::GpuBufferUsage::ReadOnly),
+///     1: Buffer(gpgpu::GpuBufferUsage::ReadWrite)
+/// );
+/// let blur = gpgpu::Kernel::builder(&fw, &shader, "blur")
+///     .layouts(vec![layout.clone()])
+///     .bind_set(0, bindings.clone())
+///     .build()
+///     .unwrap();
+/// let sharpen = gpgpu::Kernel::builder(&fw, &shader, "sharpen")
+///     .layouts(vec![layout])
+///     .bind_set(0, bindings)
+///     .build()
+///     .unwrap();
+///
+/// fw.sequence()
+///     .dispatch(&blur, 64, 1, 1)
+///     .dispatch(&sharpen, 64, 1, 1)
+///     .copy_buffer(&output, &result)
+///     .submit();
+/// ```
+pub struct Sequence<'fw> {
+    recorder: GpuRecorder<'fw>,
+}
+
+impl<'fw> Sequence<'fw> {
+    pub(crate) fn new(fw: &'fw Framework) -> Self {
+        Self {
+            recorder: GpuRecorder::new(fw),
+        }
+    }
+
+    /// Records the dispatch of `kernel`, with `x`, `y` and `z` workgroups per dimension.
+    ///
+    /// # Panics
+    /// If a set of `kernel` was never bound, see [`GpuError::UnboundEntries`](crate::GpuError::UnboundEntries).
+    pub fn dispatch(mut self, kernel: &Kernel, x: u32, y: u32, z: u32) -> Self {
+        self.recorder.dispatch_kernel(kernel, x, y, z);
+        self
+    }
+
+    /// Fallible version of [`Sequence::dispatch`], see [`GpuRecorder::try_dispatch_kernel`].
+    pub fn try_dispatch(mut self, kernel: &Kernel, x: u32, y: u32, z: u32) -> GpuResult<Self> {
+        self.recorder.try_dispatch_kernel(kernel, x, y, z)?;
+
+        Ok(self)
+    }
+
+    /// Records the indirect dispatch of `kernel`, see [`GpuRecorder::dispatch_kernel_indirect`].
+    pub fn dispatch_indirect(
+        mut self,
+        kernel: &Kernel,
+        args: &GpuBuffer<DispatchIndirectArgs>,
+        index: u64,
+    ) -> Self {
+        self.recorder.dispatch_kernel_indirect(kernel, args, index);
+        self
+    }
+
+    /// Fallible version of [`Sequence::dispatch_indirect`], see [`GpuRecorder::try_dispatch_kernel_indirect`].
+    pub fn try_dispatch_indirect(
+        mut self,
+        kernel: &Kernel,
+        args: &GpuBuffer<DispatchIndirectArgs>,
+        index: u64,
+    ) -> GpuResult<Self> {
+        self.recorder
+            .try_dispatch_kernel_indirect(kernel, args, index)?;
+
+        Ok(self)
+    }
+
+    /// Records the copy of all the elements of `src` into the beginning of `dst`, see [`GpuRecorder::copy_buffer`].
+    pub fn copy_buffer<T>(mut self, src: &GpuBuffer<T>, dst: &GpuBuffer<T>) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        self.recorder.copy_buffer(src, dst);
+        self
+    }
+
+    /// Fallible version of [`Sequence::copy_buffer`], see [`GpuRecorder::try_copy_buffer`].
+    pub fn try_copy_buffer<T>(
+        mut self,
+        src: &GpuBuffer<T>,
+        dst: &GpuBuffer<T>,
+    ) -> BufferResult<Self>
+    where
+        T: bytemuck::Pod,
+    {
+        self.recorder.try_copy_buffer(src, dst)?;
+
+        Ok(self)
+    }
+
+    /// Times the scopes and dispatches recorded afterwards with `profiler`, see [`GpuRecorder::profile`].
+    pub fn profile(mut self, profiler: &GpuProfiler) -> GpuResult<Self> {
+        self.recorder = self.recorder.profile(profiler)?;
+
+        Ok(self)
+    }
+
+    /// Opens a profiler scope labelled `label`, see [`GpuRecorder::begin_scope`].
+    pub fn begin_scope(mut self, label: &str) -> Self {
+        self.recorder.begin_scope(label);
+        self
+    }
+
+    /// Closes the innermost profiler scope, see [`GpuRecorder::end_scope`].
+    pub fn end_scope(mut self) -> Self {
+        self.recorder.end_scope();
+        self
+    }
+
+    /// Submits all the recorded commands at once, returning their [`Submission`].
+    pub fn submit(self) -> Submission {
+        self.recorder.submit()
+    }
+
+    /// Fallible version of [`Sequence::submit`], see [`GpuRecorder::try_submit`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_submit(self) -> GpuResult<Submission> {
+        self.recorder.try_submit()
+    }
+}
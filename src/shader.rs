@@ -1,10 +1,63 @@
-use crate::Framework;
+use crate::{
+    framework::{GpuError, GpuResult},
+    preprocessor::{self, FileResolver, IncludeResolver, PreprocessError},
+    specialization::{self, Override},
+    Framework,
+};
 use std::{borrow::Cow, path::Path};
 
+use thiserror::Error;
+
+pub type ShaderResult<T> = Result<T, ShaderError>;
+
+#[derive(Error, Debug)]
+pub enum ShaderError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The shader failed to parse or validate. Holds the `naga` error message.
+    #[error("invalid shader: {0}")]
+    Invalid(#[from] GpuError),
+    /// The device lacks the [`wgpu::Features::SPIRV_SHADER_PASSTHROUGH`] feature.
+    #[error("the device lacks the `SPIRV_SHADER_PASSTHROUGH` feature, which is only supported by Vulkan adapters and must be requested with `FrameworkBuilder::features`.")]
+    PassthroughUnsupported,
+    /// The directives of the source failed to process, see [`preprocessor`].
+    #[error("shader preprocessing failed: {0}")]
+    Preprocess(#[from] PreprocessError),
+    /// The processed shader failed to parse. Holds the `naga` error message, located in the
+    /// original files by the [`SourceMap`](preprocessor::SourceMap) of its includes.
+    #[error("{file}:{line}:{column}: {message}")]
+    Parse {
+        file: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The GLSL source failed to compile. Holds a diagnostic per error, with its line.
+    #[cfg(feature = "glsl")]
+    #[error("GLSL compilation failed:\n{}", .0.join("\n"))]
+    Glsl(Vec<String>),
+    /// The shader files cannot be watched, see [`ShaderWatcher`](crate::ShaderWatcher).
+    #[cfg(feature = "hot-reload")]
+    #[error("cannot watch the shader files: {0}")]
+    Watch(#[from] notify::Error),
+}
+
 /// Represents a shader.
 ///
-/// It's just a wrapper around [`wgpu::ShaderModule`].
-pub struct Shader(pub(crate) wgpu::ShaderModule);
+/// It's mostly a wrapper around [`wgpu::ShaderModule`], keeping the `naga` module of
+/// the shader to reflect its bindings (see [`SetLayout::from_shader`](crate::SetLayout::from_shader)).
+///
+/// The `override` constants of WGSL shaders are set when creating kernels with
+/// [`KernelBuilder::constant`](crate::KernelBuilder::constant). Otherwise they keep their
+/// default value, or 0 if they have none. The ones used by `@workgroup_size` attributes must be
+/// valued by integer literals, e.g. `override WG: u32 = 0x40u;` but not `= 8u * 8u;`.
+pub struct Shader {
+    pub(crate) module: wgpu::ShaderModule,
+    pub(crate) naga: Option<naga::Module>,
+    /// WGSL source of the shader, only kept if it declares `overrides`.
+    pub(crate) wgsl: Option<String>,
+    pub(crate) overrides: Vec<Override>,
+}
 
 impl Shader {
     /// Initialises a [`Shader`] from a SPIR-V file.
@@ -19,14 +72,78 @@ impl Shader {
     pub fn from_spirv_bytes(fw: &Framework, bytes: &[u8], name: Option<&str>) -> Self {
         let source = wgpu::util::make_spirv(bytes);
 
-        let shader = fw
+        let module = fw
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: name,
                 source,
             });
 
-        Self(shader)
+        Self {
+            module,
+            naga: naga::front::spv::parse_u8_slice(bytes, &Default::default()).ok(),
+            wgsl: None,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Initialises a [`Shader`] from a SPIR-V file passed to the driver untranslated.
+    /// See [`Shader::from_spirv_bytes_passthrough`].
+    ///
+    /// # Safety
+    /// The SPIR-V must be valid for the device, see [`Shader::from_spirv_bytes_passthrough`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub unsafe fn from_spirv_file_passthrough(
+        fw: &Framework,
+        path: impl AsRef<Path>,
+    ) -> ShaderResult<Self> {
+        let bytes = std::fs::read(&path)?;
+        let shader_name = path.as_ref().to_str();
+
+        Self::from_spirv_bytes_passthrough(fw, &bytes, shader_name)
+    }
+
+    /// Initialises a [`Shader`] from SPIR-V bytes with an optional `name`, passed to the driver without
+    /// being translated nor validated by `naga`, e.g. for shaders using features `naga` translates
+    /// incorrectly. The bindings are still reflected if `naga` can parse the shader.
+    ///
+    /// Fails with [`ShaderError::PassthroughUnsupported`] if the device lacks the
+    /// [`wgpu::Features::SPIRV_SHADER_PASSTHROUGH`] feature, or with the `wgpu` validation error.
+    ///
+    /// # Safety
+    /// The SPIR-V must be valid for the device, as it is not validated.
+    ///
+    /// # Panics
+    /// If `bytes` is not a SPIR-V module, whose length is a multiple of 4 bytes.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub unsafe fn from_spirv_bytes_passthrough(
+        fw: &Framework,
+        bytes: &[u8],
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        if !fw
+            .features()
+            .contains(wgpu::Features::SPIRV_SHADER_PASSTHROUGH)
+        {
+            return Err(ShaderError::PassthroughUnsupported);
+        }
+
+        let source = wgpu::util::make_spirv_raw(bytes);
+
+        let module = fw.error_scope(|| {
+            fw.device
+                .create_shader_module_spirv(&wgpu::ShaderModuleDescriptorSpirV {
+                    label: name,
+                    source,
+                })
+        })?;
+
+        Ok(Self {
+            module,
+            naga: naga::front::spv::parse_u8_slice(bytes, &Default::default()).ok(),
+            wgsl: None,
+            overrides: Vec::new(),
+        })
     }
 
     /// Initialises a [`Shader`] from a `WGSL` file.
@@ -34,12 +151,7 @@ impl Shader {
         let source_string = std::fs::read_to_string(&path)?;
         let shader_name = path.as_ref().to_str();
 
-        Ok(Self(fw.device.create_shader_module(
-            wgpu::ShaderModuleDescriptor {
-                label: shader_name,
-                source: wgpu::ShaderSource::Wgsl(Cow::Owned(source_string)),
-            },
-        )))
+        Ok(Self::from_wgsl(fw, source_string, shader_name).unwrap_or_else(|err| panic!("{}", err)))
     }
 
     /// Initialises a [`Shader`] from a `WGSL` string.
@@ -48,11 +160,204 @@ impl Shader {
         source: String,
         name: Option<&str>,
     ) -> std::io::Result<Self> {
-        Ok(Self(fw.device.create_shader_module(
-            wgpu::ShaderModuleDescriptor {
+        Ok(Self::from_wgsl(fw, source, name).unwrap_or_else(|err| panic!("{}", err)))
+    }
+
+    /// Fallible version of [`Shader::from_wgsl_file`].
+    ///
+    /// Returns the IO error of reading `path`, or the parse and validation errors of
+    /// the shader as [`ShaderError::Invalid`] instead of panicking.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_from_wgsl_file(fw: &Framework, path: impl AsRef<Path>) -> ShaderResult<Self> {
+        let source_string = std::fs::read_to_string(&path)?;
+
+        Self::try_from_wgsl_string(fw, source_string, path.as_ref().to_str())
+    }
+
+    /// Initialises a [`Shader`] from a `WGSL` file whose directives are processed with `defines`, including
+    /// the files of its `//#include` directives relative to the including file with [`FileResolver`].
+    /// See [`Shader::from_wgsl_string_with_resolver`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_wgsl_file_with_defines(
+        fw: &Framework,
+        path: impl AsRef<Path>,
+        defines: &[(&str, &str)],
+    ) -> ShaderResult<Self> {
+        let source_string = std::fs::read_to_string(&path)?;
+        let shader_name = path.as_ref().to_string_lossy();
+
+        Self::from_wgsl_string_with_resolver(
+            fw,
+            &source_string,
+            &shader_name,
+            defines,
+            &FileResolver,
+        )
+    }
+
+    /// Initialises a [`Shader`] from the `WGSL` string `source` named `name`, whose directives are processed
+    /// with `defines` like [`Shader::from_wgsl_with_defines`], including the files of its `//#include`
+    /// directives loaded by `resolver`, e.g. from embedded assets.
+    ///
+    /// Returns [`ShaderError::Preprocess`] if a directive is invalid or an include fails, [`ShaderError::Parse`]
+    /// located in the original files if the processed shader fails to parse, or its validation errors like
+    /// [`Shader::try_from_wgsl_string`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # let fw = gpgpu::Framework::default();
+    /// let library = "fn square(x: f32) -> f32 { return x * x; }";
+    /// let source = "//#include \"library.wgsl\"\n// ...";
+    ///
+    /// let resolver = |path: &str| match path {
+    ///     "library.wgsl" => Ok(library.to_owned()),
+    ///     _ => Err(std::io::ErrorKind::NotFound.into()),
+    /// };
+    ///
+    /// let shader =
+    ///     gpgpu::Shader::from_wgsl_string_with_resolver(&fw, source, "kernel.wgsl", &[], &resolver)
+    ///         .unwrap();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_wgsl_string_with_resolver(
+        fw: &Framework,
+        source: &str,
+        name: &str,
+        defines: &[(&str, &str)],
+        resolver: &impl IncludeResolver,
+    ) -> ShaderResult<Self> {
+        let processed = preprocessor::preprocess_with_includes(source, name, defines, resolver)?;
+
+        Self::from_processed(fw, processed, name)
+    }
+
+    /// Initialises a [`Shader`] from the `WGSL` source named `name` processed by
+    /// [`preprocessor::preprocess_with_includes`], failing like [`Shader::from_wgsl_string_with_resolver`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn from_processed(
+        fw: &Framework,
+        processed: preprocessor::Processed,
+        name: &str,
+    ) -> ShaderResult<Self> {
+        // The `naga` errors are located in the processed source, which parses like `Shader::from_wgsl` does.
+        let overrides = specialization::parse_overrides(&processed.source);
+        let specialized = specialization::specialize(&processed.source, &overrides, &[], true)?;
+
+        if let Err(err) = naga::front::wgsl::parse_str(&specialized) {
+            let location = err.location(&specialized).and_then(|location| {
+                let (file, line) = processed.source_map.locate(location.line_number as usize)?;
+                Some((file, line, location.line_position as usize))
+            });
+
+            if let Some((file, line, column)) = location {
+                return Err(ShaderError::Parse {
+                    file: file.to_owned(),
+                    line,
+                    column,
+                    message: err.message().to_owned(),
+                });
+            }
+        }
+
+        Self::try_from_wgsl_string(fw, processed.source, Some(name))
+    }
+
+    /// Initialises a [`Shader`] from a `WGSL` string whose directives are processed with `defines`,
+    /// the names and values defined beforehand, e.g. to toggle code paths. See [`preprocessor`]
+    /// for the syntax, and [`preprocessor::preprocess`] to get the processed source.
+    ///
+    /// Returns [`ShaderError::Preprocess`] if a directive is invalid, or the parse and validation
+    /// errors of the processed shader like [`Shader::try_from_wgsl_string`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # let fw = gpgpu::Framework::default();
+    /// # let source = "";
+    /// let shader = gpgpu::Shader::from_wgsl_with_defines(
+    ///     &fw,
+    ///     source,
+    ///     &[("USE_FAST_MATH", "1"), ("CHANNELS", "4")],
+    ///     None,
+    /// )
+    /// .unwrap();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_wgsl_with_defines(
+        fw: &Framework,
+        source: &str,
+        defines: &[(&str, &str)],
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        let processed = preprocessor::preprocess(source, defines)?;
+
+        Self::try_from_wgsl_string(fw, processed, name)
+    }
+
+    /// Fallible version of [`Shader::from_wgsl_string`].
+    ///
+    /// Returns the parse and validation errors of the shader as [`ShaderError::Invalid`]
+    /// instead of panicking.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_from_wgsl_string(
+        fw: &Framework,
+        source: String,
+        name: Option<&str>,
+    ) -> ShaderResult<Self> {
+        Ok(fw.error_scope(|| Self::from_wgsl(fw, source, name))??)
+    }
+
+    /// Fails with [`GpuError::InvalidConstant`] if an `override` cannot be emulated.
+    fn from_wgsl(fw: &Framework, source: String, name: Option<&str>) -> GpuResult<Self> {
+        let overrides = specialization::parse_overrides(&source);
+
+        let (specialized, wgsl) = if overrides.is_empty() {
+            (source, None)
+        } else {
+            let specialized = specialization::specialize(&source, &overrides, &[], true)?;
+            (specialized, Some(source))
+        };
+
+        let module = Self::compile_wgsl(fw, specialized.as_str(), name);
+
+        Ok(Self {
+            module,
+            naga: naga::front::wgsl::parse_str(&specialized).ok(),
+            wgsl,
+            overrides,
+        })
+    }
+
+    /// Returns the `@workgroup_size` of the compute `entry_point`, if the shader can be reflected.
+    ///
+    /// Sizes set by `override` constants have their default values, see [`Kernel::workgroup_size`](crate::Kernel::workgroup_size)
+    /// for the ones of [`KernelBuilder::constant`](crate::KernelBuilder::constant).
+    pub fn workgroup_size(&self, entry_point: &str) -> Option<[u32; 3]> {
+        self.naga
+            .as_ref()
+            .and_then(|module| crate::reflection::workgroup_size(module, entry_point))
+    }
+
+    /// Returns the names of the compute entry points of the shader, if it can be reflected.
+    pub fn entry_points(&self) -> Option<Vec<&str>> {
+        self.naga.as_ref().map(|module| {
+            module
+                .entry_points
+                .iter()
+                .filter(|entry| entry.stage == naga::ShaderStage::Compute)
+                .map(|entry| entry.name.as_str())
+                .collect()
+        })
+    }
+
+    pub(crate) fn compile_wgsl(
+        fw: &Framework,
+        source: &str,
+        name: Option<&str>,
+    ) -> wgpu::ShaderModule {
+        fw.device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: name,
-                source: wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
-            },
-        )))
+                source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+            })
     }
 }
@@ -0,0 +1,108 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use crate::{framework::GpuResult, Framework, SetBindings, SetLayout};
+
+/// A set of bindings shared by several [`Kernel`](crate::Kernel)s, e.g. the parameters, inputs
+/// and outputs common to the kernels of a pipeline.
+///
+/// Its [`wgpu::BindGroupLayout`] is created once and used by all the kernels built with
+/// [`KernelBuilder::shared_set`](crate::KernelBuilder::shared_set), which run it with
+/// [`Kernel::run_bound`](crate::Kernel::run_bound). Binding new resources with
+/// [`SharedSet::rebind`] creates a single bind group, used by all these kernels.
+///
+/// Cloning a [`SharedSet`] returns another handle to the same set.
+///
+/// # Example
+/// ```no_run
+/// # let fw = gpgpu::Framework::default();
+/// # let shader = gpgpu::Shader::from_wgsl_file(&fw, "shader.wgsl").unwrap();
+/// # use gpgpu::BufOps;
+/// # let (input, output) = (gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 64), gpgpu::GpuBuffer::<f32>::with_capacity(&fw, 64));
+/// let layout = gpgpu::new_set_layout!(
+///     0: Buffer(gpgpu::GpuBufferUsage::ReadOnly),
+///     1: Buffer(gpgpu::GpuBufferUsage::ReadWrite)
+/// );
+/// let shared = gpgpu::SharedSet::new(&fw, layout);
+/// shared.rebind(&fw, gpgpu::SetBindings::default().add_buffer(0, &input).add_buffer(1, &output));
+///
+/// let blur = gpgpu::Kernel::builder(&fw, &shader, "blur").shared_set(0, &shared).build().unwrap();
+/// let sharpen = gpgpu::Kernel::builder(&fw, &shader, "sharpen").shared_set(0, &shared).build().unwrap();
+///
+/// blur.run_bound(&fw, 64, 1, 1);
+/// sharpen.run_bound(&fw, 64, 1, 1);
+/// ```
+#[derive(Clone)]
+pub struct SharedSet {
+    inner: Arc<SharedSetInner>,
+}
+
+struct SharedSetInner {
+    layout: SetLayout,
+    bind_group_layout: Arc<wgpu::BindGroupLayout>,
+    bind_group: RwLock<Option<wgpu::BindGroup>>,
+}
+
+impl SharedSet {
+    /// Creates a [`SharedSet`] of `layout`, without bindings until [`SharedSet::rebind`].
+    pub fn new(fw: &Framework, layout: SetLayout) -> Self {
+        let bind_group_layout =
+            fw.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("SharedSet::new"),
+                    entries: &layout.layout_entry,
+                });
+
+        Self {
+            inner: Arc::new(SharedSetInner {
+                layout,
+                bind_group_layout: Arc::new(bind_group_layout),
+                bind_group: RwLock::new(None),
+            }),
+        }
+    }
+
+    /// Returns the [`SetLayout`] of this [`SharedSet`].
+    pub fn layout(&self) -> &SetLayout {
+        &self.inner.layout
+    }
+
+    /// Binds `bindings` to this [`SharedSet`], replacing its previous bindings for all the
+    /// kernels using it.
+    ///
+    /// # Panics
+    /// If the bindings do not match the layout of the set, see [`GpuError::BindingMismatch`](crate::GpuError::BindingMismatch).
+    pub fn rebind(&self, fw: &Framework, bindings: SetBindings) {
+        let bind_group = self
+            .bind_group(fw, &bindings)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        *self.inner.bind_group.write().unwrap() = Some(bind_group);
+    }
+
+    /// Fallible version of [`SharedSet::rebind`].
+    ///
+    /// Returns [`GpuError::BindingMismatch`](crate::GpuError::BindingMismatch) if the bindings do not match the layout of the set, or the
+    /// `wgpu` validation error instead of panicking. The previous bindings are kept on failure.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_rebind(&self, fw: &Framework, bindings: SetBindings) -> GpuResult<()> {
+        let bind_group = fw.error_scope(|| self.bind_group(fw, &bindings))??;
+
+        *self.inner.bind_group.write().unwrap() = Some(bind_group);
+
+        Ok(())
+    }
+
+    fn bind_group(&self, fw: &Framework, bindings: &SetBindings) -> GpuResult<wgpu::BindGroup> {
+        // Shared sets are bound to the set index of each kernel, the reported one is arbitrary.
+        bindings.into_bind_group(fw, 0, &self.inner.bind_group_layout, &self.inner.layout)
+    }
+
+    pub(crate) fn bind_group_layout(&self) -> Arc<wgpu::BindGroupLayout> {
+        self.inner.bind_group_layout.clone()
+    }
+
+    /// Locks the current bind group of the set, `None` until the set is bound.
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, Option<wgpu::BindGroup>> {
+        self.inner.bind_group.read().unwrap()
+    }
+}
@@ -0,0 +1,500 @@
+//! Emulation of the WGSL `override` constants, not supported by `naga` yet: the declarations are
+//! rewritten as `const` declarations before compiling the shader, and the overrides used by
+//! `@workgroup_size` attributes, which only accept literals, are replaced by their values.
+
+use std::ops::Range;
+
+use crate::framework::{GpuError, GpuResult};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScalarType {
+    Bool,
+    I32,
+    U32,
+    F32,
+}
+
+impl ScalarType {
+    fn parse(ty: &str) -> Option<Self> {
+        match ty {
+            "bool" => Some(Self::Bool),
+            "i32" => Some(Self::I32),
+            "u32" => Some(Self::U32),
+            "f32" => Some(Self::F32),
+            _ => None,
+        }
+    }
+
+    /// Infers the type of an `override` declared without one from its default `value`.
+    fn infer(value: &str) -> Self {
+        let is_hex = value.starts_with("0x") || value.starts_with("-0x");
+
+        if value == "true" || value == "false" {
+            Self::Bool
+        } else if value.ends_with('u') {
+            Self::U32
+        } else if value.contains('.') || (!is_hex && (value.contains('e') || value.ends_with('f')))
+        {
+            Self::F32
+        } else {
+            Self::I32
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::I32 => "i32",
+            Self::U32 => "u32",
+            Self::F32 => "f32",
+        }
+    }
+
+    /// Formats `value` as a WGSL literal of this type.
+    fn literal(self, value: f64) -> Option<String> {
+        match self {
+            Self::Bool => Some((value != 0.0).to_string()),
+            Self::I32
+                if value.fract() == 0.0 && (i32::MIN as f64..=i32::MAX as f64).contains(&value) =>
+            {
+                Some(format!("{}", value as i32))
+            }
+            Self::U32 if value.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&value) => {
+                Some(format!("{}u", value as u32))
+            }
+            Self::F32 if value.is_finite() && value.abs() <= f32::MAX as f64 => {
+                Some(format!("{:?}", value as f32))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An `override` declaration of a WGSL shader.
+#[derive(Clone, Debug)]
+pub(crate) struct Override {
+    name: String,
+    id: Option<String>,
+    ty: ScalarType,
+    default: Option<String>,
+    /// Bytes of the declaration in the source, from its `@id` attribute to its `;`.
+    span: Range<usize>,
+}
+
+/// Finds the module scope `override` declarations of the WGSL `source`.
+pub(crate) fn parse_overrides(source: &str) -> Vec<Override> {
+    let bytes = source.as_bytes();
+    let mut overrides = Vec::new();
+    let mut depth = 0usize;
+    let mut id: Option<(usize, String)> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + end + 4);
+                continue;
+            }
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b'@' if depth == 0 => {
+                let rest = source[i + 1..].trim_start();
+
+                if let Some(args) = rest.strip_prefix("id").map(str::trim_start) {
+                    if let (Some(inner), Some(end)) = (args.strip_prefix('('), args.find(')')) {
+                        id = Some((i, inner[..end - 1].trim().to_owned()));
+                        i = source.len() - args.len() + end + 1;
+                        continue;
+                    }
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let end = source[i..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .map_or(bytes.len(), |end| i + end);
+                let word = &source[i..end];
+
+                if depth == 0 && word == "override" {
+                    if let Some(decl_end) = source[end..].find(';').map(|decl| end + decl) {
+                        let start = id.as_ref().map_or(i, |&(start, _)| start);
+                        overrides.push(parse_declaration(
+                            &source[end..decl_end],
+                            id.take().map(|(_, id)| id),
+                            start..decl_end + 1,
+                        ));
+                        i = decl_end + 1;
+                        continue;
+                    }
+                } else if word != "override" {
+                    id = None;
+                }
+
+                i = end;
+                continue;
+            }
+            _ => (),
+        }
+
+        i += 1;
+    }
+
+    overrides
+}
+
+/// Parses `NAME [: TYPE] [= DEFAULT]`, the declaration after the `override` keyword.
+fn parse_declaration(declaration: &str, id: Option<String>, span: Range<usize>) -> Override {
+    let (left, default) = match declaration.find('=') {
+        Some(eq) => (
+            &declaration[..eq],
+            Some(declaration[eq + 1..].trim().to_owned()),
+        ),
+        None => (declaration, None),
+    };
+
+    let (name, ty) = match left.find(':') {
+        Some(colon) => (&left[..colon], ScalarType::parse(left[colon + 1..].trim())),
+        None => (left, None),
+    };
+
+    let ty = ty.unwrap_or_else(|| {
+        default
+            .as_deref()
+            .map_or(ScalarType::F32, ScalarType::infer)
+    });
+
+    Override {
+        name: name.trim().to_owned(),
+        id,
+        ty,
+        default,
+        span,
+    }
+}
+
+/// Rewrites the `overrides` of `source` as `const` declarations, valued by the `constants`
+/// named after the overrides or their `@id`, or by their default values.
+///
+/// If `zeroed` is `true`, overrides without a default nor a constant are valued 0, or 1 in
+/// `@workgroup_size` attributes, otherwise they fail with [`GpuError::InvalidConstant`], as well
+/// as constants not naming any override.
+pub(crate) fn specialize(
+    source: &str,
+    overrides: &[Override],
+    constants: &[(String, f64)],
+    zeroed: bool,
+) -> GpuResult<String> {
+    let invalid = |name: &str, reason| GpuError::InvalidConstant {
+        name: name.to_owned(),
+        reason,
+    };
+
+    for (name, _) in constants {
+        if !overrides
+            .iter()
+            .any(|over| &over.name == name || over.id.as_ref() == Some(name))
+        {
+            return Err(invalid(name, "the shader declares no such override"));
+        }
+    }
+
+    let mut specialized = String::with_capacity(source.len());
+    let mut last = 0;
+    let mut sizes = Vec::new();
+
+    for over in overrides {
+        let constant = constants
+            .iter()
+            .rev()
+            .find(|(name, _)| name == &over.name || over.id.as_ref() == Some(name));
+
+        let value = match (constant, &over.default) {
+            (Some(&(_, value)), _) => over
+                .ty
+                .literal(value)
+                .ok_or_else(|| invalid(&over.name, "its value does not fit its type"))?,
+            (None, Some(default)) => default.clone(),
+            (None, None) if zeroed => over.ty.literal(0.0).unwrap(),
+            (None, None) => {
+                return Err(invalid(
+                    &over.name,
+                    "it has no default value and was not given one",
+                ))
+            }
+        };
+
+        if matches!(over.ty, ScalarType::I32 | ScalarType::U32) {
+            // Zeroed workgroup sizes would not validate.
+            let size = match (constant, &over.default) {
+                (None, None) => Some(1),
+                _ => parse_integer(&value),
+            };
+
+            sizes.push((over.name.as_str(), size));
+        }
+
+        specialized.push_str(&source[last..over.span.start]);
+        specialized.push_str(&format!(
+            "const {}: {} = {};",
+            over.name,
+            over.ty.name(),
+            value
+        ));
+        // Keeps the lines of the source in the `naga` errors.
+        specialized.push_str(&"\n".repeat(source[over.span.clone()].matches('\n').count()));
+        last = over.span.end;
+    }
+
+    specialized.push_str(&source[last..]);
+
+    inline_workgroup_sizes(&specialized, &sizes)
+}
+
+/// Parses the WGSL integer `literal`, decimal or hexadecimal, with an optional `i` or `u` suffix.
+fn parse_integer(literal: &str) -> Option<u32> {
+    let digits = literal.trim_end_matches(['i', 'u']);
+
+    match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => digits.parse().ok(),
+    }
+}
+
+/// Replaces the arguments of the `@workgroup_size` attributes of `source` naming one of the
+/// integer overrides of `sizes` by its value.
+///
+/// Fails with [`GpuError::InvalidConstant`] if such an override is not valued by an integer
+/// literal, e.g. by an expression, which `naga` would reject as an unknown identifier.
+fn inline_workgroup_sizes(source: &str, sizes: &[(&str, Option<u32>)]) -> GpuResult<String> {
+    let mut inlined = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(at) = rest.find('@') {
+        inlined.push_str(&rest[..=at]);
+        rest = &rest[at + 1..];
+
+        let args = rest.trim_start();
+        let args = match args.strip_prefix("workgroup_size").map(str::trim_start) {
+            Some(args) if args.starts_with('(') => args,
+            _ => continue,
+        };
+        let end = match args.find(')') {
+            Some(end) => end,
+            None => continue,
+        };
+
+        let replaced = args[1..end]
+            .split(',')
+            .map(
+                |arg| match sizes.iter().find(|(name, _)| *name == arg.trim()) {
+                    Some((_, Some(size))) => Ok(size.to_string()),
+                    Some((name, None)) => Err(GpuError::InvalidConstant {
+                        name: name.to_string(),
+                        reason: "`@workgroup_size` needs its value as an integer literal",
+                    }),
+                    None => Ok(arg.to_owned()),
+                },
+            )
+            .collect::<GpuResult<Vec<_>>>()?
+            .join(",");
+
+        inlined.push_str(&rest[..rest.len() - args.len()]);
+        inlined.push('(');
+        inlined.push_str(&replaced);
+        rest = &args[end..];
+    }
+
+    inlined.push_str(rest);
+    Ok(inlined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specialize_source(source: &str, constants: &[(&str, f64)]) -> GpuResult<String> {
+        let constants = constants
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value))
+            .collect::<Vec<_>>();
+
+        specialize(source, &parse_overrides(source), &constants, false)
+    }
+
+    fn invalid_constant(result: GpuResult<String>) -> (String, &'static str) {
+        match result {
+            Err(GpuError::InvalidConstant { name, reason }) => (name, reason),
+            result => panic!(
+                "expected an invalid constant, got {:?}",
+                result.map_err(|_| ())
+            ),
+        }
+    }
+
+    #[test]
+    fn parses_declarations() {
+        let overrides = parse_overrides(
+            "
+            @id(4) override scale: f32 = 2.0;
+            override enabled = true;
+            override count = 16u;
+            override gain: i32;",
+        );
+
+        let parsed = overrides
+            .iter()
+            .map(|over| {
+                (
+                    over.name.as_str(),
+                    over.id.as_deref(),
+                    over.ty,
+                    over.default.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parsed,
+            [
+                ("scale", Some("4"), ScalarType::F32, Some("2.0")),
+                ("enabled", None, ScalarType::Bool, Some("true")),
+                ("count", None, ScalarType::U32, Some("16u")),
+                ("gain", None, ScalarType::I32, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn constants_by_name_or_id() {
+        let source = "@id(0) override scale: f32 = 1.0;\noverride bias: i32 = 0;";
+
+        assert_eq!(
+            specialize_source(source, &[("0", 2.5), ("bias", -3.0)]).unwrap(),
+            "const scale: f32 = 2.5;\nconst bias: i32 = -3;"
+        );
+        assert_eq!(
+            specialize_source(source, &[]).unwrap(),
+            "const scale: f32 = 1.0;\nconst bias: i32 = 0;"
+        );
+    }
+
+    #[test]
+    fn ignores_comments_and_function_scopes() {
+        let source = "
+            // override commented: u32 = 1u;
+            /* override block: u32 = 2u; */
+            fn main() {
+                let override_like = 3u;
+            }
+            override kept: u32 = 4u;";
+
+        let overrides = parse_overrides(source);
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].name, "kept");
+
+        assert!(specialize_source(source, &[])
+            .unwrap()
+            .ends_with("const kept: u32 = 4u;"));
+    }
+
+    #[test]
+    fn unknown_constant() {
+        let result = specialize_source("override scale: f32 = 1.0;", &[("other", 1.0)]);
+
+        assert_eq!(
+            invalid_constant(result),
+            ("other".to_owned(), "the shader declares no such override")
+        );
+    }
+
+    #[test]
+    fn out_of_range_constants() {
+        let source = "override small: i32 = 0;\noverride count: u32 = 0u;";
+
+        for constants in [
+            [("small", i32::MAX as f64 + 1.0)],
+            [("small", 0.5)],
+            [("count", -1.0)],
+            [("count", u32::MAX as f64 + 1.0)],
+        ] {
+            let (_, reason) = invalid_constant(specialize_source(source, &constants));
+            assert_eq!(reason, "its value does not fit its type");
+        }
+
+        assert_eq!(
+            specialize_source(
+                source,
+                &[("small", i32::MIN as f64), ("count", u32::MAX as f64)]
+            )
+            .unwrap(),
+            "const small: i32 = -2147483648;\nconst count: u32 = 4294967295u;"
+        );
+    }
+
+    #[test]
+    fn missing_default() {
+        let source = "override count: u32;";
+
+        assert_eq!(
+            invalid_constant(specialize_source(source, &[])),
+            (
+                "count".to_owned(),
+                "it has no default value and was not given one"
+            )
+        );
+        assert_eq!(
+            specialize(source, &parse_overrides(source), &[], true).unwrap(),
+            "const count: u32 = 0u;"
+        );
+    }
+
+    #[test]
+    fn inlines_workgroup_sizes() {
+        let source = "
+            override WG_X: u32 = 0x40u;
+            override WG_Y = 4;
+            @compute @workgroup_size(WG_X, WG_Y, 1)
+            fn main() {}";
+
+        let specialized = specialize_source(source, &[]).unwrap();
+        assert!(specialized.contains("@workgroup_size(64,4, 1)"));
+        assert!(specialized.contains("const WG_X: u32 = 0x40u;"));
+
+        let specialized = specialize_source(source, &[("WG_Y", 8.0)]).unwrap();
+        assert!(specialized.contains("@workgroup_size(64,8, 1)"));
+
+        // Zeroed sizes would not validate.
+        let source = "override WG: u32;\n@compute @workgroup_size(WG)\nfn main() {}";
+        let specialized = specialize(source, &parse_overrides(source), &[], true).unwrap();
+        assert!(specialized.contains("@workgroup_size(1)"));
+    }
+
+    #[test]
+    fn workgroup_size_expressions() {
+        let source = "
+            override WG: u32 = 8u * 8u;
+            @compute @workgroup_size(WG)
+            fn main() {}";
+
+        assert_eq!(
+            invalid_constant(specialize_source(source, &[])),
+            (
+                "WG".to_owned(),
+                "`@workgroup_size` needs its value as an integer literal"
+            )
+        );
+        assert!(specialize_source(source, &[("WG", 64.0)])
+            .unwrap()
+            .contains("@workgroup_size(64)"));
+
+        // Only sizes need literals.
+        assert!(specialize_source("override scale: u32 = 8u * 8u;", &[]).is_ok());
+    }
+}
@@ -0,0 +1,101 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::memory::{Allocation, ResourceKind};
+
+/// Smallest size class of the [`StagingPool`], in bytes.
+const MIN_SIZE_CLASS: u64 = 256;
+
+/// Reusable `MAP_READ` staging buffers of a [`Framework`](crate::Framework), bucketed by
+/// power-of-two size classes.
+pub(crate) struct StagingPool {
+    max_retained: u64,
+    buffers: Mutex<Buffers>,
+}
+
+/// Buffer taken from a [`StagingPool`], accounted as [`ResourceKind::Staging`]
+/// until it is returned with [`StagingPool::release`] or dropped.
+pub(crate) struct PooledBuffer {
+    buf: wgpu::Buffer,
+    _alloc: Allocation,
+}
+
+impl std::ops::Deref for PooledBuffer {
+    type Target = wgpu::Buffer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+
+#[derive(Default)]
+struct Buffers {
+    by_class: HashMap<u64, Vec<wgpu::Buffer>>,
+    retained: u64,
+}
+
+impl StagingPool {
+    pub(crate) fn new(max_retained: u64) -> Self {
+        Self {
+            max_retained,
+            buffers: Mutex::default(),
+        }
+    }
+
+    /// Takes a buffer of `size` bytes or more from the pool, creating it if there is none.
+    pub(crate) fn acquire(&self, fw: &crate::Framework, size: u64) -> PooledBuffer {
+        let class = Self::size_class(size);
+
+        let reused = {
+            let mut buffers = self.buffers.lock().unwrap();
+            let buffer = buffers.by_class.get_mut(&class).and_then(Vec::pop);
+
+            if buffer.is_some() {
+                buffers.retained -= class;
+            }
+
+            buffer
+        };
+
+        let buf = reused.unwrap_or_else(|| {
+            fw.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("gpgpu staging buffer"),
+                size: class,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        PooledBuffer {
+            buf,
+            _alloc: fw.memory.allocate(ResourceKind::Staging, class),
+        }
+    }
+
+    /// Returns an unmapped `buffer` taken with [`StagingPool::acquire`] to the pool.
+    /// It is dropped instead if the pool would retain more than its maximum.
+    pub(crate) fn release(&self, buffer: PooledBuffer) {
+        let buffer = buffer.buf;
+        let class = buffer.size();
+        let mut buffers = self.buffers.lock().unwrap();
+
+        if buffers.retained + class <= self.max_retained {
+            buffers.retained += class;
+            buffers.by_class.entry(class).or_default().push(buffer);
+        }
+    }
+
+    /// Drops all the retained buffers.
+    pub(crate) fn trim(&self) {
+        let mut buffers = self.buffers.lock().unwrap();
+        buffers.by_class.clear();
+        buffers.retained = 0;
+    }
+
+    pub(crate) fn retained_bytes(&self) -> u64 {
+        self.buffers.lock().unwrap().retained
+    }
+
+    fn size_class(size: u64) -> u64 {
+        size.next_power_of_two().max(MIN_SIZE_CLASS)
+    }
+}
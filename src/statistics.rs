@@ -0,0 +1,44 @@
+use crate::{framework::GpuResult, queries, GpuBuffer};
+
+/// Pipeline statistics of the kernel dispatches recorded between [`GpuRecorder::begin_statistics`]
+/// and [`GpuRecorder::end_statistics`], e.g. to check the workgroup counts of the dispatches
+/// do not launch more invocations than needed.
+///
+/// [`GpuRecorder::begin_statistics`]: crate::GpuRecorder::begin_statistics
+/// [`GpuRecorder::end_statistics`]: crate::GpuRecorder::end_statistics
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PipelineStats {
+    /// Compute shader invocations of all the dispatches.
+    pub compute_shader_invocations: u64,
+    /// Number of measured dispatches.
+    pub dispatches: u32,
+}
+
+/// Pipeline statistics queries of the dispatches of a [`GpuRecorder`](crate::GpuRecorder),
+/// returned by [`GpuRecorder::end_statistics`](crate::GpuRecorder::end_statistics).
+pub struct StatisticsQuery {
+    resolved: GpuBuffer<u64>,
+    dispatches: u32,
+}
+
+impl StatisticsQuery {
+    pub(crate) fn new(resolved: GpuBuffer<u64>, dispatches: u32) -> Self {
+        Self {
+            resolved,
+            dispatches,
+        }
+    }
+
+    /// Reads the [`PipelineStats`] of the dispatches, blocking until the GPU has executed them.
+    ///
+    /// The statistics are resolved by the commands of the recorder: they are only available once
+    /// it is submitted, all the counts are zero before.
+    pub fn read(&self) -> GpuResult<PipelineStats> {
+        let invocations = queries::read_results(&self.resolved)?;
+
+        Ok(PipelineStats {
+            compute_shader_invocations: invocations[..self.dispatches as usize].iter().sum(),
+            dispatches: self.dispatches,
+        })
+    }
+}
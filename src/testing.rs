@@ -0,0 +1,61 @@
+//! Helpers of the unit tests.
+
+use std::sync::Mutex;
+
+use crate::Framework;
+
+/// Environment variable enabling the tests running on an adapter: `software` runs them on
+/// [`Framework::software`], any other value on the default adapter.
+///
+/// The tests needing an adapter pass without running when it is unset.
+pub(crate) const ADAPTER_VAR: &str = "GPGPU_TEST_ADAPTER";
+
+/// [`FrameworkBuilder::staging_chunk_size`](crate::FrameworkBuilder::staging_chunk_size) of the framework
+/// of the tests, small enough for them to cover the chunked transfers.
+pub(crate) const STAGING_CHUNK_SIZE: u64 = 4096;
+
+/// Framework of the tests, created by the first one running.
+static FRAMEWORK: Mutex<Option<Framework>> = Mutex::new(None);
+
+/// Second framework of [`with_frameworks`]. It is never dropped, since dropping a framework
+/// of the GL backend terminates the display shared by all of them.
+static OTHER_FRAMEWORK: Mutex<Option<Framework>> = Mutex::new(None);
+
+/// Runs `test` on the [`Framework`] selected by [`ADAPTER_VAR`], or skips it if the variable is unset.
+///
+/// The tests share a single framework, and run one at a time so that their error scopes
+/// and submissions do not interleave.
+pub(crate) fn with_framework(test: impl FnOnce(&Framework)) {
+    let adapter = match std::env::var(ADAPTER_VAR) {
+        Ok(adapter) => adapter,
+        Err(_) => return,
+    };
+
+    // A failed test poisons the lock, but leaves the framework usable.
+    let mut fw = FRAMEWORK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let fw = fw.get_or_insert_with(|| build(&adapter));
+
+    test(fw)
+}
+
+/// Runs `test` on the [`Framework`] of [`with_framework`] and on a second one on the same adapter.
+pub(crate) fn with_frameworks(test: impl FnOnce(&Framework, &Framework)) {
+    with_framework(|fw| {
+        let mut other = OTHER_FRAMEWORK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let other = other.get_or_insert_with(|| build(&std::env::var(ADAPTER_VAR).unwrap()));
+
+        test(fw, other)
+    })
+}
+
+fn build(adapter: &str) -> Framework {
+    Framework::builder()
+        .force_fallback_adapter(adapter == "software")
+        .staging_chunk_size(STAGING_CHUNK_SIZE)
+        .build()
+        .unwrap_or_else(|err| panic!("{}: {}", ADAPTER_VAR, err))
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use crate::{
+    framework::GpuResult,
+    queries::{self, QuerySets},
+    Framework, GpuBuffer,
+};
+
+/// GPU execution time of a kernel dispatch, measured with timestamp queries.
+/// See [`GpuRecorder::with_timing`](crate::GpuRecorder::with_timing).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelTiming {
+    /// Entry point of the dispatched kernel.
+    pub kernel: String,
+    /// Time elapsed on the GPU between the beginning and the end of the dispatch.
+    pub gpu_duration: Duration,
+}
+
+/// Source of the duration of a [`Timing`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimingSource {
+    /// Timestamp queries written by the GPU around the dispatch, see [`KernelTiming`].
+    Timestamps,
+    /// Wall-clock time from the submission of the dispatch until [`Framework::wait_for`] returns,
+    /// used when the device does not support [`wgpu::Features::TIMESTAMP_QUERY`]. It includes the
+    /// submission and synchronization overheads.
+    WallClock,
+}
+
+/// Execution time of a kernel dispatch, returned by [`Kernel::run_timed`](crate::Kernel::run_timed).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timing {
+    /// Execution time of the dispatch.
+    pub duration: Duration,
+    /// How `duration` was measured.
+    pub source: TimingSource,
+}
+
+/// Timestamp queries written into a command encoder.
+pub(crate) struct Timestamps {
+    queries: QuerySets,
+}
+
+impl Timestamps {
+    /// Fails with [`GpuError::MissingFeature`](crate::GpuError::MissingFeature) if the device does not
+    /// support [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub(crate) fn new(fw: &Framework) -> GpuResult<Self> {
+        Ok(Self {
+            queries: QuerySets::new(
+                fw,
+                wgpu::QueryType::Timestamp,
+                wgpu::Features::TIMESTAMP_QUERY,
+            )?,
+        })
+    }
+
+    /// Writes a timestamp into `encoder` once its previous commands are completed,
+    /// returning the index of its query.
+    pub(crate) fn write(&mut self, fw: &Framework, encoder: &mut wgpu::CommandEncoder) -> u32 {
+        let (set, query) = self.queries.slot(fw);
+        encoder.write_timestamp(set, query);
+
+        self.queries.advance()
+    }
+
+    /// Records the resolution of all the written timestamps into a buffer, read by [`read_ticks`]
+    /// once the commands of `encoder` are submitted.
+    pub(crate) fn resolve(
+        &self,
+        fw: &Framework,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> GpuResult<GpuBuffer<u64>> {
+        self.queries.resolve(fw, encoder)
+    }
+
+    /// Returns the number of timestamps written so far.
+    pub(crate) fn len(&self) -> u32 {
+        self.queries.len()
+    }
+
+    /// Records the resolution of all the written timestamps into `buffer`, see [`QuerySets::resolve_into`].
+    pub(crate) fn resolve_into(&self, encoder: &mut wgpu::CommandEncoder, buffer: &wgpu::Buffer) {
+        self.queries.resolve_into(encoder, buffer)
+    }
+}
+
+/// Reads the timestamps resolved by [`Timestamps::resolve`], blocking until they are available.
+pub(crate) fn read_ticks(resolved: &GpuBuffer<u64>) -> GpuResult<Vec<u64>> {
+    queries::read_results(resolved)
+}
+
+/// Converts the timestamps `start` and `end` into the [`Duration`] between them, with the
+/// nanoseconds per tick `period` of [`wgpu::Queue::get_timestamp_period`].
+pub(crate) fn elapsed(period: f32, start: u64, end: u64) -> Duration {
+    Duration::from_nanos((end.saturating_sub(start) as f64 * period as f64).round() as u64)
+}